@@ -1,6 +1,7 @@
 mod parser;
 mod seek_sequence;
 mod standalone_executable;
+mod transaction;
 
 use std::collections::HashMap;
 use std::path::Path;
@@ -15,6 +16,7 @@ pub use parser::ParseError;
 use parser::ParseError::*;
 use parser::UpdateFileChunk;
 pub use parser::parse_patch;
+pub use parser::split_file_mode;
 use similar::TextDiff;
 use thiserror::Error;
 use tree_sitter::LanguageError;
@@ -25,6 +27,7 @@ use tree_sitter::StreamingIterator;
 use tree_sitter_bash::LANGUAGE as BASH;
 
 pub use standalone_executable::main;
+use transaction::PendingChanges;
 
 /// Detailed instructions for gpt-4.1 on how to use the `apply_patch` tool.
 pub const APPLY_PATCH_TOOL_INSTRUCTIONS: &str = include_str!("../apply_patch_tool_instructions.md");
@@ -127,15 +130,28 @@ pub fn maybe_parse_apply_patch(argv: &[String]) -> MaybeApplyPatch {
 pub enum ApplyPatchFileChange {
     Add {
         content: String,
+        /// Permission bits the file is created with, if the patch sets them.
+        mode: Option<u32>,
     },
     Delete {
-        content: String,
+        /// The file's current contents, which need not be UTF-8.
+        contents: Vec<u8>,
     },
     Update {
         unified_diff: String,
         move_path: Option<PathBuf>,
-        /// new_content that will result after the unified_diff is applied.
-        new_content: String,
+        /// new_content that will result after the unified_diff is applied;
+        /// `None` when the patch only changes the mode, leaving the contents,
+        /// which need not be UTF-8, as they are.
+        new_content: Option<String>,
+        /// New permission bits, if the patch changes them.
+        mode: Option<u32>,
+    },
+    /// A binary file added, or replaced when `replace` is set.
+    Binary {
+        contents: Vec<u8>,
+        replace: bool,
+        mode: Option<u32>,
     },
 }
 
@@ -198,7 +214,13 @@ impl ApplyPatchAction {
 + {content}
 *** End Patch"#,
         );
-        let changes = HashMap::from([(path.to_path_buf(), ApplyPatchFileChange::Add { content })]);
+        let changes = HashMap::from([(
+            path.to_path_buf(),
+            ApplyPatchFileChange::Add {
+                content,
+                mode: None,
+            },
+        )]);
         #[expect(clippy::expect_used)]
         Self {
             changes,
@@ -255,12 +277,18 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
             for hunk in hunks {
                 let path = hunk.resolve_path(&effective_cwd);
                 match hunk {
-                    Hunk::AddFile { contents, .. } => {
-                        changes.insert(path, ApplyPatchFileChange::Add { content: contents });
+                    Hunk::AddFile { contents, mode, .. } => {
+                        changes.insert(
+                            path,
+                            ApplyPatchFileChange::Add {
+                                content: contents,
+                                mode,
+                            },
+                        );
                     }
                     Hunk::DeleteFile { .. } => {
-                        let content = match std::fs::read_to_string(&path) {
-                            Ok(content) => content,
+                        let contents = match std::fs::read(&path) {
+                            Ok(contents) => contents,
                             Err(e) => {
                                 return MaybeApplyPatchVerified::CorrectnessError(
                                     ApplyPatchError::IoError(IoError {
@@ -270,16 +298,35 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                                 );
                             }
                         };
-                        changes.insert(path, ApplyPatchFileChange::Delete { content });
+                        changes.insert(path, ApplyPatchFileChange::Delete { contents });
                     }
                     Hunk::UpdateFile {
-                        move_path, chunks, ..
+                        move_path,
+                        chunks,
+                        mode,
+                        ..
                     } => {
-                        let ApplyPatchFileUpdate {
-                            unified_diff,
-                            content: contents,
-                        } = match unified_diff_from_chunks(&path, &chunks) {
-                            Ok(diff) => diff,
+                        let update = if chunks.is_empty() {
+                            // Only the mode changes, so the file may be binary
+                            // and its contents are left alone.
+                            std::fs::metadata(&path)
+                                .map(|_| (String::new(), None))
+                                .map_err(|e| {
+                                    ApplyPatchError::IoError(IoError {
+                                        context: format!("Failed to read {}", path.display()),
+                                        source: e,
+                                    })
+                                })
+                        } else {
+                            unified_diff_from_chunks(&path, &chunks).map(
+                                |ApplyPatchFileUpdate {
+                                     unified_diff,
+                                     content,
+                                 }| (unified_diff, Some(content)),
+                            )
+                        };
+                        let (unified_diff, contents) = match update {
+                            Ok(update) => update,
                             Err(e) => {
                                 return MaybeApplyPatchVerified::CorrectnessError(e);
                             }
@@ -290,6 +337,30 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                                 unified_diff,
                                 move_path: move_path.map(|p| cwd.join(p)),
                                 new_content: contents,
+                                mode,
+                            },
+                        );
+                    }
+                    Hunk::BinaryFile {
+                        contents,
+                        replace,
+                        mode,
+                        ..
+                    } => {
+                        if replace && let Err(e) = std::fs::metadata(&path) {
+                            return MaybeApplyPatchVerified::CorrectnessError(
+                                ApplyPatchError::IoError(IoError {
+                                    context: format!("Failed to read {}", path.display()),
+                                    source: e,
+                                }),
+                            );
+                        }
+                        changes.insert(
+                            path,
+                            ApplyPatchFileChange::Binary {
+                                contents,
+                                replace,
+                                mode,
                             },
                         );
                     }
@@ -510,10 +581,11 @@ pub fn apply_hunks(
     let _existing_paths: Vec<&Path> = hunks
         .iter()
         .filter_map(|hunk| match hunk {
-            Hunk::AddFile { .. } => {
+            Hunk::AddFile { .. } | Hunk::BinaryFile { replace: false, .. } => {
                 // The file is being added, so it doesn't exist yet.
                 None
             }
+            Hunk::BinaryFile { path, .. } => Some(path.as_path()),
             Hunk::DeleteFile { path } => Some(path.as_path()),
             Hunk::UpdateFile {
                 path, move_path, ..
@@ -561,34 +633,50 @@ pub struct AffectedPaths {
     pub added: Vec<PathBuf>,
     pub modified: Vec<PathBuf>,
     pub deleted: Vec<PathBuf>,
+    /// Hunks that only matched approximately, which are worth checking.
+    pub fuzzy_matches: Vec<FuzzyMatch>,
+}
+
+/// A hunk whose context lines only matched the file approximately.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub path: PathBuf,
+    /// 1-based index of the hunk among the file's hunks.
+    pub hunk: usize,
+    pub hunks: usize,
+    /// 1-based line of the file where the hunk was applied.
+    pub line: usize,
+    /// Similarity of the matched lines to the hunk's, between 0 and 1.
+    pub similarity: f32,
 }
 
 /// Apply the hunks to the filesystem, returning which files were added, modified, or deleted.
-/// Returns an error if the patch could not be applied.
+/// Every hunk is verified before anything is written, and if writing fails
+/// part way, the files already written are restored, so the patch is applied
+/// either completely or not at all.
 fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
     if hunks.is_empty() {
         anyhow::bail!("No files were modified.");
     }
 
+    let mut changes = PendingChanges::default();
     let mut added: Vec<PathBuf> = Vec::new();
     let mut modified: Vec<PathBuf> = Vec::new();
     let mut deleted: Vec<PathBuf> = Vec::new();
+    let mut fuzzy_matches: Vec<FuzzyMatch> = Vec::new();
     for hunk in hunks {
         match hunk {
-            Hunk::AddFile { path, contents } => {
-                if let Some(parent) = path.parent()
-                    && !parent.as_os_str().is_empty()
-                {
-                    std::fs::create_dir_all(parent).with_context(|| {
-                        format!("Failed to create parent directories for {}", path.display())
-                    })?;
-                }
-                std::fs::write(path, contents)
-                    .with_context(|| format!("Failed to write file {}", path.display()))?;
+            Hunk::AddFile {
+                path,
+                contents,
+                mode,
+            } => {
+                changes.write(path, contents.clone().into_bytes(), *mode);
                 added.push(path.clone());
             }
             Hunk::DeleteFile { path } => {
-                std::fs::remove_file(path)
+                changes
+                    .delete(path)
                     .with_context(|| format!("Failed to delete file {}", path.display()))?;
                 deleted.push(path.clone());
             }
@@ -596,40 +684,64 @@ fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
                 path,
                 move_path,
                 chunks,
+                mode,
             } => {
-                let AppliedPatch { new_contents, .. } =
-                    derive_new_contents_from_chunks(path, chunks)?;
+                let read_context = || format!("Failed to read file to update {}", path.display());
+                let new_contents = if chunks.is_empty() {
+                    // Only the mode changes, so the file may be binary.
+                    changes.read_bytes(path).with_context(read_context)?
+                } else {
+                    let original_contents = changes.read(path).with_context(read_context)?;
+                    let AppliedPatch {
+                        new_contents,
+                        fuzzy_matches: fuzzy,
+                        ..
+                    } = derive_new_contents_from_text(path, original_contents, chunks)?;
+                    fuzzy_matches.extend(fuzzy);
+                    new_contents.into_bytes()
+                };
                 if let Some(dest) = move_path {
-                    if let Some(parent) = dest.parent()
-                        && !parent.as_os_str().is_empty()
-                    {
-                        std::fs::create_dir_all(parent).with_context(|| {
-                            format!("Failed to create parent directories for {}", dest.display())
-                        })?;
-                    }
-                    std::fs::write(dest, new_contents)
-                        .with_context(|| format!("Failed to write file {}", dest.display()))?;
-                    std::fs::remove_file(path)
+                    changes.write(dest, new_contents, *mode);
+                    changes
+                        .delete(path)
                         .with_context(|| format!("Failed to remove original {}", path.display()))?;
                     modified.push(dest.clone());
                 } else {
-                    std::fs::write(path, new_contents)
-                        .with_context(|| format!("Failed to write file {}", path.display()))?;
+                    changes.write(path, new_contents, *mode);
                     modified.push(path.clone());
                 }
             }
+            Hunk::BinaryFile {
+                path,
+                contents,
+                replace,
+                mode,
+            } => {
+                if *replace {
+                    changes.read_bytes(path).with_context(|| {
+                        format!("Failed to read file to replace {}", path.display())
+                    })?;
+                    modified.push(path.clone());
+                } else {
+                    added.push(path.clone());
+                }
+                changes.write(path, contents.clone(), *mode);
+            }
         }
     }
+    changes.commit()?;
     Ok(AffectedPaths {
         added,
         modified,
         deleted,
+        fuzzy_matches,
     })
 }
 
 struct AppliedPatch {
     original_contents: String,
     new_contents: String,
+    fuzzy_matches: Vec<FuzzyMatch>,
 }
 
 /// Return *only* the new file contents (joined into a single `String`) after
@@ -647,7 +759,16 @@ fn derive_new_contents_from_chunks(
             }));
         }
     };
+    derive_new_contents_from_text(path, original_contents, chunks)
+}
 
+/// Apply the chunks to `original_contents`, the current contents of the file
+/// at `path`.
+fn derive_new_contents_from_text(
+    path: &Path,
+    original_contents: String,
+    chunks: &[UpdateFileChunk],
+) -> std::result::Result<AppliedPatch, ApplyPatchError> {
     let mut original_lines: Vec<String> = original_contents
         .split('\n')
         .map(|s| s.to_string())
@@ -659,7 +780,7 @@ fn derive_new_contents_from_chunks(
         original_lines.pop();
     }
 
-    let replacements = compute_replacements(&original_lines, path, chunks)?;
+    let (replacements, fuzzy_matches) = compute_replacements(&original_lines, path, chunks)?;
     let new_lines = apply_replacements(original_lines, &replacements);
     let mut new_lines = new_lines;
     if !new_lines.last().is_some_and(|s| s.is_empty()) {
@@ -669,21 +790,29 @@ fn derive_new_contents_from_chunks(
     Ok(AppliedPatch {
         original_contents,
         new_contents,
+        fuzzy_matches,
     })
 }
 
+/// `(start_index, old_len, new_lines)`: replace `old_len` lines from
+/// `start_index` with `new_lines`.
+type Replacement = (usize, usize, Vec<String>);
+
 /// Compute a list of replacements needed to transform `original_lines` into the
 /// new lines, given the patch `chunks`. Each replacement is returned as
-/// `(start_index, old_len, new_lines)`.
+/// `(start_index, old_len, new_lines)`, alongside the chunks that only matched
+/// approximately.
 fn compute_replacements(
     original_lines: &[String],
     path: &Path,
     chunks: &[UpdateFileChunk],
-) -> std::result::Result<Vec<(usize, usize, Vec<String>)>, ApplyPatchError> {
-    let mut replacements: Vec<(usize, usize, Vec<String>)> = Vec::new();
+) -> std::result::Result<(Vec<Replacement>, Vec<FuzzyMatch>), ApplyPatchError> {
+    let mut replacements: Vec<Replacement> = Vec::new();
+    let mut fuzzy_matches: Vec<FuzzyMatch> = Vec::new();
     let mut line_index: usize = 0;
 
-    for chunk in chunks {
+    for (chunk_index, chunk) in chunks.iter().enumerate() {
+        let hunk = format!("hunk {} of {}", chunk_index + 1, chunks.len());
         // If a chunk has a `change_context`, we use seek_sequence to find it, then
         // adjust our `line_index` to continue from there.
         if let Some(ctx_line) = &chunk.change_context {
@@ -696,7 +825,7 @@ fn compute_replacements(
                 line_index = idx + 1;
             } else {
                 return Err(ApplyPatchError::ComputeReplacements(format!(
-                    "Failed to find context '{}' in {}",
+                    "Failed to find context '{}' in {} ({hunk})",
                     ctx_line,
                     path.display()
                 )));
@@ -727,8 +856,13 @@ fn compute_replacements(
         // located reliably.
 
         let mut pattern: &[String] = &chunk.old_lines;
-        let mut found =
-            seek_sequence::seek_sequence(original_lines, pattern, line_index, chunk.is_end_of_file);
+        let mut found = seek_sequence::seek_hunk(
+            original_lines,
+            pattern,
+            &chunk.removed_lines,
+            line_index,
+            chunk.is_end_of_file,
+        );
 
         let mut new_slice: &[String] = &chunk.new_lines;
 
@@ -740,20 +874,65 @@ fn compute_replacements(
                 new_slice = &new_slice[..new_slice.len() - 1];
             }
 
-            found = seek_sequence::seek_sequence(
+            found = seek_sequence::seek_hunk(
                 original_lines,
                 pattern,
+                &chunk.removed_lines,
                 line_index,
                 chunk.is_end_of_file,
             );
         }
 
-        if let Some(start_idx) = found {
+        if let Some(seek_sequence::SequenceMatch {
+            start: start_idx,
+            fuzzy_similarity,
+        }) = found
+        {
+            if let Some(similarity) = fuzzy_similarity {
+                fuzzy_matches.push(FuzzyMatch {
+                    path: path.to_path_buf(),
+                    hunk: chunk_index + 1,
+                    hunks: chunks.len(),
+                    line: start_idx + 1,
+                    similarity,
+                });
+            }
             replacements.push((start_idx, pattern.len(), new_slice.to_vec()));
             line_index = start_idx + pattern.len();
         } else {
+            let approximate = seek_sequence::fuzzy_matches(
+                original_lines,
+                pattern,
+                &chunk.removed_lines,
+                line_index,
+            );
+            let closest = if approximate.len() > 1 {
+                let starts: Vec<String> = approximate
+                    .iter()
+                    .map(|start| (start + 1).to_string())
+                    .collect();
+                format!(
+                    "The lines match approximately at {} places (starting at lines {}); add more context lines so that the hunk matches only one of them.",
+                    approximate.len(),
+                    starts.join(", ")
+                )
+            } else {
+                match seek_sequence::closest_sequence(original_lines, pattern) {
+                    Some((start, similarity)) => {
+                        let end = (start + pattern.len()).min(original_lines.len());
+                        format!(
+                            "Closest match at lines {}-{} ({:.0}% similar):\n{}",
+                            start + 1,
+                            end,
+                            similarity * 100.0,
+                            original_lines[start..end].join("\n")
+                        )
+                    }
+                    None => "No similar lines were found.".to_string(),
+                }
+            };
             return Err(ApplyPatchError::ComputeReplacements(format!(
-                "Failed to find expected lines in {}:\n{}",
+                "Failed to find expected lines in {} ({hunk}):\n{}\n{closest}",
                 path.display(),
                 chunk.old_lines.join("\n"),
             )));
@@ -762,7 +941,7 @@ fn compute_replacements(
 
     replacements.sort_by(|(lhs_idx, _, _), (rhs_idx, _, _)| lhs_idx.cmp(rhs_idx));
 
-    Ok(replacements)
+    Ok((replacements, fuzzy_matches))
 }
 
 /// Apply the `(start_index, old_len, new_lines)` replacements to `original_lines`,
@@ -815,6 +994,7 @@ pub fn unified_diff_from_chunks_with_context(
     let AppliedPatch {
         original_contents,
         new_contents,
+        ..
     } = derive_new_contents_from_chunks(path, chunks)?;
     let text_diff = TextDiff::from_lines(&original_contents, &new_contents);
     let unified_diff = text_diff.unified_diff().context_radius(context).to_string();
@@ -840,6 +1020,17 @@ pub fn print_summary(
     for path in &affected.deleted {
         writeln!(out, "D {}", path.display())?;
     }
+    for fuzzy in &affected.fuzzy_matches {
+        writeln!(
+            out,
+            "Note: hunk {} of {} in {} matched approximately at line {} ({:.0}% similar).",
+            fuzzy.hunk,
+            fuzzy.hunks,
+            fuzzy.path.display(),
+            fuzzy.line,
+            fuzzy.similarity * 100.0
+        )?;
+    }
     Ok(())
 }
 
@@ -880,6 +1071,7 @@ mod tests {
         vec![Hunk::AddFile {
             path: PathBuf::from("foo"),
             contents: "hi\n".to_string(),
+            mode: None,
         }]
     }
 
@@ -941,7 +1133,8 @@ mod tests {
                     hunks,
                     vec![Hunk::AddFile {
                         path: PathBuf::from("foo"),
-                        contents: "hi\n".to_string()
+                        contents: "hi\n".to_string(),
+                        mode: None,
                     }]
                 );
             }
@@ -966,7 +1159,8 @@ mod tests {
                     hunks,
                     vec![Hunk::AddFile {
                         path: PathBuf::from("foo"),
-                        contents: "hi\n".to_string()
+                        contents: "hi\n".to_string(),
+                        mode: None,
                     }]
                 );
             }
@@ -999,7 +1193,8 @@ PATCH"#,
                     hunks,
                     vec![Hunk::AddFile {
                         path: PathBuf::from("foo"),
-                        contents: "hi\n".to_string()
+                        contents: "hi\n".to_string(),
+                        mode: None,
                     }]
                 );
             }
@@ -1096,6 +1291,71 @@ PATCH"#,
         assert_eq!(contents, "ab\ncd\n");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_file_mode_directives_set_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("run.sh");
+        let tool = dir.path().join("tool.py");
+        fs::write(&tool, "print()").unwrap();
+        let patch = wrap_patch(&format!(
+            "*** Add File: {} (mode 755)\n+#!/bin/sh\n*** Update File: {} (mode 700)",
+            script.display(),
+            tool.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o7777;
+        assert_eq!(mode(&script), 0o755);
+        assert_eq!(mode(&tool), 0o700);
+        // A mode-only update leaves the contents alone, even without a final newline.
+        assert_eq!(fs::read_to_string(&tool).unwrap(), "print()");
+    }
+
+    #[test]
+    fn test_binary_file_hunks_write_decoded_bytes() {
+        let dir = tempdir().unwrap();
+        let added = dir.path().join("logo.png");
+        let replaced = dir.path().join("data.bin");
+        fs::write(&replaced, [0xff, 0xfe]).unwrap();
+        let patch = wrap_patch(&format!(
+            "*** Add Binary File: {}\n+iVBO\n+Rw==\n*** Replace Binary File: {}\n+AAE=",
+            added.display(),
+            replaced.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+
+        assert_eq!(
+            String::from_utf8(stdout).unwrap(),
+            format!(
+                "Success. Updated the following files:\nA {}\nM {}\n",
+                added.display(),
+                replaced.display()
+            )
+        );
+        assert_eq!(fs::read(&added).unwrap(), [0x89, 0x50, 0x4e, 0x47]);
+        assert_eq!(fs::read(&replaced).unwrap(), [0, 1]);
+    }
+
+    #[test]
+    fn test_replace_binary_file_requires_existing_file() {
+        let dir = tempdir().unwrap();
+        let missing = dir.path().join("missing.bin");
+        let patch = wrap_patch(&format!(
+            "*** Replace Binary File: {}\n+AAE=",
+            missing.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        assert!(apply_patch(&patch, &mut stdout, &mut stderr).is_err());
+        assert!(!missing.exists());
+    }
+
     #[test]
     fn test_delete_file_hunk_removes_file() {
         let dir = tempdir().unwrap();
@@ -1595,7 +1855,8 @@ g
 "#
                         .to_string(),
                         move_path: None,
-                        new_content: "updated session directory content\n".to_string(),
+                        new_content: Some("updated session directory content\n".to_string()),
+                        mode: None,
                     },
                 )]),
                 patch: argv[1].clone(),
@@ -1604,6 +1865,184 @@ g
         );
     }
 
+    #[test]
+    fn test_verified_binary_delete_and_mode_change_keep_bytes() {
+        let dir = tempdir().unwrap();
+        let image = dir.path().join("image.bin");
+        let tool = dir.path().join("tool.bin");
+        fs::write(&image, [0xff, 0x00, 0xfe]).unwrap();
+        fs::write(&tool, [0xca, 0xfe]).unwrap();
+
+        let argv = vec![
+            "apply_patch".to_string(),
+            wrap_patch("*** Delete File: image.bin\n*** Update File: tool.bin (mode 755)"),
+        ];
+        let result = maybe_parse_apply_patch_verified(&argv, dir.path());
+
+        assert_eq!(
+            result,
+            MaybeApplyPatchVerified::Body(ApplyPatchAction {
+                changes: HashMap::from([
+                    (
+                        image,
+                        ApplyPatchFileChange::Delete {
+                            contents: vec![0xff, 0x00, 0xfe],
+                        },
+                    ),
+                    (
+                        tool,
+                        ApplyPatchFileChange::Update {
+                            unified_diff: String::new(),
+                            move_path: None,
+                            new_content: None,
+                            mode: Some(0o755),
+                        },
+                    ),
+                ]),
+                patch: argv[1].clone(),
+                cwd: dir.path().to_path_buf(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_failed_hunk_leaves_every_file_untouched() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("first.txt");
+        let second = dir.path().join("second.txt");
+        let added = dir.path().join("added.txt");
+        fs::write(&first, "one\n").unwrap();
+        fs::write(&second, "alpha\nbeta\ngamma\n").unwrap();
+
+        let patch = wrap_patch(&format!(
+            "*** Update File: {}\n@@\n-one\n+uno\n*** Add File: {}\n+new\n*** Update File: {}\n@@\n alpha\n-betta\n+delta",
+            first.display(),
+            added.display(),
+            second.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        assert!(apply_patch(&patch, &mut stdout, &mut stderr).is_err());
+
+        assert_eq!(fs::read_to_string(&first).unwrap(), "one\n");
+        assert!(!added.exists());
+        assert_eq!(
+            String::from_utf8(stderr).unwrap(),
+            format!(
+                "Failed to find expected lines in {} (hunk 1 of 1):\nalpha\nbetta\nClosest match at lines 1-2 (94% similar):\nalpha\nbeta\n",
+                second.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_approximate_match_asks_for_more_context() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("calls.txt");
+        fs::write(&path, "\treset(state,  1);\nother();\n  reset(state, 1);\n").unwrap();
+
+        let patch = wrap_patch(&format!(
+            "*** Update File: {}\n@@\n-reset(state,   1);\n+reset(state, 2);",
+            path.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        assert!(apply_patch(&patch, &mut stdout, &mut stderr).is_err());
+
+        let stderr = String::from_utf8(stderr).unwrap();
+        assert!(
+            stderr.contains("match approximately at 2 places (starting at lines 1, 3)"),
+            "{stderr}"
+        );
+    }
+
+    #[test]
+    fn test_approximate_context_match_is_reported() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.rs");
+        fs::write(
+            &path,
+            "let configuration = load_configuration(&path)?;\nlet retries = 3;\n",
+        )
+        .unwrap();
+
+        let patch = wrap_patch(&format!(
+            "*** Update File: {}\n@@\n let configuration = load_configuraton(&path)?;\n-let retries = 3;\n+let retries = 5;",
+            path.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+
+        // The matched range is replaced by the hunk's lines, context included.
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "let configuration = load_configuraton(&path)?;\nlet retries = 5;\n"
+        );
+        assert_eq!(
+            String::from_utf8(stdout).unwrap(),
+            format!(
+                "Success. Updated the following files:\nM {path}\nNote: hunk 1 of 1 in {path} matched approximately at line 1 (99% similar).\n",
+                path = path.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_near_miss_removed_line_is_not_applied() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.rs");
+        let original = "let configuration = load_configuration(&path)?;\n";
+        fs::write(&path, original).unwrap();
+
+        let patch = wrap_patch(&format!(
+            "*** Update File: {}\n@@\n-let configuration = load_configuraton(&path)?;\n+let configuration = Config::default();",
+            path.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        assert!(apply_patch(&patch, &mut stdout, &mut stderr).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn test_write_failure_rolls_back_earlier_files() {
+        let dir = tempdir().unwrap();
+        let updated = dir.path().join("updated.txt");
+        let deleted = dir.path().join("deleted.txt");
+        let added = dir.path().join("nested/dir/added.txt");
+        // A regular file where a directory is needed makes the last write fail.
+        let blocker = dir.path().join("blocker");
+        fs::write(&updated, "before\n").unwrap();
+        fs::write(&deleted, "keep me\n").unwrap();
+        fs::write(&blocker, "").unwrap();
+
+        let patch = wrap_patch(&format!(
+            "*** Update File: {}\n@@\n-before\n+after\n*** Delete File: {}\n*** Add File: {}\n+new\n*** Add File: {}\n+never written",
+            updated.display(),
+            deleted.display(),
+            added.display(),
+            blocker.join("child.txt").display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        assert!(apply_patch(&patch, &mut stdout, &mut stderr).is_err());
+
+        assert_eq!(fs::read_to_string(&updated).unwrap(), "before\n");
+        assert_eq!(fs::read_to_string(&deleted).unwrap(), "keep me\n");
+        assert!(!added.exists());
+        assert!(!dir.path().join("nested").exists());
+        let leftovers: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(leftovers.len(), 3, "unexpected files: {leftovers:?}");
+        assert!(String::from_utf8(stderr).unwrap().starts_with(&format!(
+            "Failed to write file {}",
+            blocker.join("child.txt").display()
+        )));
+    }
+
     #[test]
     fn test_apply_patch_fails_on_write_error() {
         let dir = tempdir().unwrap();
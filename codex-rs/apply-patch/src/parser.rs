@@ -7,12 +7,15 @@
 //! begin_patch: "*** Begin Patch" LF
 //! end_patch: "*** End Patch" LF?
 //!
-//! hunk: add_hunk | delete_hunk | update_hunk
-//! add_hunk: "*** Add File: " filename LF add_line+
+//! hunk: add_hunk | delete_hunk | update_hunk | binary_hunk
+//! add_hunk: "*** Add File: " filename file_mode? LF add_line+
 //! delete_hunk: "*** Delete File: " filename LF
-//! update_hunk: "*** Update File: " filename LF change_move? change?
+//! update_hunk: "*** Update File: " filename file_mode? LF change_move? change?
+//! binary_hunk: ("*** Add Binary File: " | "*** Replace Binary File: ") filename file_mode? LF base64_line+
 //! filename: /(.+)/
+//! file_mode: " (mode " /[0-7]{3,4}/ ")"
 //! add_line: "+" /(.+)/ LF -> line
+//! base64_line: "+" /[A-Za-z0-9+\/=]+/ LF
 //!
 //! change_move: "*** Move to: " filename LF
 //! change: (change_context | change_line)+ eof_line?
@@ -23,6 +26,8 @@
 //! The parser below is a little more lenient than the explicit spec and allows for
 //! leading/trailing whitespace around patch markers.
 use crate::ApplyPatchArgs;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -33,6 +38,9 @@ const END_PATCH_MARKER: &str = "*** End Patch";
 const ADD_FILE_MARKER: &str = "*** Add File: ";
 const DELETE_FILE_MARKER: &str = "*** Delete File: ";
 const UPDATE_FILE_MARKER: &str = "*** Update File: ";
+const ADD_BINARY_FILE_MARKER: &str = "*** Add Binary File: ";
+const REPLACE_BINARY_FILE_MARKER: &str = "*** Replace Binary File: ";
+const FILE_MODE_PREFIX: &str = " (mode ";
 const MOVE_TO_MARKER: &str = "*** Move to: ";
 const EOF_MARKER: &str = "*** End of File";
 const CHANGE_CONTEXT_MARKER: &str = "@@ ";
@@ -61,6 +69,8 @@ pub enum Hunk {
     AddFile {
         path: PathBuf,
         contents: String,
+        /// Permission bits from a `(mode 755)` suffix on the header.
+        mode: Option<u32>,
     },
     DeleteFile {
         path: PathBuf,
@@ -70,8 +80,18 @@ pub enum Hunk {
         move_path: Option<PathBuf>,
 
         /// Chunks should be in order, i.e. the `change_context` of one chunk
-        /// should occur later in the file than the previous chunk.
+        /// should occur later in the file than the previous chunk. May be
+        /// empty if the hunk only changes the file's mode.
         chunks: Vec<UpdateFileChunk>,
+        mode: Option<u32>,
+    },
+    /// A binary file given in full as base64.
+    BinaryFile {
+        path: PathBuf,
+        contents: Vec<u8>,
+        /// Set for `*** Replace Binary File`, which requires the file to exist.
+        replace: bool,
+        mode: Option<u32>,
     },
 }
 
@@ -81,6 +101,7 @@ impl Hunk {
             Hunk::AddFile { path, .. } => cwd.join(path),
             Hunk::DeleteFile { path } => cwd.join(path),
             Hunk::UpdateFile { path, .. } => cwd.join(path),
+            Hunk::BinaryFile { path, .. } => cwd.join(path),
         }
     }
 }
@@ -98,6 +119,10 @@ pub struct UpdateFileChunk {
     pub old_lines: Vec<String>,
     pub new_lines: Vec<String>,
 
+    /// Indices into `old_lines` of the lines the chunk removes. The other old
+    /// lines are context, the only lines an approximate match may differ in.
+    pub removed_lines: Vec<usize>,
+
     /// If set to true, `old_lines` must occur at the end of the source file.
     /// (Tolerance around trailing newlines should be encouraged.)
     pub is_end_of_file: bool,
@@ -245,8 +270,9 @@ fn check_start_and_end_lines_strict(
 fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), ParseError> {
     // Be tolerant of case mismatches and extra padding around marker strings.
     let first_line = lines[0].trim();
-    if let Some(path) = first_line.strip_prefix(ADD_FILE_MARKER) {
+    if let Some(header) = first_line.strip_prefix(ADD_FILE_MARKER) {
         // Add File
+        let (path, mode) = parse_file_header(header, line_number)?;
         let mut contents = String::new();
         let mut parsed_lines = 1;
         for add_line in &lines[1..] {
@@ -260,8 +286,9 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
         }
         return Ok((
             AddFile {
-                path: PathBuf::from(path),
+                path,
                 contents,
+                mode,
             },
             parsed_lines,
         ));
@@ -273,8 +300,9 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             },
             1,
         ));
-    } else if let Some(path) = first_line.strip_prefix(UPDATE_FILE_MARKER) {
+    } else if let Some(header) = first_line.strip_prefix(UPDATE_FILE_MARKER) {
         // Update File
+        let (path, mode) = parse_file_header(header, line_number)?;
         let mut remaining_lines = &lines[1..];
         let mut parsed_lines = 1;
 
@@ -312,18 +340,58 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             remaining_lines = &remaining_lines[chunk_lines..]
         }
 
-        if chunks.is_empty() {
+        if chunks.is_empty() && mode.is_none() {
             return Err(InvalidHunkError {
-                message: format!("Update file hunk for path '{path}' is empty"),
+                message: format!("Update file hunk for path '{}' is empty", path.display()),
                 line_number,
             });
         }
 
         return Ok((
             UpdateFile {
-                path: PathBuf::from(path),
+                path,
                 move_path: move_path.map(PathBuf::from),
                 chunks,
+                mode,
+            },
+            parsed_lines,
+        ));
+    } else if let Some((header, replace)) = first_line
+        .strip_prefix(ADD_BINARY_FILE_MARKER)
+        .map(|header| (header, false))
+        .or_else(|| {
+            first_line
+                .strip_prefix(REPLACE_BINARY_FILE_MARKER)
+                .map(|header| (header, true))
+        })
+    {
+        // Add or Replace Binary File
+        let (path, mode) = parse_file_header(header, line_number)?;
+        let mut encoded = String::new();
+        let mut parsed_lines = 1;
+        for line in &lines[1..] {
+            if let Some(chunk) = line.strip_prefix('+') {
+                encoded.push_str(chunk.trim());
+                parsed_lines += 1;
+            } else {
+                break;
+            }
+        }
+        let contents = BASE64_STANDARD
+            .decode(&encoded)
+            .map_err(|err| InvalidHunkError {
+                message: format!(
+                    "Binary file hunk for path '{}' is not valid base64: {err}",
+                    path.display()
+                ),
+                line_number,
+            })?;
+        return Ok((
+            BinaryFile {
+                path,
+                contents,
+                replace,
+                mode,
             },
             parsed_lines,
         ));
@@ -331,12 +399,49 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
 
     Err(InvalidHunkError {
         message: format!(
-            "'{first_line}' is not a valid hunk header. Valid hunk headers: '*** Add File: {{path}}', '*** Delete File: {{path}}', '*** Update File: {{path}}'"
+            "'{first_line}' is not a valid hunk header. Valid hunk headers: '*** Add File: {{path}}', '*** Delete File: {{path}}', '*** Update File: {{path}}', '*** Add Binary File: {{path}}', '*** Replace Binary File: {{path}}'"
         ),
         line_number,
     })
 }
 
+/// Split the path in a file header from an optional `(mode 755)` suffix,
+/// returning the path and the mode's digits.
+pub fn split_file_mode(header: &str) -> (&str, Option<&str>) {
+    match header
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once(FILE_MODE_PREFIX))
+    {
+        Some((path, mode)) => (path, Some(mode)),
+        None => (header, None),
+    }
+}
+
+fn parse_file_header(
+    header: &str,
+    line_number: usize,
+) -> Result<(PathBuf, Option<u32>), ParseError> {
+    let (path, mode) = split_file_mode(header);
+    let mode = match mode {
+        None => None,
+        Some(digits)
+            if (3..=4).contains(&digits.len())
+                && digits.chars().all(|c| matches!(c, '0'..='7')) =>
+        {
+            u32::from_str_radix(digits, 8).ok()
+        }
+        Some(digits) => {
+            return Err(InvalidHunkError {
+                message: format!(
+                    "Invalid file mode '{digits}' for path '{path}'; expected three or four octal digits, e.g. '(mode 755)'"
+                ),
+                line_number,
+            });
+        }
+    };
+    Ok((PathBuf::from(path), mode))
+}
+
 fn parse_update_file_chunk(
     lines: &[&str],
     line_number: usize,
@@ -376,6 +481,7 @@ fn parse_update_file_chunk(
         change_context,
         old_lines: Vec::new(),
         new_lines: Vec::new(),
+        removed_lines: Vec::new(),
         is_end_of_file: false,
     };
     let mut parsed_lines = 0;
@@ -407,6 +513,7 @@ fn parse_update_file_chunk(
                         chunk.new_lines.push(line_contents[1..].to_string());
                     }
                     Some('-') => {
+                        chunk.removed_lines.push(chunk.old_lines.len());
                         chunk.old_lines.push(line_contents[1..].to_string());
                     }
                     _ => {
@@ -486,7 +593,8 @@ fn test_parse_patch() {
         vec![
             AddFile {
                 path: PathBuf::from("path/add.py"),
-                contents: "abc\ndef\n".to_string()
+                contents: "abc\ndef\n".to_string(),
+                mode: None,
             },
             DeleteFile {
                 path: PathBuf::from("path/delete.py")
@@ -498,8 +606,10 @@ fn test_parse_patch() {
                     change_context: Some("def f():".to_string()),
                     old_lines: vec!["    pass".to_string()],
                     new_lines: vec!["    return 123".to_string()],
+                    removed_lines: vec![0],
                     is_end_of_file: false
-                }]
+                }],
+                mode: None,
             }
         ]
     );
@@ -525,12 +635,15 @@ fn test_parse_patch() {
                     change_context: None,
                     old_lines: vec![],
                     new_lines: vec!["line".to_string()],
+                    removed_lines: vec![],
                     is_end_of_file: false
                 }],
+                mode: None,
             },
             AddFile {
                 path: PathBuf::from("other.py"),
-                contents: "content\n".to_string()
+                contents: "content\n".to_string(),
+                mode: None,
             }
         ]
     );
@@ -555,12 +668,71 @@ fn test_parse_patch() {
                 change_context: None,
                 old_lines: vec!["import foo".to_string()],
                 new_lines: vec!["import foo".to_string(), "bar".to_string()],
+                removed_lines: vec![],
                 is_end_of_file: false,
             }],
+            mode: None,
         }]
     );
 }
 
+#[test]
+fn test_parse_file_modes_and_binary_files() {
+    assert_eq!(
+        parse_patch_text(
+            "*** Begin Patch\n\
+             *** Add File: run.sh (mode 755)\n\
+             +#!/bin/sh\n\
+             *** Update File: tool.py (mode 0644)\n\
+             *** Add Binary File: logo.png\n\
+             +iVBO\n\
+             +Rw==\n\
+             *** Replace Binary File: data.bin (mode 600)\n\
+             +AAE=\n\
+             *** End Patch",
+            ParseMode::Strict
+        )
+        .unwrap()
+        .hunks,
+        vec![
+            AddFile {
+                path: PathBuf::from("run.sh"),
+                contents: "#!/bin/sh\n".to_string(),
+                mode: Some(0o755),
+            },
+            UpdateFile {
+                path: PathBuf::from("tool.py"),
+                move_path: None,
+                chunks: Vec::new(),
+                mode: Some(0o644),
+            },
+            BinaryFile {
+                path: PathBuf::from("logo.png"),
+                contents: vec![0x89, 0x50, 0x4e, 0x47],
+                replace: false,
+                mode: None,
+            },
+            BinaryFile {
+                path: PathBuf::from("data.bin"),
+                contents: vec![0, 1],
+                replace: true,
+                mode: Some(0o600),
+            },
+        ]
+    );
+    assert_eq!(
+        parse_one_hunk(&["*** Add File: run.sh (mode 999)", "+x"], 7),
+        Err(InvalidHunkError {
+            message: "Invalid file mode '999' for path 'run.sh'; expected three or four octal digits, e.g. '(mode 755)'".to_string(),
+            line_number: 7
+        })
+    );
+    assert!(matches!(
+        parse_one_hunk(&["*** Add Binary File: a.bin", "+not base64!"], 3),
+        Err(InvalidHunkError { line_number: 3, .. })
+    ));
+}
+
 #[test]
 fn test_parse_patch_lenient() {
     let patch_text = r#"*** Begin Patch
@@ -575,8 +747,10 @@ fn test_parse_patch_lenient() {
             change_context: None,
             old_lines: vec!["import foo".to_string()],
             new_lines: vec!["import foo".to_string(), "bar".to_string()],
+            removed_lines: vec![],
             is_end_of_file: false,
         }],
+        mode: None,
     }];
     let expected_error =
         InvalidPatchError("The first line of the patch must be '*** Begin Patch'".to_string());
@@ -653,7 +827,8 @@ fn test_parse_one_hunk() {
         parse_one_hunk(&["bad"], 234),
         Err(InvalidHunkError {
             message: "'bad' is not a valid hunk header. \
-            Valid hunk headers: '*** Add File: {path}', '*** Delete File: {path}', '*** Update File: {path}'".to_string(),
+            Valid hunk headers: '*** Add File: {path}', '*** Delete File: {path}', '*** Update File: {path}', \
+            '*** Add Binary File: {path}', '*** Replace Binary File: {path}'".to_string(),
             line_number: 234
         })
     );
@@ -721,6 +896,7 @@ fn test_update_file_chunk() {
                     "add".to_string(),
                     "context2".to_string()
                 ],
+                removed_lines: vec![2],
                 is_end_of_file: false
             }),
             6
@@ -733,6 +909,7 @@ fn test_update_file_chunk() {
                 change_context: None,
                 old_lines: vec![],
                 new_lines: vec!["line".to_string()],
+                removed_lines: vec![],
                 is_end_of_file: true
             }),
             3
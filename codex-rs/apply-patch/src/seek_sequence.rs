@@ -1,22 +1,51 @@
+use similar::TextDiff;
+
 /// Attempt to find the sequence of `pattern` lines within `lines` beginning at or after `start`.
-/// Returns the starting index of the match or `None` if not found. Matches are attempted with
-/// decreasing strictness: exact match, then ignoring trailing whitespace, then ignoring leading
-/// and trailing whitespace. When `eof` is true, we first try starting at the end-of-file (so that
-/// patterns intended to match file endings are applied at the end), and fall back to searching
-/// from `start` if needed.
+/// Returns the starting index of the match or `None` if not found. This is [`seek_hunk`] for a
+/// pattern made only of context lines.
+pub(crate) fn seek_sequence(
+    lines: &[String],
+    pattern: &[String],
+    start: usize,
+    eof: bool,
+) -> Option<usize> {
+    seek_hunk(lines, pattern, &[], start, eof).map(|found| found.start)
+}
+
+/// Where [`seek_hunk`] found a pattern.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SequenceMatch {
+    pub start: usize,
+    /// The similarity of the matched lines to the pattern, between 0 and 1,
+    /// when only the tolerant passes of [`fuzzy_matches`] found them.
+    pub fuzzy_similarity: Option<f32>,
+}
+
+/// Attempt to find the sequence of `pattern` lines within `lines` beginning at or after `start`,
+/// where `removed` holds the indices of the pattern lines a hunk removes. Matches are attempted
+/// with decreasing strictness: exact match, then ignoring trailing whitespace, then ignoring
+/// leading and trailing whitespace, then normalising Unicode punctuation, and finally the
+/// tolerant matching of [`fuzzy_matches`], which is only used when it finds exactly one match.
+/// When `eof` is true, we first try starting at the end-of-file (so that patterns intended to
+/// match file endings are applied at the end), and fall back to searching from `start` if needed.
 ///
 /// Special cases handled defensively:
-///  • Empty `pattern` → returns `Some(start)` (no-op match)
-///  • `pattern.len() > lines.len()` → returns `None` (cannot match, avoids
+///  • Empty `pattern` → returns a match at `start` (no-op match)
+///  • `pattern.len() > lines.len()` → returns `None` (cannot match, avoids
 ///    out‑of‑bounds panic that occurred pre‑2025‑04‑12)
-pub(crate) fn seek_sequence(
+pub(crate) fn seek_hunk(
     lines: &[String],
     pattern: &[String],
+    removed: &[usize],
     start: usize,
     eof: bool,
-) -> Option<usize> {
+) -> Option<SequenceMatch> {
+    let exact = |start| SequenceMatch {
+        start,
+        fuzzy_similarity: None,
+    };
     if pattern.is_empty() {
-        return Some(start);
+        return Some(exact(start));
     }
 
     // When the pattern is longer than the available input there is no possible
@@ -34,7 +63,7 @@ pub(crate) fn seek_sequence(
     // Exact match first.
     for i in search_start..=lines.len().saturating_sub(pattern.len()) {
         if lines[i..i + pattern.len()] == *pattern {
-            return Some(i);
+            return Some(exact(i));
         }
     }
     // Then rstrip match.
@@ -47,7 +76,7 @@ pub(crate) fn seek_sequence(
             }
         }
         if ok {
-            return Some(i);
+            return Some(exact(i));
         }
     }
     // Finally, trim both sides to allow more lenience.
@@ -60,39 +89,15 @@ pub(crate) fn seek_sequence(
             }
         }
         if ok {
-            return Some(i);
+            return Some(exact(i));
         }
     }
 
-    // ------------------------------------------------------------------
-    // Final, most permissive pass – attempt to match after *normalising*
-    // common Unicode punctuation to their ASCII equivalents so that diffs
-    // authored with plain ASCII characters can still be applied to source
-    // files that contain typographic dashes / quotes, etc.  This mirrors the
-    // fuzzy behaviour of `git apply` which ignores minor byte-level
-    // differences when locating context lines.
-    // ------------------------------------------------------------------
-
-    fn normalise(s: &str) -> String {
-        s.trim()
-            .chars()
-            .map(|c| match c {
-                // Various dash / hyphen code-points → ASCII '-'
-                '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2015}'
-                | '\u{2212}' => '-',
-                // Fancy single quotes → '\''
-                '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => '\'',
-                // Fancy double quotes → '"'
-                '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => '"',
-                // Non-breaking space and other odd spaces → normal space
-                '\u{00A0}' | '\u{2002}' | '\u{2003}' | '\u{2004}' | '\u{2005}' | '\u{2006}'
-                | '\u{2007}' | '\u{2008}' | '\u{2009}' | '\u{200A}' | '\u{202F}' | '\u{205F}'
-                | '\u{3000}' => ' ',
-                other => other,
-            })
-            .collect::<String>()
-    }
-
+    // Normalise common Unicode punctuation to ASCII so that diffs authored
+    // with plain ASCII characters can still be applied to source files that
+    // contain typographic dashes / quotes, etc. This mirrors the fuzzy
+    // behaviour of `git apply`, which ignores minor byte-level differences
+    // when locating context lines.
     for i in search_start..=lines.len().saturating_sub(pattern.len()) {
         let mut ok = true;
         for (p_idx, pat) in pattern.iter().enumerate() {
@@ -102,15 +107,161 @@ pub(crate) fn seek_sequence(
             }
         }
         if ok {
-            return Some(i);
+            return Some(exact(i));
+        }
+    }
+
+    // Tolerant passes, which only accept a match that is unambiguous.
+    match fuzzy_matches(lines, pattern, removed, search_start)[..] {
+        [only] => Some(SequenceMatch {
+            start: only,
+            fuzzy_similarity: Some(similarity(&lines[only..only + pattern.len()], pattern)),
+        }),
+        _ => None,
+    }
+}
+
+/// Start indices at or after `start` where `pattern` matches `lines`
+/// approximately: first ignoring all differences in whitespace, including
+/// indentation and spacing within lines; failing that, allowing a small edit
+/// distance per line (about one edit per ten characters, and none for short
+/// lines). Lines are compared after Unicode punctuation normalisation. The
+/// edit distance is only allowed in context lines: the pattern lines whose
+/// indices are in `removed` must still match up to whitespace.
+pub(crate) fn fuzzy_matches(
+    lines: &[String],
+    pattern: &[String],
+    removed: &[usize],
+    start: usize,
+) -> Vec<usize> {
+    if pattern.is_empty() || pattern.len() > lines.len() {
+        return Vec::new();
+    }
+    let lines: Vec<String> = lines.iter().map(|line| fuzzy_key(line)).collect();
+    let pattern: Vec<String> = pattern.iter().map(|pat| fuzzy_key(pat)).collect();
+    let windows = start..=lines.len() - pattern.len();
+
+    let whitespace_insensitive: Vec<usize> = windows
+        .clone()
+        .filter(|&i| lines[i..i + pattern.len()] == pattern[..])
+        .collect();
+    if !whitespace_insensitive.is_empty() {
+        return whitespace_insensitive;
+    }
+
+    windows
+        .filter(|&i| {
+            lines[i..i + pattern.len()]
+                .iter()
+                .zip(&pattern)
+                .enumerate()
+                .all(|(p_idx, (line, pat))| {
+                    let max_edits = if removed.contains(&p_idx) {
+                        0
+                    } else {
+                        pat.chars().count() / 10
+                    };
+                    within_edit_distance(line, pat, max_edits)
+                })
+        })
+        .collect()
+}
+
+/// Find the window of `lines` that most resembles `pattern`, for reporting
+/// where a hunk that failed to match was probably meant to apply. Returns the
+/// start of the window and its similarity between 0 and 1. Lines are compared
+/// as in [`fuzzy_matches`], and a window must be at least half similar to be
+/// returned.
+pub(crate) fn closest_sequence(lines: &[String], pattern: &[String]) -> Option<(usize, f32)> {
+    if lines.is_empty() || pattern.is_empty() {
+        return None;
+    }
+    let window = pattern.len().min(lines.len());
+    let lines: Vec<String> = lines.iter().map(|line| fuzzy_key(line)).collect();
+    let pattern: Vec<String> = pattern.iter().map(|pat| fuzzy_key(pat)).collect();
+    let mut best: Option<(usize, f32)> = None;
+    for i in 0..=lines.len() - window {
+        let score: f32 = lines[i..i + window]
+            .iter()
+            .zip(&pattern)
+            .map(|(line, pat)| TextDiff::from_chars(line.as_str(), pat.as_str()).ratio())
+            .sum();
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((i, score));
         }
     }
+    best.map(|(i, score)| (i, score / pattern.len() as f32))
+        .filter(|(_, similarity)| *similarity >= 0.5)
+}
 
-    None
+/// The mean similarity, between 0 and 1, of `lines` to the `pattern` lines
+/// they were matched with.
+fn similarity(lines: &[String], pattern: &[String]) -> f32 {
+    let total: f32 = lines
+        .iter()
+        .zip(pattern)
+        .map(|(line, pat)| TextDiff::from_chars(line.as_str(), pat.as_str()).ratio())
+        .sum();
+    total / pattern.len() as f32
+}
+
+fn normalise(s: &str) -> String {
+    s.trim()
+        .chars()
+        .map(|c| match c {
+            // Various dash / hyphen code-points → ASCII '-'
+            '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2015}'
+            | '\u{2212}' => '-',
+            // Fancy single quotes → '\''
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => '\'',
+            // Fancy double quotes → '"'
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => '"',
+            // Non-breaking space and other odd spaces → normal space
+            '\u{00A0}' | '\u{2002}' | '\u{2003}' | '\u{2004}' | '\u{2005}' | '\u{2006}'
+            | '\u{2007}' | '\u{2008}' | '\u{2009}' | '\u{200A}' | '\u{202F}' | '\u{205F}'
+            | '\u{3000}' => ' ',
+            other => other,
+        })
+        .collect::<String>()
+}
+
+/// `s` with Unicode punctuation normalised and every run of whitespace
+/// collapsed to a single space.
+fn fuzzy_key(s: &str) -> String {
+    normalise(s)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether `a` can be turned into `b` with at most `max_edits` single
+/// character insertions, deletions or substitutions.
+fn within_edit_distance(a: &str, b: &str, max_edits: usize) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max_edits {
+        return false;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        if current.iter().min().is_some_and(|&min| min > max_edits) {
+            return false;
+        }
+        previous = current;
+    }
+    previous[b.len()] <= max_edits
 }
 
 #[cfg(test)]
 mod tests {
+    use super::closest_sequence;
+    use super::fuzzy_matches;
+    use super::seek_hunk;
     use super::seek_sequence;
 
     fn to_vec(strings: &[&str]) -> Vec<String> {
@@ -147,4 +298,94 @@ mod tests {
         // Should not panic – must return None when pattern cannot possibly fit.
         assert_eq!(seek_sequence(&lines, &pattern, 0, false), None);
     }
+
+    #[test]
+    fn test_closest_sequence_finds_most_similar_window() {
+        let lines = to_vec(&[
+            "fn main() {",
+            "    let x = 1;",
+            "    println!(\"{x}\");",
+            "}",
+        ]);
+        let pattern = to_vec(&["    let x = 2;", "    println!(\"{x}\");"]);
+        let (start, similarity) = closest_sequence(&lines, &pattern).expect("similar window");
+        assert_eq!(start, 1);
+        assert!(similarity > 0.9 && similarity < 1.0, "{similarity}");
+
+        let unrelated = to_vec(&["zzzzzzzzzzzz"]);
+        assert_eq!(closest_sequence(&lines, &unrelated), None);
+    }
+
+    #[test]
+    fn test_fuzzy_match_ignores_indentation_and_unicode_punctuation() {
+        let lines = to_vec(&[
+            "fn main() {",
+            "\tif  ready {",
+            "\t\tlog(\u{201C}done\u{201D});",
+            "\t}",
+            "}",
+        ]);
+        let pattern = to_vec(&["    if ready {", "        log(\"done\");", "    }"]);
+        assert_eq!(seek_sequence(&lines, &pattern, 0, false), Some(1));
+    }
+
+    #[test]
+    fn test_fuzzy_match_allows_small_edits_in_long_lines() {
+        let lines = to_vec(&[
+            "let configuration = load_configuration(&path)?;",
+            "short();",
+        ]);
+        let typo = to_vec(&["let configuration = load_configuraton(&path)?;"]);
+        assert_eq!(seek_sequence(&lines, &typo, 0, false), Some(0));
+
+        // Short lines must still match exactly.
+        let short = to_vec(&["shrt();"]);
+        assert_eq!(seek_sequence(&lines, &short, 0, false), None);
+    }
+
+    #[test]
+    fn test_ambiguous_fuzzy_match_is_rejected() {
+        let lines = to_vec(&["\treset(state,  1);", "", "  reset(state, 1);"]);
+        let pattern = to_vec(&["reset(state,   1);"]);
+        assert_eq!(seek_sequence(&lines, &pattern, 0, false), None);
+        assert_eq!(fuzzy_matches(&lines, &pattern, &[0], 0), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_fuzzy_match_reports_similarity() {
+        let lines = to_vec(&[
+            "let configuration = load_configuration(&path)?;",
+            "let retries = compute_retry_budget(&settings)?;",
+        ]);
+        let pattern = to_vec(&[
+            "let configuration = load_configuraton(&path)?;",
+            "let retries = compute_retry_budget(&settings)?;",
+        ]);
+        let found = seek_hunk(&lines, &pattern, &[1], 0, false).expect("fuzzy match");
+        assert_eq!(found.start, 0);
+        let similarity = found.fuzzy_similarity.expect("similarity");
+        assert!(similarity > 0.95 && similarity < 1.0, "{similarity}");
+
+        let exact = seek_hunk(&lines, &lines, &[1], 0, false).expect("exact match");
+        assert_eq!(exact.fuzzy_similarity, None);
+    }
+
+    #[test]
+    fn test_near_miss_removed_line_does_not_fuzzy_match() {
+        let lines = to_vec(&[
+            "let configuration = load_configuration(&path)?;",
+            "let retries = compute_retry_budget(&settings)?;",
+        ]);
+        // The removed line differs by one character, which is tolerated in
+        // context lines only.
+        let pattern = to_vec(&[
+            "let configuration = load_configuration(&path)?;",
+            "let retries = compute_retry_budgets(&settings)?;",
+        ]);
+        assert_eq!(seek_hunk(&lines, &pattern, &[1], 0, false), None);
+        assert_eq!(
+            seek_hunk(&lines, &pattern, &[], 0, false).map(|found| found.start),
+            Some(0)
+        );
+    }
 }
@@ -0,0 +1,334 @@
+//! All-or-nothing application of a patch's file changes.
+//!
+//! Every change is first computed in memory by [`PendingChanges`], so a hunk
+//! that fails to match aborts the patch before anything is written. Only then
+//! are the changes committed: each file is written to a temporary sibling and
+//! renamed into place, and if any write, rename or delete fails, the files
+//! already changed are restored from the originals read just before they were
+//! touched.
+
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use anyhow::Context;
+
+enum FileOp {
+    Write {
+        path: PathBuf,
+        contents: Vec<u8>,
+        /// Permission bits to give the file; if unset, an existing file keeps
+        /// its permissions.
+        mode: Option<u32>,
+    },
+    Delete {
+        path: PathBuf,
+    },
+}
+
+/// File changes that have been verified but not yet written.
+#[derive(Default)]
+pub(crate) struct PendingChanges {
+    ops: Vec<FileOp>,
+    /// Contents each touched path will have once the changes are committed;
+    /// `None` for deleted paths. Later hunks read through this so that they
+    /// see the effect of earlier hunks in the same patch.
+    overlay: HashMap<PathBuf, Option<Vec<u8>>>,
+    /// Modes set by queued writes, kept for later writes to the same path.
+    modes: HashMap<PathBuf, u32>,
+}
+
+impl PendingChanges {
+    /// Contents of `path` as of the changes queued so far.
+    pub(crate) fn read(&self, path: &Path) -> std::io::Result<String> {
+        String::from_utf8(self.read_bytes(path)?)
+            .map_err(|err| std::io::Error::new(ErrorKind::InvalidData, err))
+    }
+
+    pub(crate) fn read_bytes(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        match self.overlay.get(path) {
+            Some(Some(contents)) => Ok(contents.clone()),
+            Some(None) => Err(deleted_earlier(path)),
+            None => std::fs::read(path),
+        }
+    }
+
+    pub(crate) fn write(&mut self, path: &Path, contents: Vec<u8>, mode: Option<u32>) {
+        let mode = match mode {
+            Some(mode) => {
+                self.modes.insert(path.to_path_buf(), mode);
+                Some(mode)
+            }
+            None => self.modes.get(path).copied(),
+        };
+        self.overlay
+            .insert(path.to_path_buf(), Some(contents.clone()));
+        self.ops.push(FileOp::Write {
+            path: path.to_path_buf(),
+            contents,
+            mode,
+        });
+    }
+
+    pub(crate) fn delete(&mut self, path: &Path) -> std::io::Result<()> {
+        match self.overlay.get(path) {
+            Some(Some(_)) => {}
+            Some(None) => return Err(deleted_earlier(path)),
+            None => {
+                if std::fs::metadata(path)?.is_dir() {
+                    return Err(std::io::Error::other(format!(
+                        "{} is a directory",
+                        path.display()
+                    )));
+                }
+            }
+        }
+        self.overlay.insert(path.to_path_buf(), None);
+        self.modes.remove(path);
+        self.ops.push(FileOp::Delete {
+            path: path.to_path_buf(),
+        });
+        Ok(())
+    }
+
+    /// Write the queued changes to disk, undoing all of them if any fails.
+    pub(crate) fn commit(self) -> anyhow::Result<()> {
+        let mut journal = Journal::default();
+        for op in &self.ops {
+            if let Err(err) = journal.apply(op) {
+                let failures = journal.rollback();
+                if failures.is_empty() {
+                    return Err(err);
+                }
+                anyhow::bail!(
+                    "{err}\nRolling back the patch also failed; these files may be left modified:\n{}",
+                    failures.join("\n")
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn deleted_earlier(path: &Path) -> std::io::Error {
+    std::io::Error::new(
+        ErrorKind::NotFound,
+        format!("{} is deleted earlier in this patch", path.display()),
+    )
+}
+
+enum Undo {
+    /// Put back the original contents and permissions of a file that was
+    /// overwritten or deleted.
+    Restore {
+        path: PathBuf,
+        contents: Vec<u8>,
+        permissions: std::fs::Permissions,
+    },
+    /// Remove a file that did not exist before the patch.
+    Remove(PathBuf),
+}
+
+/// What has been changed on disk so far, and how to undo it.
+#[derive(Default)]
+struct Journal {
+    undo: Vec<Undo>,
+    created_dirs: Vec<PathBuf>,
+}
+
+impl Journal {
+    fn apply(&mut self, op: &FileOp) -> anyhow::Result<()> {
+        match op {
+            FileOp::Write {
+                path,
+                contents,
+                mode,
+            } => self.write(path, contents, *mode),
+            FileOp::Delete { path } => {
+                let delete_context = || format!("Failed to delete file {}", path.display());
+                let original = std::fs::read(path).with_context(delete_context)?;
+                let permissions = std::fs::metadata(path)
+                    .with_context(delete_context)?
+                    .permissions();
+                std::fs::remove_file(path).with_context(delete_context)?;
+                self.undo.push(Undo::Restore {
+                    path: path.clone(),
+                    contents: original,
+                    permissions,
+                });
+                Ok(())
+            }
+        }
+    }
+
+    fn write(&mut self, path: &Path, contents: &[u8], mode: Option<u32>) -> anyhow::Result<()> {
+        let write_context = || format!("Failed to write file {}", path.display());
+        // Write through symlinks rather than replacing them.
+        let target = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let original = match std::fs::read(&target) {
+            Ok(original) => {
+                // Renaming over a file the user cannot write would succeed,
+                // so check that it is writable first.
+                std::fs::OpenOptions::new()
+                    .write(true)
+                    .open(&target)
+                    .with_context(write_context)?;
+                let permissions = std::fs::metadata(&target)
+                    .with_context(write_context)?
+                    .permissions();
+                Some((original, permissions))
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => None,
+            Err(err) => return Err(err).with_context(write_context),
+        };
+        if original.is_none() {
+            self.create_parent_dirs(&target)?;
+        }
+
+        let temp = temp_path(&target);
+        let written = write_new(&temp, contents).and_then(|()| {
+            match (mode, &original) {
+                (Some(mode), _) => set_mode(&temp, mode)?,
+                (None, Some((_, permissions))) => {
+                    std::fs::set_permissions(&temp, permissions.clone())?
+                }
+                (None, None) => {}
+            }
+            std::fs::rename(&temp, &target)
+        });
+        if let Err(err) = written {
+            let _ = std::fs::remove_file(&temp);
+            return Err(err).with_context(write_context);
+        }
+
+        self.undo.push(match original {
+            Some((contents, permissions)) => Undo::Restore {
+                path: target,
+                contents,
+                permissions,
+            },
+            None => Undo::Remove(target),
+        });
+        Ok(())
+    }
+
+    /// Create the missing ancestors of `path`, remembering them so that
+    /// rollback can remove them again.
+    fn create_parent_dirs(&mut self, path: &Path) -> anyhow::Result<()> {
+        let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) else {
+            return Ok(());
+        };
+        let missing: Vec<PathBuf> = parent
+            .ancestors()
+            .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+            .map(Path::to_path_buf)
+            .collect();
+        // Outermost first, so that rollback can remove them innermost first.
+        // Recorded up front so that a partial failure is undone as well.
+        self.created_dirs.extend(missing.into_iter().rev());
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!("Failed to create parent directories for {}", path.display())
+        })?;
+        Ok(())
+    }
+
+    /// Undo every change made so far, returning a description of each file
+    /// that could not be restored.
+    fn rollback(self) -> Vec<String> {
+        let mut failures = Vec::new();
+        for undo in self.undo.into_iter().rev() {
+            let (path, result) = match undo {
+                Undo::Restore {
+                    path,
+                    contents,
+                    permissions,
+                } => {
+                    let result = restore(&path, &contents, permissions);
+                    (path, result)
+                }
+                Undo::Remove(path) => {
+                    let result = std::fs::remove_file(&path);
+                    (path, result)
+                }
+            };
+            if let Err(err) = result {
+                failures.push(format!("{}: {err}", path.display()));
+            }
+        }
+        for dir in self.created_dirs.iter().rev() {
+            // Only empty directories are removed; anything else was not ours.
+            let _ = std::fs::remove_dir(dir);
+        }
+        failures
+    }
+}
+
+/// Put back a file's original contents and permissions. Done through a
+/// temporary file, like the write it undoes, so that a mode the patch set
+/// cannot get in the way.
+fn restore(path: &Path, contents: &[u8], permissions: std::fs::Permissions) -> std::io::Result<()> {
+    let temp = temp_path(path);
+    let restored = write_new(&temp, contents)
+        .and_then(|()| std::fs::set_permissions(&temp, permissions))
+        .and_then(|()| std::fs::rename(&temp, path));
+    if restored.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    restored
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+}
+
+/// File modes only carry meaning on Unix; elsewhere they are ignored.
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// A temporary file next to `path`, so that renaming it into place does not
+/// cross filesystems. The counter keeps concurrent patches in one process,
+/// e.g. from subagents, from sharing a name.
+fn temp_path(path: &Path) -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(
+        ".{name}.{}.{n}.apply_patch.tmp",
+        std::process::id()
+    ))
+}
+
+/// Write `contents` to a file that must not exist yet, so a leftover or
+/// planted file at `path` is never written through.
+fn write_new(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?
+        .write_all(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temp_paths_are_unique_within_a_process() {
+        let path = Path::new("/work/src/lib.rs");
+        let first = temp_path(path);
+        let second = temp_path(path);
+        assert_ne!(first, second);
+        assert_eq!(first.parent(), path.parent());
+    }
+}
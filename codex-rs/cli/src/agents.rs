@@ -39,19 +39,14 @@ enum AgentsCommand {
     Show(AgentsShowCommand),
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
+#[derive(Debug, Clone, Copy, ValueEnum, Default)]
 enum ScopeFilter {
     Project,
     User,
+    #[default]
     All,
 }
 
-impl Default for ScopeFilter {
-    fn default() -> Self {
-        ScopeFilter::All
-    }
-}
-
 #[derive(Debug, Parser)]
 struct AgentsListCommand {
     /// Filter agents by scope.
@@ -165,10 +160,10 @@ async fn run_run(
                 if let Some(model) = response.resolved_model.as_ref() {
                     println!("Model: {model}");
                 }
-                if !response.requested_tools.is_empty() {
-                    if let Some(first_artifact) = response.detail_artifacts.first() {
-                        println!("Detail: {}", first_artifact.display());
-                    }
+                if !response.requested_tools.is_empty()
+                    && let Some(first_artifact) = response.detail_artifacts.first()
+                {
+                    println!("Detail: {}", first_artifact.display());
                 }
                 if let Some(summary) = response.summary.as_ref() {
                     println!("Summary: {summary}");
@@ -216,7 +211,7 @@ async fn run_show(
             )?;
             println!();
         } else {
-            eprintln!("{}", err);
+            eprintln!("{err}");
         }
         return Err(err);
     };
@@ -0,0 +1,327 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::bail;
+use clap::Parser;
+use clap::Subcommand;
+use codex_common::CliConfigOverrides;
+use codex_core::config::CONFIG_TOML_FILE;
+use codex_core::config::find_codex_home;
+use codex_core::config_edit::edit_config_value;
+use codex_core::config_edit::parse_config_key;
+use codex_core::config_edit::parse_config_value;
+use codex_core::config_layers::ConfigEntry;
+use codex_core::config_layers::ConfigLayers;
+use codex_core::config_layers::ConfigOrigin;
+use codex_core::config_layers::project_config_path;
+use codex_core::config_schema::ConfigDiagnostic;
+use codex_core::config_schema::DiagnosticSeverity;
+use codex_core::config_schema::config_schema;
+use codex_core::config_schema::validate_config;
+use codex_core::config_schema::validate_project_config;
+use toml::Value as TomlValue;
+
+/// Entry point for the `codex config` command family.
+#[derive(Debug, Parser)]
+pub struct ConfigCli {
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[clap(subcommand)]
+    command: ConfigCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCommand {
+    /// Print the settings in effect, merged from all configuration layers.
+    Show(ConfigShowCommand),
+
+    /// Print the value in effect for a key, or every value under a table.
+    Get(ConfigGetCommand),
+
+    /// Set a key in the user config, keeping the rest of the file as written.
+    Set(ConfigSetCommand),
+
+    /// Remove a key from the user config.
+    Unset(ConfigUnsetCommand),
+
+    /// List every setting in effect as `key = value` lines.
+    List(ConfigListCommand),
+
+    /// Check the user and project config files for errors and unknown keys.
+    Validate,
+
+    /// Open the user config in $VISUAL or $EDITOR, then validate it.
+    Edit,
+
+    /// Print the JSON Schema of config.toml, for editor completion.
+    Schema,
+}
+
+#[derive(Debug, Parser)]
+struct ConfigShowCommand {
+    /// Annotate each setting with the layer it came from.
+    #[arg(long = "origin")]
+    origin: bool,
+}
+
+#[derive(Debug, Parser)]
+struct ConfigGetCommand {
+    /// Dotted key, e.g. `model` or `mcp_servers.docs.command`.
+    key: String,
+
+    /// Annotate each setting with the layer it came from.
+    #[arg(long = "origin")]
+    origin: bool,
+}
+
+#[derive(Debug, Parser)]
+struct ConfigSetCommand {
+    /// Dotted key, e.g. `model` or `mcp_servers.docs.command`.
+    key: String,
+
+    /// Value, parsed as TOML (`true`, `42`, `["a", "b"]`) when the key takes
+    /// such a value and otherwise stored as a string.
+    value: String,
+}
+
+#[derive(Debug, Parser)]
+struct ConfigUnsetCommand {
+    /// Dotted key, e.g. `model` or `mcp_servers.docs`.
+    key: String,
+}
+
+#[derive(Debug, Parser)]
+struct ConfigListCommand {
+    /// Annotate each setting with the layer it came from.
+    #[arg(long = "origin")]
+    origin: bool,
+}
+
+pub async fn run_config_cli(cli: ConfigCli, cwd_override: Option<PathBuf>) -> anyhow::Result<()> {
+    let overrides = cli
+        .config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let cwd = match cwd_override {
+        Some(cwd) => std::path::absolute(cwd)?,
+        None => std::env::current_dir()?,
+    };
+    let codex_home = find_codex_home().context("failed to locate the Codex home directory")?;
+    let load_layers = || ConfigLayers::load(&codex_home, &cwd, overrides.clone());
+
+    match cli.command {
+        ConfigCommand::Show(cmd) => run_show(cmd, &load_layers()?),
+        ConfigCommand::Get(cmd) => run_get(cmd, &load_layers()?),
+        ConfigCommand::Set(cmd) => run_set(cmd, &codex_home, &load_layers()?).await,
+        ConfigCommand::Unset(cmd) => run_unset(cmd, &codex_home).await,
+        ConfigCommand::List(cmd) => {
+            let layers = load_layers()?;
+            report_ignored_layers(&layers);
+            print_entries(&layers.entries(), cmd.origin);
+            Ok(())
+        }
+        ConfigCommand::Validate => run_validate(&codex_home, &cwd),
+        ConfigCommand::Edit => run_edit(&codex_home),
+        ConfigCommand::Schema => {
+            println!("{}", serde_json::to_string_pretty(&config_schema())?);
+            Ok(())
+        }
+    }
+}
+
+fn run_show(cmd: ConfigShowCommand, layers: &ConfigLayers) -> anyhow::Result<()> {
+    report_ignored_layers(layers);
+
+    if !cmd.origin {
+        print!("{}", toml::to_string_pretty(&layers.merged())?);
+        return Ok(());
+    }
+
+    let entries = layers.entries();
+    if entries.is_empty() {
+        println!("No settings configured; built-in defaults apply.");
+        return Ok(());
+    }
+    print_entries(&entries, true);
+    Ok(())
+}
+
+fn run_get(cmd: ConfigGetCommand, layers: &ConfigLayers) -> anyhow::Result<()> {
+    let path = parse_config_key(&cmd.key)?;
+    let entries: Vec<ConfigEntry> = layers
+        .entries()
+        .into_iter()
+        .filter(|entry| entry.path.starts_with(&path))
+        .collect();
+
+    match entries.as_slice() {
+        [] => bail!("`{}` is not set; the built-in default applies.", cmd.key),
+        [entry] if entry.path == path => {
+            // Print strings bare so the output can be used in scripts.
+            let value = match &entry.value {
+                TomlValue::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            if cmd.origin {
+                println!("{value}  # {}", entry.origin);
+            } else {
+                println!("{value}");
+            }
+        }
+        entries => print_entries(entries, cmd.origin),
+    }
+    Ok(())
+}
+
+async fn run_set(
+    cmd: ConfigSetCommand,
+    codex_home: &Path,
+    layers: &ConfigLayers,
+) -> anyhow::Result<()> {
+    let path = parse_config_key(&cmd.key)?;
+    let value = parse_config_value(&path, &cmd.value);
+    let config_path = codex_home.join(CONFIG_TOML_FILE);
+    edit_config_value(codex_home, &path, Some(value.clone()))
+        .await
+        .with_context(|| format!("not saving `{}` to {}", cmd.key, config_path.display()))?;
+    println!(
+        "Set `{}` = {} in {}",
+        cmd.key,
+        value.to_string().trim(),
+        config_path.display()
+    );
+
+    // Point out when another layer will keep winning over the new value.
+    if let Some(entry) = layers.entries().into_iter().find(|entry| {
+        entry.path.starts_with(&path) && !matches!(entry.origin, ConfigOrigin::User(_))
+    }) {
+        eprintln!(
+            "Note: `{}` is also set by {}, which takes precedence.",
+            entry.key, entry.origin
+        );
+    }
+    Ok(())
+}
+
+async fn run_unset(cmd: ConfigUnsetCommand, codex_home: &Path) -> anyhow::Result<()> {
+    let path = parse_config_key(&cmd.key)?;
+    let config_path = codex_home.join(CONFIG_TOML_FILE);
+    if edit_config_value(codex_home, &path, None).await? {
+        println!("Removed `{}` from {}", cmd.key, config_path.display());
+    } else {
+        println!("`{}` is not set in {}", cmd.key, config_path.display());
+    }
+    Ok(())
+}
+
+fn run_validate(codex_home: &Path, cwd: &Path) -> anyhow::Result<()> {
+    let mut errors = 0;
+    let mut checked = 0;
+    let files = [
+        (codex_home.join(CONFIG_TOML_FILE), false),
+        (project_config_path(cwd), true),
+    ];
+    for (path, is_project) in files {
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", path.display()));
+            }
+        };
+        checked += 1;
+        let diagnostics = if is_project {
+            validate_project_config(&contents)
+        } else {
+            validate_config(&contents)
+        };
+        errors += report_diagnostics(&path, diagnostics);
+    }
+
+    if checked == 0 {
+        println!("No config files found; built-in defaults apply.");
+    }
+    if errors > 0 {
+        bail!("found {errors} error(s); Codex will not start until they are fixed.");
+    }
+    Ok(())
+}
+
+fn run_edit(codex_home: &Path) -> anyhow::Result<()> {
+    let config_path = codex_home.join(CONFIG_TOML_FILE);
+    if !config_path.exists() {
+        std::fs::create_dir_all(codex_home)?;
+        std::fs::write(&config_path, "")?;
+    }
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let Some(program) = parts.next() else {
+        bail!("$VISUAL or $EDITOR is set but empty");
+    };
+    let status = std::process::Command::new(program)
+        .args(parts)
+        .arg(&config_path)
+        .status()
+        .with_context(|| format!("failed to run editor `{editor}`"))?;
+    if !status.success() {
+        bail!("editor `{editor}` exited with {status}");
+    }
+
+    let contents = std::fs::read_to_string(&config_path)?;
+    let errors = report_diagnostics(&config_path, validate_config(&contents));
+    if errors > 0 {
+        bail!("found {errors} error(s); run `codex config edit` again to fix them.");
+    }
+    Ok(())
+}
+
+/// Print the diagnostics for one file and return how many are errors.
+fn report_diagnostics(path: &Path, diagnostics: Vec<ConfigDiagnostic>) -> usize {
+    if diagnostics.is_empty() {
+        println!("{}: ok", path.display());
+    }
+    for diagnostic in &diagnostics {
+        println!("{}:{diagnostic}", path.display());
+    }
+    diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+        .count()
+}
+
+fn report_ignored_layers(layers: &ConfigLayers) {
+    if let Some(path) = &layers.untrusted_project_config {
+        eprintln!("Ignoring {}: this project is not trusted.", path.display());
+    }
+    if let Some(path) = layers.project_config() {
+        for key in &layers.ignored_project_keys {
+            eprintln!(
+                "Ignoring `{key}` in {}: projects may not set it.",
+                path.display()
+            );
+        }
+    }
+}
+
+fn print_entries(entries: &[ConfigEntry], origin: bool) {
+    if !origin {
+        for entry in entries {
+            println!("{} = {}", entry.key, entry.value);
+        }
+        return;
+    }
+    let width = entries
+        .iter()
+        .map(|entry| entry.key.len() + entry.value.to_string().len() + 3)
+        .max()
+        .unwrap_or(0);
+    for entry in entries {
+        let setting = format!("{} = {}", entry.key, entry.value);
+        println!("{setting:<width$}  # {}", entry.origin);
+    }
+}
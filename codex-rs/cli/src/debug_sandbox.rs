@@ -7,11 +7,11 @@ use codex_core::exec_env::create_env;
 use codex_core::landlock::spawn_command_under_linux_sandbox;
 use codex_core::seatbelt::spawn_command_under_seatbelt;
 use codex_core::spawn::StdioPolicy;
+use codex_exec::exit_status::handle_exit_status;
 use codex_protocol::config_types::SandboxMode;
 
 use crate::LandlockCommand;
 use crate::SeatbeltCommand;
-use crate::exit_status::handle_exit_status;
 
 pub async fn run_command_under_seatbelt(
     command: SeatbeltCommand,
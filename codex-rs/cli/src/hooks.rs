@@ -360,11 +360,11 @@ fn render_list(snapshot: &HookRegistrySnapshot) {
 
     rows.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.2.cmp(&b.2)));
 
-    println!("{:<15} {:<8} {}", "Event", "Scope", "ID");
+    println!("{:<15} {:<8} ID", "Event", "Scope");
     for (event, scope, id, command) in rows {
-        println!("{:<15} {:<8} {}", event, scope, id);
+        println!("{event:<15} {scope:<8} {id}");
         if !command.is_empty() {
-            println!("    Command: {}", command);
+            println!("    Command: {command}");
         }
     }
 }
@@ -390,7 +390,7 @@ fn render_exec_log(records: &[HookExecutionRecord]) {
             format_core_outcome(&record.decision.decision),
         );
         if let Some(message) = &record.decision.message {
-            println!("    Message: {}", message);
+            println!("    Message: {message}");
         }
     }
 }
@@ -518,23 +518,23 @@ fn format_core_scope(scope: &HookScope) -> String {
 }
 
 fn format_proto_skip_reason(reason: &ProtoHookSkipReason) -> String {
-    format!("{:?}", reason)
+    format!("{reason:?}")
 }
 
 fn format_core_skip_reason(reason: &CoreHookSkipReason) -> String {
-    format!("{:?}", reason)
+    format!("{reason:?}")
 }
 
 fn format_proto_event(event: &ProtoHookEvent) -> String {
-    format!("{:?}", event)
+    format!("{event:?}")
 }
 
 fn format_core_event(event: &CoreHookEvent) -> String {
-    format!("{:?}", event)
+    format!("{event:?}")
 }
 
 fn format_core_outcome(outcome: &codex_core::hooks::HookOutcome) -> String {
-    format!("{:?}", outcome)
+    format!("{outcome:?}")
 }
 
 fn status_label(status: &HookValidationStatus) -> &'static str {
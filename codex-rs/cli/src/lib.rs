@@ -1,5 +1,4 @@
 pub mod debug_sandbox;
-mod exit_status;
 pub mod login;
 pub mod proto;
 
@@ -18,12 +18,18 @@ use codex_tui::Cli as TuiCli;
 use std::path::PathBuf;
 
 mod agents;
+mod config_cmd;
 mod hooks;
 mod mcp_cmd;
+mod review;
+mod sessions;
 
 use crate::agents::AgentsCli;
+use crate::config_cmd::ConfigCli;
 use crate::mcp_cmd::McpCli;
 use crate::proto::ProtoCli;
+use crate::review::ReviewCommand;
+use crate::sessions::SessionsCli;
 
 /// Codex CLI
 ///
@@ -91,6 +97,15 @@ enum Subcommand {
 
     /// Manage subagents (list, run, show).
     Agents(AgentsCli),
+
+    /// Review local changes and report prioritized findings; exits with status 4 on P0/P1 findings.
+    Review(ReviewCommand),
+
+    /// List, search, export and clean up recorded sessions.
+    Sessions(SessionsCli),
+
+    /// Inspect the configuration in effect and where each setting comes from.
+    Config(ConfigCli),
 }
 
 #[derive(Debug, Parser)]
@@ -111,6 +126,11 @@ struct ResumeCommand {
     #[arg(long = "last", default_value_t = false, conflicts_with = "session_id")]
     last: bool,
 
+    /// Instead of continuing the session, start a new one that branches off
+    /// after this user turn (numbered from 1). The original is left untouched.
+    #[arg(long = "fork-at", value_name = "TURN")]
+    fork_at: Option<usize>,
+
     #[clap(flatten)]
     config_overrides: TuiCli,
 }
@@ -205,6 +225,7 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
         Some(Subcommand::Resume(ResumeCommand {
             session_id,
             last,
+            fork_at,
             config_overrides,
         })) => {
             interactive = finalize_resume_interactive(
@@ -212,6 +233,7 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                 root_config_overrides.clone(),
                 session_id,
                 last,
+                fork_at,
                 config_overrides,
             );
             codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
@@ -296,6 +318,30 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             agents::run_agents_cli(agents_cli, cwd_override).await?;
         }
+        Some(Subcommand::Review(mut review_cli)) => {
+            let cwd_override = interactive.cwd.clone();
+            prepend_config_flags(
+                &mut review_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            review::run_review_command(review_cli, cwd_override, codex_linux_sandbox_exe).await?;
+        }
+        Some(Subcommand::Sessions(mut sessions_cli)) => {
+            let cwd_override = interactive.cwd.clone();
+            prepend_config_flags(
+                &mut sessions_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            sessions::run_sessions_cli(sessions_cli, cwd_override).await?;
+        }
+        Some(Subcommand::Config(mut config_cli)) => {
+            let cwd_override = interactive.cwd.clone();
+            prepend_config_flags(
+                &mut config_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            config_cmd::run_config_cli(config_cli, cwd_override).await?;
+        }
     }
 
     Ok(())
@@ -318,6 +364,7 @@ fn finalize_resume_interactive(
     root_config_overrides: CliConfigOverrides,
     session_id: Option<String>,
     last: bool,
+    fork_at: Option<usize>,
     resume_cli: TuiCli,
 ) -> TuiCli {
     // Start with the parsed interactive CLI so resume shares the same
@@ -326,6 +373,7 @@ fn finalize_resume_interactive(
     interactive.resume_picker = resume_session_id.is_none() && !last;
     interactive.resume_last = last;
     interactive.resume_session_id = resume_session_id;
+    interactive.resume_fork_at = fork_at;
 
     // Merge resume-scoped flags and overrides with highest precedence.
     merge_resume_cli_flags(&mut interactive, resume_cli);
@@ -367,6 +415,12 @@ fn merge_resume_cli_flags(interactive: &mut TuiCli, resume_cli: TuiCli) {
     if let Some(cwd) = resume_cli.cwd {
         interactive.cwd = Some(cwd);
     }
+    if let Some(record) = resume_cli.record {
+        interactive.record = Some(record);
+    }
+    if let Some(replay) = resume_cli.replay {
+        interactive.replay = Some(replay);
+    }
     if resume_cli.web_search {
         interactive.web_search = true;
     }
@@ -404,13 +458,21 @@ mod tests {
         let Subcommand::Resume(ResumeCommand {
             session_id,
             last,
+            fork_at,
             config_overrides: resume_cli,
         }) = subcommand.expect("resume present")
         else {
             unreachable!()
         };
 
-        finalize_resume_interactive(interactive, root_overrides, session_id, last, resume_cli)
+        finalize_resume_interactive(
+            interactive,
+            root_overrides,
+            session_id,
+            last,
+            fork_at,
+            resume_cli,
+        )
     }
 
     #[test]
@@ -447,6 +509,15 @@ mod tests {
         assert_eq!(interactive.resume_session_id.as_deref(), Some("1234"));
     }
 
+    #[test]
+    fn resume_fork_at_with_session_id() {
+        let interactive =
+            finalize_from_args(["codex", "resume", "1234", "--fork-at", "3"].as_ref());
+        assert!(!interactive.resume_picker);
+        assert_eq!(interactive.resume_session_id.as_deref(), Some("1234"));
+        assert_eq!(interactive.resume_fork_at, Some(3));
+    }
+
     #[test]
     fn resume_merges_option_flags_and_full_auto() {
         let interactive = finalize_from_args(
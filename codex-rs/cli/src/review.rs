@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::bail;
+use clap::Parser;
+use codex_common::CliConfigOverrides;
+use codex_core::AuthManager;
+use codex_core::ConversationManager;
+use codex_core::NewConversation;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::review::build_review_request;
+use codex_core::review::has_blocking_findings;
+use codex_exec::ReviewArgs;
+use codex_exec::ReviewFormat;
+use codex_exec::exit_status::BLOCKING_FINDINGS_EXIT_CODE;
+use codex_exec::format_review;
+use codex_exec::format_review_text;
+use codex_protocol::config_types::SandboxMode;
+
+#[derive(Debug, Parser)]
+pub struct ReviewCommand {
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[clap(flatten)]
+    target: ReviewArgs,
+
+    /// Emit the structured review result as JSON. Shorthand for
+    /// `--review-format json`.
+    #[arg(
+        long = "json",
+        default_value_t = false,
+        conflicts_with = "review_format"
+    )]
+    json: bool,
+
+    /// Emit the review result as SARIF, GitHub annotations, or JSON.
+    #[arg(long = "review-format", value_enum)]
+    review_format: Option<ReviewFormat>,
+}
+
+impl ReviewCommand {
+    fn review_format(&self) -> Option<ReviewFormat> {
+        if self.json {
+            Some(ReviewFormat::Json)
+        } else {
+            self.review_format
+        }
+    }
+}
+
+pub async fn run_review_command(
+    cmd: ReviewCommand,
+    cwd_override: Option<PathBuf>,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let cli_kv_overrides = cmd
+        .config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(
+        cli_kv_overrides,
+        ConfigOverrides {
+            cwd: cwd_override,
+            // Reviews are headless and must never modify the working tree.
+            approval_policy: Some(AskForApproval::Never),
+            sandbox_mode: Some(SandboxMode::ReadOnly),
+            codex_linux_sandbox_exe,
+            ..Default::default()
+        },
+    )?;
+
+    let target = cmd.target.target();
+    let review_request = build_review_request(&config.cwd, &target)
+        .await
+        .with_context(|| format!("failed to prepare review of {}", target.description()))?;
+    if cmd.review_format().is_none() {
+        eprintln!("Reviewing {}…", review_request.user_facing_hint);
+    }
+
+    let cwd = config.cwd.clone();
+    let conversation_manager =
+        ConversationManager::new(AuthManager::shared(config.codex_home.clone()));
+    let NewConversation { conversation, .. } =
+        conversation_manager.new_conversation(config).await?;
+    conversation.submit(Op::Review { review_request }).await?;
+
+    let mut review_output = None;
+    loop {
+        let event = conversation.next_event().await?;
+        match event.msg {
+            EventMsg::ExitedReviewMode(exited) => review_output = exited.review_output,
+            EventMsg::TaskComplete(_) => break,
+            EventMsg::TurnAborted(_) => bail!("review was interrupted"),
+            EventMsg::Error(err) => bail!("review failed: {}", err.message),
+            EventMsg::StreamError(err) => eprintln!("{}", err.message),
+            EventMsg::ShutdownComplete => break,
+            _ => {}
+        }
+    }
+    conversation.submit(Op::Shutdown).await.ok();
+
+    // A review that produced no result must not pass as one without findings.
+    let Some(review_output) = review_output else {
+        bail!("review finished without a result");
+    };
+    match cmd.review_format() {
+        Some(format) => println!("{}", format_review(&review_output, format, &cwd)),
+        None => print!("{}", format_review_text(&review_output, &cwd)),
+    }
+
+    if has_blocking_findings(&review_output) {
+        std::process::exit(BLOCKING_FINDINGS_EXIT_CODE);
+    }
+    Ok(())
+}
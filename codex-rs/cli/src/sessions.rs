@@ -0,0 +1,557 @@
+use clap::ArgGroup;
+use clap::Parser;
+use clap::Subcommand;
+use codex_common::CliConfigOverrides;
+use codex_core::ConversationItem;
+use codex_core::ExportFormat;
+use codex_core::RolloutRecorder;
+use codex_core::SessionIndex;
+use codex_core::SessionRetention;
+use codex_core::SessionSearchHit;
+use codex_core::SessionSearchQuery;
+use codex_core::Transcript;
+use codex_core::TranscriptEntry;
+use codex_core::archive_session;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::find_conversation_path_by_id_str;
+use codex_core::parse_age;
+use codex_core::parse_time_bound;
+use codex_core::prunable_sessions;
+use codex_core::remove_session;
+use codex_core::session_in_use;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ForkedFrom;
+use codex_protocol::protocol::InputMessageKind;
+use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Entry point for the `codex sessions` command family.
+#[derive(Debug, Parser)]
+pub struct SessionsCli {
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[clap(subcommand)]
+    command: SessionsCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum SessionsCommand {
+    /// List recorded sessions, newest first.
+    List(SessionsListCommand),
+
+    /// Show details about a session.
+    Show(SessionsShowCommand),
+
+    /// Delete sessions.
+    Rm(SessionsRemoveCommand),
+
+    /// Move sessions to `$CODEX_HOME/archived_sessions`.
+    Archive(SessionsArchiveCommand),
+
+    /// Delete (or archive) old sessions.
+    Prune(SessionsPruneCommand),
+
+    /// Search past sessions by message text, commands run and files patched.
+    Search(SessionsSearchCommand),
+
+    /// Export a session as a readable Markdown, HTML or JSON document.
+    Export(SessionsExportCommand),
+}
+
+#[derive(Debug, Parser)]
+struct SessionsListCommand {
+    /// Maximum number of sessions to show.
+    #[arg(long = "limit", default_value_t = 20)]
+    limit: usize,
+
+    /// Emit JSON instead of plaintext output.
+    #[arg(long = "json")]
+    json: bool,
+}
+
+#[derive(Debug, Parser)]
+struct SessionsShowCommand {
+    /// Session id (UUID) or path to a rollout `.jsonl` file.
+    #[arg(value_name = "SESSION")]
+    session: String,
+
+    /// Emit JSON instead of plaintext output.
+    #[arg(long = "json")]
+    json: bool,
+}
+
+#[derive(Debug, Parser)]
+struct SessionsRemoveCommand {
+    /// Session ids (UUIDs) or rollout paths.
+    #[arg(value_name = "SESSION", required = true)]
+    sessions: Vec<String>,
+}
+
+#[derive(Debug, Parser)]
+struct SessionsArchiveCommand {
+    /// Session ids (UUIDs) or rollout paths.
+    #[arg(value_name = "SESSION", required = true)]
+    sessions: Vec<String>,
+}
+
+#[derive(Debug, Parser)]
+#[command(group(ArgGroup::new("policy").required(true).multiple(true).args(["older_than", "keep"])))]
+struct SessionsPruneCommand {
+    /// Prune sessions started longer ago than this (e.g. 30d, 12h, 2w).
+    #[arg(long = "older-than", value_name = "AGE", value_parser = parse_age_arg)]
+    older_than: Option<chrono::Duration>,
+
+    /// Prune everything but the newest N sessions.
+    #[arg(long = "keep", value_name = "N")]
+    keep: Option<usize>,
+
+    /// Move pruned sessions to `$CODEX_HOME/archived_sessions` instead of
+    /// deleting them.
+    #[arg(long = "archive")]
+    archive: bool,
+
+    /// Print what would be pruned without changing anything.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+}
+
+#[derive(Debug, Parser)]
+struct SessionsSearchCommand {
+    /// Words that must all appear in the session (case-insensitive). Also
+    /// accepts `cwd:`, `model:`, `branch:`, `since:` and `until:` filters.
+    #[arg(value_name = "QUERY", num_args = 0..)]
+    query: Vec<String>,
+
+    /// Only sessions started in this directory or below it.
+    #[arg(long = "cwd", value_name = "DIR")]
+    cwd: Option<PathBuf>,
+
+    /// Only sessions started on or after this date (YYYY-MM-DD) or within
+    /// this age (e.g. 7d, 12h).
+    #[arg(long = "since", value_name = "WHEN")]
+    since: Option<String>,
+
+    /// Only sessions started on or before this date (YYYY-MM-DD) or more
+    /// than this age ago.
+    #[arg(long = "until", value_name = "WHEN")]
+    until: Option<String>,
+
+    /// Only sessions that used a model whose name contains this text.
+    #[arg(long = "model", value_name = "MODEL")]
+    model: Option<String>,
+
+    /// Only sessions recorded on this git branch.
+    #[arg(long = "branch", value_name = "BRANCH")]
+    branch: Option<String>,
+
+    /// Maximum number of sessions to show.
+    #[arg(long = "limit", default_value_t = 20)]
+    limit: usize,
+
+    /// Emit JSON instead of plaintext output.
+    #[arg(long = "json")]
+    json: bool,
+}
+
+#[derive(Debug, Parser)]
+struct SessionsExportCommand {
+    /// Session id (UUID) or path to a rollout `.jsonl` file.
+    #[arg(value_name = "SESSION")]
+    session: String,
+
+    /// Output format: md, html or json.
+    #[arg(long = "format", short = 'f', default_value = "md")]
+    format: ExportFormat,
+
+    /// Mask env-var-like secrets (`*_API_KEY=...`), bearer tokens and common
+    /// API key formats.
+    #[arg(long = "redact")]
+    redact: bool,
+
+    /// Write to this file instead of stdout.
+    #[arg(long = "output", short = 'o', value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+pub async fn run_sessions_cli(
+    cli: SessionsCli,
+    cwd_override: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let overrides = cli.config_overrides.clone();
+    let config = load_config(&overrides, cwd_override)?;
+
+    match cli.command {
+        SessionsCommand::List(cmd) => run_list(cmd, &config).await?,
+        SessionsCommand::Show(cmd) => run_show(cmd, &config).await?,
+        SessionsCommand::Rm(cmd) => run_remove(cmd, &config).await?,
+        SessionsCommand::Archive(cmd) => run_archive(cmd, &config).await?,
+        SessionsCommand::Prune(cmd) => run_prune(cmd, &config).await?,
+        SessionsCommand::Search(cmd) => run_search(cmd, &config).await?,
+        SessionsCommand::Export(cmd) => run_export(cmd, &config).await?,
+    }
+
+    Ok(())
+}
+
+fn load_config(
+    overrides: &CliConfigOverrides,
+    cwd_override: Option<PathBuf>,
+) -> anyhow::Result<Config> {
+    let pairs = overrides.parse_overrides().map_err(anyhow::Error::msg)?;
+    Ok(Config::load_with_cli_overrides(
+        pairs,
+        ConfigOverrides {
+            cwd: cwd_override,
+            ..Default::default()
+        },
+    )?)
+}
+
+fn parse_age_arg(value: &str) -> Result<chrono::Duration, String> {
+    parse_age(value).ok_or_else(|| format!("invalid age `{value}`; expected e.g. 30d, 12h or 2w"))
+}
+
+#[derive(Debug, Serialize)]
+struct ListedSession {
+    id: Option<String>,
+    timestamp: Option<String>,
+    cwd: Option<String>,
+    path: PathBuf,
+    preview: Option<String>,
+}
+
+impl ListedSession {
+    fn from_item(item: &ConversationItem) -> Self {
+        let meta = item.head.first();
+        let field = |name: &str| {
+            meta.and_then(|meta| meta.get(name))
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+        };
+        Self {
+            id: field("id"),
+            timestamp: field("timestamp"),
+            cwd: field("cwd"),
+            path: item.path.clone(),
+            preview: first_user_message(&item.head),
+        }
+    }
+}
+
+/// First plain user message among the head records of a rollout.
+fn first_user_message(head: &[serde_json::Value]) -> Option<String> {
+    head.iter()
+        .filter_map(|value| serde_json::from_value::<ResponseItem>(value.clone()).ok())
+        .find_map(|item| match item {
+            ResponseItem::Message { role, content, .. } if role == "user" => {
+                content.into_iter().find_map(|content| match content {
+                    ContentItem::InputText { text }
+                        if matches!(
+                            InputMessageKind::from(("user", text.as_str())),
+                            InputMessageKind::Plain
+                        ) =>
+                    {
+                        Some(text)
+                    }
+                    _ => None,
+                })
+            }
+            _ => None,
+        })
+}
+
+async fn run_list(cmd: SessionsListCommand, config: &Config) -> anyhow::Result<()> {
+    let mut sessions = Vec::new();
+    let mut cursor = None;
+    while sessions.len() < cmd.limit {
+        let page = RolloutRecorder::list_conversations(
+            &config.codex_home,
+            cmd.limit - sessions.len(),
+            cursor.as_ref(),
+        )
+        .await?;
+        sessions.extend(page.items.iter().map(ListedSession::from_item));
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+
+    if cmd.json {
+        println!("{}", serde_json::to_string_pretty(&sessions)?);
+        return Ok(());
+    }
+    if sessions.is_empty() {
+        println!("No sessions recorded.");
+        return Ok(());
+    }
+    for session in &sessions {
+        let preview = session
+            .preview
+            .as_deref()
+            .and_then(|preview| preview.lines().next())
+            .unwrap_or("(no message yet)");
+        println!(
+            "{}  {}  {}",
+            session.id.as_deref().unwrap_or("(no id)"),
+            session.timestamp.as_deref().unwrap_or("unknown time"),
+            truncate_chars(preview, 80)
+        );
+    }
+    Ok(())
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((idx, _)) => format!("{}…", &text[..idx]),
+        None => text.to_string(),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SessionDetails {
+    path: PathBuf,
+    id: Option<String>,
+    timestamp: Option<String>,
+    cwd: Option<PathBuf>,
+    git_branch: Option<String>,
+    forked_from: Option<ForkedFrom>,
+    models: Vec<String>,
+    user_messages: usize,
+    commands: usize,
+    files_changed: Vec<String>,
+    first_message: Option<String>,
+}
+
+async fn run_show(cmd: SessionsShowCommand, config: &Config) -> anyhow::Result<()> {
+    let path = resolve_session(&cmd.session, &config.codex_home).await?;
+    let transcript = Transcript::load(&path).await?;
+    let mut files_changed: Vec<String> = Vec::new();
+    for entry in &transcript.entries {
+        if let TranscriptEntry::Patch { files } = entry {
+            for file in files {
+                let path = file.move_path.as_ref().unwrap_or(&file.path);
+                if !files_changed.contains(path) {
+                    files_changed.push(path.clone());
+                }
+            }
+        }
+    }
+    let count = |matches: fn(&TranscriptEntry) -> bool| {
+        transcript.entries.iter().filter(|e| matches(e)).count()
+    };
+    let details = SessionDetails {
+        path,
+        id: transcript.id.clone(),
+        timestamp: transcript.timestamp.clone(),
+        cwd: transcript.cwd.clone(),
+        git_branch: transcript.git_branch.clone(),
+        forked_from: transcript.forked_from.clone(),
+        models: transcript.models.clone(),
+        user_messages: count(|e| matches!(e, TranscriptEntry::User { .. })),
+        commands: count(|e| matches!(e, TranscriptEntry::Command { .. })),
+        files_changed,
+        first_message: transcript.entries.iter().find_map(|e| match e {
+            TranscriptEntry::User { text } => Some(text.clone()),
+            _ => None,
+        }),
+    };
+
+    if cmd.json {
+        println!("{}", serde_json::to_string_pretty(&details)?);
+        return Ok(());
+    }
+    println!("{}", details.id.as_deref().unwrap_or("(no id)"));
+    println!("  path: {}", details.path.display());
+    if let Some(timestamp) = &details.timestamp {
+        println!("  started: {timestamp}");
+    }
+    if let Some(cwd) = &details.cwd {
+        println!("  cwd: {}", cwd.display());
+    }
+    if let Some(branch) = &details.git_branch {
+        println!("  branch: {branch}");
+    }
+    if let Some(forked_from) = &details.forked_from {
+        println!(
+            "  forked from: {} after turn {}",
+            forked_from.conversation_id, forked_from.turn
+        );
+    }
+    if !details.models.is_empty() {
+        println!("  model: {}", details.models.join(", "));
+    }
+    println!(
+        "  {} user message(s), {} command(s), {} file(s) changed",
+        details.user_messages,
+        details.commands,
+        details.files_changed.len()
+    );
+    for file in &details.files_changed {
+        println!("    {file}");
+    }
+    if let Some(first) = &details.first_message {
+        println!("  first message: {}", truncate_chars(first.trim(), 200));
+    }
+    Ok(())
+}
+
+async fn run_remove(cmd: SessionsRemoveCommand, config: &Config) -> anyhow::Result<()> {
+    for session in &cmd.sessions {
+        let path = resolve_session(session, &config.codex_home).await?;
+        remove_session(&config.codex_home, &path).await?;
+        println!("Removed {}", path.display());
+    }
+    Ok(())
+}
+
+async fn run_archive(cmd: SessionsArchiveCommand, config: &Config) -> anyhow::Result<()> {
+    for session in &cmd.sessions {
+        let path = resolve_session(session, &config.codex_home).await?;
+        let archived = archive_session(&config.codex_home, &path).await?;
+        println!("Archived {} to {}", path.display(), archived.display());
+    }
+    Ok(())
+}
+
+async fn run_prune(cmd: SessionsPruneCommand, config: &Config) -> anyhow::Result<()> {
+    let retention = SessionRetention {
+        max_sessions: cmd.keep,
+        max_age: cmd.older_than,
+    };
+    let mut prunable =
+        prunable_sessions(&config.codex_home, &retention, chrono::Utc::now()).await?;
+    // Sessions that may still be running are left for a later prune.
+    let mut in_use = Vec::new();
+    for file in &prunable {
+        if session_in_use(&file.path).await {
+            in_use.push(file.path.clone());
+        }
+    }
+    prunable.retain(|file| !in_use.contains(&file.path));
+    for path in &in_use {
+        println!("Skipped {} (in use)", path.display());
+    }
+    if prunable.is_empty() {
+        println!("Nothing to prune.");
+        return Ok(());
+    }
+    let verb = match (cmd.dry_run, cmd.archive) {
+        (true, true) => "Would archive",
+        (true, false) => "Would remove",
+        (false, true) => "Archived",
+        (false, false) => "Removed",
+    };
+    for file in &prunable {
+        if !cmd.dry_run {
+            if cmd.archive {
+                archive_session(&config.codex_home, &file.path).await?;
+            } else {
+                remove_session(&config.codex_home, &file.path).await?;
+            }
+        }
+        println!("{verb} {}", file.path.display());
+    }
+    println!("{verb} {} session(s).", prunable.len());
+    Ok(())
+}
+
+async fn run_search(cmd: SessionsSearchCommand, config: &Config) -> anyhow::Result<()> {
+    let mut query = SessionSearchQuery::parse(&cmd.query.join(" ")).map_err(anyhow::Error::msg)?;
+    let now = chrono::Utc::now();
+    if let Some(cwd) = cmd.cwd {
+        query.cwd = Some(std::path::absolute(cwd)?);
+    }
+    if let Some(since) = cmd.since {
+        query.since = Some(parse_time_bound(&since, now, false).map_err(anyhow::Error::msg)?);
+    }
+    if let Some(until) = cmd.until {
+        query.until = Some(parse_time_bound(&until, now, true).map_err(anyhow::Error::msg)?);
+    }
+    if cmd.model.is_some() {
+        query.model = cmd.model;
+    }
+    if cmd.branch.is_some() {
+        query.git_branch = cmd.branch;
+    }
+
+    let codex_home = config.codex_home.clone();
+    let index = tokio::task::spawn_blocking(move || SessionIndex::open(&codex_home)).await??;
+    let hits = index.search(&query, cmd.limit);
+
+    if cmd.json {
+        println!("{}", serde_json::to_string_pretty(&hits)?);
+        return Ok(());
+    }
+    if hits.is_empty() {
+        println!("No matching sessions.");
+        return Ok(());
+    }
+    for hit in &hits {
+        print_hit(hit);
+    }
+    Ok(())
+}
+
+async fn run_export(cmd: SessionsExportCommand, config: &Config) -> anyhow::Result<()> {
+    let path = resolve_session(&cmd.session, &config.codex_home).await?;
+    let mut transcript = Transcript::load(&path).await?;
+    if cmd.redact {
+        transcript.redact();
+    }
+    let rendered = transcript.render(cmd.format);
+    match cmd.output {
+        Some(output) => {
+            std::fs::write(&output, rendered)?;
+            eprintln!("Exported {} to {}", path.display(), output.display());
+        }
+        None => print!("{rendered}"),
+    }
+    Ok(())
+}
+
+/// Accept either a rollout path or a session id.
+async fn resolve_session(session: &str, codex_home: &Path) -> anyhow::Result<PathBuf> {
+    let as_path = PathBuf::from(session);
+    if as_path.is_file() {
+        return Ok(as_path);
+    }
+    find_conversation_path_by_id_str(codex_home, session)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("no session found with id {session}"))
+}
+
+fn print_hit(hit: &SessionSearchHit) {
+    let when = hit
+        .timestamp
+        .map(|ts| ts.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "unknown time".to_string());
+    println!("{}  {when}", hit.id.as_deref().unwrap_or("(no id)"));
+
+    let mut details = Vec::new();
+    if let Some(cwd) = &hit.cwd {
+        details.push(cwd.display().to_string());
+    }
+    if let Some(branch) = &hit.git_branch {
+        details.push(format!("branch {branch}"));
+    }
+    if !hit.models.is_empty() {
+        details.push(hit.models.join(", "));
+    }
+    if !details.is_empty() {
+        println!("  {}", details.join("  ·  "));
+    }
+    if hit.snippets.is_empty()
+        && let Some(first) = &hit.first_user_message
+    {
+        println!("  {}", first.lines().next().unwrap_or_default());
+    }
+    for snippet in &hit.snippets {
+        println!("  {}: {}", snippet.kind.label(), snippet.text);
+    }
+    println!();
+}
@@ -0,0 +1,141 @@
+use std::path::Path;
+
+use anyhow::Result;
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+#[test]
+fn set_get_and_unset_round_trip_through_config_toml() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let config_path = codex_home.path().join("config.toml");
+    std::fs::write(
+        &config_path,
+        "model = \"o3\"\n\n# keep me\napproval_policy = \"never\"\n",
+    )?;
+
+    codex_command(codex_home.path())?
+        .args(["config", "set", "mcp_servers.docs.args", r#"["--stdio"]"#])
+        .assert()
+        .failure()
+        .stderr(contains("missing field `command`"));
+
+    codex_command(codex_home.path())?
+        .args(["config", "set", "model_reasoning_effort", "high"])
+        .assert()
+        .success()
+        .stdout(contains("Set `model_reasoning_effort` = \"high\""));
+
+    codex_command(codex_home.path())?
+        .args(["config", "get", "model_reasoning_effort"])
+        .assert()
+        .success()
+        .stdout("high\n");
+
+    // `review_model` takes a string, so this is not stored as a TOML date.
+    codex_command(codex_home.path())?
+        .args(["config", "set", "review_model", "2025-01-01"])
+        .assert()
+        .success()
+        .stdout(contains("Set `review_model` = \"2025-01-01\""));
+    codex_command(codex_home.path())?
+        .args(["config", "unset", "review_model"])
+        .assert()
+        .success();
+
+    codex_command(codex_home.path())?
+        .args(["config", "unset", "model"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        std::fs::read_to_string(&config_path)?,
+        "\n# keep me\napproval_policy = \"never\"\nmodel_reasoning_effort = \"high\"\n"
+    );
+
+    codex_command(codex_home.path())?
+        .args(["config", "get", "model"])
+        .assert()
+        .failure()
+        .stderr(contains("`model` is not set"));
+
+    Ok(())
+}
+
+#[test]
+fn validate_reports_locations_and_fails_on_errors() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        "modle = \"o3\"\napproval_policy = \"sometimes\"\n",
+    )?;
+
+    codex_command(codex_home.path())?
+        .args(["config", "validate"])
+        .assert()
+        .failure()
+        .stdout(contains(
+            "config.toml:1:1: warning: unknown key `modle` is ignored",
+        ))
+        .stdout(contains(
+            "config.toml:2:19: error: unknown variant `sometimes`",
+        ))
+        .stderr(contains("found 1 error(s)"));
+
+    std::fs::write(codex_home.path().join("config.toml"), "model = \"o3\"\n")?;
+    codex_command(codex_home.path())?
+        .args(["config", "validate"])
+        .assert()
+        .success()
+        .stdout(contains("config.toml: ok"))
+        .stdout(contains("error").not());
+
+    Ok(())
+}
+
+#[test]
+fn validate_warns_about_keys_projects_may_not_set() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let project = TempDir::new()?;
+    std::fs::create_dir(project.path().join(".codex"))?;
+    std::fs::write(
+        project.path().join(".codex").join("config.toml"),
+        "model = \"o3\"\nsandbox_mode = \"danger-full-access\"\n",
+    )?;
+
+    codex_command(codex_home.path())?
+        .args(["-C"])
+        .arg(project.path())
+        .args(["config", "validate"])
+        .assert()
+        .success()
+        .stdout(contains(
+            "config.toml:2:1: warning: `sandbox_mode` is ignored: projects may not set it",
+        ))
+        .stdout(contains("`model` is ignored").not());
+
+    Ok(())
+}
+
+#[test]
+fn schema_describes_config_keys() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let output = codex_command(codex_home.path())?
+        .args(["config", "schema"])
+        .output()?;
+    assert!(output.status.success());
+
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(schema["title"], "ConfigToml");
+    assert!(schema["properties"]["approval_policy"].is_object());
+    assert!(schema["properties"]["mcp_servers"].is_object());
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use std::path::Path;
+
+use anyhow::Result;
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use tempfile::TempDir;
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+fn write_project(project: &Path) -> Result<()> {
+    std::fs::create_dir_all(project.join(".codex"))?;
+    std::fs::write(
+        project.join(".codex/config.toml"),
+        "model = \"project-model\"\nsandbox_mode = \"danger-full-access\"\n",
+    )?;
+    Ok(())
+}
+
+#[test]
+fn show_origin_attributes_settings_to_their_layers() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let project = TempDir::new()?;
+    write_project(project.path())?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        format!(
+            "model_reasoning_effort = \"high\"\n\n[projects.{}]\ntrust_level = \"trusted\"\n",
+            toml_string(project.path())
+        ),
+    )?;
+
+    codex_command(codex_home.path())?
+        .args(["-C"])
+        .arg(project.path())
+        .args(["-c", "model_verbosity=low", "config", "show", "--origin"])
+        .assert()
+        .success()
+        .stdout(contains("model = \"project-model\""))
+        .stdout(contains("# project config"))
+        .stdout(contains("model_reasoning_effort = \"high\""))
+        .stdout(contains("# user config"))
+        .stdout(contains("model_verbosity = \"low\""))
+        .stdout(contains("# -c override"))
+        .stdout(contains("sandbox_mode").not())
+        .stderr(contains("Ignoring `sandbox_mode`"));
+
+    Ok(())
+}
+
+#[test]
+fn show_ignores_untrusted_project_config() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let project = TempDir::new()?;
+    write_project(project.path())?;
+
+    codex_command(codex_home.path())?
+        .args(["-C"])
+        .arg(project.path())
+        .args(["config", "show"])
+        .assert()
+        .success()
+        .stdout(contains("project-model").not())
+        .stderr(contains("this project is not trusted"));
+
+    Ok(())
+}
+
+fn toml_string(path: &Path) -> String {
+    format!("{:?}", path.to_string_lossy())
+}
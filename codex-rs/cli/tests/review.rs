@@ -0,0 +1,43 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use tempfile::TempDir;
+
+fn git(repo: &Path, args: &[&str]) -> Result<()> {
+    let status = Command::new("git").args(args).current_dir(repo).status()?;
+    anyhow::ensure!(status.success(), "git {args:?} failed");
+    Ok(())
+}
+
+#[test]
+fn review_without_a_result_fails() -> Result<()> {
+    let home = TempDir::new()?;
+    let repo = TempDir::new()?;
+    git(repo.path(), &["init", "--quiet"])?;
+    std::fs::write(repo.path().join("lib.rs"), "fn main() {}\n")?;
+    git(repo.path(), &["add", "lib.rs"])?;
+
+    // The model finishes without ever answering.
+    let fixture = home.path().join("fixture.sse");
+    std::fs::write(
+        &fixture,
+        "event: response.created\ndata: {\"type\":\"response.created\",\"response\":{\"id\":\"resp1\"}}\n\n\
+         event: response.completed\ndata: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp1\",\"output\":[]}}\n\n",
+    )?;
+
+    assert_cmd::Command::cargo_bin("codex")?
+        .env("CODEX_HOME", home.path())
+        .env("OPENAI_API_KEY", "dummy")
+        .env("CODEX_RS_SSE_FIXTURE", &fixture)
+        .env("OPENAI_BASE_URL", "http://unused.local")
+        .current_dir(repo.path())
+        .args(["review", "--staged"])
+        .assert()
+        .failure()
+        .stderr(contains("review finished without a result"))
+        .stdout(contains("No findings").not());
+    Ok(())
+}
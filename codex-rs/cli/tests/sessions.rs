@@ -0,0 +1,201 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use anyhow::Result;
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::json;
+use tempfile::TempDir;
+
+const FIRST_ID: &str = "00000000-0000-0000-0000-000000000001";
+const SECOND_ID: &str = "00000000-0000-0000-0000-000000000002";
+const THIRD_ID: &str = "00000000-0000-0000-0000-000000000003";
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+/// Write a rollout for a session started on 2025-01-`day` and backdate it so
+/// it does not count as in use.
+fn write_session(codex_home: &Path, day: u32, id: &str, message: &str) -> Result<PathBuf> {
+    let dir = codex_home.join(format!("sessions/2025/01/{day:02}"));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("rollout-2025-01-{day:02}T12-00-00-{id}.jsonl"));
+    let timestamp = format!("2025-01-{day:02}T12:00:00.000Z");
+    let lines = [
+        json!({
+            "timestamp": timestamp,
+            "type": "session_meta",
+            "payload": {
+                "id": id,
+                "timestamp": timestamp,
+                "cwd": "/repo",
+                "originator": "codex_cli_rs",
+                "cli_version": "0.0.0",
+                "instructions": null,
+            },
+        }),
+        json!({
+            "timestamp": timestamp,
+            "type": "response_item",
+            "payload": {
+                "type": "message",
+                "role": "user",
+                "content": [{ "type": "input_text", "text": message }],
+            },
+        }),
+        json!({
+            "timestamp": timestamp,
+            "type": "event_msg",
+            "payload": { "type": "user_message", "message": message },
+        }),
+    ];
+    let contents: String = lines.iter().map(|line| format!("{line}\n")).collect();
+    std::fs::write(&path, contents)?;
+    std::fs::File::options()
+        .write(true)
+        .open(&path)?
+        .set_modified(SystemTime::now() - Duration::from_secs(48 * 60 * 60))?;
+    Ok(path)
+}
+
+fn seed(codex_home: &Path) -> Result<[PathBuf; 3]> {
+    Ok([
+        write_session(codex_home, 1, FIRST_ID, "Fix the flaky migration test")?,
+        write_session(codex_home, 2, SECOND_ID, "Write docs for the CLI")?,
+        write_session(codex_home, 3, THIRD_ID, "Bump the MSRV")?,
+    ])
+}
+
+#[test]
+fn list_shows_sessions_newest_first() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    codex_command(codex_home.path())?
+        .args(["sessions", "list"])
+        .assert()
+        .success()
+        .stdout(contains("No sessions recorded."));
+
+    seed(codex_home.path())?;
+    let output = codex_command(codex_home.path())?
+        .args(["sessions", "list"])
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let ids: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .collect();
+    assert_eq!(ids, vec![THIRD_ID, SECOND_ID, FIRST_ID]);
+    assert!(stdout.contains("Write docs for the CLI"));
+    Ok(())
+}
+
+#[test]
+fn show_describes_a_session_by_id() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let [_, second, _] = seed(codex_home.path())?;
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "show", SECOND_ID])
+        .assert()
+        .success()
+        .stdout(
+            contains(SECOND_ID)
+                .and(contains(format!("path: {}", second.display())))
+                .and(contains("first message: Write docs for the CLI")),
+        );
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "show", "00000000-0000-0000-0000-000000000009"])
+        .assert()
+        .failure();
+    Ok(())
+}
+
+#[test]
+fn rm_deletes_the_rollout() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let [first, second, _] = seed(codex_home.path())?;
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "rm", FIRST_ID])
+        .assert()
+        .success()
+        .stdout(contains("Removed"));
+    assert!(!first.exists());
+    assert!(second.exists());
+
+    let outside = codex_home.path().join("config.toml");
+    std::fs::write(&outside, "")?;
+    codex_command(codex_home.path())?
+        .args(["sessions", "rm"])
+        .arg(&outside)
+        .assert()
+        .failure();
+    assert!(outside.exists());
+    Ok(())
+}
+
+#[test]
+fn archive_moves_the_rollout_out_of_sessions() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let [first, _, _] = seed(codex_home.path())?;
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "archive", FIRST_ID])
+        .assert()
+        .success()
+        .stdout(contains("Archived"));
+    assert!(!first.exists());
+    let file_name = first.file_name().expect("file name");
+    assert!(
+        codex_home
+            .path()
+            .join("archived_sessions")
+            .join(file_name)
+            .exists()
+    );
+    Ok(())
+}
+
+#[test]
+fn prune_keeps_the_newest_and_skips_sessions_in_use() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let [first, second, third] = seed(codex_home.path())?;
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "prune", "--keep", "1", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(contains("Would remove 2 session(s)."));
+    assert!(first.exists() && second.exists());
+
+    // A session written to just now may still be running.
+    std::fs::File::options()
+        .append(true)
+        .open(&second)?
+        .set_modified(SystemTime::now())?;
+    codex_command(codex_home.path())?
+        .args(["sessions", "prune", "--keep", "1"])
+        .assert()
+        .success()
+        .stdout(
+            contains(format!("Skipped {} (in use)", second.display()))
+                .and(contains("Removed 1 session(s).")),
+        );
+    assert!(!first.exists());
+    assert!(second.exists());
+    assert!(third.exists());
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "prune", "--keep", "1", "--archive"])
+        .assert()
+        .success()
+        .stdout(contains("Nothing to prune."));
+    Ok(())
+}
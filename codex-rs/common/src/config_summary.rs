@@ -26,6 +26,16 @@ pub fn create_config_summary_entries(config: &Config) -> Vec<(&'static str, Stri
             "reasoning summaries",
             config.model_reasoning_summary.to_string(),
         ));
+    } else if config.model_provider.wire_api == WireApi::Anthropic
+        && config.model_family.supports_reasoning_summaries
+    {
+        entries.push((
+            "reasoning effort",
+            config
+                .model_reasoning_effort
+                .unwrap_or_default()
+                .to_string(),
+        ));
     }
 
     entries
@@ -0,0 +1,873 @@
+//! Client for the Anthropic Messages API (`wire_api = "anthropic"`).
+//!
+//! The request is built from the same [`Prompt`] as the other wire protocols,
+//! and the streamed reply is mapped onto [`ResponseEvent`]s the way the
+//! Responses API produces them: text and thinking stream as deltas and every
+//! content block ends in exactly one `OutputItemDone`, so unlike Chat
+//! Completions no aggregation is needed.
+//!
+//! Thinking blocks are kept as `ResponseItem::Reasoning` with the thinking in
+//! `summary` and the block signature in `encrypted_content`, because the API
+//! requires them to be sent back unmodified on the next request. Redacted
+//! thinking has an empty `summary` and keeps its opaque data in
+//! `encrypted_content`. Either is prefixed with [`REASONING_ORIGIN`], so that
+//! after a failover between wire APIs neither is sent reasoning the other
+//! produced: the signatures are only valid for the API that issued them.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use bytes::Bytes;
+use eventsource_stream::Eventsource;
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+
+use crate::ModelProviderInfo;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::model_family::ModelFamily;
+use crate::model_recording::ModelStreamRecording;
+use crate::model_recording::ModelTurn;
+use crate::model_recording::record_response;
+use crate::openai_tools::OpenAiTool;
+use crate::openai_tools::create_tools_json_for_anthropic_api;
+use crate::output_schema::FINAL_OUTPUT_TOOL_NAME;
+use crate::protocol::TokenUsage;
+use crate::util::backoff;
+use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ReasoningItemReasoningSummary;
+use codex_protocol::models::ResponseItem;
+
+/// Prefix of the `encrypted_content` of reasoning items made from thinking
+/// blocks.
+const REASONING_ORIGIN: &str = "anthropic:";
+
+/// Whether `item` is reasoning produced by the Messages API.
+pub(crate) fn is_anthropic_reasoning(item: &ResponseItem) -> bool {
+    matches!(
+        item,
+        ResponseItem::Reasoning {
+            encrypted_content: Some(encrypted),
+            ..
+        } if encrypted.starts_with(REASONING_ORIGIN)
+    )
+}
+
+/// `max_tokens` is required by the API; used when the model's output limit is
+/// unknown.
+const DEFAULT_MAX_OUTPUT_TOKENS: u64 = 8_192;
+
+/// The API rejects thinking budgets below this.
+const MIN_THINKING_BUDGET_TOKENS: u64 = 1_024;
+
+/// Implementation for the Anthropic Messages API. `effort` enables extended
+/// thinking and is `None` for models that do not support it.
+pub(crate) async fn stream_anthropic_messages(
+    prompt: &Prompt,
+    model_family: &ModelFamily,
+    client: &reqwest::Client,
+    provider: &ModelProviderInfo,
+    recording: Option<&ModelStreamRecording>,
+    effort: Option<ReasoningEffortConfig>,
+    max_output_tokens: Option<u64>,
+) -> Result<ResponseStream> {
+    let full_instructions = prompt.get_full_instructions(model_family);
+    let max_tokens = max_output_tokens.unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS);
+
+    let mut tools_json = create_tools_json_for_anthropic_api(&prompt.tools);
+    // As with Chat Completions, the output schema becomes a tool the model
+    // is asked to call with its answer; `process_anthropic_sse` turns that call back
+    // into a message.
+    if let Some(schema) = &prompt.output_schema {
+        tools_json.push(json!({
+            "name": FINAL_OUTPUT_TOOL_NAME,
+            "description": "Submit the final answer. Call this exactly once, when the task is complete.",
+            "input_schema": schema,
+        }));
+    }
+    // Cache the tools, the system prompt and the conversation so far: each
+    // request then only pays full price for what was added since the last.
+    if let Some(tool) = tools_json.last_mut() {
+        tool["cache_control"] = json!({"type": "ephemeral"});
+    }
+
+    let mut payload = json!({
+        "model": model_family.slug,
+        "max_tokens": max_tokens,
+        "system": [{
+            "type": "text",
+            "text": full_instructions,
+            "cache_control": {"type": "ephemeral"},
+        }],
+        "messages": build_messages(&prompt.get_formatted_input()),
+        "stream": true,
+    });
+    if !tools_json.is_empty() {
+        payload["tools"] = json!(tools_json);
+    }
+    if prompt.forces_final_output_tool() {
+        // Forced tool use cannot be combined with thinking.
+        payload["tool_choice"] = json!({"type": "tool", "name": FINAL_OUTPUT_TOOL_NAME});
+    } else if let Some(thinking) = effort.and_then(|effort| thinking_param(effort, max_tokens)) {
+        payload["thinking"] = thinking;
+    }
+
+    debug!(
+        "POST to {}: {}",
+        provider.get_full_url(&None),
+        serde_json::to_string_pretty(&payload).unwrap_or_default()
+    );
+
+    let freeform_tools: HashSet<String> = prompt
+        .tools
+        .iter()
+        .filter_map(|tool| match tool {
+            OpenAiTool::Freeform(tool) => Some(tool.name.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let mut record_to = None;
+    if let Some(recording) = recording {
+        match recording.begin_turn(&payload).await? {
+            ModelTurn::Replayed(bytes) => {
+                let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+                tokio::spawn(process_anthropic_sse(
+                    futures::stream::iter([Ok(bytes)]),
+                    tx_event,
+                    provider.stream_idle_timeout(),
+                    freeform_tools,
+                ));
+                return Ok(ResponseStream { rx_event });
+            }
+            ModelTurn::Recording { dir, turn } => record_to = Some((dir, turn)),
+        }
+    }
+
+    let mut attempt = 0;
+    let max_retries = provider.request_max_retries();
+    loop {
+        attempt += 1;
+
+        let req_builder = provider.create_request_builder(client, &None).await?;
+
+        let res = req_builder
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .json(&payload)
+            .send()
+            .await;
+
+        match res {
+            Ok(resp) if resp.status().is_success() => {
+                let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+                let stream = resp.bytes_stream().map_err(CodexErr::Reqwest);
+                let idle_timeout = provider.stream_idle_timeout();
+                match &record_to {
+                    Some((dir, turn)) => tokio::spawn(process_anthropic_sse(
+                        record_response(stream, dir, *turn).await,
+                        tx_event,
+                        idle_timeout,
+                        freeform_tools,
+                    )),
+                    None => tokio::spawn(process_anthropic_sse(
+                        stream,
+                        tx_event,
+                        idle_timeout,
+                        freeform_tools,
+                    )),
+                };
+                return Ok(ResponseStream { rx_event });
+            }
+            Ok(res) => {
+                let status = res.status();
+                if !(status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()) {
+                    let body = (res.text().await).unwrap_or_default();
+                    return Err(CodexErr::UnexpectedStatus(status, body));
+                }
+
+                if attempt > max_retries {
+                    return Err(CodexErr::RetryLimit(status));
+                }
+
+                let retry_after_secs = res
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|s| s.parse::<u64>().ok());
+
+                let delay = retry_after_secs
+                    .map(|s| Duration::from_millis(s * 1_000))
+                    .unwrap_or_else(|| backoff(attempt));
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                if attempt > max_retries {
+                    return Err(e.into());
+                }
+                let delay = backoff(attempt);
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// The `thinking` parameter for `effort`, or `None` if thinking is off or
+/// does not fit in `max_tokens`.
+fn thinking_param(effort: ReasoningEffortConfig, max_tokens: u64) -> Option<Value> {
+    let budget = match effort {
+        ReasoningEffortConfig::Minimal => return None,
+        ReasoningEffortConfig::Low => 4_096,
+        ReasoningEffortConfig::Medium => 10_240,
+        ReasoningEffortConfig::High => 24_576,
+    };
+    // The budget counts towards `max_tokens`, so leave room for the answer.
+    let budget = u64::min(budget, max_tokens / 2);
+    if budget < MIN_THINKING_BUDGET_TOKENS {
+        return None;
+    }
+    Some(json!({"type": "enabled", "budget_tokens": budget}))
+}
+
+/// Convert the conversation into Messages API `messages`. Consecutive items
+/// with the same role are merged into one message, since the API expects the
+/// roles to alternate and tool results to follow their tool use directly.
+fn build_messages(input: &[ResponseItem]) -> Vec<Value> {
+    let mut messages: Vec<Value> = Vec::new();
+    let mut push = |role: &str, block: Value| {
+        if let Some(last) = messages.last_mut()
+            && last["role"] == role
+            && let Some(content) = last["content"].as_array_mut()
+        {
+            content.push(block);
+        } else {
+            messages.push(json!({"role": role, "content": [block]}));
+        }
+    };
+
+    for item in input {
+        match item {
+            ResponseItem::Message { role, content, .. } => {
+                let role = if role == "assistant" {
+                    "assistant"
+                } else {
+                    "user"
+                };
+                for part in content {
+                    match part {
+                        ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                            // The API rejects empty text blocks.
+                            if !text.is_empty() {
+                                push(role, json!({"type": "text", "text": text}));
+                            }
+                        }
+                        ContentItem::InputImage { image_url } => {
+                            push(role, image_block(image_url));
+                        }
+                    }
+                }
+            }
+            ResponseItem::Reasoning {
+                summary,
+                encrypted_content: Some(encrypted),
+                ..
+            } if encrypted.starts_with(REASONING_ORIGIN) => {
+                let signature = &encrypted[REASONING_ORIGIN.len()..];
+                let thinking: String = summary
+                    .iter()
+                    .map(|ReasoningItemReasoningSummary::SummaryText { text }| text.as_str())
+                    .collect();
+                if thinking.is_empty() {
+                    push(
+                        "assistant",
+                        json!({"type": "redacted_thinking", "data": signature}),
+                    );
+                } else {
+                    push(
+                        "assistant",
+                        json!({"type": "thinking", "thinking": thinking, "signature": signature}),
+                    );
+                }
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                let input = serde_json::from_str::<Value>(arguments)
+                    .ok()
+                    .filter(Value::is_object)
+                    .unwrap_or_else(|| json!({}));
+                push(
+                    "assistant",
+                    json!({"type": "tool_use", "id": call_id, "name": name, "input": input}),
+                );
+            }
+            ResponseItem::LocalShellCall {
+                id,
+                call_id,
+                action,
+                ..
+            } => {
+                let id = call_id.clone().or_else(|| id.clone()).unwrap_or_default();
+                push(
+                    "assistant",
+                    json!({"type": "tool_use", "id": id, "name": "local_shell", "input": action}),
+                );
+            }
+            ResponseItem::CustomToolCall {
+                call_id,
+                name,
+                input,
+                ..
+            } => {
+                push(
+                    "assistant",
+                    json!({"type": "tool_use", "id": call_id, "name": name, "input": {"input": input}}),
+                );
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                let mut block = json!({
+                    "type": "tool_result",
+                    "tool_use_id": call_id,
+                    "content": output.content,
+                });
+                if output.success == Some(false) {
+                    block["is_error"] = json!(true);
+                }
+                push("user", block);
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                push(
+                    "user",
+                    json!({"type": "tool_result", "tool_use_id": call_id, "content": output}),
+                );
+            }
+            // Reasoning from another wire API carries no signature this API
+            // accepts; web searches run on OpenAI's side.
+            ResponseItem::Reasoning { .. }
+            | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::Other => {}
+        }
+    }
+
+    // Mark the end of the conversation as a cache breakpoint. Thinking blocks
+    // cannot carry `cache_control`.
+    if let Some(block) = messages
+        .last_mut()
+        .and_then(|message| message["content"].as_array_mut())
+        .and_then(|content| content.last_mut())
+        && !matches!(
+            block["type"].as_str(),
+            Some("thinking" | "redacted_thinking")
+        )
+    {
+        block["cache_control"] = json!({"type": "ephemeral"});
+    }
+    messages
+}
+
+/// Images arrive as data URLs from pasted files, or as plain URLs.
+fn image_block(image_url: &str) -> Value {
+    if let Some(rest) = image_url.strip_prefix("data:")
+        && let Some((media_type, data)) = rest.split_once(";base64,")
+    {
+        return json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": media_type, "data": data},
+        });
+    }
+    json!({"type": "image", "source": {"type": "url", "url": image_url}})
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    MessageStart {
+        message: MessageStart,
+    },
+    ContentBlockStart {
+        index: u64,
+        content_block: ContentBlock,
+    },
+    ContentBlockDelta {
+        index: u64,
+        delta: ContentBlockDelta,
+    },
+    ContentBlockStop {
+        index: u64,
+    },
+    MessageDelta {
+        usage: Option<Usage>,
+    },
+    MessageStop,
+    Error {
+        error: ApiError,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageStart {
+    id: String,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    Text {
+        #[serde(default)]
+        text: String,
+    },
+    Thinking {
+        #[serde(default)]
+        thinking: String,
+        #[serde(default)]
+        signature: String,
+    },
+    RedactedThinking {
+        data: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        /// The input arrives as JSON fragments in `input_json_delta`s.
+        #[serde(skip)]
+        input_json: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlockDelta {
+    TextDelta {
+        text: String,
+    },
+    ThinkingDelta {
+        thinking: String,
+    },
+    SignatureDelta {
+        signature: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// Token counts as reported by the API. `message_start` carries all of them
+/// and `message_delta` repeats the running totals that changed.
+#[derive(Debug, Default, Deserialize)]
+struct Usage {
+    input_tokens: Option<u64>,
+    cache_creation_input_tokens: Option<u64>,
+    cache_read_input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+}
+
+impl Usage {
+    fn update(&mut self, other: Usage) {
+        self.input_tokens = other.input_tokens.or(self.input_tokens);
+        self.cache_creation_input_tokens = other
+            .cache_creation_input_tokens
+            .or(self.cache_creation_input_tokens);
+        self.cache_read_input_tokens = other
+            .cache_read_input_tokens
+            .or(self.cache_read_input_tokens);
+        self.output_tokens = other.output_tokens.or(self.output_tokens);
+    }
+}
+
+impl From<&Usage> for TokenUsage {
+    /// `input_tokens` excludes cached tokens in the Messages API but includes
+    /// them in [`TokenUsage`], matching the Responses API.
+    fn from(usage: &Usage) -> Self {
+        let cached_input_tokens = usage.cache_read_input_tokens.unwrap_or(0);
+        let cache_write_input_tokens = usage.cache_creation_input_tokens.unwrap_or(0);
+        let input_tokens =
+            usage.input_tokens.unwrap_or(0) + cache_write_input_tokens + cached_input_tokens;
+        let output_tokens = usage.output_tokens.unwrap_or(0);
+        TokenUsage {
+            input_tokens,
+            cached_input_tokens,
+            cache_write_input_tokens,
+            output_tokens,
+            reasoning_output_tokens: 0,
+            total_tokens: input_tokens + output_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    #[serde(rename = "type")]
+    kind: String,
+    message: String,
+}
+
+/// SSE processor for the Messages API streaming format.
+async fn process_anthropic_sse<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent>>,
+    idle_timeout: Duration,
+    freeform_tools: HashSet<String>,
+) where
+    S: Stream<Item = Result<Bytes>> + Unpin,
+{
+    let mut stream = stream.eventsource();
+    let mut response_id = String::new();
+    let mut usage = Usage::default();
+    let mut blocks: BTreeMap<u64, ContentBlock> = BTreeMap::new();
+
+    loop {
+        let sse = match timeout(idle_timeout, stream.next()).await {
+            Ok(Some(Ok(sse))) => sse,
+            Ok(Some(Err(e))) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(e.to_string(), None)))
+                    .await;
+                return;
+            }
+            Ok(None) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "stream closed before message_stop".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+            Err(_) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "idle timeout waiting for SSE".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+        };
+
+        trace!("anthropic SSE event: {}", sse.data);
+        let event = match serde_json::from_str::<StreamEvent>(&sse.data) {
+            Ok(event) => event,
+            Err(e) => {
+                debug!(
+                    "Failed to parse Anthropic SSE event: {e}, data: {}",
+                    sse.data
+                );
+                continue;
+            }
+        };
+
+        match event {
+            StreamEvent::MessageStart { message } => {
+                response_id = message.id;
+                if let Some(start_usage) = message.usage {
+                    usage.update(start_usage);
+                }
+                let _ = tx_event.send(Ok(ResponseEvent::Created)).await;
+            }
+            StreamEvent::ContentBlockStart {
+                index,
+                content_block,
+            } => {
+                blocks.insert(index, content_block);
+            }
+            StreamEvent::ContentBlockDelta { index, delta } => {
+                let Some(block) = blocks.get_mut(&index) else {
+                    continue;
+                };
+                let event = match (block, delta) {
+                    (ContentBlock::Text { text }, ContentBlockDelta::TextDelta { text: delta }) => {
+                        text.push_str(&delta);
+                        Some(ResponseEvent::OutputTextDelta(delta))
+                    }
+                    (
+                        ContentBlock::Thinking { thinking, .. },
+                        ContentBlockDelta::ThinkingDelta { thinking: delta },
+                    ) => {
+                        thinking.push_str(&delta);
+                        Some(ResponseEvent::ReasoningSummaryDelta(delta))
+                    }
+                    (
+                        ContentBlock::Thinking { signature, .. },
+                        ContentBlockDelta::SignatureDelta { signature: delta },
+                    ) => {
+                        signature.push_str(&delta);
+                        None
+                    }
+                    (
+                        ContentBlock::ToolUse { input_json, .. },
+                        ContentBlockDelta::InputJsonDelta { partial_json },
+                    ) => {
+                        input_json.push_str(&partial_json);
+                        None
+                    }
+                    _ => None,
+                };
+                if let Some(event) = event
+                    && tx_event.send(Ok(event)).await.is_err()
+                {
+                    return;
+                }
+            }
+            StreamEvent::ContentBlockStop { index } => {
+                if let Some(item) = blocks
+                    .remove(&index)
+                    .and_then(|block| block_to_item(block, &freeform_tools))
+                {
+                    let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+                }
+            }
+            StreamEvent::MessageDelta {
+                usage: Some(delta_usage),
+            } => usage.update(delta_usage),
+            StreamEvent::MessageDelta { usage: None } | StreamEvent::Other => {}
+            StreamEvent::MessageStop => {
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id,
+                        token_usage: Some(TokenUsage::from(&usage)),
+                    }))
+                    .await;
+                return;
+            }
+            StreamEvent::Error { error } => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        format!("{}: {}", error.kind, error.message),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+        }
+    }
+}
+
+/// The conversation item for a finished content block.
+fn block_to_item(block: ContentBlock, freeform_tools: &HashSet<String>) -> Option<ResponseItem> {
+    match block {
+        ContentBlock::Text { text } if !text.is_empty() => Some(ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText { text }],
+        }),
+        ContentBlock::Thinking {
+            thinking,
+            signature,
+        } => Some(ResponseItem::Reasoning {
+            id: String::new(),
+            summary: vec![ReasoningItemReasoningSummary::SummaryText { text: thinking }],
+            content: None,
+            encrypted_content: Some(format!("{REASONING_ORIGIN}{signature}")),
+        }),
+        ContentBlock::RedactedThinking { data } => Some(ResponseItem::Reasoning {
+            id: String::new(),
+            summary: Vec::new(),
+            content: None,
+            encrypted_content: Some(format!("{REASONING_ORIGIN}{data}")),
+        }),
+        ContentBlock::ToolUse {
+            id,
+            name,
+            input_json,
+        } => {
+            let arguments = if input_json.trim().is_empty() {
+                "{}".to_string()
+            } else {
+                input_json
+            };
+            if name == FINAL_OUTPUT_TOOL_NAME {
+                // The forced output-schema tool carries the final answer.
+                return Some(ResponseItem::Message {
+                    id: None,
+                    role: "assistant".to_string(),
+                    content: vec![ContentItem::OutputText { text: arguments }],
+                });
+            }
+            if freeform_tools.contains(&name) {
+                let input = serde_json::from_str::<Value>(&arguments)
+                    .ok()
+                    .and_then(|args| {
+                        args.get("input")
+                            .and_then(Value::as_str)
+                            .map(str::to_string)
+                    })
+                    .unwrap_or_default();
+                return Some(ResponseItem::CustomToolCall {
+                    id: None,
+                    status: None,
+                    call_id: id,
+                    name,
+                    input,
+                });
+            }
+            Some(ResponseItem::FunctionCall {
+                id: None,
+                name,
+                arguments,
+                call_id: id,
+            })
+        }
+        ContentBlock::Text { .. } | ContentBlock::Other => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    fn user(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn build_messages_groups_tool_use_and_results_by_role() {
+        let input = vec![
+            user("list files"),
+            ResponseItem::Reasoning {
+                id: String::new(),
+                summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: "use ls".to_string(),
+                }],
+                content: None,
+                encrypted_content: Some("anthropic:sig".to_string()),
+            },
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["ls"]}"#.to_string(),
+                call_id: "toolu_1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "toolu_1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "a.txt".to_string(),
+                    success: Some(false),
+                },
+            },
+            user("thanks"),
+        ];
+
+        assert_eq!(
+            build_messages(&input),
+            vec![
+                json!({"role": "user", "content": [{"type": "text", "text": "list files"}]}),
+                json!({"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "use ls", "signature": "sig"},
+                    {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {"command": ["ls"]}},
+                ]}),
+                json!({"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "a.txt", "is_error": true},
+                    {"type": "text", "text": "thanks", "cache_control": {"type": "ephemeral"}},
+                ]}),
+            ]
+        );
+    }
+
+    #[test]
+    fn build_messages_drops_foreign_reasoning_and_converts_images() {
+        let input = vec![
+            ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputImage {
+                    image_url: "data:image/png;base64,AAAA".to_string(),
+                }],
+            },
+            ResponseItem::Reasoning {
+                id: "rs_1".to_string(),
+                summary: Vec::new(),
+                content: None,
+                encrypted_content: None,
+            },
+            // Reasoning from the Responses API, e.g. before a failover.
+            ResponseItem::Reasoning {
+                id: "rs_2".to_string(),
+                summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: "think".to_string(),
+                }],
+                content: None,
+                encrypted_content: Some("gAAAAB".to_string()),
+            },
+        ];
+
+        assert_eq!(
+            build_messages(&input),
+            vec![json!({"role": "user", "content": [{
+                "type": "image",
+                "source": {"type": "base64", "media_type": "image/png", "data": "AAAA"},
+                "cache_control": {"type": "ephemeral"},
+            }]})]
+        );
+    }
+
+    #[test]
+    fn thinking_budget_follows_effort_and_fits_max_tokens() {
+        assert_eq!(thinking_param(ReasoningEffortConfig::Minimal, 32_000), None);
+        assert_eq!(
+            thinking_param(ReasoningEffortConfig::High, 32_000),
+            Some(json!({"type": "enabled", "budget_tokens": 16_000}))
+        );
+        assert_eq!(
+            thinking_param(ReasoningEffortConfig::Low, 64_000),
+            Some(json!({"type": "enabled", "budget_tokens": 4_096}))
+        );
+        assert_eq!(thinking_param(ReasoningEffortConfig::Medium, 1_024), None);
+    }
+
+    #[test]
+    fn usage_counts_cached_tokens_as_input() {
+        let mut usage = Usage {
+            input_tokens: Some(10),
+            cache_creation_input_tokens: Some(100),
+            cache_read_input_tokens: Some(1_000),
+            output_tokens: Some(1),
+        };
+        usage.update(Usage {
+            output_tokens: Some(42),
+            ..Usage::default()
+        });
+
+        let token_usage = TokenUsage::from(&usage);
+        assert_eq!(
+            (
+                token_usage.input_tokens,
+                token_usage.cached_input_tokens,
+                token_usage.cache_write_input_tokens,
+                token_usage.output_tokens,
+                token_usage.total_tokens,
+            ),
+            (1_110, 1_000, 100, 42, 1_152)
+        );
+    }
+}
@@ -106,19 +106,38 @@ pub(crate) fn convert_apply_patch_to_protocol(
     let mut result = HashMap::with_capacity(changes.len());
     for (path, change) in changes {
         let protocol_change = match change {
-            ApplyPatchFileChange::Add { content } => FileChange::Add {
+            ApplyPatchFileChange::Add { content, mode } => FileChange::Add {
                 content: content.clone(),
+                mode: *mode,
             },
-            ApplyPatchFileChange::Delete { content } => FileChange::Delete {
-                content: content.clone(),
+            ApplyPatchFileChange::Delete { contents } => match std::str::from_utf8(contents) {
+                Ok(content) => FileChange::Delete {
+                    content: content.to_string(),
+                    binary_size: None,
+                },
+                Err(_) => FileChange::Delete {
+                    content: String::new(),
+                    binary_size: Some(contents.len() as u64),
+                },
             },
             ApplyPatchFileChange::Update {
                 unified_diff,
                 move_path,
                 new_content: _new_content,
+                mode,
             } => FileChange::Update {
                 unified_diff: unified_diff.clone(),
                 move_path: move_path.clone(),
+                mode: *mode,
+            },
+            ApplyPatchFileChange::Binary {
+                contents,
+                replace,
+                mode,
+            } => FileChange::Binary {
+                size: contents.len() as u64,
+                replace: *replace,
+                mode: *mode,
             },
         };
         result.insert(path.clone(), protocol_change);
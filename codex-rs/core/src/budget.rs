@@ -0,0 +1,150 @@
+//! Limits on how much work a session may do before its task is stopped.
+//!
+//! Budgets cover the whole conversation, not a single task: turns, tool calls
+//! and tokens accumulate across user inputs and the timeout runs from the
+//! moment the session starts. Unattended runs (`codex exec`, MCP clients) use
+//! them to bound cost.
+
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::protocol::BudgetExhausted;
+
+/// Limits for a session. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionBudget {
+    /// Maximum number of model requests.
+    pub max_turns: Option<u64>,
+    /// Maximum number of tokens (input plus output) across all requests.
+    pub max_tokens: Option<u64>,
+    /// Maximum wall-clock time for the session.
+    pub timeout: Option<Duration>,
+    /// Maximum number of tool calls the model may make.
+    pub max_tool_calls: Option<u64>,
+}
+
+impl SessionBudget {
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Usage counted against a [`SessionBudget`].
+#[derive(Debug)]
+pub(crate) struct BudgetTracker {
+    budget: SessionBudget,
+    started: Instant,
+    turns: u64,
+    tool_calls: u64,
+}
+
+impl BudgetTracker {
+    pub(crate) fn new(budget: SessionBudget) -> Self {
+        Self {
+            budget,
+            started: Instant::now(),
+            turns: 0,
+            tool_calls: 0,
+        }
+    }
+
+    /// When the session times out, if it has a timeout.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.budget.timeout.map(|timeout| self.started + timeout)
+    }
+
+    /// Count a completed model turn.
+    pub(crate) fn record_turn(&mut self) {
+        self.turns += 1;
+    }
+
+    /// Count a tool call about to be dispatched. Returns `false`, without
+    /// counting it, when the tool call budget is already spent.
+    pub(crate) fn claim_tool_call(&mut self) -> bool {
+        if self
+            .budget
+            .max_tool_calls
+            .is_some_and(|limit| self.tool_calls >= limit)
+        {
+            return false;
+        }
+        self.tool_calls += 1;
+        true
+    }
+
+    /// The first budget that has run out, given the session's total token
+    /// usage so far.
+    pub(crate) fn exhausted(&self, total_tokens: u64) -> Option<BudgetExhausted> {
+        let reached = |limit: Option<u64>, used: u64| limit.is_some_and(|limit| used >= limit);
+        if self
+            .deadline()
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            Some(BudgetExhausted::Timeout)
+        } else if reached(self.budget.max_turns, self.turns) {
+            Some(BudgetExhausted::MaxTurns)
+        } else if reached(self.budget.max_tool_calls, self.tool_calls) {
+            Some(BudgetExhausted::MaxToolCalls)
+        } else if reached(self.budget.max_tokens, total_tokens) {
+            Some(BudgetExhausted::MaxTokens)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn reports_the_first_exhausted_budget() {
+        let mut tracker = BudgetTracker::new(SessionBudget {
+            max_turns: Some(3),
+            max_tokens: Some(1_000),
+            max_tool_calls: Some(2),
+            timeout: None,
+        });
+        assert_eq!(tracker.exhausted(0), None);
+
+        tracker.record_turn();
+        assert!(tracker.claim_tool_call());
+        assert_eq!(tracker.exhausted(999), None);
+        assert_eq!(tracker.exhausted(1_000), Some(BudgetExhausted::MaxTokens));
+
+        tracker.record_turn();
+        assert!(tracker.claim_tool_call());
+        assert_eq!(tracker.exhausted(0), Some(BudgetExhausted::MaxToolCalls));
+
+        tracker.record_turn();
+        assert_eq!(tracker.exhausted(0), Some(BudgetExhausted::MaxTurns));
+    }
+
+    #[test]
+    fn refuses_tool_calls_beyond_the_budget_within_a_turn() {
+        let mut tracker = BudgetTracker::new(SessionBudget {
+            max_tool_calls: Some(2),
+            ..Default::default()
+        });
+        let claimed: Vec<bool> = (0..4).map(|_| tracker.claim_tool_call()).collect();
+        assert_eq!(claimed, vec![true, true, false, false]);
+
+        let mut unlimited = BudgetTracker::new(SessionBudget::default());
+        assert!((0..100).all(|_| unlimited.claim_tool_call()));
+    }
+
+    #[test]
+    fn timeout_runs_from_session_start() {
+        let tracker = BudgetTracker::new(SessionBudget {
+            timeout: Some(Duration::ZERO),
+            ..Default::default()
+        });
+        assert_eq!(tracker.exhausted(0), Some(BudgetExhausted::Timeout));
+        assert!(
+            BudgetTracker::new(SessionBudget::default())
+                .deadline()
+                .is_none()
+        );
+    }
+}
@@ -22,7 +22,11 @@ use crate::client_common::ResponseStream;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::model_family::ModelFamily;
+use crate::model_recording::ModelStreamRecording;
+use crate::model_recording::ModelTurn;
+use crate::model_recording::record_response;
 use crate::openai_tools::create_tools_json_for_chat_completions_api;
+use crate::output_schema::FINAL_OUTPUT_TOOL_NAME;
 use crate::util::backoff;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ReasoningItemContent;
@@ -34,6 +38,7 @@ pub(crate) async fn stream_chat_completions(
     model_family: &ModelFamily,
     client: &reqwest::Client,
     provider: &ModelProviderInfo,
+    recording: Option<&ModelStreamRecording>,
 ) -> Result<ResponseStream> {
     // Build messages array
     let mut messages = Vec::<serde_json::Value>::new();
@@ -267,13 +272,35 @@ pub(crate) async fn stream_chat_completions(
         }
     }
 
-    let tools_json = create_tools_json_for_chat_completions_api(&prompt.tools)?;
-    let payload = json!({
+    let mut tools_json = create_tools_json_for_chat_completions_api(&prompt.tools)?;
+    // Chat Completions has no response format shared by every provider, so
+    // the schema becomes a tool the model is asked to call with its answer.
+    // `process_chat_sse` turns that call back into a message.
+    if let Some(schema) = &prompt.output_schema {
+        tools_json.push(json!({
+            "type": "function",
+            "function": {
+                "name": FINAL_OUTPUT_TOOL_NAME,
+                "description": "Submit the final answer. Call this exactly once, when the task is complete.",
+                "parameters": schema,
+            }
+        }));
+    }
+    let mut payload = json!({
         "model": model_family.slug,
         "messages": messages,
         "stream": true,
-        "tools": tools_json,
     });
+    // Some servers reject `tools` outright for models that cannot call them.
+    if !tools_json.is_empty() {
+        payload["tools"] = json!(tools_json);
+    }
+    if prompt.forces_final_output_tool() {
+        payload["tool_choice"] = json!({
+            "type": "function",
+            "function": {"name": FINAL_OUTPUT_TOOL_NAME},
+        });
+    }
 
     debug!(
         "POST to {}: {}",
@@ -281,6 +308,22 @@ pub(crate) async fn stream_chat_completions(
         serde_json::to_string_pretty(&payload).unwrap_or_default()
     );
 
+    let mut record_to = None;
+    if let Some(recording) = recording {
+        match recording.begin_turn(&payload).await? {
+            ModelTurn::Replayed(bytes) => {
+                let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+                tokio::spawn(process_chat_sse(
+                    futures::stream::iter([Ok(bytes)]),
+                    tx_event,
+                    provider.stream_idle_timeout(),
+                ));
+                return Ok(ResponseStream { rx_event });
+            }
+            ModelTurn::Recording { dir, turn } => record_to = Some((dir, turn)),
+        }
+    }
+
     let mut attempt = 0;
     let max_retries = provider.request_max_retries();
     loop {
@@ -298,11 +341,15 @@ pub(crate) async fn stream_chat_completions(
             Ok(resp) if resp.status().is_success() => {
                 let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
                 let stream = resp.bytes_stream().map_err(CodexErr::Reqwest);
-                tokio::spawn(process_chat_sse(
-                    stream,
-                    tx_event,
-                    provider.stream_idle_timeout(),
-                ));
+                let idle_timeout = provider.stream_idle_timeout();
+                match &record_to {
+                    Some((dir, turn)) => tokio::spawn(process_chat_sse(
+                        record_response(stream, dir, *turn).await,
+                        tx_event,
+                        idle_timeout,
+                    )),
+                    None => tokio::spawn(process_chat_sse(stream, tx_event, idle_timeout)),
+                };
                 return Ok(ResponseStream { rx_event });
             }
             Ok(res) => {
@@ -561,12 +608,25 @@ async fn process_chat_sse<S>(
                             let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
                         }
 
-                        // Then emit the FunctionCall response item.
-                        let item = ResponseItem::FunctionCall {
-                            id: None,
-                            name: fn_call_state.name.clone().unwrap_or_else(|| "".to_string()),
-                            arguments: fn_call_state.arguments.clone(),
-                            call_id: fn_call_state.call_id.clone().unwrap_or_else(String::new),
+                        // The forced output-schema tool carries the final
+                        // answer; surface it as the assistant message.
+                        let item = if fn_call_state.name.as_deref() == Some(FINAL_OUTPUT_TOOL_NAME)
+                        {
+                            ResponseItem::Message {
+                                role: "assistant".to_string(),
+                                content: vec![ContentItem::OutputText {
+                                    text: fn_call_state.arguments.clone(),
+                                }],
+                                id: None,
+                            }
+                        } else {
+                            // Then emit the FunctionCall response item.
+                            ResponseItem::FunctionCall {
+                                id: None,
+                                name: fn_call_state.name.clone().unwrap_or_else(|| "".to_string()),
+                                arguments: fn_call_state.arguments.clone(),
+                                call_id: fn_call_state.call_id.clone().unwrap_or_else(String::new),
+                            }
                         };
 
                         let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
@@ -0,0 +1,809 @@
+//! Restorable copies of the files the agent edits, so `/undo` and `/rewind`
+//! can revert a conversation's changes to the workspace.
+//!
+//! When a user turn begins, every file in the workspace that git would not
+//! ignore is copied into a content-addressed store under
+//! `$CODEX_HOME/checkpoints/<conversation id>`, so changes made by shell
+//! commands are covered as well as those made by `apply_patch`. Files the
+//! agent patches outside the workspace, or that are ignored or too large to
+//! snapshot, are copied before `apply_patch` first changes them. When the turn
+//! ends the workspace is compared with its snapshot and only the files that
+//! changed are kept, along with their new contents, so edits made by the user
+//! afterwards can be detected.
+//!
+//! Checkpoints are keyed by user turn, carried over when a conversation is
+//! forked, pruned to the newest [`MAX_CHECKPOINTS`] turns, and removed along
+//! with their session.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use codex_protocol::mcp_protocol::ConversationId;
+use serde::Deserialize;
+use serde::Serialize;
+use sha1::Digest;
+use sha1::Sha1;
+use tracing::warn;
+
+const CHECKPOINTS_SUBDIR: &str = "checkpoints";
+const BLOBS_SUBDIR: &str = "blobs";
+const STATE_FILE: &str = "checkpoints.json";
+
+/// User turns whose changes can be restored; older checkpoints are dropped.
+pub(crate) const MAX_CHECKPOINTS: usize = 50;
+
+/// Workspaces with more files than this are not snapshotted; only the files
+/// `apply_patch` changes are then restorable.
+const MAX_SNAPSHOT_FILES: usize = 20_000;
+
+/// Larger files are left out of workspace snapshots.
+const MAX_SNAPSHOT_FILE_BYTES: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum CheckpointError {
+    #[error("only {available} turn(s) can be undone")]
+    NotEnoughCheckpoints { requested: usize, available: usize },
+    #[error("failed to restore {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+}
+
+/// One file changed during a user turn. `before` and `after` name stored
+/// copies of its contents; `None` means the file did not exist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct TrackedFile {
+    path: PathBuf,
+    before: Option<String>,
+    #[serde(default)]
+    after: Option<String>,
+}
+
+/// The files changed during one user turn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Checkpoint {
+    /// Zero-based index of the user turn in the conversation.
+    turn: usize,
+    files: Vec<TrackedFile>,
+    /// Set once the turn is over and `files` holds what it changed.
+    #[serde(default)]
+    finished: bool,
+    /// While the turn runs, the stored copy of each file as it was when the
+    /// turn began (`None` when it did not exist).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    snapshot: BTreeMap<PathBuf, Option<String>>,
+    /// Whether `snapshot` covers the whole workspace, so files missing from
+    /// it were created during the turn.
+    #[serde(default)]
+    workspace: bool,
+    /// Workspace files too large or unreadable to snapshot, which are left
+    /// alone unless `apply_patch` changes them.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    skipped: BTreeSet<PathBuf>,
+}
+
+/// The files of the workspace that git would not ignore.
+#[derive(Debug, Default)]
+struct WorkspaceSnapshot {
+    /// Blob id of each stored file.
+    files: BTreeMap<PathBuf, String>,
+    /// Files that were not stored.
+    skipped: BTreeSet<PathBuf>,
+}
+
+/// Outcome of [`CheckpointStore::rewind`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct RewindOutcome {
+    /// False when nothing was restored because of `conflicts`.
+    pub(crate) applied: bool,
+    /// Workspace-relative paths that were rewritten or removed.
+    pub(crate) restored: Vec<PathBuf>,
+    /// Workspace-relative paths changed by the agent and edited again since.
+    pub(crate) conflicts: Vec<PathBuf>,
+}
+
+pub(crate) struct CheckpointStore {
+    dir: PathBuf,
+    cwd: PathBuf,
+    checkpoints: Mutex<Vec<Checkpoint>>,
+}
+
+impl CheckpointStore {
+    /// Open the checkpoints of `conversation_id`, e.g. when it is resumed.
+    pub(crate) async fn load(
+        codex_home: &Path,
+        conversation_id: ConversationId,
+        cwd: &Path,
+    ) -> Self {
+        let dir = checkpoints_dir(codex_home, conversation_id);
+        let checkpoints = read_state(&dir).await;
+        Self {
+            dir,
+            cwd: cwd.to_path_buf(),
+            checkpoints: Mutex::new(checkpoints),
+        }
+    }
+
+    /// Take over the checkpoints of the first `turns` user turns of `parent`,
+    /// the conversation this one was forked from.
+    pub(crate) async fn inherit(&self, codex_home: &Path, parent: ConversationId, turns: usize) {
+        let parent_dir = checkpoints_dir(codex_home, parent);
+        let inherited: Vec<Checkpoint> = read_state(&parent_dir)
+            .await
+            .into_iter()
+            .filter(|checkpoint| checkpoint.turn < turns)
+            .collect();
+        if inherited.is_empty() {
+            return;
+        }
+        for id in referenced_blobs(&inherited) {
+            let from = parent_dir.join(BLOBS_SUBDIR).join(&id);
+            if let Err(err) = self.copy_blob(&from, &id).await {
+                warn!("failed to carry over checkpoint of {}: {err}", parent);
+                return;
+            }
+        }
+        *self.lock() = inherited;
+        self.persist().await;
+    }
+
+    /// Start the checkpoint of user turn `turn`, finishing the previous one,
+    /// and snapshot the workspace.
+    pub(crate) async fn begin_turn(&self, turn: usize) {
+        self.finish_open_checkpoint().await;
+        let workspace = self.snapshot_workspace().await;
+        {
+            let mut checkpoints = self.lock();
+            let covered = workspace.is_some();
+            let WorkspaceSnapshot { files, skipped } = workspace.unwrap_or_default();
+            checkpoints.push(Checkpoint {
+                turn,
+                files: Vec::new(),
+                finished: false,
+                snapshot: files
+                    .into_iter()
+                    .map(|(path, id)| (path, Some(id)))
+                    .collect(),
+                workspace: covered,
+                skipped,
+            });
+            let excess = checkpoints.len().saturating_sub(MAX_CHECKPOINTS);
+            checkpoints.drain(..excess);
+        }
+        // Only after the snapshot, so unchanged files keep their stored copy.
+        self.collect_garbage().await;
+        self.persist().await;
+    }
+
+    /// Copy the current contents of `paths` before the agent changes them,
+    /// unless the open checkpoint's snapshot already holds them.
+    pub(crate) async fn record_before(&self, paths: &[PathBuf]) {
+        let mut recorded = false;
+        for path in paths {
+            let is_new = self.lock().last().is_some_and(|checkpoint| {
+                !checkpoint.finished && !checkpoint.snapshot.contains_key(path)
+            });
+            if !is_new {
+                continue;
+            }
+            let before = match self.store_blob(path).await {
+                Ok(before) => before,
+                Err(err) => {
+                    warn!("failed to checkpoint {}: {err}", path.display());
+                    continue;
+                }
+            };
+            if let Some(checkpoint) = self.lock().last_mut() {
+                checkpoint.snapshot.insert(path.clone(), before);
+                recorded = true;
+            }
+        }
+        if recorded {
+            self.persist().await;
+        }
+    }
+
+    /// Record which files changed during the open checkpoint's turn, and
+    /// what they look like now that it is over.
+    pub(crate) async fn end_turn(&self) {
+        self.finish_open_checkpoint().await;
+        self.persist().await;
+    }
+
+    /// Restore the files changed since user turn `user_turns - turns` began,
+    /// where `user_turns` is the number of turns in the conversation. Files
+    /// the user edited afterwards are reported as conflicts and, unless
+    /// `force` is set, nothing is restored.
+    pub(crate) async fn rewind(
+        &self,
+        turns: usize,
+        user_turns: usize,
+        force: bool,
+    ) -> Result<RewindOutcome, CheckpointError> {
+        if turns == 0 || turns > user_turns {
+            return Err(CheckpointError::NotEnoughCheckpoints {
+                requested: turns,
+                available: user_turns,
+            });
+        }
+        let target = user_turns - turns;
+        // A turn cut short by a crash is compared with the workspace as is.
+        self.finish_open_checkpoint().await;
+
+        // Per path, the state before the earliest rewound turn touched it and
+        // the state after the latest one did (`None` while still unknown).
+        let mut files: BTreeMap<PathBuf, (Option<String>, Option<Option<String>>)> =
+            BTreeMap::new();
+        for checkpoint in self.lock().iter().filter(|c| c.turn >= target) {
+            for file in &checkpoint.files {
+                let after = checkpoint.finished.then(|| file.after.clone());
+                files
+                    .entry(file.path.clone())
+                    .and_modify(|(_, latest)| *latest = after.clone())
+                    .or_insert((file.before.clone(), after));
+            }
+        }
+
+        let mut restore = Vec::new();
+        let mut conflicts = Vec::new();
+        for (path, (before, after)) in files {
+            let current = hash_file(&path)
+                .await
+                .map_err(|source| CheckpointError::Io {
+                    path: path.clone(),
+                    source,
+                })?;
+            if after.is_some_and(|after| after != current) {
+                conflicts.push(self.display_path(&path));
+            }
+            if before != current {
+                restore.push((path, before));
+            }
+        }
+        if !conflicts.is_empty() && !force {
+            return Ok(RewindOutcome {
+                applied: false,
+                restored: Vec::new(),
+                conflicts,
+            });
+        }
+
+        let mut restored = Vec::new();
+        for (path, before) in restore {
+            self.restore_file(&path, before.as_deref())
+                .await
+                .map_err(|source| CheckpointError::Io {
+                    path: path.clone(),
+                    source,
+                })?;
+            restored.push(self.display_path(&path));
+        }
+
+        self.lock().retain(|checkpoint| checkpoint.turn < target);
+        self.collect_garbage().await;
+        self.persist().await;
+
+        Ok(RewindOutcome {
+            applied: true,
+            restored,
+            conflicts,
+        })
+    }
+
+    async fn finish_open_checkpoint(&self) {
+        let (mut before, workspace, mut skipped) = match self.lock().last() {
+            Some(checkpoint) if !checkpoint.finished => (
+                checkpoint.snapshot.clone(),
+                checkpoint.workspace,
+                checkpoint.skipped.clone(),
+            ),
+            _ => return,
+        };
+        // Checkpoints persisted before workspace snapshots list the files
+        // `apply_patch` changed instead.
+        if let Some(checkpoint) = self.lock().last() {
+            for file in &checkpoint.files {
+                before
+                    .entry(file.path.clone())
+                    .or_insert_with(|| file.before.clone());
+            }
+        }
+
+        let mut after: BTreeMap<PathBuf, Option<String>> = BTreeMap::new();
+        if workspace && let Some(snapshot) = self.snapshot_workspace().await {
+            after.extend(
+                snapshot
+                    .files
+                    .into_iter()
+                    .map(|(path, id)| (path, Some(id))),
+            );
+            skipped.extend(snapshot.skipped);
+        }
+        for path in before.keys() {
+            if after.contains_key(path) {
+                continue;
+            }
+            let id = match self.store_blob(path).await {
+                Ok(id) => id,
+                Err(err) => {
+                    warn!("failed to checkpoint {}: {err}", path.display());
+                    None
+                }
+            };
+            after.insert(path.clone(), id);
+        }
+
+        // A file that could not be stored at either end is not known to have
+        // been created or deleted during the turn.
+        let paths: BTreeSet<&PathBuf> = before
+            .keys()
+            .chain(after.keys().filter(|path| !skipped.contains(*path)))
+            .collect();
+        let files: Vec<TrackedFile> = paths
+            .into_iter()
+            .filter_map(|path| {
+                let before = before.get(path).cloned().flatten();
+                let after = after.get(path).cloned().flatten();
+                (before != after).then(|| TrackedFile {
+                    path: path.clone(),
+                    before,
+                    after,
+                })
+            })
+            .collect();
+        if let Some(checkpoint) = self.lock().last_mut() {
+            checkpoint.files = files;
+            checkpoint.snapshot.clear();
+            checkpoint.skipped.clear();
+            checkpoint.finished = true;
+        }
+    }
+
+    /// Store every file in the workspace that git would not ignore, or return
+    /// `None` when the workspace is too large or cannot be read.
+    async fn snapshot_workspace(&self) -> Option<WorkspaceSnapshot> {
+        let root = self.cwd.clone();
+        let blobs = self.dir.join(BLOBS_SUBDIR);
+        // Never snapshot the checkpoints themselves.
+        let exclude = self.dir.parent().unwrap_or(&self.dir).to_path_buf();
+        let result =
+            tokio::task::spawn_blocking(move || snapshot_workspace(&root, &blobs, &exclude)).await;
+        match result {
+            Ok(Ok(files)) => files,
+            Ok(Err(err)) => {
+                warn!("failed to snapshot {}: {err}", self.cwd.display());
+                None
+            }
+            Err(err) => {
+                warn!("failed to snapshot {}: {err}", self.cwd.display());
+                None
+            }
+        }
+    }
+
+    /// Copy `path` into the blob store and return its id, or `None` when the
+    /// file does not exist.
+    async fn store_blob(&self, path: &Path) -> io::Result<Option<String>> {
+        let contents = match tokio::fs::read(path).await {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let id = blob_id(&contents);
+        let blob = self.dir.join(BLOBS_SUBDIR).join(&id);
+        if !tokio::fs::try_exists(&blob).await? {
+            tokio::fs::create_dir_all(self.dir.join(BLOBS_SUBDIR)).await?;
+            tokio::fs::write(&blob, contents).await?;
+        }
+        Ok(Some(id))
+    }
+
+    async fn copy_blob(&self, from: &Path, id: &str) -> io::Result<()> {
+        let blobs = self.dir.join(BLOBS_SUBDIR);
+        tokio::fs::create_dir_all(&blobs).await?;
+        let to = blobs.join(id);
+        if tokio::fs::hard_link(from, &to).await.is_err() && !tokio::fs::try_exists(&to).await? {
+            tokio::fs::copy(from, &to).await?;
+        }
+        Ok(())
+    }
+
+    async fn restore_file(&self, path: &Path, blob: Option<&str>) -> io::Result<()> {
+        match blob {
+            Some(id) => {
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::copy(self.dir.join(BLOBS_SUBDIR).join(id), path).await?;
+                Ok(())
+            }
+            None => match tokio::fs::remove_file(path).await {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// Delete stored copies no remaining checkpoint refers to.
+    async fn collect_garbage(&self) {
+        let referenced = referenced_blobs(&self.lock());
+        let Ok(mut entries) = tokio::fs::read_dir(self.dir.join(BLOBS_SUBDIR)).await else {
+            return;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !referenced.contains(&name)
+                && let Err(err) = tokio::fs::remove_file(entry.path()).await
+            {
+                warn!("failed to prune checkpoint blob {name}: {err}");
+            }
+        }
+    }
+
+    fn display_path(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.cwd).unwrap_or(path).to_path_buf()
+    }
+
+    async fn persist(&self) {
+        let checkpoints = self.lock().clone();
+        let result = async {
+            let json = serde_json::to_string(&checkpoints).map_err(io::Error::other)?;
+            tokio::fs::create_dir_all(&self.dir).await?;
+            tokio::fs::write(self.dir.join(STATE_FILE), json).await
+        }
+        .await;
+        if let Err(err) = result {
+            warn!("failed to persist checkpoints: {err}");
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Checkpoint>> {
+        #[expect(clippy::expect_used)]
+        self.checkpoints.lock().expect("poisoned lock")
+    }
+}
+
+/// Delete the checkpoints of `conversation_id`, e.g. when its session is
+/// removed.
+pub(crate) async fn remove_checkpoints(
+    codex_home: &Path,
+    conversation_id: ConversationId,
+) -> io::Result<()> {
+    match tokio::fs::remove_dir_all(checkpoints_dir(codex_home, conversation_id)).await {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+fn checkpoints_dir(codex_home: &Path, conversation_id: ConversationId) -> PathBuf {
+    codex_home
+        .join(CHECKPOINTS_SUBDIR)
+        .join(conversation_id.to_string())
+}
+
+async fn read_state(dir: &Path) -> Vec<Checkpoint> {
+    tokio::fs::read_to_string(dir.join(STATE_FILE))
+        .await
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn referenced_blobs(checkpoints: &[Checkpoint]) -> HashSet<String> {
+    checkpoints
+        .iter()
+        .flat_map(|checkpoint| {
+            checkpoint
+                .files
+                .iter()
+                .flat_map(|file| [&file.before, &file.after])
+                .chain(checkpoint.snapshot.values())
+        })
+        .flatten()
+        .cloned()
+        .collect()
+}
+
+/// Copy the files under `root` that git would not ignore into `blobs`,
+/// skipping `exclude` and symlinks. Returns `None` when there are more than
+/// [`MAX_SNAPSHOT_FILES`].
+fn snapshot_workspace(
+    root: &Path,
+    blobs: &Path,
+    exclude: &Path,
+) -> io::Result<Option<WorkspaceSnapshot>> {
+    std::fs::create_dir_all(blobs)?;
+    let exclude = exclude.to_path_buf();
+    let walker = ignore::WalkBuilder::new(root)
+        .hidden(false)
+        .filter_entry(move |entry| entry.file_name() != ".git" && entry.path() != exclude)
+        .build();
+    let mut snapshot = WorkspaceSnapshot::default();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                warn!("skipping part of the workspace snapshot: {err}");
+                continue;
+            }
+        };
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+        if snapshot.files.len() + snapshot.skipped.len() == MAX_SNAPSHOT_FILES {
+            return Ok(None);
+        }
+        if entry
+            .metadata()
+            .is_ok_and(|metadata| metadata.len() > MAX_SNAPSHOT_FILE_BYTES)
+        {
+            snapshot.skipped.insert(entry.into_path());
+            continue;
+        }
+        let contents = match std::fs::read(entry.path()) {
+            Ok(contents) => contents,
+            Err(err) => {
+                warn!("failed to snapshot {}: {err}", entry.path().display());
+                snapshot.skipped.insert(entry.into_path());
+                continue;
+            }
+        };
+        let id = blob_id(&contents);
+        let blob = blobs.join(&id);
+        if !blob.exists() {
+            std::fs::write(&blob, contents)?;
+        }
+        snapshot.files.insert(entry.into_path(), id);
+    }
+    Ok(Some(snapshot))
+}
+
+fn blob_id(contents: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(contents);
+    format!("{:x}", hasher.finalize())
+}
+
+/// The blob id `path` would be stored under, without storing it.
+async fn hash_file(path: &Path) -> io::Result<Option<String>> {
+    match tokio::fs::read(path).await {
+        Ok(contents) => Ok(Some(blob_id(&contents))),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    async fn store_for(
+        codex_home: &TempDir,
+        workspace: &TempDir,
+        conversation_id: ConversationId,
+    ) -> CheckpointStore {
+        CheckpointStore::load(codex_home.path(), conversation_id, workspace.path()).await
+    }
+
+    /// Run user turn `turn`, letting `edit` change `paths` as `apply_patch`
+    /// would.
+    async fn run_turn(store: &CheckpointStore, turn: usize, paths: &[&Path], edit: impl FnOnce()) {
+        store.begin_turn(turn).await;
+        let paths: Vec<PathBuf> = paths.iter().map(|path| path.to_path_buf()).collect();
+        store.record_before(&paths).await;
+        edit();
+        store.end_turn().await;
+    }
+
+    fn read(path: &Path) -> String {
+        std::fs::read_to_string(path).expect("read")
+    }
+
+    #[tokio::test]
+    async fn undo_restores_modified_added_and_deleted_files() {
+        let codex_home = TempDir::new().expect("codex home");
+        let workspace = TempDir::new().expect("workspace");
+        let root = workspace.path();
+        let edited = root.join("edited.txt");
+        let deleted = root.join("deleted.txt");
+        let added = root.join("added.txt");
+        let shell_written = root.join("shell.txt");
+        std::fs::write(&edited, "before\n").expect("write");
+        std::fs::write(&deleted, "keep me\n").expect("write");
+        let store = store_for(&codex_home, &workspace, ConversationId::new()).await;
+
+        run_turn(&store, 0, &[&edited, &deleted, &added], || {
+            std::fs::write(&edited, "after\n").expect("write");
+            std::fs::remove_file(&deleted).expect("remove");
+            std::fs::write(&added, "new\n").expect("write");
+            // Not reported to the store, but covered by the snapshot.
+            std::fs::write(&shell_written, "shell\n").expect("write");
+        })
+        .await;
+
+        let outcome = store.rewind(1, 1, false).await.expect("rewind");
+        assert!(outcome.applied);
+        assert_eq!(outcome.conflicts, Vec::<PathBuf>::new());
+        assert_eq!(
+            outcome.restored,
+            vec![
+                PathBuf::from("added.txt"),
+                PathBuf::from("deleted.txt"),
+                PathBuf::from("edited.txt"),
+                PathBuf::from("shell.txt"),
+            ]
+        );
+        assert_eq!(read(&edited), "before\n");
+        assert_eq!(read(&deleted), "keep me\n");
+        assert!(!added.exists());
+        assert!(!shell_written.exists());
+        assert_eq!(store.lock().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn shell_changes_are_restored_and_ignored_files_left_alone() {
+        let codex_home = TempDir::new().expect("codex home");
+        let workspace = TempDir::new().expect("workspace");
+        let root = workspace.path();
+        std::fs::create_dir(root.join(".git")).expect("git dir");
+        std::fs::write(root.join(".gitignore"), "target/\n").expect("write");
+        let config = root.join("config.txt");
+        let script = root.join("script.sh");
+        let artifact = root.join("target").join("out");
+        std::fs::write(&config, "v1\n").expect("write");
+        let store = store_for(&codex_home, &workspace, ConversationId::new()).await;
+
+        run_turn(&store, 0, &[], || {
+            std::fs::write(&config, "v2\n").expect("write");
+            std::fs::write(&script, "echo hi\n").expect("write");
+            std::fs::create_dir(root.join("target")).expect("target dir");
+            std::fs::write(&artifact, "built\n").expect("write");
+        })
+        .await;
+
+        let outcome = store.rewind(1, 1, false).await.expect("rewind");
+        assert!(outcome.applied);
+        assert_eq!(
+            outcome.restored,
+            vec![PathBuf::from("config.txt"), PathBuf::from("script.sh")]
+        );
+        assert_eq!(read(&config), "v1\n");
+        assert!(!script.exists());
+        assert_eq!(read(&artifact), "built\n");
+    }
+
+    #[tokio::test]
+    async fn user_edits_after_turn_are_reported_as_conflicts() {
+        let codex_home = TempDir::new().expect("codex home");
+        let workspace = TempDir::new().expect("workspace");
+        let file = workspace.path().join("file.txt");
+        std::fs::write(&file, "one\n").expect("write");
+        let store = store_for(&codex_home, &workspace, ConversationId::new()).await;
+
+        run_turn(&store, 0, &[&file], || {
+            std::fs::write(&file, "two\n").expect("write");
+        })
+        .await;
+        std::fs::write(&file, "three\n").expect("write");
+
+        let outcome = store.rewind(1, 1, false).await.expect("rewind");
+        assert!(!outcome.applied);
+        assert_eq!(outcome.conflicts, vec![PathBuf::from("file.txt")]);
+        assert_eq!(read(&file), "three\n");
+        assert_eq!(store.lock().len(), 1);
+
+        let forced = store.rewind(1, 1, true).await.expect("forced rewind");
+        assert!(forced.applied);
+        assert_eq!(read(&file), "one\n");
+    }
+
+    #[tokio::test]
+    async fn rewind_spans_multiple_turns_and_survives_reload() {
+        let codex_home = TempDir::new().expect("codex home");
+        let workspace = TempDir::new().expect("workspace");
+        let file = workspace.path().join("file.txt");
+        std::fs::write(&file, "v1\n").expect("write");
+        let conversation_id = ConversationId::new();
+        let first = store_for(&codex_home, &workspace, conversation_id).await;
+        for (turn, version) in ["v2\n", "v3\n"].into_iter().enumerate() {
+            run_turn(&first, turn, &[&file], || {
+                std::fs::write(&file, version).expect("write");
+            })
+            .await;
+        }
+
+        let reloaded = store_for(&codex_home, &workspace, conversation_id).await;
+        assert_eq!(reloaded.lock().len(), 2);
+        let err = reloaded
+            .rewind(3, 2, false)
+            .await
+            .expect_err("too many turns");
+        assert!(matches!(
+            err,
+            CheckpointError::NotEnoughCheckpoints {
+                requested: 3,
+                available: 2
+            }
+        ));
+
+        reloaded.rewind(2, 2, false).await.expect("rewind");
+        assert_eq!(read(&file), "v1\n");
+    }
+
+    #[tokio::test]
+    async fn rewinding_twice_carries_checkpoints_across_forks() {
+        let codex_home = TempDir::new().expect("codex home");
+        let workspace = TempDir::new().expect("workspace");
+        let file = workspace.path().join("file.txt");
+        std::fs::write(&file, "v1\n").expect("write");
+        let parent_id = ConversationId::new();
+        let parent = store_for(&codex_home, &workspace, parent_id).await;
+        for (turn, version) in ["v2\n", "v3\n", "v4\n"].into_iter().enumerate() {
+            run_turn(&parent, turn, &[&file], || {
+                std::fs::write(&file, version).expect("write");
+            })
+            .await;
+        }
+
+        // `/rewind` restores the files, then forks the conversation without
+        // its last turn.
+        parent.rewind(1, 3, false).await.expect("first rewind");
+        assert_eq!(read(&file), "v3\n");
+        let child_id = ConversationId::new();
+        let child = store_for(&codex_home, &workspace, child_id).await;
+        child.inherit(codex_home.path(), parent_id, 2).await;
+        assert_eq!(child.lock().len(), 2);
+
+        // The parent's checkpoints no longer matter to the fork.
+        remove_checkpoints(codex_home.path(), parent_id)
+            .await
+            .expect("remove parent");
+
+        child.rewind(1, 2, false).await.expect("second rewind");
+        assert_eq!(read(&file), "v2\n");
+
+        let grandchild = store_for(&codex_home, &workspace, ConversationId::new()).await;
+        grandchild.inherit(codex_home.path(), child_id, 1).await;
+        grandchild.rewind(1, 1, false).await.expect("third rewind");
+        assert_eq!(read(&file), "v1\n");
+    }
+
+    #[tokio::test]
+    async fn old_checkpoints_and_their_copies_are_pruned() {
+        let codex_home = TempDir::new().expect("codex home");
+        let workspace = TempDir::new().expect("workspace");
+        let file = workspace.path().join("file.txt");
+        std::fs::write(&file, "0\n").expect("write");
+        let conversation_id = ConversationId::new();
+        let store = store_for(&codex_home, &workspace, conversation_id).await;
+        for turn in 0..MAX_CHECKPOINTS + 5 {
+            run_turn(&store, turn, &[&file], || {
+                std::fs::write(&file, format!("{}\n", turn + 1)).expect("write");
+            })
+            .await;
+        }
+
+        let checkpoints = store.lock().clone();
+        assert_eq!(checkpoints.len(), MAX_CHECKPOINTS);
+        assert_eq!(checkpoints.first().map(|c| c.turn), Some(5));
+        let blobs = std::fs::read_dir(
+            checkpoints_dir(codex_home.path(), conversation_id).join(BLOBS_SUBDIR),
+        )
+        .expect("blobs")
+        .count();
+        assert_eq!(blobs, referenced_blobs(&checkpoints).len());
+
+        remove_checkpoints(codex_home.path(), conversation_id)
+            .await
+            .expect("remove");
+        assert!(!checkpoints_dir(codex_home.path(), conversation_id).exists());
+    }
+}
@@ -21,6 +21,8 @@ use tracing::debug;
 use tracing::trace;
 use tracing::warn;
 
+use crate::anthropic_messages::is_anthropic_reasoning;
+use crate::anthropic_messages::stream_anthropic_messages;
 use crate::chat_completions::AggregateStreamExt;
 use crate::chat_completions::stream_chat_completions;
 use crate::client_common::Prompt;
@@ -35,10 +37,13 @@ use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::UsageLimitReachedError;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
+use crate::model_fallback::FallbackChain;
+use crate::model_fallback::ModelSwitch;
 use crate::model_family::ModelFamily;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
-use crate::openai_model_info::get_model_info;
+use crate::model_recording::ModelTurn;
+use crate::model_recording::record_response;
 use crate::openai_tools::create_tools_json_for_responses_api;
 use crate::protocol::TokenUsage;
 use crate::token_data::PlanType;
@@ -67,10 +72,9 @@ struct Error {
 
 #[derive(Debug, Clone)]
 pub struct ModelClient {
-    config: Arc<Config>,
+    fallback: Arc<FallbackChain>,
     auth_manager: Option<Arc<AuthManager>>,
     client: reqwest::Client,
-    provider: ModelProviderInfo,
     conversation_id: ConversationId,
     effort: Option<ReasoningEffortConfig>,
     summary: ReasoningSummaryConfig,
@@ -88,10 +92,9 @@ impl ModelClient {
         let client = create_client();
 
         Self {
-            config,
+            fallback: Arc::new(FallbackChain::new(config, provider)),
             auth_manager,
             client,
-            provider,
             conversation_id,
             effort,
             summary,
@@ -99,37 +102,59 @@ impl ModelClient {
     }
 
     pub fn get_model_context_window(&self) -> Option<u64> {
-        self.config
-            .model_context_window
-            .or_else(|| get_model_info(&self.config.model_family).map(|info| info.context_window))
+        let config = self.config();
+        config.model_context_window.or_else(|| {
+            config
+                .model_info_for(&config.model_family)
+                .map(|info| info.context_window)
+        })
     }
 
     pub fn get_auto_compact_token_limit(&self) -> Option<i64> {
-        self.config.model_auto_compact_token_limit.or_else(|| {
-            get_model_info(&self.config.model_family).and_then(|info| info.auto_compact_token_limit)
+        let config = self.config();
+        config.model_auto_compact_token_limit.or_else(|| {
+            config
+                .model_info_for(&config.model_family)
+                .and_then(|info| info.auto_compact_token_limit)
         })
     }
 
-    /// Dispatches to either the Responses or Chat implementation depending on
-    /// the provider config.  Public callers always invoke `stream()` – the
-    /// specialised helpers are private to avoid accidental misuse.
+    /// Streams the prompt from the active model. Requests that fail in a way
+    /// `fallback_policy` fails over on are retried on the next fallback model.
     pub async fn stream(&self, prompt: &Prompt) -> Result<ResponseStream> {
-        match self.provider.wire_api {
+        loop {
+            match self.stream_active_model(prompt).await {
+                Err(err) if self.fallback.fail_over(&err) => {
+                    warn!("{err}; retrying on {}", self.fallback.active().label);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Dispatches to the Responses, Chat or Anthropic implementation depending
+    /// on the provider config.  Public callers always invoke `stream()` – the
+    /// specialised helpers are private to avoid accidental misuse.
+    async fn stream_active_model(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        let config = self.config();
+        let provider = self.provider();
+        match provider.wire_api {
             WireApi::Responses => self.stream_responses(prompt).await,
             WireApi::Chat => {
                 // Create the raw streaming connection first.
                 let response_stream = stream_chat_completions(
                     prompt,
-                    &self.config.model_family,
+                    &config.model_family,
                     &self.client,
-                    &self.provider,
+                    provider,
+                    config.model_stream_recording.as_ref(),
                 )
                 .await?;
 
                 // Wrap it with the aggregation adapter so callers see *only*
                 // the final assistant message per turn (matching the
                 // behaviour of the Responses API).
-                let mut aggregated = if self.config.show_raw_agent_reasoning {
+                let mut aggregated = if config.show_raw_agent_reasoning {
                     crate::chat_completions::AggregatedChatStream::streaming_mode(response_stream)
                 } else {
                     response_stream.aggregate()
@@ -151,26 +176,43 @@ impl ModelClient {
 
                 Ok(ResponseStream { rx_event: rx })
             }
+            WireApi::Anthropic => {
+                // Extended thinking is requested the way reasoning is for
+                // the Responses API: only for models that support it.
+                let effort = config
+                    .model_family
+                    .supports_reasoning_summaries
+                    .then(|| self.effort.unwrap_or_default());
+                stream_anthropic_messages(
+                    prompt,
+                    &config.model_family,
+                    &self.client,
+                    provider,
+                    config.model_stream_recording.as_ref(),
+                    effort,
+                    config.model_max_output_tokens,
+                )
+                .await
+            }
         }
     }
 
     /// Implementation for the OpenAI *Responses* experimental API.
     async fn stream_responses(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        let config = self.config();
+        let provider = self.provider();
         if let Some(path) = &*CODEX_RS_SSE_FIXTURE {
             // short circuit for tests
             warn!(path, "Streaming from fixture");
-            return stream_from_fixture(path, self.provider.clone()).await;
+            return stream_from_fixture(path, provider.clone()).await;
         }
 
         let auth_manager = self.auth_manager.clone();
 
-        let full_instructions = prompt.get_full_instructions(&self.config.model_family);
+        let full_instructions = prompt.get_full_instructions(&config.model_family);
         let tools_json = create_tools_json_for_responses_api(&prompt.tools)?;
-        let reasoning = create_reasoning_param_for_request(
-            &self.config.model_family,
-            self.effort,
-            self.summary,
-        );
+        let reasoning =
+            create_reasoning_param_for_request(&config.model_family, self.effort, self.summary);
 
         let include: Vec<String> = if reasoning.is_some() {
             vec!["reasoning.encrypted_content".to_string()]
@@ -178,20 +220,24 @@ impl ModelClient {
             vec![]
         };
 
-        let input_with_instructions = prompt.get_formatted_input();
+        let mut input_with_instructions = prompt.get_formatted_input();
+        // Thinking from the Messages API, e.g. before a failover, has no
+        // encrypted content this API can decrypt.
+        input_with_instructions.retain(|item| !is_anthropic_reasoning(item));
 
         // Only include `text.verbosity` for GPT-5 family models
-        let text = if self.config.model_family.family == "gpt-5" {
-            create_text_param_for_request(self.config.model_verbosity)
+        let verbosity = if config.model_family.family == "gpt-5" {
+            config.model_verbosity
         } else {
-            if self.config.model_verbosity.is_some() {
+            if config.model_verbosity.is_some() {
                 warn!(
                     "model_verbosity is set but ignored for non-gpt-5 model family: {}",
-                    self.config.model_family.family
+                    config.model_family.family
                 );
             }
             None
         };
+        let text = create_text_param_for_request(verbosity, prompt.output_schema.as_ref());
 
         // In general, we want to explicitly send `store: false` when using the Responses API,
         // but in practice, the Azure Responses API rejects `store: false`:
@@ -200,10 +246,10 @@ impl ModelClient {
         // - If store = false and id is not sent an error is thrown that ID is required
         //
         // For Azure, we send `store: true` and preserve reasoning item IDs.
-        let azure_workaround = self.provider.is_azure_responses_endpoint();
+        let azure_workaround = provider.is_azure_responses_endpoint();
 
         let payload = ResponsesApiRequest {
-            model: &self.config.model,
+            model: &config.model,
             instructions: &full_instructions,
             input: &input_with_instructions,
             tools: &tools_json,
@@ -223,8 +269,24 @@ impl ModelClient {
         }
         let payload_body = serde_json::to_string(&payload_json)?;
 
+        let mut record_to = None;
+        if let Some(recording) = &config.model_stream_recording {
+            match recording.begin_turn(&payload_json).await? {
+                ModelTurn::Replayed(bytes) => {
+                    let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+                    tokio::spawn(process_sse(
+                        futures::stream::iter([Ok(bytes)]),
+                        tx_event,
+                        provider.stream_idle_timeout(),
+                    ));
+                    return Ok(ResponseStream { rx_event });
+                }
+                ModelTurn::Recording { dir, turn } => record_to = Some((dir, turn)),
+            }
+        }
+
         let mut attempt = 0;
-        let max_retries = self.provider.request_max_retries();
+        let max_retries = provider.request_max_retries();
 
         loop {
             attempt += 1;
@@ -234,14 +296,11 @@ impl ModelClient {
 
             trace!(
                 "POST to {}: {}",
-                self.provider.get_full_url(&auth),
+                provider.get_full_url(&auth),
                 payload_body.as_str()
             );
 
-            let mut req_builder = self
-                .provider
-                .create_request_builder(&self.client, &auth)
-                .await?;
+            let mut req_builder = provider.create_request_builder(&self.client, &auth).await?;
 
             req_builder = req_builder
                 .header("OpenAI-Beta", "responses=experimental")
@@ -276,11 +335,15 @@ impl ModelClient {
 
                     // spawn task to process SSE
                     let stream = resp.bytes_stream().map_err(CodexErr::Reqwest);
-                    tokio::spawn(process_sse(
-                        stream,
-                        tx_event,
-                        self.provider.stream_idle_timeout(),
-                    ));
+                    let idle_timeout = provider.stream_idle_timeout();
+                    match &record_to {
+                        Some((dir, turn)) => tokio::spawn(process_sse(
+                            record_response(stream, dir, *turn).await,
+                            tx_event,
+                            idle_timeout,
+                        )),
+                        None => tokio::spawn(process_sse(stream, tx_event, idle_timeout)),
+                    };
 
                     return Ok(ResponseStream { rx_event });
                 }
@@ -362,22 +425,46 @@ impl ModelClient {
         }
     }
 
+    fn config(&self) -> &Config {
+        &self.fallback.active().config
+    }
+
+    fn provider(&self) -> &ModelProviderInfo {
+        &self.fallback.active().provider
+    }
+
     pub fn get_provider(&self) -> ModelProviderInfo {
-        self.provider.clone()
+        self.provider().clone()
     }
 
     /// Returns the currently configured model slug.
     pub fn get_model(&self) -> String {
-        self.config.model.clone()
+        self.config().model.clone()
     }
 
     /// Returns the currently configured model family.
     pub fn get_model_family(&self) -> ModelFamily {
-        self.config.model_family.clone()
+        self.config().model_family.clone()
     }
 
     pub fn get_config(&self) -> Arc<Config> {
-        Arc::clone(&self.config)
+        Arc::clone(&self.fallback.active().config)
+    }
+
+    /// Moves to the next fallback model if `fallback_policy` fails over on
+    /// `err`. Returns whether it did.
+    pub(crate) fn fail_over(&self, err: &CodexErr) -> bool {
+        self.fallback.fail_over(err)
+    }
+
+    /// Returns the fallback switches made since the last call.
+    pub(crate) fn take_model_switches(&self) -> Vec<ModelSwitch> {
+        self.fallback.take_switches()
+    }
+
+    /// Goes back to the session model after a fallback.
+    pub(crate) fn reset_fallback(&self) {
+        self.fallback.reset();
     }
 
     /// Returns the current reasoning effort setting.
@@ -430,6 +517,7 @@ impl From<ResponseCompletedUsage> for TokenUsage {
                 .input_tokens_details
                 .map(|d| d.cached_tokens)
                 .unwrap_or(0),
+            cache_write_input_tokens: 0,
             output_tokens: val.output_tokens,
             reasoning_output_tokens: val
                 .output_tokens_details
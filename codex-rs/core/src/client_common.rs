@@ -1,6 +1,7 @@
 use crate::error::Result;
 use crate::model_family::ModelFamily;
 use crate::openai_tools::OpenAiTool;
+use crate::output_schema::OUTPUT_SCHEMA_NAME;
 use crate::protocol::TokenUsage;
 use codex_apply_patch::APPLY_PATCH_TOOL_INSTRUCTIONS;
 use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
@@ -9,6 +10,7 @@ use codex_protocol::config_types::Verbosity as VerbosityConfig;
 use codex_protocol::models::ResponseItem;
 use futures::Stream;
 use serde::Serialize;
+use serde_json::Value;
 use std::borrow::Cow;
 use std::ops::Deref;
 use std::pin::Pin;
@@ -31,6 +33,13 @@ pub struct Prompt {
 
     /// Optional override for the built-in BASE_INSTRUCTIONS.
     pub base_instructions_override: Option<String>,
+
+    /// JSON Schema the final assistant message must follow, if any.
+    pub output_schema: Option<Value>,
+
+    /// Set on the turn that must end the task with the final answer: the
+    /// model already replied in plain text instead of following the schema.
+    pub(crate) final_turn: bool,
 }
 
 impl Prompt {
@@ -62,6 +71,14 @@ impl Prompt {
     pub(crate) fn get_formatted_input(&self) -> Vec<ResponseItem> {
         self.input.clone()
     }
+
+    /// Whether providers that take the output schema as a final-output tool
+    /// (Chat Completions, Anthropic) must force a call to it: when it is the
+    /// model's only tool, or on the final turn. Forcing it on every request
+    /// would rule out other tool calls while the model is still working.
+    pub(crate) fn forces_final_output_tool(&self) -> bool {
+        self.output_schema.is_some() && (self.tools.is_empty() || self.final_turn)
+    }
 }
 
 #[derive(Debug)]
@@ -89,11 +106,24 @@ pub(crate) struct Reasoning {
     pub(crate) summary: Option<ReasoningSummaryConfig>,
 }
 
-/// Controls under the `text` field in the Responses API for GPT-5.
-#[derive(Debug, Serialize, Default, Clone, Copy)]
+/// Controls under the `text` field in the Responses API. `verbosity` is only
+/// sent to GPT-5 models.
+#[derive(Debug, Serialize, Default, Clone)]
 pub(crate) struct TextControls {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) verbosity: Option<OpenAiVerbosity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) format: Option<TextFormat>,
+}
+
+/// Structured output format requested through `text.format`.
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct TextFormat {
+    #[serde(rename = "type")]
+    pub(crate) r#type: &'static str,
+    pub(crate) name: &'static str,
+    pub(crate) schema: Value,
+    pub(crate) strict: bool,
 }
 
 #[derive(Debug, Serialize, Default, Clone, Copy)]
@@ -155,9 +185,22 @@ pub(crate) fn create_reasoning_param_for_request(
 
 pub(crate) fn create_text_param_for_request(
     verbosity: Option<VerbosityConfig>,
+    output_schema: Option<&Value>,
 ) -> Option<TextControls> {
-    verbosity.map(|v| TextControls {
-        verbosity: Some(v.into()),
+    if verbosity.is_none() && output_schema.is_none() {
+        return None;
+    }
+    // Strict mode rejects schemas that leave properties optional, so the
+    // schema is only a hint here and the output is validated afterwards.
+    let format = output_schema.map(|schema| TextFormat {
+        r#type: "json_schema",
+        name: OUTPUT_SCHEMA_NAME,
+        schema: schema.clone(),
+        strict: false,
+    });
+    Some(TextControls {
+        verbosity: verbosity.map(Into::into),
+        format,
     })
 }
 
@@ -254,6 +297,7 @@ mod tests {
             prompt_cache_key: None,
             text: Some(TextControls {
                 verbosity: Some(OpenAiVerbosity::Low),
+                format: None,
             }),
         };
 
@@ -288,4 +332,40 @@ mod tests {
         let v = serde_json::to_value(&req).expect("json");
         assert!(v.get("text").is_none());
     }
+
+    #[test]
+    fn forces_final_output_tool_when_it_is_the_only_tool_or_on_the_final_turn() {
+        let mut prompt = Prompt {
+            output_schema: Some(serde_json::json!({ "type": "object" })),
+            ..Default::default()
+        };
+        assert!(prompt.forces_final_output_tool());
+
+        prompt.tools.push(OpenAiTool::WebSearch {});
+        assert!(!prompt.forces_final_output_tool());
+
+        prompt.final_turn = true;
+        assert!(prompt.forces_final_output_tool());
+
+        let prompt = Prompt::default();
+        assert!(!prompt.forces_final_output_tool());
+    }
+
+    #[test]
+    fn serializes_output_schema_as_text_format() {
+        let schema = serde_json::json!({ "type": "object" });
+        let text = create_text_param_for_request(None, Some(&schema));
+        let v = serde_json::to_value(&text).expect("json");
+        assert_eq!(
+            v,
+            serde_json::json!({
+                "format": {
+                    "type": "json_schema",
+                    "name": "codex_output_schema",
+                    "schema": { "type": "object" },
+                    "strict": false
+                }
+            })
+        );
+    }
 }
@@ -20,6 +20,7 @@ use codex_apply_patch::maybe_parse_apply_patch_verified;
 use codex_protocol::mcp_protocol::ConversationId;
 use codex_protocol::protocol::ConversationPathResponseEvent;
 use codex_protocol::protocol::ExitedReviewModeEvent;
+use codex_protocol::protocol::ModelFallbackItem;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::TaskStartedEvent;
@@ -45,12 +46,21 @@ use crate::apply_patch::ApplyPatchExec;
 use crate::apply_patch::CODEX_APPLY_PATCH_ARG1;
 use crate::apply_patch::InternalApplyPatchInvocation;
 use crate::apply_patch::convert_apply_patch_to_protocol;
+use crate::budget::BudgetTracker;
+use crate::checkpoints::CheckpointStore;
+use crate::checkpoints::RewindOutcome;
 use crate::client::ModelClient;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::config::Config;
 use crate::config_types::ShellEnvironmentPolicy;
 use crate::conversation_history::ConversationHistory;
+use crate::conversation_manager::fork_origin;
+use crate::conversation_manager::user_turn_count;
+use crate::cost::CostLimits;
+use crate::cost::CostTracker;
+use crate::cost::add_daily_cost;
+use crate::cost::load_daily_cost;
 use crate::environment_context::EnvironmentContext;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
@@ -74,13 +84,16 @@ use crate::hooks::{HookDecision, HookLogWriter, HookOutcome, HookScope};
 use crate::mcp_connection_manager::MCP_TOOL_NAME_DELIMITER;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::mcp_tool_call::handle_mcp_tool_call;
-use crate::model_family::find_family_for_model;
-use crate::openai_model_info::get_model_info;
+use crate::model_fallback::FailureClass;
+use crate::model_fallback::should_compact;
+use crate::model_provider_info::WireApi;
 use crate::openai_tools::ApplyPatchToolArgs;
 use crate::openai_tools::SubagentToolRegistration;
 use crate::openai_tools::ToolsConfig;
 use crate::openai_tools::ToolsConfigParams;
 use crate::openai_tools::get_openai_tools;
+use crate::output_schema::FINAL_OUTPUT_REQUEST;
+use crate::output_schema::validate_output;
 use crate::parse_command::parse_command;
 use crate::plan_mode::PLAN_MODE_SYSTEM_PROMPT;
 use crate::plan_mode::PlanArtifact;
@@ -102,6 +115,10 @@ use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
+use crate::protocol::BudgetExhausted;
+use crate::protocol::CheckpointRestoredEvent;
+use crate::protocol::ContextPinnedEvent;
+use crate::protocol::CostLimitApprovalRequestEvent;
 use crate::protocol::ErrorEvent;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
@@ -179,6 +196,8 @@ use codex_protocol::protocol::InitialHistory;
 mod compact;
 use self::compact::build_compacted_history;
 use self::compact::collect_user_messages;
+use self::compact::pinned_context_text;
+use self::compact::pinned_notes;
 
 // A convenience extension trait for acquiring mutex locks where poisoning is
 // unrecoverable and should abort the program. This avoids scattered `.unwrap()`
@@ -327,9 +346,15 @@ struct State {
     pending_subagent_approvals: HashMap<String, oneshot::Sender<SubagentApprovalDecision>>,
     history: ConversationHistory,
     token_info: Option<TokenUsageInfo>,
+    /// Usage against the session budget; `None` when the session is unlimited.
+    budget: Option<BudgetTracker>,
+    cost: CostTracker,
     next_internal_sub_id: u64,
     plan_mode: Option<PlanModeSession>,
     plan_mode_prompt_recorded: bool,
+    /// User turns in the conversation, including ones carried over when it
+    /// was resumed or forked. Keys workspace checkpoints.
+    user_turns: usize,
 }
 
 /// Context for an initialized model agent
@@ -360,6 +385,8 @@ pub(crate) struct Session {
     codex_linux_sandbox_exe: Option<PathBuf>,
     user_shell: shell::Shell,
     show_raw_agent_reasoning: bool,
+    /// Workspace snapshots backing `Op::RestoreCheckpoint`.
+    checkpoints: Option<CheckpointStore>,
 }
 
 /// The context needed for a single turn of the conversation.
@@ -454,15 +481,14 @@ fn build_subagent_guidance(inventory: &SubagentInventory) -> Option<String> {
         return None;
     }
 
-    let mut lines = Vec::new();
-    lines.push("Subagent delegation guidance:".to_string());
-    lines.push(
+    let mut lines = vec![
+        "Subagent delegation guidance:".to_string(),
         "- Read available subagent descriptions each turn and follow any directives they contain."
             .to_string(),
-    );
-    lines.push("- When a description says to proactively handle the current request, name the subagent, ask the user for approval, invoke it with `invoke_subagent` (retry with `confirmed: true` after approval), and relay the subagent's report before adding your own analysis. Continue solo only if the user declines.".to_string());
-    lines.push("- Delegate only when the user's request matches the subagent's remit.".to_string());
-    lines.push("Available subagents:".to_string());
+        "- When a description says to proactively handle the current request, name the subagent, ask the user for approval, invoke it with `invoke_subagent` (retry with `confirmed: true` after approval), and relay the subagent's report before adding your own analysis. Continue solo only if the user declines.".to_string(),
+        "- Delegate only when the user's request matches the subagent's remit.".to_string(),
+        "Available subagents:".to_string(),
+    ];
 
     for record in inventory.subagents.values() {
         let name = &record.definition.name;
@@ -525,13 +551,12 @@ async fn handle_slash_command_turn(
 ) {
     let provider = base_context.client.get_provider();
     let auth_manager = base_context.client.get_auth_manager();
-    let model_family =
-        find_family_for_model(&model_override).unwrap_or_else(|| config.model_family.clone());
+    let model_family = config.model_family_or_default(&model_override);
 
     let mut per_turn_config = (*config).clone();
     per_turn_config.model = model_override;
     per_turn_config.model_family = model_family.clone();
-    if let Some(model_info) = get_model_info(&model_family) {
+    if let Some(model_info) = config.model_info_for(&model_family) {
         per_turn_config.model_context_window = Some(model_info.context_window);
     }
 
@@ -615,13 +640,25 @@ impl Session {
         }
 
         let (conversation_id, rollout_params) = match &initial_history {
-            InitialHistory::New | InitialHistory::Forked(_) => {
+            InitialHistory::New => {
                 let conversation_id = ConversationId::default();
                 (
                     conversation_id,
                     RolloutRecorderParams::new(conversation_id, user_instructions.clone()),
                 )
             }
+            InitialHistory::Forked(items) => {
+                let conversation_id = ConversationId::default();
+                let params = match fork_origin(items) {
+                    Some(forked_from) => RolloutRecorderParams::fork(
+                        conversation_id,
+                        user_instructions.clone(),
+                        forked_from,
+                    ),
+                    None => RolloutRecorderParams::new(conversation_id, user_instructions.clone()),
+                };
+                (conversation_id, params)
+            }
             InitialHistory::Resumed(resumed_history) => (
                 resumed_history.conversation_id,
                 RolloutRecorderParams::resume(resumed_history.rollout_path.clone()),
@@ -655,6 +692,10 @@ impl Session {
         // Create the mutable state for the Session.
         let mut state = State {
             history: ConversationHistory::new(),
+            budget: (!config.session_budget.is_unlimited())
+                .then(|| BudgetTracker::new(config.session_budget)),
+            cost: CostTracker::new(config.cost_limits, load_daily_cost(&config.codex_home)),
+            user_turns: user_turn_count(&initial_history.get_rollout_items()),
             ..Default::default()
         };
 
@@ -766,6 +807,21 @@ impl Session {
             },
         );
 
+        let checkpoints = if config.checkpoints {
+            let store =
+                CheckpointStore::load(&config.codex_home, conversation_id, &config.cwd).await;
+            if let InitialHistory::Forked(items) = &initial_history
+                && let Some(origin) = fork_origin(items)
+            {
+                store
+                    .inherit(&config.codex_home, origin.conversation_id, origin.turn)
+                    .await;
+            }
+            Some(store)
+        } else {
+            None
+        };
+
         let sess = Arc::new(Session {
             conversation_id,
             tx_event: tx_event.clone(),
@@ -781,6 +837,7 @@ impl Session {
             codex_linux_sandbox_exe: config.codex_linux_sandbox_exe.clone(),
             user_shell: default_shell,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            checkpoints,
         });
 
         if config.plan_mode.plan_enabled
@@ -1111,21 +1168,127 @@ impl Session {
         }
     }
 
-    fn update_token_usage_info(
+    async fn update_token_usage_info(
         &self,
         turn_context: &TurnContext,
         token_usage: &Option<TokenUsage>,
     ) -> Option<TokenUsageInfo> {
+        // Price the request with the model that served it, which may be a
+        // fallback.
+        let config = turn_context.client.get_config();
+        let cost_usd = token_usage
+            .as_ref()
+            .and_then(|usage| Some(config.model_pricing()?.cost_usd(usage)));
+        let mut daily_usd = None;
+        if let Some(cost_usd) = cost_usd.filter(|cost_usd| *cost_usd > 0.0) {
+            let codex_home = config.codex_home.clone();
+            match tokio::task::spawn_blocking(move || add_daily_cost(&codex_home, cost_usd)).await {
+                Ok(Ok(total)) => daily_usd = Some(total),
+                Ok(Err(e)) => warn!("failed to record daily cost: {e}"),
+                Err(e) => warn!("failed to record daily cost: {e}"),
+            }
+        }
+
         let mut state = self.state.lock_unchecked();
-        let info = TokenUsageInfo::new_or_append(
+        if token_usage.is_some() {
+            state.cost.record(cost_usd);
+        }
+        if let Some(daily_usd) = daily_usd {
+            state.cost.set_daily_usd(daily_usd);
+        }
+        let mut info = TokenUsageInfo::new_or_append(
             &state.token_info,
             token_usage,
             turn_context.client.get_model_context_window(),
         );
+        if let Some(info) = info.as_mut() {
+            info.cost = state.cost.info();
+        }
         state.token_info = info.clone();
         info
     }
 
+    /// If spending has reached a cost limit, ask the user whether to keep
+    /// going. Returns whether to continue.
+    async fn confirm_cost_limit(&self, sub_id: &str, limits: CostLimits) -> bool {
+        let Some(reached) = self.state.lock_unchecked().cost.limit_reached() else {
+            return true;
+        };
+        let Some(limit_usd) = limits.get(reached.limit) else {
+            return true;
+        };
+
+        let (tx_approve, rx_approve) = oneshot::channel();
+        let prev_entry = {
+            let mut state = self.state.lock_unchecked();
+            state
+                .pending_approvals
+                .insert(sub_id.to_string(), tx_approve)
+        };
+        if prev_entry.is_some() {
+            warn!("Overwriting existing pending approval for sub_id: {sub_id}");
+        }
+        self.send_event(Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::CostLimitApprovalRequest(CostLimitApprovalRequestEvent {
+                id: sub_id.to_string(),
+                limit: reached.limit,
+                limit_usd,
+                spent_usd: reached.spent_usd,
+            }),
+        })
+        .await;
+
+        let decision = rx_approve.await.unwrap_or_default();
+        self.state
+            .lock_unchecked()
+            .cost
+            .resolve(reached, limits, decision)
+    }
+
+    /// The session budget that has run out, if any.
+    fn budget_exhausted(&self) -> Option<BudgetExhausted> {
+        let state = self.state.lock_unchecked();
+        let total_tokens = state
+            .token_info
+            .as_ref()
+            .map(|info| info.total_token_usage.total_tokens)
+            .unwrap_or_default();
+        state.budget.as_ref()?.exhausted(total_tokens)
+    }
+
+    /// When the session budget times out, if it has a timeout.
+    fn budget_deadline(&self) -> Option<std::time::Instant> {
+        let state = self.state.lock_unchecked();
+        state.budget.as_ref()?.deadline()
+    }
+
+    /// Count a finished model turn against the session budget.
+    fn record_budget_turn(&self) {
+        let mut state = self.state.lock_unchecked();
+        if let Some(budget) = state.budget.as_mut() {
+            budget.record_turn();
+        }
+    }
+
+    /// Count a tool call against the session budget before it is dispatched.
+    /// Returns `false` when the budget allows no more tool calls.
+    fn claim_budget_tool_call(&self) -> bool {
+        let mut state = self.state.lock_unchecked();
+        state
+            .budget
+            .as_mut()
+            .is_none_or(|budget| budget.claim_tool_call())
+    }
+
+    /// Claim the index of the next user turn.
+    fn next_user_turn(&self) -> usize {
+        let mut state = self.state.lock_unchecked();
+        let turn = state.user_turns;
+        state.user_turns += 1;
+        turn
+    }
+
     /// Record a user input item to conversation history and also persist a
     /// corresponding UserMessage EventMsg to rollout.
     async fn record_input_and_rollout_usermsg(&self, response_input: &ResponseInputItem) {
@@ -1167,6 +1330,19 @@ impl Session {
                 changes,
             }) => {
                 turn_diff_tracker.on_patch_begin(&changes);
+                if let Some(checkpoints) = self.checkpoints.as_ref() {
+                    let paths: Vec<PathBuf> = changes
+                        .iter()
+                        .flat_map(|(path, change)| {
+                            let move_path = match change {
+                                FileChange::Update { move_path, .. } => move_path.clone(),
+                                _ => None,
+                            };
+                            std::iter::once(path.clone()).chain(move_path)
+                        })
+                        .collect();
+                    checkpoints.record_before(&paths).await;
+                }
 
                 EventMsg::PatchApplyBegin(PatchApplyBeginEvent {
                     call_id,
@@ -1346,43 +1522,43 @@ impl Session {
 
         let pre_hook_decision = self.run_pre_tool_hooks(&raw_command).await;
 
-        if let Some(decision) = &pre_hook_decision {
-            if decision.decision != HookOutcome::Allow {
-                let mut messages: Vec<String> = decision
-                    .message
-                    .iter()
-                    .chain(decision.system_message.iter())
-                    .cloned()
-                    .collect();
-
-                if messages.is_empty() {
-                    messages.push(format!(
-                        "Command blocked by pre-tool hook (outcome: {:?}).",
-                        decision.decision
-                    ));
-                }
+        if let Some(decision) = &pre_hook_decision
+            && decision.decision != HookOutcome::Allow
+        {
+            let mut messages: Vec<String> = decision
+                .message
+                .iter()
+                .chain(decision.system_message.iter())
+                .cloned()
+                .collect();
 
-                let combined_message = messages.join("\n");
-                let exit_code = if decision.exit_code == 0 {
-                    1
-                } else {
-                    decision.exit_code
-                };
+            if messages.is_empty() {
+                messages.push(format!(
+                    "Command blocked by pre-tool hook (outcome: {:?}).",
+                    decision.decision
+                ));
+            }
 
-                let output = ExecToolCallOutput {
-                    exit_code,
-                    stdout: StreamOutput::new(String::new()),
-                    stderr: StreamOutput::new(combined_message.clone()),
-                    aggregated_output: StreamOutput::new(combined_message),
-                    duration: Duration::default(),
-                    timed_out: false,
-                };
+            let combined_message = messages.join("\n");
+            let exit_code = if decision.exit_code == 0 {
+                1
+            } else {
+                decision.exit_code
+            };
 
-                self.run_post_tool_hooks(&raw_command, Some(&output), pre_hook_decision.as_ref())
-                    .await;
+            let output = ExecToolCallOutput {
+                exit_code,
+                stdout: StreamOutput::new(String::new()),
+                stderr: StreamOutput::new(combined_message.clone()),
+                aggregated_output: StreamOutput::new(combined_message),
+                duration: Duration::default(),
+                timed_out: false,
+            };
 
-                return Ok(output);
-            }
+            self.run_post_tool_hooks(&raw_command, Some(&output), pre_hook_decision.as_ref())
+                .await;
+
+            return Ok(output);
         }
 
         self.on_exec_command_begin(turn_diff_tracker, begin_ctx.clone())
@@ -1556,6 +1732,10 @@ impl Session {
         items
     }
 
+    fn has_current_task(&self) -> bool {
+        self.state.lock_unchecked().current_task.is_some()
+    }
+
     /// Returns the input if there was no task running to inject into
     pub fn inject_input(&self, input: Vec<InputItem>) -> Result<(), Vec<InputItem>> {
         let mut state = self.state.lock_unchecked();
@@ -2023,14 +2203,14 @@ impl AgentTask {
         turn_context: Arc<TurnContext>,
         sub_id: String,
         input: Vec<InputItem>,
-        compact_instructions: String,
+        instructions: Option<String>,
     ) -> Self {
         let handle = {
             let sess = sess.clone();
             let sub_id = sub_id.clone();
             let tc = Arc::clone(&turn_context);
             tokio::spawn(async move {
-                compact::run_compact_task(sess, tc, sub_id, input, compact_instructions).await
+                compact::run_compact_task(sess, tc, sub_id, input, instructions).await
             })
             .abort_handle()
         };
@@ -2125,8 +2305,7 @@ async fn submission_loop(
 
                 // Effective model + family
                 let (effective_model, effective_family) = if let Some(ref m) = model {
-                    let fam =
-                        find_family_for_model(m).unwrap_or_else(|| config.model_family.clone());
+                    let fam = config.model_family_or_default(m);
                     (m.clone(), fam)
                 } else {
                     (prev.client.get_model(), prev.client.get_model_family())
@@ -2142,7 +2321,7 @@ async fn submission_loop(
                 let mut updated_config = (*config).clone();
                 updated_config.model = effective_model.clone();
                 updated_config.model_family = effective_family.clone();
-                if let Some(model_info) = get_model_info(&effective_family) {
+                if let Some(model_info) = config.model_info_for(&effective_family) {
                     updated_config.model_context_window = Some(model_info.context_window);
                 }
 
@@ -2357,15 +2536,14 @@ async fn submission_loop(
                     let provider = turn_context.client.get_provider();
                     let auth_manager = turn_context.client.get_auth_manager();
 
-                    // Derive a model family for the requested model; fall back to the session's.
-                    let model_family = find_family_for_model(&model)
-                        .unwrap_or_else(|| config.model_family.clone());
+                    // Derive a model family for the requested model, even an unknown one.
+                    let model_family = config.model_family_or_default(&model);
 
                     // Create a per‑turn Config clone with the requested model/family.
                     let mut per_turn_config = (*config).clone();
                     per_turn_config.model = model.clone();
                     per_turn_config.model_family = model_family.clone();
-                    if let Some(model_info) = get_model_info(&model_family) {
+                    if let Some(model_info) = config.model_info_for(&model_family) {
                         per_turn_config.model_context_window = Some(model_info.context_window);
                     }
 
@@ -2445,6 +2623,12 @@ async fn submission_loop(
                 }
                 other => sess.notify_approval(&id, other),
             },
+            Op::CostLimitApproval { id, decision } => match decision {
+                ReviewDecision::Abort => {
+                    sess.interrupt_task();
+                }
+                other => sess.notify_approval(&id, other),
+            },
             Op::SubagentApproval { id, name, decision } => {
                 trace!(subagent = %name, request_id = %id, decision = ?decision, "subagent approval received");
                 sess.notify_subagent_approval(&id, decision);
@@ -2572,7 +2756,20 @@ async fn submission_loop(
                 };
                 sess.send_event(event).await;
             }
-            Op::Compact => {
+            Op::Compact { instructions } => {
+                // A running task can only be asked to summarize, so custom
+                // instructions would be lost.
+                if instructions.is_some() && sess.has_current_task() {
+                    let event = Event {
+                        id: sub.id,
+                        msg: EventMsg::Error(ErrorEvent {
+                            message: "Cannot compact with instructions while a task is running."
+                                .to_string(),
+                        }),
+                    };
+                    sess.send_event(event).await;
+                    continue;
+                }
                 // Attempt to inject input into current task
                 if let Err(items) = sess.inject_input(vec![InputItem::Text {
                     text: compact::COMPACT_TRIGGER_TEXT.to_string(),
@@ -2582,6 +2779,7 @@ async fn submission_loop(
                         Arc::clone(&turn_context),
                         sub.id,
                         items,
+                        instructions,
                     );
                 }
             }
@@ -2791,6 +2989,14 @@ async fn submission_loop(
                 };
                 sess.send_event(event).await;
             }
+            Op::RestoreCheckpoint { turns, force } => {
+                let msg = restore_checkpoint(&sess, turns, force).await;
+                sess.send_event(Event { id: sub.id, msg }).await;
+            }
+            Op::Pin { text } => {
+                let msg = pin_context(&sess, text).await;
+                sess.send_event(Event { id: sub.id, msg }).await;
+            }
             Op::Review { review_request } => {
                 spawn_review_thread(
                     sess.clone(),
@@ -2809,6 +3015,57 @@ async fn submission_loop(
     debug!("Agent loop exited");
 }
 
+async fn restore_checkpoint(sess: &Session, turns: u32, force: bool) -> EventMsg {
+    let Some(checkpoints) = sess.checkpoints.as_ref() else {
+        return EventMsg::Error(ErrorEvent {
+            message: "Checkpoints are not available in this session.".to_string(),
+        });
+    };
+    if sess.has_current_task() {
+        return EventMsg::Error(ErrorEvent {
+            message: "Cannot restore a checkpoint while a task is running.".to_string(),
+        });
+    }
+    let user_turns = sess.state.lock_unchecked().user_turns;
+    match checkpoints.rewind(turns as usize, user_turns, force).await {
+        Ok(RewindOutcome {
+            applied,
+            restored,
+            conflicts,
+        }) => EventMsg::CheckpointRestored(CheckpointRestoredEvent {
+            turns,
+            applied,
+            restored_paths: restored,
+            conflicts,
+        }),
+        Err(err) => EventMsg::Error(ErrorEvent {
+            message: format!("Failed to restore checkpoint: {err}"),
+        }),
+    }
+}
+
+async fn pin_context(sess: &Session, text: Option<String>) -> EventMsg {
+    let mut pins = {
+        let state = sess.state.lock_unchecked();
+        pinned_notes(&state.history.contents())
+    };
+    if let Some(note) = text
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+    {
+        // A running task picks the note up between model requests; otherwise
+        // it is recorded right away and sent with the next turn.
+        if let Err(items) = sess.inject_input(vec![InputItem::Text {
+            text: pinned_context_text(&note),
+        }]) {
+            let item: ResponseItem = ResponseInputItem::from(items).into();
+            sess.record_conversation_items(&[item]).await;
+        }
+        pins.push(note);
+    }
+    EventMsg::ContextPinned(ContextPinnedEvent { pins })
+}
+
 /// Spawn a review thread using the given prompt.
 async fn spawn_review_thread(
     sess: Arc<Session>,
@@ -2818,8 +3075,7 @@ async fn spawn_review_thread(
     review_request: ReviewRequest,
 ) {
     let model = config.review_model.clone();
-    let review_model_family = find_family_for_model(&model)
-        .unwrap_or_else(|| parent_turn_context.client.get_model_family());
+    let review_model_family = config.model_family_or_default(&model);
     let tools_config = ToolsConfig::new(&ToolsConfigParams {
         model_family: &review_model_family,
         approval_policy: parent_turn_context.approval_policy,
@@ -2841,7 +3097,7 @@ async fn spawn_review_thread(
     let mut per_turn_config = (*config).clone();
     per_turn_config.model = model.clone();
     per_turn_config.model_family = model_family.clone();
-    if let Some(model_info) = get_model_info(&model_family) {
+    if let Some(model_info) = config.model_info_for(&model_family) {
         per_turn_config.model_context_window = Some(model_info.context_window);
     }
 
@@ -2918,6 +3174,8 @@ async fn run_task(
     if input.is_empty() {
         return;
     }
+    // A fallback only lasts for the task whose turn failed.
+    turn_context.client.reset_fallback();
     let event = Event {
         id: sub_id.clone(),
         msg: EventMsg::TaskStarted(TaskStartedEvent {
@@ -2938,14 +3196,34 @@ async fn run_task(
         sess.record_input_and_rollout_usermsg(&initial_input_for_turn)
             .await;
     }
+    let checkpoints = sess.checkpoints.as_ref().filter(|_| !is_review_mode);
+    if !is_review_mode {
+        let turn = sess.next_user_turn();
+        if let Some(checkpoints) = checkpoints {
+            checkpoints.begin_turn(turn).await;
+        }
+    }
 
     let mut last_agent_message: Option<String> = None;
     // Although from the perspective of codex.rs, TurnDiffTracker has the lifecycle of a Task which contains
     // many turns, from the perspective of the user, it is a single turn.
     let mut turn_diff_tracker = TurnDiffTracker::new();
     let mut auto_compact_recently_attempted = false;
+    let mut budget_exhausted = None;
+    let mut final_turn = false;
+    let cost_limits = turn_context.client.get_config().cost_limits;
 
     loop {
+        if let Some(reason) = sess.budget_exhausted() {
+            budget_exhausted = Some(reason);
+            break;
+        }
+        // Earlier tasks, or other sessions today, may have reached a limit.
+        if !sess.confirm_cost_limit(&sub_id, cost_limits).await {
+            budget_exhausted = Some(BudgetExhausted::MaxCost);
+            break;
+        }
+
         // Note that pending_input would be something like a message the user
         // submitted through the UI while the model was running. Though the UI
         // may support this, the model might not.
@@ -2971,6 +3249,16 @@ async fn run_task(
             }
             review_thread_history.clone()
         } else {
+            // Messages sent while the task runs start new user turns.
+            let messages = pending_input.iter().filter(
+                |item| matches!(item, ResponseItem::Message { role, .. } if role == "user"),
+            );
+            for _ in messages {
+                let turn = sess.next_user_turn();
+                if let Some(checkpoints) = checkpoints {
+                    checkpoints.begin_turn(turn).await;
+                }
+            }
             sess.record_conversation_items(&pending_input).await;
             sess.turn_input_with_history(pending_input)
         };
@@ -2988,15 +3276,25 @@ async fn run_task(
                 })
             })
             .collect();
-        match run_turn(
+        let turn = run_turn(
             &sess,
             turn_context.as_ref(),
             &mut turn_diff_tracker,
             sub_id.clone(),
             turn_input,
-        )
-        .await
-        {
+            final_turn,
+        );
+        let turn_result = match sess.budget_deadline() {
+            Some(deadline) => match tokio::time::timeout_at(deadline.into(), turn).await {
+                Ok(result) => result,
+                Err(_) => {
+                    budget_exhausted = Some(BudgetExhausted::Timeout);
+                    break;
+                }
+            },
+            None => turn.await,
+        };
+        match turn_result {
             Ok(turn_output) => {
                 let TurnRunResult {
                     processed_items,
@@ -3104,6 +3402,7 @@ async fn run_task(
                         responses.push(response);
                     }
                 }
+                sess.record_budget_turn();
 
                 // Only attempt to take the lock if there is something to record.
                 if !items_to_record_in_conversation_history.is_empty() {
@@ -3116,6 +3415,16 @@ async fn run_task(
                     }
                 }
 
+                // Ask as soon as a request has spent past a limit, before
+                // anything else is sent for the task.
+                if !sess.confirm_cost_limit(&sub_id, cost_limits).await {
+                    last_agent_message = get_last_assistant_message_from_turn(
+                        &items_to_record_in_conversation_history,
+                    );
+                    budget_exhausted = Some(BudgetExhausted::MaxCost);
+                    break;
+                }
+
                 if token_limit_reached {
                     if auto_compact_recently_attempted {
                         let limit_str = limit.to_string();
@@ -3144,6 +3453,22 @@ async fn run_task(
                     last_agent_message = get_last_assistant_message_from_turn(
                         &items_to_record_in_conversation_history,
                     );
+                    // Chat Completions and Anthropic providers only see the
+                    // output schema as a tool. If the model answered in plain
+                    // text instead, ask once more with that tool forced.
+                    if !final_turn
+                        && !is_review_mode
+                        && turn_context.client.get_provider().wire_api != WireApi::Responses
+                        && let Some(schema) = &turn_context.client.get_config().output_schema
+                        && validate_output(
+                            schema,
+                            last_agent_message.as_deref().unwrap_or_default(),
+                        )
+                        .is_err()
+                    {
+                        final_turn = true;
+                        continue;
+                    }
                     sess.maybe_notify(UserNotification::AgentTurnComplete {
                         turn_id: sub_id.clone(),
                         input_messages: turn_input_messages,
@@ -3155,6 +3480,20 @@ async fn run_task(
             }
             Err(e) => {
                 info!("Turn error: {e:#}");
+                let fallback_policy = turn_context.client.get_config().fallback_policy;
+                if !is_review_mode
+                    && !auto_compact_recently_attempted
+                    && should_compact(&fallback_policy, &e)
+                {
+                    sess.notify_background_event(
+                        &sub_id,
+                        format!("{e}; compacting the conversation and retrying."),
+                    )
+                    .await;
+                    auto_compact_recently_attempted = true;
+                    compact::run_inline_auto_compact_task(sess.clone(), turn_context.clone()).await;
+                    continue;
+                }
                 let event = Event {
                     id: sub_id.clone(),
                     msg: EventMsg::Error(ErrorEvent {
@@ -3184,10 +3523,17 @@ async fn run_task(
         .await;
     }
 
+    if let Some(checkpoints) = checkpoints {
+        checkpoints.end_turn().await;
+    }
+
     sess.remove_task(&sub_id);
     let event = Event {
         id: sub_id,
-        msg: EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message,
+            budget_exhausted,
+        }),
     };
     sess.send_event(event).await;
 }
@@ -3222,7 +3568,8 @@ async fn run_turn(
     turn_context: &TurnContext,
     turn_diff_tracker: &mut TurnDiffTracker,
     sub_id: String,
-    input: Vec<ResponseItem>,
+    mut input: Vec<ResponseItem>,
+    final_turn: bool,
 ) -> CodexResult<TurnRunResult> {
     let tools = get_openai_tools(
         &turn_context.tools_config,
@@ -3230,10 +3577,27 @@ async fn run_turn(
         turn_context.subagent_tool.as_ref(),
     );
 
+    // Review threads produce their own structured output.
+    let output_schema = if turn_context.is_review_mode {
+        None
+    } else {
+        turn_context.client.get_config().output_schema.clone()
+    };
+    if final_turn && output_schema.is_some() {
+        input.push(ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: FINAL_OUTPUT_REQUEST.to_string(),
+            }],
+        });
+    }
     let prompt = Prompt {
         input,
         tools,
         base_instructions_override: turn_context.base_instructions.clone(),
+        output_schema,
+        final_turn,
     };
 
     let mut retries = 0;
@@ -3245,6 +3609,15 @@ async fn run_turn(
             Err(e @ (CodexErr::UsageLimitReached(_) | CodexErr::UsageNotIncluded)) => {
                 return Err(e);
             }
+            Err(e) if FailureClass::of(&e) == Some(FailureClass::ContextOverflow) => {
+                // The same request will not fit on a retry; only another
+                // model can take it.
+                if !turn_context.client.fail_over(&e) {
+                    return Err(e);
+                }
+                report_model_switches(sess, turn_context, &sub_id).await;
+                retries = 0;
+            }
             Err(e) => {
                 // Use the configured provider-specific stream retry budget.
                 let max_retries = turn_context.client.get_provider().stream_max_retries();
@@ -3270,6 +3643,9 @@ async fn run_turn(
                     .await;
 
                     tokio::time::sleep(delay).await;
+                } else if turn_context.client.fail_over(&e) {
+                    report_model_switches(sess, turn_context, &sub_id).await;
+                    retries = 0;
                 } else {
                     return Err(e);
                 }
@@ -3278,6 +3654,29 @@ async fn run_turn(
     }
 }
 
+/// Reports the fallback switches the client made: a background event for
+/// the UI and a `TurnContextItem` naming the new model in the rollout.
+async fn report_model_switches(sess: &Session, turn_context: &TurnContext, sub_id: &str) {
+    for switch in turn_context.client.take_model_switches() {
+        let ModelFallbackItem { from, to, reason } = &switch.fallback;
+        sess.notify_background_event(
+            sub_id,
+            format!("{from} failed ({reason}); switching to {to}"),
+        )
+        .await;
+        let rollout_item = RolloutItem::TurnContext(TurnContextItem {
+            cwd: turn_context.cwd.clone(),
+            approval_policy: turn_context.approval_policy,
+            sandbox_policy: turn_context.sandbox_policy.clone(),
+            model: switch.model,
+            effort: turn_context.client.get_reasoning_effort(),
+            summary: turn_context.client.get_reasoning_summary(),
+            fallback: Some(switch.fallback),
+        });
+        sess.persist_rollout_items(&[rollout_item]).await;
+    }
+}
+
 /// When the model is prompted, it returns a stream of events. Some of these
 /// events map to a `ResponseItem`. A `ResponseItem` may need to be
 /// "handled" such that it produces a `ResponseInputItem` that needs to be
@@ -3363,9 +3762,12 @@ async fn try_run_turn(
         model: turn_context.client.get_model(),
         effort: turn_context.client.get_reasoning_effort(),
         summary: turn_context.client.get_reasoning_summary(),
+        fallback: None,
     });
     sess.persist_rollout_items(&[rollout_item]).await;
-    let mut stream = turn_context.client.clone().stream(&prompt).await?;
+    let stream = turn_context.client.clone().stream(&prompt).await;
+    report_model_switches(sess, turn_context, sub_id).await;
+    let mut stream = stream?;
 
     let mut output = Vec::new();
 
@@ -3418,7 +3820,9 @@ async fn try_run_turn(
                 response_id: _,
                 token_usage,
             } => {
-                let info = sess.update_token_usage_info(turn_context, &token_usage);
+                let info = sess
+                    .update_token_usage_info(turn_context, &token_usage)
+                    .await;
                 let _ = sess
                     .send_event(Event {
                         id: sub_id.to_string(),
@@ -3493,6 +3897,9 @@ async fn handle_response_item(
     item: ResponseItem,
 ) -> CodexResult<Option<ResponseInputItem>> {
     debug!(?item, "Output item");
+    if let Some(refused) = refuse_tool_call_over_budget(sess, &item) {
+        return Ok(Some(refused));
+    }
     let output = match item {
         ResponseItem::FunctionCall {
             name,
@@ -3609,6 +4016,36 @@ async fn handle_response_item(
     Ok(output)
 }
 
+/// The output returned instead of running a tool call once the session's
+/// tool call budget is spent; the task stops after the current turn.
+fn refuse_tool_call_over_budget(sess: &Session, item: &ResponseItem) -> Option<ResponseInputItem> {
+    let call_id = match item {
+        ResponseItem::FunctionCall { call_id, .. }
+        | ResponseItem::CustomToolCall { call_id, .. } => call_id.clone(),
+        ResponseItem::LocalShellCall { call_id, id, .. } => {
+            call_id.clone().or_else(|| id.clone()).unwrap_or_default()
+        }
+        _ => return None,
+    };
+    if sess.claim_budget_tool_call() {
+        return None;
+    }
+    let message = "tool call budget exhausted; the call was not run".to_string();
+    Some(match item {
+        ResponseItem::CustomToolCall { .. } => ResponseInputItem::CustomToolCallOutput {
+            call_id,
+            output: message,
+        },
+        _ => ResponseInputItem::FunctionCallOutput {
+            call_id,
+            output: FunctionCallOutputPayload {
+                content: message,
+                success: Some(false),
+            },
+        },
+    })
+}
+
 async fn handle_unified_exec_tool_call(
     sess: &Session,
     call_id: String,
@@ -5187,6 +5624,7 @@ mod tests {
             codex_linux_sandbox_exe: None,
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            checkpoints: None,
             #[cfg(feature = "slash_commands")]
             slash_commands: None,
         };
@@ -5253,6 +5691,8 @@ mod tests {
         live_history.replace(rebuilt1);
         rollout_items.push(RolloutItem::Compacted(CompactedItem {
             message: summary1.to_string(),
+            model: None,
+            instructions: None,
         }));
 
         let user2 = ResponseItem::Message {
@@ -5286,6 +5726,8 @@ mod tests {
         live_history.replace(rebuilt2);
         rollout_items.push(RolloutItem::Compacted(CompactedItem {
             message: summary2.to_string(),
+            model: None,
+            instructions: None,
         }));
 
         let user3 = ResponseItem::Message {
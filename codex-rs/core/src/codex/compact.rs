@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use super::AgentTask;
@@ -6,9 +7,12 @@ use super::Session;
 use super::TurnContext;
 use super::get_last_assistant_message_from_turn;
 use crate::Prompt;
+use crate::client::ModelClient;
 use crate::client_common::ResponseEvent;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
+use crate::plan_tool::StepStatus;
+use crate::plan_tool::UpdatePlanArgs;
 use crate::protocol::AgentMessageEvent;
 use crate::protocol::CompactedItem;
 use crate::protocol::ErrorEvent;
@@ -19,15 +23,24 @@ use crate::protocol::InputMessageKind;
 use crate::protocol::TaskCompleteEvent;
 use crate::protocol::TaskStartedEvent;
 use crate::protocol::TurnContextItem;
+use crate::truncate::truncate_middle;
 use crate::util::backoff;
 use askama::Template;
 use codex_protocol::models::ContentItem;
+use codex_protocol::models::LocalShellAction;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::RolloutItem;
 use futures::prelude::*;
 
 pub(super) const COMPACT_TRIGGER_TEXT: &str = "Start Summarization";
+const PINNED_CONTEXT_OPEN_TAG: &str = "<pinned_context>";
+const PINNED_CONTEXT_CLOSE_TAG: &str = "</pinned_context>";
+const PRESERVED_CONTEXT_OPEN_TAG: &str = "<preserved_context>";
+const PRESERVED_CONTEXT_CLOSE_TAG: &str = "</preserved_context>";
+const PLAN_SUMMARY_PREFIX: &str = "Plan Ready for Execution";
+const UPDATE_PLAN_TOOL_NAME: &str = "update_plan";
+const TOOL_CALL_LABEL_MAX_BYTES: usize = 200;
 const SUMMARIZATION_PROMPT: &str = include_str!("../../templates/compact/prompt.md");
 
 #[derive(Template)]
@@ -42,14 +55,9 @@ pub(super) fn spawn_compact_task(
     turn_context: Arc<TurnContext>,
     sub_id: String,
     input: Vec<InputItem>,
+    instructions: Option<String>,
 ) {
-    let task = AgentTask::compact(
-        sess.clone(),
-        turn_context,
-        sub_id,
-        input,
-        SUMMARIZATION_PROMPT.to_string(),
-    );
+    let task = AgentTask::compact(sess.clone(), turn_context, sub_id, input, instructions);
     sess.set_task(task);
 }
 
@@ -61,15 +69,7 @@ pub(super) async fn run_inline_auto_compact_task(
     let input = vec![InputItem::Text {
         text: COMPACT_TRIGGER_TEXT.to_string(),
     }];
-    run_compact_task_inner(
-        sess,
-        turn_context,
-        sub_id,
-        input,
-        SUMMARIZATION_PROMPT.to_string(),
-        false,
-    )
-    .await;
+    run_compact_task_inner(sess, turn_context, sub_id, input, None, false).await;
 }
 
 pub(super) async fn run_compact_task(
@@ -77,17 +77,9 @@ pub(super) async fn run_compact_task(
     turn_context: Arc<TurnContext>,
     sub_id: String,
     input: Vec<InputItem>,
-    compact_instructions: String,
+    instructions: Option<String>,
 ) {
-    run_compact_task_inner(
-        sess,
-        turn_context,
-        sub_id,
-        input,
-        compact_instructions,
-        true,
-    )
-    .await;
+    run_compact_task_inner(sess, turn_context, sub_id, input, instructions, true).await;
 }
 
 async fn run_compact_task_inner(
@@ -95,10 +87,14 @@ async fn run_compact_task_inner(
     turn_context: Arc<TurnContext>,
     sub_id: String,
     input: Vec<InputItem>,
-    compact_instructions: String,
+    instructions: Option<String>,
     remove_task_on_completion: bool,
 ) {
-    let model_context_window = turn_context.client.get_model_context_window();
+    let instructions = instructions
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty());
+    let client = compaction_client(&sess, &turn_context.client);
+    let model_context_window = client.get_model_context_window();
     let start_event = Event {
         id: sub_id.clone(),
         msg: EventMsg::TaskStarted(TaskStartedEvent {
@@ -108,16 +104,18 @@ async fn run_compact_task_inner(
     sess.send_event(start_event).await;
 
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
-    let instructions_override = compact_instructions;
+    let instructions_override = summarization_prompt(instructions.as_deref());
     let turn_input = sess.turn_input_with_history(vec![initial_input_for_turn.clone().into()]);
 
     let prompt = Prompt {
         input: turn_input,
         tools: Vec::new(),
         base_instructions_override: Some(instructions_override),
+        output_schema: None,
+        final_turn: false,
     };
 
-    let max_retries = turn_context.client.get_provider().stream_max_retries();
+    let max_retries = client.get_provider().stream_max_retries();
     let mut retries = 0;
 
     let rollout_item = RolloutItem::TurnContext(TurnContextItem {
@@ -127,11 +125,12 @@ async fn run_compact_task_inner(
         model: turn_context.client.get_model(),
         effort: turn_context.client.get_reasoning_effort(),
         summary: turn_context.client.get_reasoning_summary(),
+        fallback: None,
     });
     sess.persist_rollout_items(&[rollout_item]).await;
 
     loop {
-        let attempt_result = drain_to_completed(&sess, turn_context.as_ref(), &prompt).await;
+        let attempt_result = drain_to_completed(&sess, &client, &prompt).await;
 
         match attempt_result {
             Ok(()) => {
@@ -177,16 +176,43 @@ async fn run_compact_task_inner(
     let summary_text = get_last_assistant_message_from_turn(&history_snapshot).unwrap_or_default();
     let user_messages = collect_user_messages(&history_snapshot);
     let initial_context = sess.build_initial_context(turn_context.as_ref());
-    let new_history = build_compacted_history(initial_context, &user_messages, &summary_text);
+    let preserved = {
+        let config = turn_context.client.get_config();
+        let plan_artifact = if config.compact_preserve_plan {
+            let state = sess.state.lock_unchecked();
+            state
+                .plan_mode
+                .as_ref()
+                .filter(|session| session.plan_artifact.entry_count() > 0)
+                .map(|session| session.plan_artifact.to_summary_markdown())
+        } else {
+            None
+        };
+        collect_preserved_context(
+            &history_snapshot,
+            &PreserveOptions {
+                tool_output_budget: config.compact_tool_output_budget,
+                preserve_plan: config.compact_preserve_plan,
+                plan_artifact,
+            },
+        )
+    };
+    let mut new_history = build_compacted_history(initial_context, &user_messages, &summary_text);
+    new_history.extend(preserved.iter().cloned());
     {
         let mut state = sess.state.lock_unchecked();
         state.history.replace(new_history);
     }
 
-    let rollout_item = RolloutItem::Compacted(CompactedItem {
+    // The carried-over items follow the Compacted entry so that resuming
+    // rebuilds the same history.
+    let mut rollout_items = vec![RolloutItem::Compacted(CompactedItem {
         message: summary_text.clone(),
-    });
-    sess.persist_rollout_items(&[rollout_item]).await;
+        model: Some(client.get_model()),
+        instructions,
+    })];
+    rollout_items.extend(preserved.into_iter().map(RolloutItem::ResponseItem));
+    sess.persist_rollout_items(&rollout_items).await;
 
     let event = Event {
         id: sub_id.clone(),
@@ -199,6 +225,7 @@ async fn run_compact_task_inner(
         id: sub_id.clone(),
         msg: EventMsg::TaskComplete(TaskCompleteEvent {
             last_agent_message: None,
+            budget_exhausted: None,
         }),
     };
     sess.send_event(event).await;
@@ -232,10 +259,229 @@ pub(crate) fn collect_user_messages(items: &[ResponseItem]) -> Vec<String> {
             }
             _ => None,
         })
-        .filter(|text| !is_session_prefix_message(text))
+        .filter(|text| !is_session_prefix_message(text) && !is_carried_over_context(text))
         .collect()
 }
 
+fn is_carried_over_context(text: &str) -> bool {
+    let text = text.trim_start();
+    text.starts_with(PINNED_CONTEXT_OPEN_TAG) || text.starts_with(PRESERVED_CONTEXT_OPEN_TAG)
+}
+
+/// Wrap a `/pin` note as the user message text recorded in history.
+pub(crate) fn pinned_context_text(note: &str) -> String {
+    format!("{PINNED_CONTEXT_OPEN_TAG}\n{note}\n{PINNED_CONTEXT_CLOSE_TAG}")
+}
+
+/// Notes pinned with `/pin`, oldest first.
+pub(crate) fn pinned_notes(items: &[ResponseItem]) -> Vec<String> {
+    items.iter().filter_map(pinned_note).collect()
+}
+
+fn pinned_note(item: &ResponseItem) -> Option<String> {
+    let ResponseItem::Message { role, content, .. } = item else {
+        return None;
+    };
+    if role != "user" {
+        return None;
+    }
+    let text = content_items_to_text(content)?;
+    let note = text
+        .trim()
+        .strip_prefix(PINNED_CONTEXT_OPEN_TAG)?
+        .strip_suffix(PINNED_CONTEXT_CLOSE_TAG)?;
+    Some(note.trim().to_string())
+}
+
+/// What compaction carries over besides the summary.
+pub(crate) struct PreserveOptions {
+    /// Bytes of the most recent tool output to keep.
+    pub(crate) tool_output_budget: usize,
+    /// Keep the latest `update_plan` call and `plan_artifact`.
+    pub(crate) preserve_plan: bool,
+    /// Markdown rendering of the active Plan Mode artifact, if any.
+    pub(crate) plan_artifact: Option<String>,
+}
+
+/// Items appended after the summary bridge so work in progress survives
+/// compaction: pinned notes verbatim, then a single message holding the
+/// latest plan and the most recent tool results.
+pub(crate) fn collect_preserved_context(
+    items: &[ResponseItem],
+    options: &PreserveOptions,
+) -> Vec<ResponseItem> {
+    let mut preserved: Vec<ResponseItem> = items
+        .iter()
+        .filter(|item| pinned_note(item).is_some())
+        .cloned()
+        .collect();
+
+    let mut sections = Vec::new();
+    if options.preserve_plan {
+        if let Some(plan) = latest_plan(items) {
+            sections.push(format!("## Current plan\n{plan}"));
+        }
+        if let Some(artifact) = options.plan_artifact.as_deref().or_else(|| {
+            items
+                .iter()
+                .rev()
+                .find_map(|item| applied_plan_summary(item))
+        }) {
+            sections.push(format!("## Plan Mode plan\n{artifact}"));
+        }
+    }
+    let tool_results = recent_tool_results(items, options.tool_output_budget);
+    if !tool_results.is_empty() {
+        sections.push(format!(
+            "## Recent tool results\n{}",
+            tool_results.join("\n\n")
+        ));
+    }
+
+    if !sections.is_empty() {
+        preserved.push(ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: format!(
+                    "{PRESERVED_CONTEXT_OPEN_TAG}\nThis state was carried over from before the conversation was summarized.\n\n{}\n{PRESERVED_CONTEXT_CLOSE_TAG}",
+                    sections.join("\n\n")
+                ),
+            }],
+        });
+    }
+    preserved
+}
+
+/// The most recent `update_plan` call, rendered as a checklist.
+fn latest_plan(items: &[ResponseItem]) -> Option<String> {
+    let arguments = items.iter().rev().find_map(|item| match item {
+        ResponseItem::FunctionCall {
+            name, arguments, ..
+        } if name == UPDATE_PLAN_TOOL_NAME => Some(arguments),
+        _ => None,
+    })?;
+    let Ok(args) = serde_json::from_str::<UpdatePlanArgs>(arguments) else {
+        return Some(arguments.clone());
+    };
+    let mut lines = Vec::new();
+    if let Some(explanation) = args.explanation.filter(|text| !text.trim().is_empty()) {
+        lines.push(explanation.trim().to_string());
+    }
+    for item in args.plan {
+        let status = match item.status {
+            StepStatus::Pending => "pending",
+            StepStatus::InProgress => "in_progress",
+            StepStatus::Completed => "completed",
+        };
+        lines.push(format!("- [{status}] {}", item.step));
+    }
+    Some(lines.join("\n"))
+}
+
+/// The summary recorded by `/apply-plan`, see `Session::record_plan_summary`.
+fn applied_plan_summary(item: &ResponseItem) -> Option<&str> {
+    match item {
+        ResponseItem::Message { role, content, .. } if role == "system" => {
+            content.iter().find_map(|content| match content {
+                ContentItem::InputText { text } if text.starts_with(PLAN_SUMMARY_PREFIX) => {
+                    Some(text.as_str())
+                }
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Newest-last tool results whose combined output fits in `budget` bytes.
+/// Only the newest result is ever truncated (in the middle) to fit.
+fn recent_tool_results(items: &[ResponseItem], budget: usize) -> Vec<String> {
+    if budget == 0 {
+        return Vec::new();
+    }
+    let mut calls: HashMap<&str, String> = HashMap::new();
+    for item in items {
+        match item {
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                if name != UPDATE_PLAN_TOOL_NAME {
+                    calls.insert(call_id, describe_function_call(name, arguments));
+                }
+            }
+            ResponseItem::LocalShellCall {
+                call_id: Some(call_id),
+                action: LocalShellAction::Exec(exec),
+                ..
+            } => {
+                calls.insert(call_id, format!("shell: {}", exec.command.join(" ")));
+            }
+            ResponseItem::CustomToolCall {
+                call_id,
+                name,
+                input,
+                ..
+            } => {
+                let (input, _) = truncate_middle(input, TOOL_CALL_LABEL_MAX_BYTES);
+                calls.insert(call_id, format!("{name}: {input}"));
+            }
+            _ => {}
+        }
+    }
+
+    let mut remaining = budget;
+    let mut results = Vec::new();
+    for item in items.iter().rev() {
+        let (call_id, output) = match item {
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                (call_id.as_str(), output.content.as_str())
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                (call_id.as_str(), output.as_str())
+            }
+            _ => continue,
+        };
+        let Some(label) = calls.get(call_id) else {
+            continue;
+        };
+        if output.len() > remaining && !results.is_empty() {
+            break;
+        }
+        let (output, truncated) = truncate_middle(output, remaining);
+        results.push(format!("### {label}\n{output}"));
+        if truncated.is_some() {
+            break;
+        }
+        remaining -= output.len();
+        if remaining == 0 {
+            break;
+        }
+    }
+    results.reverse();
+    results
+}
+
+fn describe_function_call(name: &str, arguments: &str) -> String {
+    let command = serde_json::from_str::<serde_json::Value>(arguments)
+        .ok()
+        .and_then(|value| {
+            value.get("command")?.as_array().map(|parts| {
+                parts
+                    .iter()
+                    .filter_map(|part| part.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+        });
+    let detail = command.unwrap_or_else(|| arguments.to_string());
+    let (detail, _) = truncate_middle(&detail, TOOL_CALL_LABEL_MAX_BYTES);
+    format!("{name}: {detail}")
+}
+
 fn is_session_prefix_message(text: &str) -> bool {
     matches!(
         InputMessageKind::from(("user", text)),
@@ -274,12 +520,55 @@ pub(crate) fn build_compacted_history(
     history
 }
 
+/// The compact prompt, with the user's `/compact <instructions>` appended.
+fn summarization_prompt(instructions: Option<&str>) -> String {
+    match instructions {
+        Some(instructions) => format!(
+            "{SUMMARIZATION_PROMPT}\n\nThe user asked for the following when summarizing:\n{instructions}\n"
+        ),
+        None => SUMMARIZATION_PROMPT.to_string(),
+    }
+}
+
+/// Client for the summarization request: the session client unless
+/// `compact_model` or `compact_provider` is configured.
+fn compaction_client(sess: &Session, client: &ModelClient) -> ModelClient {
+    let config = client.get_config();
+    if config.compact_model.is_none() && config.compact_provider.is_none() {
+        return client.clone();
+    }
+
+    let (model, model_family) = match &config.compact_model {
+        Some(model) => (model.clone(), config.model_family_or_default(model)),
+        None => (client.get_model(), client.get_model_family()),
+    };
+    let mut compact_config = (*config).clone();
+    compact_config.model = model;
+    compact_config.model_context_window = config
+        .model_info_for(&model_family)
+        .map(|info| info.context_window)
+        .or(config.model_context_window);
+    compact_config.model_family = model_family;
+    let provider = config
+        .compact_provider
+        .clone()
+        .unwrap_or_else(|| client.get_provider());
+    ModelClient::new(
+        Arc::new(compact_config),
+        client.get_auth_manager(),
+        provider,
+        client.get_reasoning_effort(),
+        client.get_reasoning_summary(),
+        sess.conversation_id,
+    )
+}
+
 async fn drain_to_completed(
     sess: &Session,
-    turn_context: &TurnContext,
+    client: &ModelClient,
     prompt: &Prompt,
 ) -> CodexResult<()> {
-    let mut stream = turn_context.client.clone().stream(prompt).await?;
+    let mut stream = client.clone().stream(prompt).await?;
     loop {
         let maybe_event = stream.next().await;
         let Some(event) = maybe_event else {
@@ -305,6 +594,7 @@ async fn drain_to_completed(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -397,4 +687,138 @@ mod tests {
 
         assert_eq!(vec!["real user message".to_string()], collected);
     }
+
+    fn user_text(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    fn function_call(name: &str, arguments: &str, call_id: &str) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: None,
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+            call_id: call_id.to_string(),
+        }
+    }
+
+    fn function_output(call_id: &str, content: &str) -> ResponseItem {
+        ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: content.to_string(),
+                success: Some(true),
+            },
+        }
+    }
+
+    fn options(tool_output_budget: usize) -> PreserveOptions {
+        PreserveOptions {
+            tool_output_budget,
+            preserve_plan: true,
+            plan_artifact: None,
+        }
+    }
+
+    fn message_text(item: &ResponseItem) -> String {
+        match item {
+            ResponseItem::Message { content, .. } => {
+                content_items_to_text(content).unwrap_or_default()
+            }
+            other => panic!("expected a message, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn pinned_notes_round_trip_and_are_not_user_messages() {
+        let items = vec![
+            user_text("real user message"),
+            user_text(&pinned_context_text("keep the v2 schema")),
+        ];
+
+        assert_eq!(vec!["keep the v2 schema".to_string()], pinned_notes(&items));
+        assert_eq!(
+            vec!["real user message".to_string()],
+            collect_user_messages(&items)
+        );
+    }
+
+    #[test]
+    fn preserved_context_keeps_pins_plan_and_recent_tool_results() {
+        let items = vec![
+            user_text(&pinned_context_text("keep the v2 schema")),
+            function_call(
+                "update_plan",
+                r#"{"plan":[{"step":"write migration","status":"completed"},{"step":"backfill","status":"in_progress"}]}"#,
+                "plan-1",
+            ),
+            function_output("plan-1", "Plan updated"),
+            function_call("shell", r#"{"command":["cat","old.rs"]}"#, "call-1"),
+            function_output("call-1", "old contents"),
+            function_call("shell", r#"{"command":["cat","src/db.rs"]}"#, "call-2"),
+            function_output("call-2", "fn migrate() {}"),
+        ];
+
+        // Only the newest output fits the budget.
+        let preserved = collect_preserved_context(&items, &options(20));
+
+        assert_eq!(preserved.len(), 2);
+        assert_eq!(items[0], preserved[0]);
+        assert_eq!(
+            message_text(&preserved[1]),
+            "<preserved_context>\n\
+             This state was carried over from before the conversation was summarized.\n\n\
+             ## Current plan\n\
+             - [completed] write migration\n\
+             - [in_progress] backfill\n\n\
+             ## Recent tool results\n\
+             ### shell: cat src/db.rs\n\
+             fn migrate() {}\n\
+             </preserved_context>"
+        );
+        assert!(collect_user_messages(&preserved).is_empty());
+    }
+
+    #[test]
+    fn preserved_context_is_empty_without_pins_plan_or_budget() {
+        let items = vec![
+            user_text("hello"),
+            function_call("shell", r#"{"command":["ls"]}"#, "call-1"),
+            function_output("call-1", "Cargo.toml"),
+        ];
+
+        assert!(collect_preserved_context(&items, &options(0)).is_empty());
+    }
+
+    #[test]
+    fn preserved_context_prefers_active_plan_mode_artifact() {
+        let items = vec![ResponseItem::Message {
+            id: None,
+            role: "system".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "Plan Ready for Execution: old plan".to_string(),
+            }],
+        }];
+        let from_history = collect_preserved_context(&items, &options(0));
+        assert!(
+            message_text(&from_history[0])
+                .contains("## Plan Mode plan\nPlan Ready for Execution: old plan")
+        );
+
+        let from_session = collect_preserved_context(
+            &items,
+            &PreserveOptions {
+                plan_artifact: Some("Plan Ready for Execution: new plan".to_string()),
+                ..options(0)
+            },
+        );
+        let text = message_text(&from_session[0]);
+        assert!(text.contains("new plan"));
+        assert!(!text.contains("old plan"));
+    }
 }
@@ -1,6 +1,10 @@
+use crate::budget::SessionBudget;
+use crate::config_layers::ConfigLayers;
 use crate::config_profile::ConfigProfile;
+use crate::config_types::FallbackPolicy;
 use crate::config_types::History;
 use crate::config_types::McpServerConfig;
+use crate::config_types::ModelCapabilities;
 use crate::config_types::Notifications;
 use crate::config_types::ReasoningSummaryFormat;
 use crate::config_types::SandboxWorkspaceWrite;
@@ -8,15 +12,23 @@ use crate::config_types::ShellEnvironmentPolicy;
 use crate::config_types::ShellEnvironmentPolicyToml;
 use crate::config_types::Tui;
 use crate::config_types::UriBasedFileOpener;
+use crate::cost::CostLimits;
+use crate::cost::ModelPricing;
+use crate::flags::CODEX_RECORD_DIR;
 use crate::git_info::resolve_root_git_project_for_trust;
 use crate::hooks::config_loader::{HookConfigError, HookConfigLoader};
 use crate::hooks::{HookRegistry, HookScope};
+use crate::model_fallback::FallbackModel;
+use crate::model_fallback::resolve_fallback_models;
 use crate::model_family::ModelFamily;
 use crate::model_family::derive_default_model_family;
 use crate::model_family::find_family_for_model;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::built_in_model_providers;
+use crate::model_recording::ModelStreamRecording;
+use crate::openai_model_info::ModelInfo;
 use crate::openai_model_info::get_model_info;
+use crate::openai_model_info::get_model_pricing;
 use crate::plan_mode::PlanModeConfig;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
@@ -31,6 +43,7 @@ use codex_protocol::config_types::Verbosity;
 use codex_protocol::mcp_protocol::Tools;
 use codex_protocol::mcp_protocol::UserSavedConfig;
 use dirs::home_dir;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
@@ -54,7 +67,10 @@ pub const GPT_5_CODEX_MEDIUM_MODEL: &str = "gpt-5-codex";
 /// the context window.
 pub(crate) const PROJECT_DOC_MAX_BYTES: usize = 32 * 1024; // 32 KiB
 
-pub(crate) const CONFIG_TOML_FILE: &str = "config.toml";
+pub const CONFIG_TOML_FILE: &str = "config.toml";
+
+/// Bytes of recent tool output carried over by compaction by default.
+const DEFAULT_COMPACT_TOOL_OUTPUT_BUDGET: usize = 16 * 1024;
 
 /// Application configuration loaded from disk and merged with overrides.
 #[derive(Debug, Clone, PartialEq)]
@@ -65,6 +81,21 @@ pub struct Config {
     /// Model used specifically for review sessions. Defaults to "gpt-5".
     pub review_model: String,
 
+    /// Model used to summarize the conversation when compacting. Defaults to
+    /// the session model.
+    pub compact_model: Option<String>,
+
+    /// Provider used with `compact_model`. Defaults to the session provider.
+    pub compact_provider: Option<ModelProviderInfo>,
+
+    /// Bytes of the most recent tool output carried over verbatim when the
+    /// conversation is compacted. `0` disables.
+    pub compact_tool_output_budget: usize,
+
+    /// Whether compaction carries over the latest `update_plan` state and
+    /// Plan Mode artifact.
+    pub compact_preserve_plan: bool,
+
     pub model_family: ModelFamily,
 
     /// Size of the context window for the model, in tokens.
@@ -82,6 +113,12 @@ pub struct Config {
     /// Info needed to make an API request to the model.
     pub model_provider: ModelProviderInfo,
 
+    /// Models a failed turn is retried on, in order.
+    pub fallback_models: Vec<FallbackModel>,
+
+    /// Which failures move a turn to `fallback_models`.
+    pub fallback_policy: FallbackPolicy,
+
     /// Approval policy for executing commands.
     pub approval_policy: AskForApproval,
 
@@ -206,10 +243,40 @@ pub struct Config {
     /// The active profile name used to derive this `Config` (if any).
     pub active_profile: Option<String>,
 
+    /// Profiles defined in the merged configuration, which the TUI offers to
+    /// switch between.
+    pub profiles: HashMap<String, ConfigProfile>,
+
     /// When true, disables burst-paste detection for typed input entirely.
     /// All characters are inserted as they are received, and no buffering
     /// or placeholder replacement will occur for fast keypress bursts.
     pub disable_paste_burst: bool,
+
+    /// Record model traffic to, or replay it from, a directory. Set by
+    /// `--record`/`--replay` or the `CODEX_RECORD_DIR` environment variable.
+    pub model_stream_recording: Option<ModelStreamRecording>,
+
+    /// JSON Schema the final agent message must conform to. Set by
+    /// `codex exec --output-schema`.
+    pub output_schema: Option<serde_json::Value>,
+
+    /// Turn, token, time and tool call limits for each session. Set by
+    /// `codex exec` flags and MCP `newConversation` parameters.
+    pub session_budget: SessionBudget,
+
+    /// Per-token prices from `[pricing]`, keyed by provider id then model.
+    /// Models not listed use the built-in prices.
+    pub pricing: HashMap<String, HashMap<String, ModelPricing>>,
+
+    /// `max_session_cost` and `max_daily_cost`.
+    pub cost_limits: CostLimits,
+
+    /// Capability overrides from `[model_capabilities]`, keyed by model slug.
+    pub model_capabilities: HashMap<String, ModelCapabilities>,
+
+    /// Snapshot the files the agent edits so `/undo` and `/rewind` can
+    /// restore them.
+    pub checkpoints: bool,
 }
 
 impl Config {
@@ -217,16 +284,45 @@ impl Config {
         &self.cwd
     }
 
+    /// Prices for the configured model, if known.
+    pub fn model_pricing(&self) -> Option<ModelPricing> {
+        self.pricing
+            .get(&self.model_provider_id)
+            .and_then(|models| models.get(&self.model))
+            .copied()
+            .or_else(|| get_model_pricing(&self.model_family))
+    }
+
     pub fn codex_home(&self) -> &PathBuf {
         &self.codex_home
     }
 
+    /// The model family for `model` with its `[model_capabilities]` entry
+    /// applied, or `None` for a model that is neither known nor configured.
+    pub fn model_family_for(&self, model: &str) -> Option<ModelFamily> {
+        model_family_with_capabilities(model, &self.model_capabilities)
+    }
+
+    /// [`Self::model_family_for`], or a family derived from the slug for a
+    /// model that is neither known nor configured, e.g. one the provider
+    /// lists.
+    pub fn model_family_or_default(&self, model: &str) -> ModelFamily {
+        self.model_family_for(model)
+            .unwrap_or_else(|| derive_default_model_family(model))
+    }
+
+    /// Context window and output limit for `model_family`, with its
+    /// `[model_capabilities]` entry applied.
+    pub(crate) fn model_info_for(&self, model_family: &ModelFamily) -> Option<ModelInfo> {
+        model_info_with_capabilities(model_family, &self.model_capabilities)
+    }
+
     /// Load configuration with *generic* CLI overrides (`-c key=value`) applied
-    /// **in between** the values parsed from `config.toml` and the
+    /// **in between** the values parsed from the config files and the
     /// strongly-typed overrides specified via [`ConfigOverrides`].
     ///
-    /// The precedence order is therefore: `config.toml` < `-c` overrides <
-    /// `ConfigOverrides`.
+    /// The precedence order is therefore: `config.toml` < trusted project
+    /// `.codex/config.toml` < `-c` overrides < `ConfigOverrides`.
     pub fn load_with_cli_overrides(
         cli_overrides: Vec<(String, TomlValue)>,
         overrides: ConfigOverrides,
@@ -236,13 +332,13 @@ impl Config {
         // `Config` instance.
         let codex_home = find_codex_home()?;
 
-        // Step 1: parse `config.toml` into a generic JSON value.
-        let mut root_value = load_config_as_toml(&codex_home)?;
+        // Step 1: resolve the working directory, which decides the project
+        // config that applies.
+        let cwd = resolve_cwd(overrides.cwd.clone())?;
 
-        // Step 2: apply the `-c` overrides.
-        for (path, value) in cli_overrides.into_iter() {
-            apply_toml_override(&mut root_value, &path, value);
-        }
+        // Step 2: merge `config.toml`, the project config and the `-c`
+        // overrides into a generic TOML value.
+        let root_value = ConfigLayers::load(&codex_home, &cwd, cli_overrides)?.merged();
 
         // Step 3: deserialize into `ConfigToml` so that Serde can enforce the
         // correct types.
@@ -252,6 +348,10 @@ impl Config {
         })?;
 
         // Step 4: merge with the strongly-typed overrides.
+        let overrides = ConfigOverrides {
+            cwd: Some(cwd),
+            ..overrides
+        };
         Self::load_from_base_config_with_overrides(cfg, overrides, codex_home)
     }
 }
@@ -581,8 +681,27 @@ pub async fn persist_model_selection(
     Ok(())
 }
 
+/// Resolve the session's working directory: `cwd` if absolute, otherwise
+/// relative to the current directory.
+fn resolve_cwd(cwd: Option<PathBuf>) -> std::io::Result<PathBuf> {
+    match cwd {
+        None => {
+            tracing::info!("cwd not set, using current dir");
+            env::current_dir()
+        }
+        Some(p) if p.is_absolute() => Ok(p),
+        Some(p) => {
+            // Resolve relative path against the current working directory.
+            tracing::info!("cwd is relative, resolving against current dir");
+            let mut current = env::current_dir()?;
+            current.push(p);
+            Ok(current)
+        }
+    }
+}
+
 /// Apply a single dotted-path override onto a TOML value.
-fn apply_toml_override(root: &mut TomlValue, path: &str, value: TomlValue) {
+pub(crate) fn apply_toml_override(root: &mut TomlValue, path: &str, value: TomlValue) {
     use toml::value::Table;
 
     let segments: Vec<&str> = path.split('.').collect();
@@ -712,10 +831,11 @@ fn push_hook_sources_for_path(
     if let Ok(entries) = fs::read_dir(candidate) {
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.is_file() && is_toml_file(&path) {
-                if !sources.iter().any(|(_, existing)| existing == &path) {
-                    sources.push((scope.clone(), path));
-                }
+            if path.is_file()
+                && is_toml_file(&path)
+                && !sources.iter().any(|(_, existing)| existing == &path)
+            {
+                sources.push((scope.clone(), path));
             }
         }
     }
@@ -731,13 +851,26 @@ fn is_toml_file(path: &Path) -> bool {
 }
 
 /// Base config deserialized from ~/.codex/config.toml.
-#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
 pub struct ConfigToml {
     /// Optional override of model selection.
     pub model: Option<String>,
     /// Review model override used by the `/review` feature.
     pub review_model: Option<String>,
 
+    /// Model used to summarize the conversation when compacting, e.g. a
+    /// cheaper model than the one driving the session.
+    pub compact_model: Option<String>,
+
+    /// Key into `model_providers` for `compact_model`.
+    pub compact_provider: Option<String>,
+
+    /// Bytes of recent tool output kept across compaction. Defaults to 16 KiB.
+    pub compact_tool_output_budget: Option<usize>,
+
+    /// Keep the latest plan across compaction. Defaults to true.
+    pub compact_preserve_plan: Option<bool>,
+
     /// Provider to use from the model_providers map.
     pub model_provider: Option<String>,
 
@@ -750,6 +883,36 @@ pub struct ConfigToml {
     /// Token usage threshold triggering auto-compaction of conversation history.
     pub model_auto_compact_token_limit: Option<i64>,
 
+    /// Models to retry a failed turn on, in order, written as
+    /// `provider:model` (or just `model` for the session's provider).
+    #[serde(default)]
+    pub fallback_models: Vec<String>,
+
+    /// Which failures move a turn to `fallback_models`.
+    #[serde(default)]
+    pub fallback_policy: FallbackPolicy,
+
+    /// Per-token prices in US dollars per million tokens, keyed by provider
+    /// id then model. Overrides the built-in prices.
+    #[serde(default)]
+    pub pricing: HashMap<String, HashMap<String, ModelPricing>>,
+
+    /// Ask before continuing once a session has cost this many US dollars.
+    pub max_session_cost: Option<f64>,
+
+    /// Ask before continuing once today's sessions have cost this many US
+    /// dollars.
+    pub max_daily_cost: Option<f64>,
+
+    /// Capabilities of models Codex cannot infer from their names, keyed by
+    /// model slug.
+    #[serde(default)]
+    pub model_capabilities: HashMap<String, ModelCapabilities>,
+
+    /// Snapshot the files the agent edits so `/undo` and `/rewind` can
+    /// restore them. Defaults to `true`.
+    pub checkpoints: Option<bool>,
+
     /// Default approval policy for executing commands.
     pub approval_policy: Option<AskForApproval>,
 
@@ -843,14 +1006,14 @@ pub struct ConfigToml {
     pub subagents: Option<SubagentsToml>,
 }
 
-#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default, JsonSchema)]
 pub struct SubagentsToml {
     pub enabled: Option<bool>,
     pub default_model: Option<String>,
     pub discovery: Option<SubagentDiscoveryToml>,
 }
 
-#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum SubagentDiscoveryToml {
     #[default]
@@ -867,6 +1030,16 @@ impl From<SubagentDiscoveryToml> for SubagentDiscoveryMode {
     }
 }
 
+impl SubagentsToml {
+    fn overlay(self, profile: SubagentsToml) -> Self {
+        Self {
+            enabled: profile.enabled.or(self.enabled),
+            default_model: profile.default_model.or(self.default_model),
+            discovery: profile.discovery.or(self.discovery),
+        }
+    }
+}
+
 impl From<SubagentsToml> for SubagentConfig {
     fn from(toml: SubagentsToml) -> Self {
         let discovery = toml
@@ -906,12 +1079,12 @@ impl From<ConfigToml> for UserSavedConfig {
     }
 }
 
-#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
 pub struct ProjectConfig {
     pub trust_level: Option<String>,
 }
 
-#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
 pub struct PlanModeToml {
     #[serde(default)]
     pub plan_enabled: Option<bool>,
@@ -923,7 +1096,7 @@ pub struct PlanModeToml {
     pub apply_requires_confirmation: Option<bool>,
 }
 
-#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
 pub struct ToolsToml {
     #[serde(default, alias = "web_search_request")]
     pub web_search: Option<bool>,
@@ -933,6 +1106,43 @@ pub struct ToolsToml {
     pub view_image: Option<bool>,
 }
 
+impl ToolsToml {
+    fn overlay(self, profile: ToolsToml) -> Self {
+        Self {
+            web_search: profile.web_search.or(self.web_search),
+            view_image: profile.view_image.or(self.view_image),
+        }
+    }
+}
+
+impl PlanModeToml {
+    fn overlay(self, profile: PlanModeToml) -> Self {
+        Self {
+            plan_enabled: profile.plan_enabled.or(self.plan_enabled),
+            allowed_read_only_tools: profile
+                .allowed_read_only_tools
+                .or(self.allowed_read_only_tools),
+            planning_model: profile.planning_model.or(self.planning_model),
+            apply_requires_confirmation: profile
+                .apply_requires_confirmation
+                .or(self.apply_requires_confirmation),
+        }
+    }
+}
+
+/// Overlay a profile's copy of a settings table on the top-level one, field by
+/// field.
+fn overlay_table<T: Default>(
+    base: Option<T>,
+    profile: Option<T>,
+    overlay: fn(T, T) -> T,
+) -> Option<T> {
+    match profile {
+        Some(profile) => Some(overlay(base.unwrap_or_default(), profile)),
+        None => base,
+    }
+}
+
 impl From<ToolsToml> for Tools {
     fn from(tools_toml: ToolsToml) -> Self {
         Self {
@@ -1045,6 +1255,8 @@ pub struct ConfigOverrides {
     pub show_raw_agent_reasoning: Option<bool>,
     pub tools_web_search_request: Option<bool>,
     pub plan_mode_enabled: Option<bool>,
+    pub record_dir: Option<PathBuf>,
+    pub replay_dir: Option<PathBuf>,
 }
 
 impl Config {
@@ -1074,6 +1286,8 @@ impl Config {
             show_raw_agent_reasoning,
             tools_web_search_request: override_tools_web_search_request,
             plan_mode_enabled,
+            record_dir,
+            replay_dir,
         } = overrides;
 
         let active_profile_name = config_profile_key
@@ -1094,7 +1308,31 @@ impl Config {
             None => ConfigProfile::default(),
         };
 
-        let sandbox_policy = cfg.derive_sandbox_policy(sandbox_mode);
+        // Tables a profile carries are overlaid on the top-level ones.
+        let mut cfg = cfg;
+        if config_profile.sandbox_workspace_write.is_some() {
+            cfg.sandbox_workspace_write = config_profile.sandbox_workspace_write;
+        }
+        cfg.mcp_servers.extend(config_profile.mcp_servers);
+        for name in &config_profile.disabled_mcp_servers {
+            if cfg.mcp_servers.remove(name).is_none() {
+                tracing::warn!("profile disables unknown MCP server `{name}`");
+            }
+        }
+        cfg.tools = overlay_table(cfg.tools, config_profile.tools, ToolsToml::overlay);
+        cfg.plan_mode = overlay_table(
+            cfg.plan_mode,
+            config_profile.plan_mode,
+            PlanModeToml::overlay,
+        );
+        cfg.subagents = overlay_table(
+            cfg.subagents,
+            config_profile.subagents,
+            SubagentsToml::overlay,
+        );
+
+        let sandbox_policy =
+            cfg.derive_sandbox_policy(sandbox_mode.or(config_profile.sandbox_mode));
 
         let mut model_providers = built_in_model_providers();
         // Merge user-defined providers into the built-in list.
@@ -1116,27 +1354,37 @@ impl Config {
             })?
             .clone();
 
-        let shell_environment_policy = cfg.shell_environment_policy.into();
-
-        let resolved_cwd = {
-            use std::env;
+        let fallback_models = resolve_fallback_models(
+            config_profile
+                .fallback_models
+                .as_ref()
+                .unwrap_or(&cfg.fallback_models),
+            &model_provider_id,
+            &model_providers,
+        )?;
 
-            match cwd {
-                None => {
-                    tracing::info!("cwd not set, using current dir");
-                    env::current_dir()?
-                }
-                Some(p) if p.is_absolute() => p,
-                Some(p) => {
-                    // Resolve relative path against the current working directory.
-                    tracing::info!("cwd is relative, resolving against current dir");
-                    let mut current = env::current_dir()?;
-                    current.push(p);
-                    current
-                }
-            }
+        let cost_limits = CostLimits {
+            max_session_usd: validate_cost_limit("max_session_cost", cfg.max_session_cost)?,
+            max_daily_usd: validate_cost_limit("max_daily_cost", cfg.max_daily_cost)?,
         };
 
+        let compact_provider = cfg
+            .compact_provider
+            .as_ref()
+            .map(|id| {
+                model_providers.get(id).cloned().ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        format!("Compact model provider `{id}` not found"),
+                    )
+                })
+            })
+            .transpose()?;
+
+        let shell_environment_policy = cfg.shell_environment_policy.into();
+
+        let resolved_cwd = resolve_cwd(cwd)?;
+
         let history = cfg.history.unwrap_or_default();
 
         let subagents = cfg
@@ -1158,8 +1406,8 @@ impl Config {
             .or(cfg.model)
             .unwrap_or_else(default_model);
 
-        let mut model_family =
-            find_family_for_model(&model).unwrap_or_else(|| derive_default_model_family(&model));
+        let mut model_family = model_family_with_capabilities(&model, &cfg.model_capabilities)
+            .unwrap_or_else(|| derive_default_model_family(&model));
 
         if let Some(supports_reasoning_summaries) = cfg.model_supports_reasoning_summaries {
             model_family.supports_reasoning_summaries = supports_reasoning_summaries;
@@ -1168,7 +1416,8 @@ impl Config {
             model_family.reasoning_summary_format = model_reasoning_summary_format;
         }
 
-        let openai_model_info = get_model_info(&model_family);
+        let openai_model_info =
+            model_info_with_capabilities(&model_family, &cfg.model_capabilities);
         let model_context_window = cfg
             .model_context_window
             .or_else(|| openai_model_info.as_ref().map(|info| info.context_window));
@@ -1225,15 +1474,31 @@ impl Config {
             hook_registry.insert(vec![definition]);
         }
 
+        let model_stream_recording = match (replay_dir, record_dir) {
+            (Some(dir), _) => Some(ModelStreamRecording::replay(dir)),
+            (None, Some(dir)) => Some(ModelStreamRecording::record(dir)),
+            (None, None) => {
+                CODEX_RECORD_DIR.map(|dir| ModelStreamRecording::record(PathBuf::from(dir)))
+            }
+        };
+
         let config = Self {
             model,
             review_model,
+            compact_model: cfg.compact_model,
+            compact_provider,
+            compact_tool_output_budget: cfg
+                .compact_tool_output_budget
+                .unwrap_or(DEFAULT_COMPACT_TOOL_OUTPUT_BUDGET),
+            compact_preserve_plan: cfg.compact_preserve_plan.unwrap_or(true),
             model_family,
             model_context_window,
             model_max_output_tokens,
             model_auto_compact_token_limit,
             model_provider_id,
             model_provider,
+            fallback_models,
+            fallback_policy: cfg.fallback_policy,
             cwd: resolved_cwd,
             approval_policy: approval_policy
                 .or(config_profile.approval_policy)
@@ -1283,7 +1548,15 @@ impl Config {
             plan_mode: plan_mode_config,
             subagents,
             active_profile: active_profile_name,
+            profiles: cfg.profiles,
             disable_paste_burst: cfg.disable_paste_burst.unwrap_or(false),
+            model_stream_recording,
+            output_schema: None,
+            session_budget: SessionBudget::default(),
+            pricing: cfg.pricing,
+            cost_limits,
+            model_capabilities: cfg.model_capabilities,
+            checkpoints: cfg.checkpoints.unwrap_or(true),
             tui_notifications: cfg
                 .tui
                 .as_ref()
@@ -1353,6 +1626,50 @@ impl Config {
     }
 }
 
+fn model_family_with_capabilities(
+    model: &str,
+    model_capabilities: &HashMap<String, ModelCapabilities>,
+) -> Option<ModelFamily> {
+    let capabilities = model_capabilities.get(model);
+    let family = find_family_for_model(model)
+        .or_else(|| capabilities.map(|_| derive_default_model_family(model)))?;
+    Some(match capabilities {
+        Some(capabilities) => family.with_capabilities(capabilities),
+        None => family,
+    })
+}
+
+fn model_info_with_capabilities(
+    model_family: &ModelFamily,
+    model_capabilities: &HashMap<String, ModelCapabilities>,
+) -> Option<ModelInfo> {
+    let mut info = get_model_info(model_family);
+    let Some(capabilities) = model_capabilities.get(&model_family.slug) else {
+        return info;
+    };
+    if let Some(context_window) = capabilities.context_window {
+        let max_output_tokens = capabilities.max_output_tokens.unwrap_or(context_window);
+        info.get_or_insert_with(|| ModelInfo::new(context_window, max_output_tokens))
+            .context_window = context_window;
+    }
+    if let Some(info) = info.as_mut()
+        && let Some(max_output_tokens) = capabilities.max_output_tokens
+    {
+        info.max_output_tokens = max_output_tokens;
+    }
+    info
+}
+
+fn validate_cost_limit(key: &str, limit: Option<f64>) -> std::io::Result<Option<f64>> {
+    match limit {
+        Some(usd) if !(usd.is_finite() && usd > 0.0) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("`{key}` must be a positive number of US dollars, got {usd}"),
+        )),
+        limit => Ok(limit),
+    }
+}
+
 fn default_model() -> String {
     OPENAI_DEFAULT_MODEL.to_string()
 }
@@ -1400,6 +1717,7 @@ pub fn log_dir(cfg: &Config) -> std::io::Result<PathBuf> {
 mod tests {
     use crate::config_types::HistoryPersistence;
     use crate::subagents::SubagentConfig;
+    use crate::tool_apply_patch::ApplyPatchToolType;
 
     use super::*;
     use pretty_assertions::assert_eq;
@@ -1418,6 +1736,8 @@ persistence = "save-all"
             Some(History {
                 persistence: HistoryPersistence::SaveAll,
                 max_bytes: None,
+                max_sessions: None,
+                max_age: None,
             }),
             history_with_persistence_cfg.history
         );
@@ -1433,9 +1753,36 @@ persistence = "none"
             Some(History {
                 persistence: HistoryPersistence::None,
                 max_bytes: None,
+                max_sessions: None,
+                max_age: None,
             }),
             history_no_persistence_cfg.history
         );
+
+        let history_with_retention = r#"
+[history]
+persistence = "save-all"
+max_sessions = 200
+max_age = "30d"
+"#;
+        let history_with_retention_cfg = toml::from_str::<ConfigToml>(history_with_retention)
+            .expect("TOML deserialization should succeed");
+        assert_eq!(
+            Some(History {
+                persistence: HistoryPersistence::SaveAll,
+                max_bytes: None,
+                max_sessions: Some(200),
+                max_age: chrono::Duration::try_days(30),
+            }),
+            history_with_retention_cfg.history
+        );
+
+        let invalid_max_age = r#"
+[history]
+persistence = "save-all"
+max_age = "a month"
+"#;
+        assert!(toml::from_str::<ConfigToml>(invalid_max_age).is_err());
     }
 
     #[test]
@@ -1819,12 +2166,18 @@ model_verbosity = "high"
             Config {
                 model: "o3".to_string(),
                 review_model: "gpt-5".to_string(),
+                compact_model: None,
+                compact_provider: None,
+                compact_tool_output_budget: DEFAULT_COMPACT_TOOL_OUTPUT_BUDGET,
+                compact_preserve_plan: true,
                 model_family: find_family_for_model("o3").expect("known model slug"),
                 model_context_window: Some(200_000),
                 model_max_output_tokens: Some(100_000),
                 model_auto_compact_token_limit: None,
                 model_provider_id: "openai".to_string(),
                 model_provider: fixture.openai_provider.clone(),
+                fallback_models: Vec::new(),
+                fallback_policy: FallbackPolicy::default(),
                 approval_policy: AskForApproval::Never,
                 sandbox_policy: SandboxPolicy::new_read_only_policy(),
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
@@ -1855,7 +2208,15 @@ model_verbosity = "high"
                 hook_registry: HookRegistry::default(),
                 plan_mode: PlanModeConfig::default(),
                 active_profile: Some("o3".to_string()),
+                profiles: fixture.cfg.profiles,
                 disable_paste_burst: false,
+                model_stream_recording: None,
+                output_schema: None,
+                session_budget: SessionBudget::default(),
+                pricing: HashMap::new(),
+                cost_limits: CostLimits::default(),
+                model_capabilities: HashMap::new(),
+                checkpoints: true,
                 tui_notifications: Default::default(),
             },
             o3_profile_config
@@ -1880,12 +2241,18 @@ model_verbosity = "high"
         let expected_gpt3_profile_config = Config {
             model: "gpt-3.5-turbo".to_string(),
             review_model: "gpt-5".to_string(),
+            compact_model: None,
+            compact_provider: None,
+            compact_tool_output_budget: DEFAULT_COMPACT_TOOL_OUTPUT_BUDGET,
+            compact_preserve_plan: true,
             model_family: find_family_for_model("gpt-3.5-turbo").expect("known model slug"),
             model_context_window: Some(16_385),
             model_max_output_tokens: Some(4_096),
             model_auto_compact_token_limit: None,
             model_provider_id: "openai-chat-completions".to_string(),
             model_provider: fixture.openai_chat_completions_provider.clone(),
+            fallback_models: Vec::new(),
+            fallback_policy: FallbackPolicy::default(),
             approval_policy: AskForApproval::UnlessTrusted,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
@@ -1916,7 +2283,15 @@ model_verbosity = "high"
             hook_registry: HookRegistry::default(),
             plan_mode: PlanModeConfig::default(),
             active_profile: Some("gpt3".to_string()),
+            profiles: fixture.cfg.profiles.clone(),
             disable_paste_burst: false,
+            model_stream_recording: None,
+            output_schema: None,
+            session_budget: SessionBudget::default(),
+            pricing: HashMap::new(),
+            cost_limits: CostLimits::default(),
+            model_capabilities: HashMap::new(),
+            checkpoints: true,
             tui_notifications: Default::default(),
         };
 
@@ -1956,12 +2331,18 @@ model_verbosity = "high"
         let expected_zdr_profile_config = Config {
             model: "o3".to_string(),
             review_model: "gpt-5".to_string(),
+            compact_model: None,
+            compact_provider: None,
+            compact_tool_output_budget: DEFAULT_COMPACT_TOOL_OUTPUT_BUDGET,
+            compact_preserve_plan: true,
             model_family: find_family_for_model("o3").expect("known model slug"),
             model_context_window: Some(200_000),
             model_max_output_tokens: Some(100_000),
             model_auto_compact_token_limit: None,
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            fallback_models: Vec::new(),
+            fallback_policy: FallbackPolicy::default(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
@@ -1992,7 +2373,15 @@ model_verbosity = "high"
             hook_registry: HookRegistry::default(),
             plan_mode: PlanModeConfig::default(),
             active_profile: Some("zdr".to_string()),
+            profiles: fixture.cfg.profiles,
             disable_paste_burst: false,
+            model_stream_recording: None,
+            output_schema: None,
+            session_budget: SessionBudget::default(),
+            pricing: HashMap::new(),
+            cost_limits: CostLimits::default(),
+            model_capabilities: HashMap::new(),
+            checkpoints: true,
             tui_notifications: Default::default(),
         };
 
@@ -2018,12 +2407,18 @@ model_verbosity = "high"
         let expected_gpt5_profile_config = Config {
             model: "gpt-5".to_string(),
             review_model: "gpt-5".to_string(),
+            compact_model: None,
+            compact_provider: None,
+            compact_tool_output_budget: DEFAULT_COMPACT_TOOL_OUTPUT_BUDGET,
+            compact_preserve_plan: true,
             model_family: find_family_for_model("gpt-5").expect("known model slug"),
             model_context_window: Some(272_000),
             model_max_output_tokens: Some(128_000),
             model_auto_compact_token_limit: None,
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            fallback_models: Vec::new(),
+            fallback_policy: FallbackPolicy::default(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
@@ -2054,7 +2449,15 @@ model_verbosity = "high"
             hook_registry: HookRegistry::default(),
             plan_mode: PlanModeConfig::default(),
             active_profile: Some("gpt5".to_string()),
+            profiles: fixture.cfg.profiles,
             disable_paste_burst: false,
+            model_stream_recording: None,
+            output_schema: None,
+            session_budget: SessionBudget::default(),
+            pricing: HashMap::new(),
+            cost_limits: CostLimits::default(),
+            model_capabilities: HashMap::new(),
+            checkpoints: true,
             tui_notifications: Default::default(),
         };
 
@@ -2063,6 +2466,124 @@ model_verbosity = "high"
         Ok(())
     }
 
+    #[test]
+    fn profile_overlays_sandbox_mcp_servers_tools_plan_mode_and_subagents() -> std::io::Result<()> {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+sandbox_mode = "read-only"
+
+[mcp_servers.docs]
+command = "docs-server"
+
+[mcp_servers.github]
+command = "github-server"
+
+[tools]
+web_search = true
+
+[plan_mode]
+plan_enabled = true
+planning_model = "o3"
+
+[profiles.autonomous]
+sandbox_mode = "workspace-write"
+disabled_mcp_servers = ["github"]
+
+[profiles.autonomous.sandbox_workspace_write]
+network_access = true
+
+[profiles.autonomous.mcp_servers.refactor]
+command = "refactor-server"
+
+[profiles.autonomous.tools]
+view_image = false
+
+[profiles.autonomous.plan_mode]
+plan_enabled = false
+
+[profiles.autonomous.subagents]
+enabled = true
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let codex_home = TempDir::new()?;
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                config_profile: Some("autonomous".to_string()),
+                cwd: Some(codex_home.path().to_path_buf()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert!(matches!(
+            config.sandbox_policy,
+            SandboxPolicy::WorkspaceWrite {
+                network_access: true,
+                ..
+            }
+        ));
+        let mut servers: Vec<&str> = config.mcp_servers.keys().map(String::as_str).collect();
+        servers.sort_unstable();
+        assert_eq!(servers, vec!["docs", "refactor"]);
+        assert!(config.tools_web_search_request);
+        assert!(!config.include_view_image_tool);
+        assert!(!config.plan_mode.plan_enabled);
+        assert_eq!(config.plan_mode.planning_model.as_deref(), Some("o3"));
+        assert!(config.subagents.is_enabled());
+
+        Ok(())
+    }
+
+    #[test]
+    fn model_capabilities_override_known_and_unknown_models() -> std::io::Result<()> {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+model = "llama3:8b"
+
+[model_capabilities."llama3:8b"]
+context_window = 8192
+supports_tools = false
+
+[model_capabilities.o3]
+supports_reasoning = false
+apply_patch_format = "function"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let codex_home = TempDir::new()?;
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                cwd: Some(codex_home.path().to_path_buf()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert!(!config.model_family.supports_tools);
+        assert_eq!(config.model_context_window, Some(8192));
+        assert_eq!(config.model_max_output_tokens, Some(8192));
+
+        let o3 = config.model_family_for("o3").expect("known model slug");
+        assert!(!o3.supports_reasoning_summaries);
+        assert_eq!(o3.apply_patch_tool_type, Some(ApplyPatchToolType::Function));
+        assert_eq!(
+            config.model_info_for(&o3).map(|info| info.context_window),
+            Some(200_000)
+        );
+        assert_eq!(config.model_family_for("mystery-model"), None);
+        assert_eq!(
+            config.model_family_or_default("mystery-model"),
+            derive_default_model_family("mystery-model")
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_set_project_trusted_writes_explicit_tables() -> anyhow::Result<()> {
         let project_dir = Path::new("/some/path");
@@ -1,5 +1,10 @@
 use crate::config::CONFIG_TOML_FILE;
+use crate::config_schema::DiagnosticSeverity;
+use crate::config_schema::schema_types;
+use crate::config_schema::validate_config;
 use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
 use std::path::Path;
 use tempfile::NamedTempFile;
 use toml_edit::DocumentMut;
@@ -186,6 +191,88 @@ async fn persist_overrides_with_behavior(
     Ok(())
 }
 
+/// Split a dotted key as written on the command line, e.g.
+/// `mcp_servers.docs.command` or `projects."/my/repo".trust_level`.
+pub fn parse_config_key(key: &str) -> Result<Vec<String>> {
+    let keys = toml_edit::Key::parse(key).map_err(|err| anyhow!("invalid key `{key}`: {err}"))?;
+    Ok(keys.iter().map(|key| key.get().to_string()).collect())
+}
+
+/// Parse a value for the key at `segments` the way `-c key=value` does: as
+/// TOML if possible, otherwise as a plain string. A value that parses as
+/// something other than a string, such as `2025-01-01` (a TOML date), is
+/// only kept as such if the schema allows that type for the key.
+pub fn parse_config_value(segments: &[String], raw: &str) -> toml_edit::Value {
+    let Ok(value) = raw.trim().parse::<toml_edit::Value>() else {
+        return raw.into();
+    };
+    if value.is_str() {
+        return value;
+    }
+    let allowed = match schema_types(segments) {
+        None => return value,
+        Some(types) => types,
+    };
+    let fits = |ty: &str| match &value {
+        toml_edit::Value::Integer(_) => ty == "integer" || ty == "number",
+        toml_edit::Value::Float(_) => ty == "number",
+        toml_edit::Value::Boolean(_) => ty == "boolean",
+        toml_edit::Value::Array(_) => ty == "array",
+        toml_edit::Value::InlineTable(_) => ty == "object",
+        toml_edit::Value::String(_) | toml_edit::Value::Datetime(_) => false,
+    };
+    if allowed.iter().any(|ty| fits(ty)) {
+        value
+    } else {
+        raw.into()
+    }
+}
+
+/// Set (`Some`) or remove (`None`) the value at `segments` in `config.toml`,
+/// keeping the rest of the file as written. The change is only saved if the
+/// resulting file still loads; otherwise the validation errors are returned.
+/// Returns whether the file changed.
+pub async fn edit_config_value(
+    codex_home: &Path,
+    segments: &[String],
+    value: Option<toml_edit::Value>,
+) -> Result<bool> {
+    let config_path = codex_home.join(CONFIG_TOML_FILE);
+    let mut doc = match tokio::fs::read_to_string(&config_path).await {
+        Ok(contents) => contents.parse::<DocumentMut>()?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => DocumentMut::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    match value {
+        Some(value) => {
+            apply_toml_edit_override_segments(&mut doc, &segments, toml_edit::Item::Value(value));
+        }
+        None => {
+            if !remove_toml_edit_segments(&mut doc, &segments) {
+                return Ok(false);
+            }
+        }
+    }
+
+    let contents = doc.to_string();
+    let errors: Vec<String> = validate_config(&contents)
+        .into_iter()
+        .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+        .map(|diagnostic| diagnostic.message)
+        .collect();
+    if !errors.is_empty() {
+        bail!("{}", errors.join("\n"));
+    }
+
+    tokio::fs::create_dir_all(codex_home).await?;
+    let tmp_file = NamedTempFile::new_in(codex_home)?;
+    tokio::fs::write(tmp_file.path(), contents).await?;
+    tmp_file.persist(config_path)?;
+    Ok(true)
+}
+
 fn remove_toml_edit_segments(doc: &mut DocumentMut, segments: &[&str]) -> bool {
     use toml_edit::Item;
 
@@ -740,6 +827,94 @@ model_reasoning_effort = "high"
         assert!(!codex_home.join(CONFIG_TOML_FILE).exists());
     }
 
+    /// Verifies typed values are written under quoted keys and comments survive.
+    #[tokio::test]
+    async fn edit_config_value_sets_typed_values_and_keeps_comments() {
+        let tmpdir = tempdir().expect("tmp");
+        let codex_home = tmpdir.path();
+        let seed = "# my settings\nmodel = \"o3\" # pinned\n";
+        tokio::fs::write(codex_home.join(CONFIG_TOML_FILE), seed)
+            .await
+            .expect("seed write");
+
+        let key = parse_config_key("mcp_servers.\"my docs\".args").expect("key");
+        assert_eq!(key, vec!["mcp_servers", "my docs", "args"]);
+        let command_key = parse_config_key("mcp_servers.\"my docs\".command").expect("key");
+        edit_config_value(
+            codex_home,
+            &command_key,
+            Some(parse_config_value(&command_key, "docs-server")),
+        )
+        .await
+        .expect("set command");
+        let changed = edit_config_value(
+            codex_home,
+            &key,
+            Some(parse_config_value(&key, r#"["--stdio", "-v"]"#)),
+        )
+        .await
+        .expect("set args");
+        assert!(changed);
+
+        let contents = read_config(codex_home).await;
+        let expected = r#"# my settings
+model = "o3" # pinned
+
+[mcp_servers."my docs"]
+command = "docs-server"
+args = ["--stdio", "-v"]
+"#;
+        assert_eq!(contents, expected);
+    }
+
+    /// Verifies values only take a non-string type the key's schema allows.
+    #[test]
+    fn parse_config_value_follows_the_schema_type() {
+        let key = |key: &str| parse_config_key(key).expect("key");
+        assert_eq!(
+            parse_config_value(&key("model_context_window"), "42").as_integer(),
+            Some(42)
+        );
+        assert_eq!(
+            parse_config_value(&key("model"), "2025-01-01").as_str(),
+            Some("2025-01-01")
+        );
+        assert_eq!(
+            parse_config_value(&key("mcp_servers.docs.env.RETRIES"), "3").as_str(),
+            Some("3")
+        );
+        assert_eq!(
+            parse_config_value(&key("tools.web_search"), "true").as_bool(),
+            Some(true)
+        );
+    }
+
+    /// Verifies a value that would stop Codex from loading is not saved.
+    #[tokio::test]
+    async fn edit_config_value_rejects_invalid_values() {
+        let tmpdir = tempdir().expect("tmp");
+        let codex_home = tmpdir.path();
+
+        let key = parse_config_key("approval_policy").expect("key");
+        let err = edit_config_value(
+            codex_home,
+            &key,
+            Some(parse_config_value(&key, "sometimes")),
+        )
+        .await
+        .expect_err("invalid approval policy");
+        assert!(
+            err.to_string().contains("unknown variant `sometimes`"),
+            "{err}"
+        );
+        assert!(!codex_home.join(CONFIG_TOML_FILE).exists());
+
+        let removed = edit_config_value(codex_home, &["model".to_string()], None)
+            .await
+            .expect("unset");
+        assert!(!removed);
+    }
+
     // Test helper moved to bottom per review guidance.
     async fn read_config(codex_home: &Path) -> String {
         let p = codex_home.join(CONFIG_TOML_FILE);
@@ -0,0 +1,431 @@
+//! The layers `config.toml` settings are read from.
+//!
+//! In increasing order of precedence:
+//!
+//! 1. the user's `$CODEX_HOME/config.toml`;
+//! 2. the project's `.codex/config.toml` at the root of the git repository
+//!    containing the working directory (or in the working directory itself
+//!    outside a repository), if it is a trusted project (see
+//!    [`ConfigToml::is_cwd_trusted`]);
+//! 3. `-c key=value` overrides.
+//!
+//! A project may only set the keys in [`PROJECT_CONFIG_KEYS`]; anything that
+//! could widen what the agent is allowed to do without asking, such as
+//! `approval_policy`, `sandbox_workspace_write`, `mcp_servers`, `tools` or
+//! `projects`, stays with the user.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use toml::Value as TomlValue;
+
+use crate::config::CONFIG_TOML_FILE;
+use crate::config::ConfigToml;
+use crate::config::ProjectConfig;
+use crate::config::apply_toml_override;
+use crate::config::load_config_as_toml;
+use crate::git_info::get_git_repo_root;
+
+/// Top-level keys a project's `.codex/config.toml` may set.
+pub const PROJECT_CONFIG_KEYS: &[&str] = &[
+    "model",
+    "review_model",
+    "compact_model",
+    "model_context_window",
+    "model_max_output_tokens",
+    "model_auto_compact_token_limit",
+    "model_reasoning_effort",
+    "model_reasoning_summary",
+    "model_verbosity",
+    "plan_mode",
+    "project_doc_max_bytes",
+    "subagents",
+];
+
+/// Where an effective configuration value was set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    /// The user's `$CODEX_HOME/config.toml`.
+    User(PathBuf),
+    /// A trusted project's `.codex/config.toml`.
+    Project(PathBuf),
+    /// A `-c key=value` override.
+    CliOverride,
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigOrigin::User(path) => write!(f, "user config {}", path.display()),
+            ConfigOrigin::Project(path) => write!(f, "project config {}", path.display()),
+            ConfigOrigin::CliOverride => write!(f, "-c override"),
+        }
+    }
+}
+
+/// A setting in the merged configuration, keyed by its dotted path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigEntry {
+    pub key: String,
+    /// The unquoted segments of `key`.
+    pub path: Vec<String>,
+    pub value: TomlValue,
+    pub origin: ConfigOrigin,
+}
+
+/// The configuration layers that apply in a working directory.
+#[derive(Debug, Clone)]
+pub struct ConfigLayers {
+    user_path: PathBuf,
+    user: TomlValue,
+    /// The project layer, if the project has a config and is trusted.
+    project: Option<(PathBuf, TomlValue)>,
+    cli_overrides: Vec<(String, TomlValue)>,
+    /// A project config that was not loaded because the project is not
+    /// trusted.
+    pub untrusted_project_config: Option<PathBuf>,
+    /// Keys in the project config that projects may not set.
+    pub ignored_project_keys: Vec<String>,
+}
+
+/// Path of the project config for `cwd`: in the root of the git repository
+/// containing it, or in `cwd` itself outside a repository.
+pub fn project_config_path(cwd: &Path) -> PathBuf {
+    get_git_repo_root(cwd)
+        .unwrap_or_else(|| cwd.to_path_buf())
+        .join(".codex")
+        .join(CONFIG_TOML_FILE)
+}
+
+impl ConfigLayers {
+    /// Read the layers that apply in `cwd`, which must be absolute.
+    pub fn load(
+        codex_home: &Path,
+        cwd: &Path,
+        cli_overrides: Vec<(String, TomlValue)>,
+    ) -> std::io::Result<Self> {
+        let mut layers = Self {
+            user_path: codex_home.join(CONFIG_TOML_FILE),
+            user: load_config_as_toml(codex_home)?,
+            project: None,
+            cli_overrides,
+            untrusted_project_config: None,
+            ignored_project_keys: Vec::new(),
+        };
+
+        let project_path = project_config_path(cwd);
+        let contents = match std::fs::read_to_string(&project_path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(layers),
+            Err(err) => {
+                tracing::error!("Failed to read {}: {err}", project_path.display());
+                return Err(err);
+            }
+        };
+        // Trust is decided by the user's settings alone, so that a project
+        // cannot vouch for itself.
+        if !layers.is_trusted(cwd) {
+            tracing::info!(
+                "Ignoring {}: {} is not a trusted project",
+                project_path.display(),
+                cwd.display()
+            );
+            layers.untrusted_project_config = Some(project_path);
+            return Ok(layers);
+        }
+        let mut project = toml::from_str::<TomlValue>(&contents).map_err(|e| {
+            tracing::error!("Failed to parse {}: {e}", project_path.display());
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("{}: {e}", project_path.display()),
+            )
+        })?;
+        if let TomlValue::Table(table) = &mut project {
+            table.retain(|key, _| {
+                let allowed = PROJECT_CONFIG_KEYS.contains(&key);
+                if !allowed {
+                    tracing::warn!(
+                        "Ignoring `{key}` in {}: projects may not set it",
+                        project_path.display()
+                    );
+                    layers.ignored_project_keys.push(key.to_string());
+                }
+                allowed
+            });
+        }
+        layers.project = Some((project_path, project));
+        Ok(layers)
+    }
+
+    /// Path of the project config in effect, if any.
+    pub fn project_config(&self) -> Option<&Path> {
+        self.project.as_ref().map(|(path, _)| path.as_path())
+    }
+
+    fn is_trusted(&self, cwd: &Path) -> bool {
+        let mut root = self.user.clone();
+        for (path, value) in &self.cli_overrides {
+            apply_toml_override(&mut root, path, value.clone());
+        }
+        let projects = root
+            .get("projects")
+            .cloned()
+            .and_then(|projects| projects.try_into::<HashMap<String, ProjectConfig>>().ok());
+        ConfigToml {
+            projects,
+            ..Default::default()
+        }
+        .is_cwd_trusted(cwd)
+    }
+
+    /// All layers merged into one value, ready to deserialize into
+    /// [`ConfigToml`].
+    pub fn merged(&self) -> TomlValue {
+        let mut root = self.user.clone();
+        if let Some((_, project)) = &self.project {
+            merge_toml(&mut root, project.clone());
+        }
+        for (path, value) in &self.cli_overrides {
+            apply_toml_override(&mut root, path, value.clone());
+        }
+        root
+    }
+
+    /// Every value in the merged configuration with the layer that set it,
+    /// sorted by key. Tables are flattened into their leaves.
+    pub fn entries(&self) -> Vec<ConfigEntry> {
+        let mut leaves = Vec::new();
+        collect_leaves(&self.merged(), &mut Vec::new(), &mut leaves);
+        let mut entries: Vec<ConfigEntry> = leaves
+            .into_iter()
+            .map(|(segments, value)| ConfigEntry {
+                key: dotted_key(&segments),
+                origin: self.origin_of(&segments),
+                path: segments,
+                value,
+            })
+            .collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        entries
+    }
+
+    fn origin_of(&self, segments: &[String]) -> ConfigOrigin {
+        let overridden = self.cli_overrides.iter().any(|(path, _)| {
+            let path: Vec<&str> = path.split('.').collect();
+            path.len() <= segments.len() && path.iter().zip(segments).all(|(a, b)| *a == b)
+        });
+        if overridden {
+            return ConfigOrigin::CliOverride;
+        }
+        if let Some((path, project)) = &self.project
+            && lookup(project, segments).is_some()
+        {
+            return ConfigOrigin::Project(path.clone());
+        }
+        ConfigOrigin::User(self.user_path.clone())
+    }
+}
+
+/// Merge `overlay` into `base`: tables are merged key by key, anything else
+/// in `overlay` replaces the value in `base`.
+fn merge_toml(base: &mut TomlValue, overlay: TomlValue) {
+    match (base, overlay) {
+        (TomlValue::Table(base), TomlValue::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+fn lookup<'a>(value: &'a TomlValue, segments: &[String]) -> Option<&'a TomlValue> {
+    segments
+        .iter()
+        .try_fold(value, |value, segment| value.get(segment.as_str()))
+}
+
+fn collect_leaves(
+    value: &TomlValue,
+    prefix: &mut Vec<String>,
+    leaves: &mut Vec<(Vec<String>, TomlValue)>,
+) {
+    match value {
+        TomlValue::Table(table) if !table.is_empty() => {
+            for (key, value) in table {
+                prefix.push(key.clone());
+                collect_leaves(value, prefix, leaves);
+                prefix.pop();
+            }
+        }
+        _ if prefix.is_empty() => {}
+        _ => leaves.push((prefix.clone(), value.clone())),
+    }
+}
+
+/// Join key segments the way they would be written in TOML, quoting any
+/// that are not bare keys.
+fn dotted_key(segments: &[String]) -> String {
+    segments
+        .iter()
+        .map(|segment| {
+            let bare = !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if bare {
+                segment.clone()
+            } else {
+                TomlValue::String(segment.clone()).to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn write_user_config(codex_home: &Path, project: &Path, trusted: bool) {
+        let trust = if trusted {
+            format!(
+                "[projects.{}]\ntrust_level = \"trusted\"\n",
+                TomlValue::String(project.to_string_lossy().into_owned())
+            )
+        } else {
+            String::new()
+        };
+        std::fs::write(
+            codex_home.join(CONFIG_TOML_FILE),
+            format!("model = \"user-model\"\napproval_policy = \"untrusted\"\n{trust}"),
+        )
+        .unwrap();
+    }
+
+    fn write_project_config(project: &Path) {
+        std::fs::create_dir_all(project.join(".codex")).unwrap();
+        std::fs::write(
+            project_config_path(project),
+            "model = \"project-model\"\napproval_policy = \"never\"\n\n\
+             [mcp_servers.docs]\ncommand = \"docs-server\"\n",
+        )
+        .unwrap();
+    }
+
+    fn origins(layers: &ConfigLayers) -> Vec<(String, ConfigOrigin)> {
+        layers
+            .entries()
+            .into_iter()
+            .filter(|entry| !entry.key.starts_with("projects."))
+            .map(|entry| (entry.key, entry.origin))
+            .collect()
+    }
+
+    #[test]
+    fn trusted_project_config_sits_between_user_config_and_overrides() {
+        let codex_home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        write_user_config(codex_home.path(), project.path(), true);
+        write_project_config(project.path());
+
+        let layers = ConfigLayers::load(
+            codex_home.path(),
+            project.path(),
+            vec![("mcp_servers.docs.command".to_string(), "other".into())],
+        )
+        .unwrap();
+
+        let merged = layers.merged();
+        assert_eq!(merged["model"].as_str(), Some("project-model"));
+        // Projects cannot change the approval policy.
+        assert_eq!(merged["approval_policy"].as_str(), Some("untrusted"));
+        // Nor start MCP servers.
+        assert_eq!(
+            layers.ignored_project_keys,
+            vec!["approval_policy", "mcp_servers"]
+        );
+
+        let user = ConfigOrigin::User(codex_home.path().join(CONFIG_TOML_FILE));
+        let project_origin = ConfigOrigin::Project(project_config_path(project.path()));
+        assert_eq!(
+            origins(&layers),
+            vec![
+                ("approval_policy".to_string(), user),
+                (
+                    "mcp_servers.docs.command".to_string(),
+                    ConfigOrigin::CliOverride
+                ),
+                ("model".to_string(), project_origin),
+            ]
+        );
+    }
+
+    #[test]
+    fn project_config_is_read_from_the_repository_root() {
+        let codex_home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        let status = std::process::Command::new("git")
+            .args(["init", "--quiet"])
+            .current_dir(project.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let subdir = project.path().join("crates").join("app");
+        std::fs::create_dir_all(&subdir).unwrap();
+        // Trust is granted to the main repository, which git reports
+        // canonicalized.
+        write_user_config(
+            codex_home.path(),
+            &project.path().canonicalize().unwrap(),
+            true,
+        );
+        write_project_config(project.path());
+
+        let layers = ConfigLayers::load(codex_home.path(), &subdir, Vec::new()).unwrap();
+
+        let expected = project.path().join(".codex").join(CONFIG_TOML_FILE);
+        assert_eq!(project_config_path(&subdir), expected);
+        assert_eq!(layers.project_config(), Some(expected.as_path()));
+        assert_eq!(layers.merged()["model"].as_str(), Some("project-model"));
+    }
+
+    #[test]
+    fn untrusted_project_config_is_ignored() {
+        let codex_home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        write_user_config(codex_home.path(), project.path(), false);
+        write_project_config(project.path());
+
+        let layers = ConfigLayers::load(codex_home.path(), project.path(), Vec::new()).unwrap();
+
+        assert_eq!(layers.project_config(), None);
+        assert_eq!(
+            layers.untrusted_project_config,
+            Some(project_config_path(project.path()))
+        );
+        assert_eq!(layers.merged()["model"].as_str(), Some("user-model"));
+        assert!(layers.merged().get("mcp_servers").is_none());
+    }
+
+    #[test]
+    fn dotted_keys_quote_segments_that_are_not_bare() {
+        assert_eq!(
+            dotted_key(&["projects".to_string(), "/tmp/my project".to_string()]),
+            "projects.\"/tmp/my project\""
+        );
+        assert_eq!(
+            dotted_key(&["mcp_servers".to_string(), "docs-v2".to_string()]),
+            "mcp_servers.docs-v2"
+        );
+    }
+}
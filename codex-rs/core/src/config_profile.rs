@@ -1,25 +1,50 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::config::PlanModeToml;
+use crate::config::SubagentsToml;
+use crate::config::ToolsToml;
+use crate::config_types::McpServerConfig;
+use crate::config_types::SandboxWorkspaceWrite;
 use crate::protocol::AskForApproval;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::config_types::SandboxMode;
 use codex_protocol::config_types::Verbosity;
 
 /// Collection of common configuration options that a user can define as a unit
 /// in `config.toml`.
-#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, JsonSchema)]
 pub struct ConfigProfile {
     pub model: Option<String>,
     /// The key in the `model_providers` map identifying the
     /// [`ModelProviderInfo`] to use.
     pub model_provider: Option<String>,
+    /// Replaces the top-level `fallback_models` list.
+    pub fallback_models: Option<Vec<String>>,
     pub approval_policy: Option<AskForApproval>,
     pub model_reasoning_effort: Option<ReasoningEffort>,
     pub model_reasoning_summary: Option<ReasoningSummary>,
     pub model_verbosity: Option<Verbosity>,
     pub chatgpt_base_url: Option<String>,
     pub experimental_instructions_file: Option<PathBuf>,
+    pub sandbox_mode: Option<SandboxMode>,
+    /// Replaces the top-level `sandbox_workspace_write` table.
+    pub sandbox_workspace_write: Option<SandboxWorkspaceWrite>,
+    /// MCP servers to add, or to replace top-level servers of the same name.
+    #[serde(default)]
+    pub mcp_servers: HashMap<String, McpServerConfig>,
+    /// Names of top-level MCP servers to leave out.
+    #[serde(default)]
+    pub disabled_mcp_servers: Vec<String>,
+    /// Per-toggle overrides of the top-level `tools` table.
+    pub tools: Option<ToolsToml>,
+    /// Per-field overrides of the top-level `plan_mode` table.
+    pub plan_mode: Option<PlanModeToml>,
+    /// Per-field overrides of the top-level `subagents` table.
+    pub subagents: Option<SubagentsToml>,
 }
 
 impl From<ConfigProfile> for codex_protocol::mcp_protocol::Profile {
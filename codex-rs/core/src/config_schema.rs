@@ -0,0 +1,368 @@
+//! JSON Schema for `config.toml`, and validation of config files against it.
+//!
+//! The schema is derived from [`ConfigToml`], so it always matches what Codex
+//! actually reads. Validation reports the errors that would stop Codex from
+//! loading the file, plus warnings for keys Codex would silently ignore.
+
+use std::fmt;
+use std::ops::Range;
+
+use serde_json::Value as JsonValue;
+use toml_edit::TableLike;
+
+use crate::config::ConfigToml;
+use crate::config_layers::PROJECT_CONFIG_KEYS;
+
+/// Keys that serde accepts as aliases but the schema does not list.
+const KEY_ALIASES: &[&str] = &["web_search_request"];
+
+/// The JSON Schema of `config.toml`, for editor completion and validation.
+pub fn config_schema() -> JsonValue {
+    let schema = schemars::schema_for!(ConfigToml);
+    serde_json::to_value(schema).unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    /// Codex cannot load the file.
+    Error,
+    /// Codex loads the file but ignores part of it.
+    Warning,
+}
+
+impl fmt::Display for DiagnosticSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticSeverity::Error => write!(f, "error"),
+            DiagnosticSeverity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found in a config file. `line` and `column` are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigDiagnostic {
+    pub severity: DiagnosticSeverity,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.line, self.column, self.severity, self.message
+        )
+    }
+}
+
+/// Check the contents of a `config.toml` file, returning every problem found
+/// in source order.
+pub fn validate_config(contents: &str) -> Vec<ConfigDiagnostic> {
+    let diagnostic = |severity, span: Option<Range<usize>>, message: String| {
+        let (line, column) = line_and_column(contents, span.map_or(0, |span| span.start));
+        ConfigDiagnostic {
+            severity,
+            line,
+            column,
+            message,
+        }
+    };
+
+    let document = match toml_edit::Document::parse(contents) {
+        Ok(document) => document,
+        Err(err) => {
+            return vec![diagnostic(
+                DiagnosticSeverity::Error,
+                err.span(),
+                err.message().to_string(),
+            )];
+        }
+    };
+
+    let mut diagnostics = Vec::new();
+    if let Err(err) = toml::from_str::<ConfigToml>(contents) {
+        diagnostics.push(diagnostic(
+            DiagnosticSeverity::Error,
+            err.span(),
+            err.message().trim().to_string(),
+        ));
+    }
+
+    let schema = config_schema();
+    let mut unknown = Vec::new();
+    if let Some(root) = document.as_item().as_table_like() {
+        find_unknown_keys(root, &schema, &schema, &mut Vec::new(), &mut unknown);
+    }
+    diagnostics.extend(unknown.into_iter().map(|(key, span)| {
+        diagnostic(
+            DiagnosticSeverity::Warning,
+            span,
+            format!("unknown key `{key}` is ignored"),
+        )
+    }));
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    diagnostics
+}
+
+/// Check the contents of a project's `.codex/config.toml`: as
+/// [`validate_config`] does, plus a warning for each top-level key that
+/// projects may not set and that Codex therefore ignores.
+pub fn validate_project_config(contents: &str) -> Vec<ConfigDiagnostic> {
+    let mut diagnostics = validate_config(contents);
+    let Ok(document) = toml_edit::Document::parse(contents) else {
+        return diagnostics;
+    };
+    for (key, _) in document.iter() {
+        if PROJECT_CONFIG_KEYS.contains(&key) {
+            continue;
+        }
+        let span = document.get_key_value(key).and_then(|(key, _)| key.span());
+        let (line, column) = line_and_column(contents, span.map_or(0, |span| span.start));
+        diagnostics.push(ConfigDiagnostic {
+            severity: DiagnosticSeverity::Warning,
+            line,
+            column,
+            message: format!("`{key}` is ignored: projects may not set it"),
+        });
+    }
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    diagnostics
+}
+
+/// The JSON types (`"string"`, `"integer"`, ...) the schema allows for the
+/// key at `path`. `None` if the schema does not constrain the value; empty if
+/// the key is not in the schema.
+pub fn schema_types(path: &[String]) -> Option<Vec<String>> {
+    let root = config_schema();
+    let mut schema = &root;
+    for segment in path {
+        let Some(object) = object_schema(schema, &root) else {
+            return Some(Vec::new());
+        };
+        let child = object
+            .get("properties")
+            .and_then(|properties| properties.get(segment))
+            .or(object
+                .get("additionalProperties")
+                .filter(|additional| !additional.is_boolean()));
+        match child {
+            Some(child) => schema = child,
+            None if object.get("additionalProperties") == Some(&JsonValue::Bool(true)) => {
+                return None;
+            }
+            None => return Some(Vec::new()),
+        }
+    }
+    let mut types = Vec::new();
+    collect_types(schema, &root, &mut types).then_some(types)
+}
+
+/// Add the types `schema` allows to `types`, returning false if it allows
+/// any value.
+fn collect_types(schema: &JsonValue, root: &JsonValue, types: &mut Vec<String>) -> bool {
+    if let Some(reference) = schema.get("$ref").and_then(JsonValue::as_str) {
+        return match reference
+            .strip_prefix("#/definitions/")
+            .and_then(|name| root.get("definitions")?.get(name))
+        {
+            Some(definition) => collect_types(definition, root, types),
+            None => false,
+        };
+    }
+    let mut constrained = false;
+    match schema.get("type") {
+        Some(JsonValue::String(ty)) => {
+            types.push(ty.clone());
+            constrained = true;
+        }
+        Some(JsonValue::Array(tys)) => {
+            types.extend(tys.iter().filter_map(JsonValue::as_str).map(str::to_string));
+            constrained = true;
+        }
+        _ => {}
+    }
+    for keyword in ["allOf", "anyOf", "oneOf"] {
+        for subschema in schema
+            .get(keyword)
+            .and_then(JsonValue::as_array)
+            .into_iter()
+            .flatten()
+        {
+            if !collect_types(subschema, root, types) {
+                return false;
+            }
+            constrained = true;
+        }
+    }
+    constrained
+}
+
+fn find_unknown_keys(
+    table: &dyn TableLike,
+    schema: &JsonValue,
+    root: &JsonValue,
+    path: &mut Vec<String>,
+    unknown: &mut Vec<(String, Option<Range<usize>>)>,
+) {
+    let Some(object) = object_schema(schema, root) else {
+        return;
+    };
+    let properties = object.get("properties").and_then(JsonValue::as_object);
+    let additional = object.get("additionalProperties");
+    for (key, item) in table.iter() {
+        path.push(key.to_string());
+        let child = properties
+            .and_then(|properties| properties.get(key))
+            .or(additional.filter(|additional| additional.is_object()));
+        match child {
+            Some(child) => {
+                if let Some(child_table) = item.as_table_like() {
+                    find_unknown_keys(child_table, child, root, path, unknown);
+                }
+            }
+            None if additional == Some(&JsonValue::Bool(true)) || KEY_ALIASES.contains(&key) => {}
+            None => {
+                let span = table.get_key_value(key).and_then(|(key, _)| key.span());
+                unknown.push((path.join("."), span));
+            }
+        }
+        path.pop();
+    }
+}
+
+/// The schema describing the keys of an object, following `$ref`s and the
+/// `anyOf` wrappers schemars emits for `Option`s.
+fn object_schema<'a>(schema: &'a JsonValue, root: &'a JsonValue) -> Option<&'a JsonValue> {
+    if let Some(reference) = schema.get("$ref").and_then(JsonValue::as_str) {
+        let name = reference.strip_prefix("#/definitions/")?;
+        return object_schema(root.get("definitions")?.get(name)?, root);
+    }
+    if schema.get("properties").is_some() || schema.get("additionalProperties").is_some() {
+        return Some(schema);
+    }
+    ["allOf", "anyOf", "oneOf"]
+        .iter()
+        .filter_map(|keyword| schema.get(keyword).and_then(JsonValue::as_array))
+        .flatten()
+        .find_map(|subschema| object_schema(subschema, root))
+}
+
+fn line_and_column(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset.min(contents.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+        + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn summarize(contents: &str) -> Vec<String> {
+        validate_config(contents)
+            .into_iter()
+            .map(|diagnostic| {
+                format!(
+                    "{}:{} {}",
+                    diagnostic.line, diagnostic.column, diagnostic.severity
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn valid_config_has_no_diagnostics() {
+        let contents = r#"
+model = "o3"
+approval_policy = "on-request"
+
+[tools]
+web_search_request = true
+
+[mcp_servers.docs]
+command = "docs-server"
+args = ["--stdio"]
+
+[profiles.fast.plan_mode]
+plan_enabled = true
+"#;
+        assert_eq!(validate_config(contents), Vec::new());
+    }
+
+    #[test]
+    fn invalid_value_is_reported_where_it_is_written() {
+        let contents = "model = \"o3\"\napproval_policy = \"sometimes\"\n";
+        let diagnostics = validate_config(contents);
+        assert_eq!(summarize(contents), vec!["2:19 error"]);
+        assert!(
+            diagnostics[0]
+                .message
+                .contains("unknown variant `sometimes`"),
+            "{}",
+            diagnostics[0].message
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_warnings() {
+        let contents = "modle = \"o3\"\n\n[mcp_servers.docs]\ncommand = \"docs\"\nargz = []\n";
+        let diagnostics = validate_config(contents);
+        assert_eq!(summarize(contents), vec!["1:1 warning", "5:1 warning"]);
+        assert_eq!(
+            diagnostics[1].message,
+            "unknown key `mcp_servers.docs.argz` is ignored"
+        );
+    }
+
+    #[test]
+    fn project_config_warns_about_keys_projects_may_not_set() {
+        let contents = "model = \"o3\"\napproval_policy = \"never\"\n\n[projects.\"/tmp\"]\ntrust_level = \"trusted\"\n";
+        let diagnostics = validate_project_config(contents);
+        assert_eq!(summarize(contents), Vec::<String>::new());
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "2:1: warning: `approval_policy` is ignored: projects may not set it",
+                "4:2: warning: `projects` is ignored: projects may not set it",
+            ]
+        );
+    }
+
+    #[test]
+    fn schema_types_follow_the_key_path() {
+        let path = |key: &str| key.split('.').map(str::to_string).collect::<Vec<_>>();
+        let types = |key: &str| {
+            schema_types(&path(key)).map(|mut types| {
+                types.sort();
+                types.dedup();
+                types
+            })
+        };
+        assert_eq!(types("model"), Some(vec!["null".into(), "string".into()]));
+        assert_eq!(
+            types("model_context_window"),
+            Some(vec!["integer".into(), "null".into()])
+        );
+        assert_eq!(
+            types("mcp_servers.docs.env.TOKEN"),
+            Some(vec!["string".into()])
+        );
+        assert_eq!(types("modle"), Some(Vec::new()));
+    }
+
+    #[test]
+    fn syntax_errors_stop_validation() {
+        assert_eq!(summarize("model = \n"), vec!["1:9 error"]);
+    }
+}
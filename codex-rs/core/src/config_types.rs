@@ -7,9 +7,14 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use wildmatch::WildMatchPattern;
 
+use chrono::Duration;
+use schemars::JsonSchema;
 use serde::Deserialize;
+use serde::Deserializer;
 
-#[derive(Deserialize, Debug, Clone, PartialEq)]
+use crate::rollout::search::parse_age;
+
+#[derive(Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 pub struct McpServerConfig {
     pub command: String,
 
@@ -24,7 +29,7 @@ pub struct McpServerConfig {
     pub startup_timeout_ms: Option<u64>,
 }
 
-#[derive(Deserialize, Debug, Copy, Clone, PartialEq)]
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, JsonSchema)]
 pub enum UriBasedFileOpener {
     #[serde(rename = "vscode")]
     VsCode,
@@ -56,7 +61,7 @@ impl UriBasedFileOpener {
 }
 
 /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
-#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 pub struct History {
     /// If true, history entries will not be written to disk.
     pub persistence: HistoryPersistence,
@@ -64,9 +69,33 @@ pub struct History {
     /// If set, the maximum size of the history file in bytes.
     /// TODO(mbolin): Not currently honored.
     pub max_bytes: Option<usize>,
+
+    /// If set, only the newest `max_sessions` session rollouts under
+    /// `~/.codex/sessions` are kept; older ones are deleted at startup.
+    pub max_sessions: Option<usize>,
+
+    /// If set, session rollouts older than this (e.g. `"30d"`, `"12w"`) are
+    /// deleted at startup.
+    #[serde(default, deserialize_with = "deserialize_max_age")]
+    #[schemars(with = "Option<String>")]
+    pub max_age: Option<Duration>,
+}
+
+fn deserialize_max_age<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(value) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    parse_age(&value).map(Some).ok_or_else(|| {
+        serde::de::Error::custom(format!(
+            "invalid max_age `{value}`; expected an age like 30d, 12h or 2w"
+        ))
+    })
 }
 
-#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Default)]
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Default, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum HistoryPersistence {
     /// Save all history entries to disk.
@@ -76,7 +105,7 @@ pub enum HistoryPersistence {
     None,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum Notifications {
     Enabled(bool),
@@ -90,14 +119,14 @@ impl Default for Notifications {
 }
 
 /// Collection of settings that are specific to the TUI.
-#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 pub struct Tui {
     /// Enable desktop notifications from the TUI when the terminal is unfocused.
     /// Defaults to `false`.
     pub notifications: Notifications,
 }
 
-#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 pub struct SandboxWorkspaceWrite {
     #[serde(default)]
     pub writable_roots: Vec<PathBuf>,
@@ -120,7 +149,7 @@ impl From<SandboxWorkspaceWrite> for codex_protocol::mcp_protocol::SandboxSettin
     }
 }
 
-#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum ShellEnvironmentPolicyInherit {
     /// "Core" environment variables for the platform. On UNIX, this would
@@ -137,7 +166,7 @@ pub enum ShellEnvironmentPolicyInherit {
 
 /// Policy for building the `env` when spawning a process via either the
 /// `shell` or `local_shell` tool.
-#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 pub struct ShellEnvironmentPolicyToml {
     pub inherit: Option<ShellEnvironmentPolicyInherit>,
 
@@ -216,10 +245,84 @@ impl From<ShellEnvironmentPolicyToml> for ShellEnvironmentPolicy {
     }
 }
 
-#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default, Hash)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default, Hash, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum ReasoningSummaryFormat {
     #[default]
     None,
     Experimental,
 }
+
+/// What to do when a request fails in a way `fallback_models` can address,
+/// configured as `[fallback_policy]` in `config.toml`.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default, JsonSchema)]
+pub struct FallbackPolicy {
+    /// 429 responses that outlast `request_max_retries`.
+    #[serde(default)]
+    pub rate_limit: FallbackAction,
+
+    /// 5xx responses that outlast `request_max_retries`.
+    #[serde(default)]
+    pub server_error: FallbackAction,
+
+    /// Requests rejected because the conversation no longer fits the
+    /// model's context window.
+    #[serde(default)]
+    pub context_overflow: ContextOverflowAction,
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum FallbackAction {
+    /// Fail the turn.
+    Fail,
+    /// Retry the turn on the next entry of `fallback_models`.
+    #[default]
+    FailOver,
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContextOverflowAction {
+    /// Fail the turn.
+    Fail,
+    /// Retry the turn on the next entry of `fallback_models`.
+    FailOver,
+    /// Compact the conversation, then retry the turn on the same model.
+    #[default]
+    Compact,
+}
+
+/// What Codex should assume about a model it cannot recognize by name,
+/// configured as `[model_capabilities."<model>"]` in `config.toml`. Unset
+/// fields keep the built-in value for the model.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+pub struct ModelCapabilities {
+    /// Size of the context window in tokens.
+    pub context_window: Option<u64>,
+
+    /// Maximum number of output tokens per response.
+    pub max_output_tokens: Option<u64>,
+
+    /// Whether the model can call tools. Models that cannot are sent no
+    /// tools, so they can only answer in text.
+    pub supports_tools: Option<bool>,
+
+    /// Whether requests may set reasoning effort and summaries.
+    pub supports_reasoning: Option<bool>,
+
+    /// How the model edits files.
+    pub apply_patch_format: Option<ApplyPatchFormat>,
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApplyPatchFormat {
+    /// An `apply_patch` tool taking the patch as free-form text.
+    Freeform,
+    /// An `apply_patch` function tool taking the patch as a JSON argument.
+    Function,
+    /// The `apply_patch` command run through the shell tool, with
+    /// instructions on its format.
+    Shell,
+}
@@ -8,8 +8,10 @@ use crate::codex_conversation::CodexConversation;
 use crate::config::Config;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
+use crate::model_recording::ModelStreamRecording;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
+use crate::protocol::InputMessageKind;
 use crate::protocol::SessionConfiguredEvent;
 use crate::rollout::RolloutRecorder;
 use crate::subagents::{
@@ -17,10 +19,13 @@ use crate::subagents::{
     build_inventory_for_config, execute_subagent_invocation,
 };
 use codex_protocol::mcp_protocol::ConversationId;
+use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ForkedFrom;
 use codex_protocol::protocol::InitialHistory;
 use codex_protocol::protocol::RolloutItem;
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -73,7 +78,12 @@ impl ConversationManager {
             codex,
             conversation_id,
             session,
-        } = Codex::spawn(config, auth_manager, InitialHistory::New).await?;
+        } = Codex::spawn(
+            own_recording_turns(config),
+            auth_manager,
+            InitialHistory::New,
+        )
+        .await?;
         self.finalize_spawn(codex, conversation_id, session).await
     }
 
@@ -132,7 +142,7 @@ impl ConversationManager {
             codex,
             conversation_id,
             session,
-        } = Codex::spawn(config, auth_manager, initial_history).await?;
+        } = Codex::spawn(own_recording_turns(config), auth_manager, initial_history).await?;
         self.finalize_spawn(codex, conversation_id, session).await
     }
 
@@ -171,7 +181,33 @@ impl ConversationManager {
             codex,
             conversation_id,
             session,
-        } = Codex::spawn(config, auth_manager, history).await?;
+        } = Codex::spawn(own_recording_turns(config), auth_manager, history).await?;
+
+        self.finalize_spawn(codex, conversation_id, session).await
+    }
+
+    /// Fork a recorded session after its first `turn` user turns, dropping
+    /// everything from the next user message on. The new conversation gets a
+    /// fresh id and its rollout records the parent in `forked_from`.
+    pub async fn fork_conversation_at_turn(
+        &self,
+        turn: usize,
+        config: Config,
+        path: PathBuf,
+    ) -> CodexResult<NewConversation> {
+        let history = RolloutRecorder::get_rollout_history(&path).await?;
+        let available = user_turns(&history.get_rollout_items()).len();
+        if turn == 0 || turn > available {
+            return Err(CodexErr::ForkTurnOutOfRange { turn, available });
+        }
+        let history = truncate_to_user_turns(history, turn);
+
+        let auth_manager = self.auth_manager.clone();
+        let CodexSpawnOk {
+            codex,
+            conversation_id,
+            session,
+        } = Codex::spawn(own_recording_turns(config), auth_manager, history).await?;
 
         self.finalize_spawn(codex, conversation_id, session).await
     }
@@ -216,6 +252,16 @@ impl ConversationManager {
     }
 }
 
+/// Give a conversation started here its own model recording turns, numbered
+/// from 1. Subagents are spawned directly and keep counting with their parent.
+fn own_recording_turns(mut config: Config) -> Config {
+    config.model_stream_recording = config
+        .model_stream_recording
+        .as_ref()
+        .map(ModelStreamRecording::for_new_conversation);
+    config
+}
+
 /// Return a prefix of `items` obtained by dropping the last `n` user messages
 /// and all items that follow them.
 fn truncate_after_dropping_last_messages(history: InitialHistory, n: usize) -> InitialHistory {
@@ -252,6 +298,97 @@ fn truncate_after_dropping_last_messages(history: InitialHistory, n: usize) -> I
     }
 }
 
+/// A user turn in a recorded session, numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserTurn {
+    pub turn: usize,
+    pub message: String,
+}
+
+/// The user turns of the session recorded at `rollout_path`, oldest first.
+pub async fn list_user_turns(rollout_path: &Path) -> std::io::Result<Vec<UserTurn>> {
+    let history = RolloutRecorder::get_rollout_history(rollout_path).await?;
+    Ok(user_turns(&history.get_rollout_items()))
+}
+
+/// Session prefix messages (user instructions, environment context) are not
+/// turns.
+fn user_turns(items: &[RolloutItem]) -> Vec<UserTurn> {
+    user_turn_positions(items)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (_, message))| UserTurn {
+            turn: idx + 1,
+            message,
+        })
+        .collect()
+}
+
+/// Number of user turns recorded in `items`.
+pub(crate) fn user_turn_count(items: &[RolloutItem]) -> usize {
+    user_turn_positions(items).len()
+}
+
+/// Where a forked history came from: the first session meta line in `items`
+/// is the parent, and the turn is the number of user turns carried over.
+pub(crate) fn fork_origin(items: &[RolloutItem]) -> Option<ForkedFrom> {
+    let conversation_id = items.iter().find_map(|item| match item {
+        RolloutItem::SessionMeta(meta_line) => Some(meta_line.meta.id),
+        _ => None,
+    })?;
+    Some(ForkedFrom {
+        conversation_id,
+        turn: user_turn_count(items),
+    })
+}
+
+fn user_turn_positions(items: &[RolloutItem]) -> Vec<(usize, String)> {
+    items
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, item)| {
+            let RolloutItem::ResponseItem(ResponseItem::Message { role, content, .. }) = item
+            else {
+                return None;
+            };
+            if role != "user" {
+                return None;
+            }
+            let text = content
+                .iter()
+                .filter_map(|content| match content {
+                    ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                        Some(text.as_str())
+                    }
+                    ContentItem::InputImage { .. } => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            matches!(
+                InputMessageKind::from(("user", text.as_str())),
+                InputMessageKind::Plain
+            )
+            .then_some((idx, text))
+        })
+        .collect()
+}
+
+/// Keep the first `turns` user turns and everything recorded before the next
+/// user message.
+fn truncate_to_user_turns(history: InitialHistory, turns: usize) -> InitialHistory {
+    let items = history.get_rollout_items();
+    let cut_idx = user_turn_positions(&items)
+        .get(turns)
+        .map(|(idx, _)| *idx)
+        .unwrap_or(items.len());
+    let kept: Vec<RolloutItem> = items.into_iter().take(cut_idx).collect();
+    if kept.is_empty() {
+        InitialHistory::New
+    } else {
+        InitialHistory::Forked(kept)
+    }
+}
+
 fn log_subagent_inventory(inventory: &SubagentInventory) {
     for event in &inventory.discovery_events {
         info!(target = "codex::subagents", message = %event.message);
@@ -302,6 +439,9 @@ mod tests {
     use codex_protocol::models::ContentItem;
     use codex_protocol::models::ReasoningItemReasoningSummary;
     use codex_protocol::models::ResponseItem;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use pretty_assertions::assert_eq;
 
     fn user_msg(text: &str) -> ResponseItem {
         ResponseItem::Message {
@@ -373,4 +513,51 @@ mod tests {
         let truncated2 = truncate_after_dropping_last_messages(InitialHistory::Forked(initial2), 2);
         assert!(matches!(truncated2, InitialHistory::New));
     }
+
+    #[test]
+    fn fork_at_turn_keeps_prefix_and_records_parent() {
+        let parent_id = ConversationId::default();
+        let meta = RolloutItem::SessionMeta(SessionMetaLine {
+            meta: SessionMeta {
+                id: parent_id,
+                ..Default::default()
+            },
+            git: None,
+            forked_from: None,
+        });
+        let items = vec![
+            meta,
+            RolloutItem::ResponseItem(user_msg(
+                "<environment_context>\n  <cwd>/tmp</cwd>\n</environment_context>",
+            )),
+            RolloutItem::ResponseItem(user_msg("u1")),
+            RolloutItem::ResponseItem(assistant_msg("a1")),
+            RolloutItem::ResponseItem(user_msg("u2")),
+            RolloutItem::ResponseItem(assistant_msg("a2")),
+            RolloutItem::ResponseItem(user_msg("u3")),
+        ];
+
+        let turns = user_turns(&items);
+        assert_eq!(
+            turns
+                .iter()
+                .map(|turn| (turn.turn, turn.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(1, "u1"), (2, "u2"), (3, "u3")]
+        );
+
+        let forked = truncate_to_user_turns(InitialHistory::Forked(items.clone()), 2);
+        let kept = forked.get_rollout_items();
+        assert_eq!(
+            serde_json::to_value(&kept).unwrap(),
+            serde_json::to_value(&items[..6]).unwrap()
+        );
+        assert_eq!(
+            fork_origin(&kept),
+            Some(ForkedFrom {
+                conversation_id: parent_id,
+                turn: 2,
+            })
+        );
+    }
 }
@@ -0,0 +1,321 @@
+//! Turning token usage into money.
+//!
+//! Prices come from `[pricing.<provider>."<model>"]` in `config.toml`, falling
+//! back to the built-in table for known models. Every priced request is added
+//! to a per-day total in `$CODEX_HOME/daily_costs.json`, shared by all
+//! sessions, so `max_daily_cost` can be enforced across them.
+
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::protocol::CostInfo;
+use crate::protocol::CostLimit;
+use crate::protocol::ReviewDecision;
+use crate::protocol::TokenUsage;
+
+/// Writing to the prompt cache is billed at this multiple of the input price.
+const CACHE_WRITE_PRICE_MULTIPLIER: f64 = 1.25;
+
+const DAILY_COSTS_FILENAME: &str = "daily_costs.json";
+
+const MAX_RETRIES: usize = 10;
+const RETRY_SLEEP: Duration = Duration::from_millis(100);
+
+/// Prices for a model, in US dollars per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct ModelPricing {
+    /// Price of uncached input tokens.
+    pub input: f64,
+    /// Price of input tokens served from the prompt cache. Defaults to
+    /// `input`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input: Option<f64>,
+    /// Price of output tokens, reasoning tokens included.
+    pub output: f64,
+}
+
+impl ModelPricing {
+    pub(crate) const fn new(input: f64, cached_input: f64, output: f64) -> Self {
+        Self {
+            input,
+            cached_input: Some(cached_input),
+            output,
+        }
+    }
+
+    /// What `usage` cost. Reasoning tokens are billed as output and are
+    /// already counted in `output_tokens`.
+    pub fn cost_usd(&self, usage: &TokenUsage) -> f64 {
+        let per_token = |price: f64, tokens: u64| price * tokens as f64 / 1_000_000.0;
+        let cache_writes = usage.cache_write_input_tokens;
+        per_token(
+            self.input,
+            usage.non_cached_input().saturating_sub(cache_writes),
+        ) + per_token(self.input * CACHE_WRITE_PRICE_MULTIPLIER, cache_writes)
+            + per_token(
+                self.cached_input.unwrap_or(self.input),
+                usage.cached_input(),
+            )
+            + per_token(self.output, usage.output_tokens)
+    }
+}
+
+/// Cost limits for a session. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CostLimits {
+    pub max_session_usd: Option<f64>,
+    pub max_daily_usd: Option<f64>,
+}
+
+impl CostLimits {
+    pub fn get(&self, limit: CostLimit) -> Option<f64> {
+        match limit {
+            CostLimit::Session => self.max_session_usd,
+            CostLimit::Daily => self.max_daily_usd,
+        }
+    }
+}
+
+/// A limit the session has reached.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CostLimitReached {
+    pub limit: CostLimit,
+    pub spent_usd: f64,
+}
+
+/// Spend counted against [`CostLimits`].
+#[derive(Debug, Default)]
+pub(crate) struct CostTracker {
+    /// Whether any request so far had a price.
+    priced: bool,
+    session_usd: f64,
+    last_usd: f64,
+    daily_usd: f64,
+    /// Spend at which to ask again; `None` once the user stopped the asking.
+    session_threshold: Option<f64>,
+    daily_threshold: Option<f64>,
+}
+
+impl CostTracker {
+    /// `daily_usd` is what earlier sessions spent today.
+    pub(crate) fn new(limits: CostLimits, daily_usd: f64) -> Self {
+        Self {
+            daily_usd,
+            session_threshold: limits.max_session_usd,
+            daily_threshold: limits.max_daily_usd,
+            ..Default::default()
+        }
+    }
+
+    /// Count a request that cost `cost_usd`, or that had no known price.
+    pub(crate) fn record(&mut self, cost_usd: Option<f64>) {
+        self.last_usd = cost_usd.unwrap_or_default();
+        if let Some(cost_usd) = cost_usd {
+            self.priced = true;
+            self.session_usd += cost_usd;
+            self.daily_usd += cost_usd;
+        }
+    }
+
+    /// Replace the daily total with the one read back from disk, which
+    /// includes other sessions running today.
+    pub(crate) fn set_daily_usd(&mut self, daily_usd: f64) {
+        self.daily_usd = daily_usd;
+    }
+
+    pub(crate) fn info(&self) -> Option<CostInfo> {
+        self.priced.then_some(CostInfo {
+            session_usd: self.session_usd,
+            last_usd: self.last_usd,
+            daily_usd: self.daily_usd,
+        })
+    }
+
+    /// The first limit spending has reached, if any.
+    pub(crate) fn limit_reached(&self) -> Option<CostLimitReached> {
+        let reached = |limit, threshold: Option<f64>, spent_usd: f64| {
+            threshold
+                .is_some_and(|threshold| spent_usd >= threshold)
+                .then_some(CostLimitReached { limit, spent_usd })
+        };
+        reached(CostLimit::Session, self.session_threshold, self.session_usd)
+            .or_else(|| reached(CostLimit::Daily, self.daily_threshold, self.daily_usd))
+    }
+
+    /// Apply the user's answer to a reached limit. `Approved` asks again once
+    /// the same amount has been spent on top, `ApprovedForSession` stops
+    /// asking about this limit. Returns whether to keep going.
+    pub(crate) fn resolve(
+        &mut self,
+        reached: CostLimitReached,
+        limits: CostLimits,
+        decision: ReviewDecision,
+    ) -> bool {
+        let threshold = match reached.limit {
+            CostLimit::Session => &mut self.session_threshold,
+            CostLimit::Daily => &mut self.daily_threshold,
+        };
+        match decision {
+            ReviewDecision::Approved => {
+                *threshold = limits
+                    .get(reached.limit)
+                    .map(|step| reached.spent_usd + step);
+                true
+            }
+            ReviewDecision::ApprovedForSession => {
+                *threshold = None;
+                true
+            }
+            ReviewDecision::Denied | ReviewDecision::Abort => false,
+        }
+    }
+}
+
+fn daily_costs_path(codex_home: &Path) -> PathBuf {
+    codex_home.join(DAILY_COSTS_FILENAME)
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// What all sessions have spent today.
+pub(crate) fn load_daily_cost(codex_home: &Path) -> f64 {
+    std::fs::read_to_string(daily_costs_path(codex_home))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<BTreeMap<String, f64>>(&contents).ok())
+        .and_then(|costs| costs.get(&today()).copied())
+        .unwrap_or_default()
+}
+
+/// Add `cost_usd` to today's total and return the new total. Blocks while
+/// another session holds the file.
+pub(crate) fn add_daily_cost(codex_home: &Path, cost_usd: f64) -> std::io::Result<f64> {
+    std::fs::create_dir_all(codex_home)?;
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(daily_costs_path(codex_home))?;
+
+    for _ in 0..MAX_RETRIES {
+        match file.try_lock() {
+            Ok(()) => {
+                let mut contents = String::new();
+                file.read_to_string(&mut contents)?;
+                // A corrupt file starts over rather than blocking the session.
+                let mut costs: BTreeMap<String, f64> =
+                    serde_json::from_str(&contents).unwrap_or_default();
+                let total = costs.entry(today()).or_default();
+                *total += cost_usd;
+                let total = *total;
+
+                let contents = serde_json::to_string_pretty(&costs)?;
+                file.set_len(0)?;
+                file.seek(SeekFrom::Start(0))?;
+                file.write_all(contents.as_bytes())?;
+                file.flush()?;
+                return Ok(total);
+            }
+            Err(std::fs::TryLockError::WouldBlock) => std::thread::sleep(RETRY_SLEEP),
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(std::io::Error::new(
+        std::io::ErrorKind::WouldBlock,
+        "could not acquire exclusive lock on daily cost file after multiple attempts",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn usage(input: u64, cached: u64, output: u64, reasoning: u64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: input,
+            cached_input_tokens: cached,
+            cache_write_input_tokens: 0,
+            output_tokens: output,
+            reasoning_output_tokens: reasoning,
+            total_tokens: input + output,
+        }
+    }
+
+    #[test]
+    fn prices_cached_and_reasoning_tokens() {
+        let pricing = ModelPricing::new(1.25, 0.125, 10.0);
+        // 600k uncached + 400k cached input, 200k output of which 150k reasoning.
+        let cost = pricing.cost_usd(&usage(1_000_000, 400_000, 200_000, 150_000));
+        assert!((cost - (0.75 + 0.05 + 2.0)).abs() < 1e-9, "{cost}");
+
+        let uncached = ModelPricing {
+            cached_input: None,
+            ..pricing
+        };
+        let cost = uncached.cost_usd(&usage(1_000_000, 400_000, 0, 0));
+        assert!((cost - 1.25).abs() < 1e-9, "{cost}");
+
+        // 200k of the 600k uncached input tokens were written to the cache.
+        let cost = pricing.cost_usd(&TokenUsage {
+            cache_write_input_tokens: 200_000,
+            ..usage(1_000_000, 400_000, 0, 0)
+        });
+        assert!((cost - (0.5 + 0.3125 + 0.05)).abs() < 1e-9, "{cost}");
+    }
+
+    #[test]
+    fn asks_again_after_another_limit_is_spent() {
+        let limits = CostLimits {
+            max_session_usd: Some(1.0),
+            max_daily_usd: Some(5.0),
+        };
+        let mut tracker = CostTracker::new(limits, 3.5);
+        assert_eq!(tracker.info(), None);
+
+        tracker.record(Some(0.6));
+        assert_eq!(tracker.limit_reached(), None);
+        tracker.record(Some(0.6));
+        let reached = tracker.limit_reached().expect("session limit reached");
+        assert_eq!(reached.limit, CostLimit::Session);
+        assert!(tracker.resolve(reached, limits, ReviewDecision::Approved));
+        assert_eq!(tracker.limit_reached(), None);
+
+        // The daily limit is reached next; stop asking about it.
+        tracker.record(Some(0.4));
+        let reached = tracker.limit_reached().expect("daily limit reached");
+        assert_eq!(reached.limit, CostLimit::Daily);
+        assert!(tracker.resolve(reached, limits, ReviewDecision::ApprovedForSession));
+        assert_eq!(tracker.limit_reached(), None);
+
+        tracker.record(Some(1.0));
+        let reached = tracker
+            .limit_reached()
+            .expect("session limit reached again");
+        assert!(!tracker.resolve(reached, limits, ReviewDecision::Denied));
+    }
+
+    #[test]
+    fn daily_costs_accumulate_on_disk() {
+        let home = TempDir::new().unwrap();
+        assert_eq!(load_daily_cost(home.path()), 0.0);
+        assert_eq!(add_daily_cost(home.path(), 0.25).unwrap(), 0.25);
+        assert_eq!(add_daily_cost(home.path(), 0.5).unwrap(), 0.75);
+        assert_eq!(load_daily_cost(home.path()), 0.75);
+    }
+}
@@ -60,6 +60,13 @@ pub enum CodexErr {
     #[error("session configured event was not the first event in the stream")]
     SessionConfiguredNotFirstEvent,
 
+    #[error("cannot fork at turn {turn}: the session has {available} user turn(s)")]
+    ForkTurnOutOfRange { turn: usize, available: usize },
+
+    /// Returned in replay mode when no recorded response matches a request.
+    #[error("replay failed: {0}")]
+    Replay(String),
+
     /// Returned by run_command_stream when the spawned child process timed out (10s).
     #[error("timeout waiting for child process to exit")]
     Timeout,
@@ -13,4 +13,8 @@ env_flags! {
 
     /// Fixture path for offline tests (see client.rs).
     pub CODEX_RS_SSE_FIXTURE: Option<&str> = None;
+
+    /// Record model requests and responses to this directory (see
+    /// model_recording.rs). `--record` takes precedence.
+    pub CODEX_RECORD_DIR: Option<&str> = None;
 }
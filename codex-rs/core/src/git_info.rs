@@ -123,6 +123,81 @@ pub async fn git_diff_to_remote(cwd: &Path) -> Option<GitDiffToRemote> {
     })
 }
 
+/// Returns the diff of the working tree (including untracked files) against
+/// the merge base of `HEAD` and `base`, which may be any local or remote
+/// branch name or revision.
+pub async fn git_diff_to_base(cwd: &Path, base: &str) -> Option<GitDiffToRemote> {
+    get_git_repo_root(cwd)?;
+
+    // `--end-of-options` keeps a base such as `--output=x` from being read as
+    // an option.
+    let output =
+        run_git_command_with_timeout(&["merge-base", "--end-of-options", "HEAD", base], cwd)
+            .await?;
+    if !output.status.success() {
+        return None;
+    }
+    let base_sha = GitSha::new(String::from_utf8(output.stdout).ok()?.trim());
+    let diff = diff_against_sha(cwd, &base_sha).await?;
+
+    Some(GitDiffToRemote {
+        sha: base_sha,
+        diff,
+    })
+}
+
+/// Returns the diff introduced by a single commit.
+pub async fn git_diff_for_commit(cwd: &Path, commit: &str) -> Option<String> {
+    let output = run_git_command_with_timeout(
+        &[
+            "show",
+            "--no-textconv",
+            "--no-ext-diff",
+            "--format=commit %H%n%n%B",
+            "--end-of-options",
+            commit,
+        ],
+        cwd,
+    )
+    .await?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// Returns the diff of the changes currently staged in the index.
+pub async fn git_diff_staged(cwd: &Path) -> Option<String> {
+    get_git_repo_root(cwd)?;
+
+    let output =
+        run_git_command_with_timeout(&["diff", "--cached", "--no-textconv", "--no-ext-diff"], cwd)
+            .await?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// Returns true when `name` refers to a local branch or a branch on any
+/// remote.
+pub async fn git_branch_exists(cwd: &Path, name: &str) -> bool {
+    let mut refs = vec![format!("refs/heads/{name}"), format!("refs/remotes/{name}")];
+    for remote in get_git_remotes(cwd).await.unwrap_or_default() {
+        refs.push(format!("refs/remotes/{remote}/{name}"));
+    }
+    for reference in refs {
+        if let Some(output) =
+            run_git_command_with_timeout(&["rev-parse", "--verify", "--quiet", &reference], cwd)
+                .await
+            && output.status.success()
+        {
+            return true;
+        }
+    }
+    false
+}
+
 /// Run a git command with a timeout to prevent blocking on large repositories
 async fn run_git_command_with_timeout(args: &[&str], cwd: &Path) -> Option<std::process::Output> {
     let result = timeout(
@@ -900,6 +975,54 @@ mod tests {
         assert!(state.diff.contains("updated"));
     }
 
+    #[tokio::test]
+    async fn test_git_diff_to_base_and_staged() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let repo_path = create_test_git_repo(&temp_dir).await;
+        let branch = Command::new("git")
+            .args(["rev-parse", "--abbrev-ref", "HEAD"])
+            .current_dir(&repo_path)
+            .output()
+            .await
+            .expect("Failed to get branch");
+        let branch = String::from_utf8(branch.stdout).unwrap().trim().to_string();
+        assert!(git_branch_exists(&repo_path, &branch).await);
+        assert!(!git_branch_exists(&repo_path, "does-not-exist").await);
+
+        fs::write(repo_path.join("test.txt"), "staged change").unwrap();
+        Command::new("git")
+            .args(["add", "test.txt"])
+            .current_dir(&repo_path)
+            .output()
+            .await
+            .expect("Failed to add file");
+        fs::write(repo_path.join("new.txt"), "untracked").unwrap();
+
+        let staged = git_diff_staged(&repo_path)
+            .await
+            .expect("Should compute staged diff");
+        assert!(staged.contains("staged change"));
+        assert!(!staged.contains("untracked"));
+
+        let against_base = git_diff_to_base(&repo_path, &branch)
+            .await
+            .expect("Should compute diff against base");
+        assert!(against_base.diff.contains("staged change"));
+        assert!(against_base.diff.contains("untracked"));
+    }
+
+    #[tokio::test]
+    async fn test_git_diff_revisions_are_not_options() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let repo_path = create_test_git_repo(&temp_dir).await;
+        let output_path = temp_dir.path().join("injected.txt");
+        let revision = format!("--output={}", output_path.display());
+
+        assert_eq!(git_diff_for_commit(&repo_path, &revision).await, None);
+        assert!(git_diff_to_base(&repo_path, &revision).await.is_none());
+        assert!(!output_path.exists());
+    }
+
     #[test]
     fn test_git_info_serialization() {
         let git_info = GitInfo {
@@ -116,16 +116,14 @@ impl HookConfigLoader {
             HookConfigError::InvalidConfiguration("config must be a TOML table".into())
         })?;
 
-        if let Some(notifications) = table.get("notifications") {
-            if let Some(notify) = notifications.get("notify") {
-                if notify
-                    .as_array()
-                    .map(|arr| !arr.is_empty())
-                    .unwrap_or(false)
-                {
-                    return Ok(());
-                }
-            }
+        if let Some(notifications) = table.get("notifications")
+            && let Some(notify) = notifications.get("notify")
+            && notify
+                .as_array()
+                .map(|arr| !arr.is_empty())
+                .unwrap_or(false)
+        {
+            return Ok(());
         }
 
         Err(HookConfigError::InvalidConfiguration(
@@ -174,21 +172,20 @@ impl HookConfigLoader {
                     continue;
                 }
 
-                let mut definition = HookDefinition::default();
-                definition.id = hook.id;
-                definition.event = hook.event;
-                definition.notes = hook.notes;
-                definition.command = hook.command;
-                definition.working_dir = hook.working_dir;
-                definition.timeout_ms = hook.timeout_ms;
-                definition.allow_parallel = hook.allow_parallel;
-                definition.schema_versions = hook.schema_versions;
-                definition.env = hook.env;
-                definition.matchers = hook.matchers;
-                definition.scope = scope.clone();
-                definition.source_path = Some(path.clone());
-
-                definitions.push(definition);
+                definitions.push(HookDefinition {
+                    id: hook.id,
+                    event: hook.event,
+                    notes: hook.notes,
+                    command: hook.command,
+                    working_dir: hook.working_dir,
+                    timeout_ms: hook.timeout_ms,
+                    allow_parallel: hook.allow_parallel,
+                    schema_versions: hook.schema_versions,
+                    env: hook.env,
+                    matchers: hook.matchers,
+                    scope: scope.clone(),
+                    source_path: Some(path.clone()),
+                });
                 summary.loaded_hooks += 1;
             }
 
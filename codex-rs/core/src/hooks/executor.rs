@@ -140,8 +140,10 @@ impl HookExecutor {
 
     /// Log a synthetic PostToolUse hook invocation to capture execution results.
     pub async fn record_post_tool_use(&self, command: &str, exit_code: i32) {
-        let mut decision = HookDecision::default();
-        decision.exit_code = exit_code;
+        let decision = HookDecision {
+            exit_code,
+            ..Default::default()
+        };
         self.log_decision(
             HookEvent::PostToolUse,
             "codex.posttool.audit",
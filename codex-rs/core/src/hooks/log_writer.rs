@@ -28,10 +28,10 @@ impl HookLogWriter {
     /// Append the given record as a JSON line, creating parent directories
     /// and the log file if necessary.
     pub async fn append(&self, record: &HookExecutionRecord) -> Result<(), HookLogWriterError> {
-        if let Some(parent) = self.path.parent() {
-            if !parent.as_os_str().is_empty() {
-                create_dir_all(parent).await?;
-            }
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            create_dir_all(parent).await?;
         }
 
         let mut file = OpenOptions::new()
@@ -53,12 +53,11 @@ impl HookLogWriter {
 
 /// Remove fields that should not be persisted to disk (e.g. synthesized data).
 fn scrub_private_fields(value: &mut Value) {
-    if let Some(obj) = value.as_object_mut() {
-        if let Some(extra) = obj.get_mut("extra") {
-            if extra.is_null() {
-                obj.remove("extra");
-            }
-        }
+    if let Some(obj) = value.as_object_mut()
+        && let Some(extra) = obj.get_mut("extra")
+        && extra.is_null()
+    {
+        obj.remove("extra");
     }
 }
 
@@ -23,10 +23,10 @@ pub fn build_hook_registry_snapshot(
 
     let mut events = HashMap::new();
     for (event, definitions) in registry.events.iter() {
-        if let Some(filter_event) = event_filter {
-            if convert_hook_event(event) != *filter_event {
-                continue;
-            }
+        if let Some(filter_event) = event_filter
+            && convert_hook_event(event) != *filter_event
+        {
+            continue;
         }
 
         let converted_defs: Vec<_> = definitions
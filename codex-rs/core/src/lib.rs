@@ -5,10 +5,13 @@
 // the TUI or the tracing stack).
 #![deny(clippy::print_stdout, clippy::print_stderr)]
 
+mod anthropic_messages;
 mod apply_patch;
 pub mod auth;
 pub mod bash;
+pub mod budget;
 mod chat_completions;
+mod checkpoints;
 mod client;
 mod client_common;
 pub mod codex;
@@ -17,9 +20,12 @@ pub mod token_data;
 pub use codex_conversation::CodexConversation;
 pub mod config;
 pub mod config_edit;
+pub mod config_layers;
 pub mod config_profile;
+pub mod config_schema;
 pub mod config_types;
 mod conversation_history;
+pub mod cost;
 pub mod custom_prompts;
 mod environment_context;
 pub mod error;
@@ -36,6 +42,8 @@ mod mcp_connection_manager;
 mod mcp_tool_call;
 mod message_history;
 mod model_provider_info;
+pub mod model_recording;
+pub mod output_schema;
 pub mod parse_command;
 mod truncate;
 mod unified_exec;
@@ -50,16 +58,21 @@ mod event_mapping;
 pub use codex_protocol::protocol::InitialHistory;
 pub use conversation_manager::ConversationManager;
 pub use conversation_manager::NewConversation;
+pub use conversation_manager::UserTurn;
+pub use conversation_manager::list_user_turns;
 // Re-export common auth types for workspace consumers
 pub use auth::AuthManager;
 pub use auth::CodexAuth;
 pub mod default_client;
+pub mod model_discovery;
+pub mod model_fallback;
 pub mod model_family;
 mod openai_model_info;
 mod openai_tools;
 pub mod plan_mode;
 pub mod plan_tool;
 pub mod project_doc;
+pub mod review;
 mod rollout;
 pub(crate) mod safety;
 pub mod seatbelt;
@@ -75,10 +88,27 @@ pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
 pub use rollout::RolloutRecorder;
 pub use rollout::SESSIONS_SUBDIR;
 pub use rollout::SessionMeta;
+pub use rollout::export::ExportFormat;
+pub use rollout::export::Transcript;
+pub use rollout::export::TranscriptEntry;
+pub use rollout::export::redact_secrets;
 pub use rollout::find_conversation_path_by_id_str;
 pub use rollout::list::ConversationItem;
 pub use rollout::list::ConversationsPage;
 pub use rollout::list::Cursor;
+pub use rollout::retention::SessionFile;
+pub use rollout::retention::SessionRetention;
+pub use rollout::retention::archive_session;
+pub use rollout::retention::prunable_sessions;
+pub use rollout::retention::remove_session;
+pub use rollout::retention::session_in_use;
+pub use rollout::search::SearchEntryKind;
+pub use rollout::search::SearchSnippet;
+pub use rollout::search::SessionIndex;
+pub use rollout::search::SessionSearchHit;
+pub use rollout::search::SessionSearchQuery;
+pub use rollout::search::parse_age;
+pub use rollout::search::parse_time_bound;
 mod user_notification;
 pub mod util;
 
@@ -0,0 +1,261 @@
+//! Listing the models a provider serves.
+//!
+//! `/model` offers every model the session's provider reports, in addition to
+//! the built-in presets. Providers are asked through the OpenAI-style
+//! `GET /models` endpoint; `codex-ollama` lists a local Ollama server through
+//! its native API instead. Either way, results are cached per provider in
+//! `$CODEX_HOME/models_cache.json` so the popup does not wait on the network.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::CodexAuth;
+use crate::config::Config;
+use crate::default_client::create_client;
+use crate::error::CodexErr;
+use crate::model_provider_info::ModelProviderInfo;
+
+const MODELS_CACHE_FILENAME: &str = "models_cache.json";
+
+/// How long a provider's listing is used before it is fetched again.
+pub const MODELS_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Substrings of model ids that cannot serve a conversation, such as
+/// embedding, speech and image models in OpenAI's listing.
+const NON_CHAT_MARKERS: &[&str] = &[
+    "embedding",
+    "moderation",
+    "whisper",
+    "tts",
+    "transcribe",
+    "dall-e",
+    "image",
+];
+
+#[derive(Deserialize)]
+struct ModelsResponse {
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedModels {
+    /// Seconds since the Unix epoch.
+    fetched_at: u64,
+    models: Vec<String>,
+}
+
+impl CachedModels {
+    fn is_fresh(&self) -> bool {
+        now_secs().saturating_sub(self.fetched_at) < MODELS_CACHE_TTL.as_secs()
+    }
+}
+
+/// Ask `provider` for the models it serves, skipping ones that cannot hold a
+/// conversation.
+pub async fn fetch_provider_models(
+    provider: &ModelProviderInfo,
+    auth: Option<CodexAuth>,
+) -> crate::error::Result<Vec<String>> {
+    let client = create_client();
+    let response = provider
+        .create_models_request_builder(&client, &auth)
+        .await?
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(CodexErr::UnexpectedStatus(status, body));
+    }
+    let listing: ModelsResponse = response.json().await?;
+    let mut models: Vec<String> = listing
+        .data
+        .into_iter()
+        .map(|entry| entry.id)
+        .filter(|id| is_chat_model(id))
+        .collect();
+    models.sort();
+    models.dedup();
+    Ok(models)
+}
+
+fn is_chat_model(id: &str) -> bool {
+    let id = id.to_lowercase();
+    !NON_CHAT_MARKERS.iter().any(|marker| id.contains(marker))
+}
+
+/// The models of the session's provider, from the cache when it is fresh.
+pub async fn discover_provider_models(
+    config: &Config,
+    auth: Option<CodexAuth>,
+) -> io::Result<Vec<String>> {
+    discover_models(&config.codex_home, &config.model_provider_id, || async {
+        fetch_provider_models(&config.model_provider, auth)
+            .await
+            .map_err(io::Error::other)
+    })
+    .await
+}
+
+/// The cached models of `provider_id` while they are fresh; otherwise the
+/// result of `fetch`, which is cached. A failed fetch falls back to a stale
+/// cache entry when there is one.
+pub async fn discover_models<F, Fut>(
+    codex_home: &Path,
+    provider_id: &str,
+    fetch: F,
+) -> io::Result<Vec<String>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = io::Result<Vec<String>>>,
+{
+    let cached = read_cache(codex_home).await.remove(provider_id);
+    if let Some(cached) = cached.as_ref()
+        && cached.is_fresh()
+    {
+        return Ok(cached.models.clone());
+    }
+
+    match fetch().await {
+        Ok(models) => {
+            if let Err(e) = write_cache_entry(codex_home, provider_id, &models).await {
+                warn!("failed to cache models for provider {provider_id}: {e}");
+            }
+            Ok(models)
+        }
+        Err(err) => match cached {
+            Some(cached) => {
+                warn!("failed to list models for provider {provider_id}, using cache: {err}");
+                Ok(cached.models)
+            }
+            None => Err(err),
+        },
+    }
+}
+
+fn cache_path(codex_home: &Path) -> PathBuf {
+    codex_home.join(MODELS_CACHE_FILENAME)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+async fn read_cache(codex_home: &Path) -> BTreeMap<String, CachedModels> {
+    tokio::fs::read_to_string(cache_path(codex_home))
+        .await
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+async fn write_cache_entry(
+    codex_home: &Path,
+    provider_id: &str,
+    models: &[String],
+) -> io::Result<()> {
+    let mut cache = read_cache(codex_home).await;
+    cache.insert(
+        provider_id.to_string(),
+        CachedModels {
+            fetched_at: now_secs(),
+            models: models.to_vec(),
+        },
+    );
+
+    // Write a sibling file and rename it, so readers never see a partial
+    // cache.
+    tokio::fs::create_dir_all(codex_home).await?;
+    let tmp_path = codex_home.join(format!(
+        "{MODELS_CACHE_FILENAME}.{}.tmp",
+        std::process::id()
+    ));
+    tokio::fs::write(&tmp_path, serde_json::to_string_pretty(&cache)?).await?;
+    tokio::fs::rename(&tmp_path, cache_path(codex_home)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn caches_listing_and_falls_back_to_it() {
+        let home = TempDir::new().unwrap();
+        let listed = discover_models(home.path(), "ollama", || async {
+            Ok(vec!["llama3:8b".to_string()])
+        })
+        .await
+        .unwrap();
+        assert_eq!(listed, vec!["llama3:8b"]);
+
+        // Fresh cache entries are used without fetching.
+        let cached = discover_models(home.path(), "ollama", || async {
+            Err(io::Error::other("should not fetch"))
+        })
+        .await
+        .unwrap();
+        assert_eq!(cached, vec!["llama3:8b"]);
+
+        // A stale entry is refreshed, and kept when the refresh fails.
+        write_cache_entry(home.path(), "ollama", &["old".to_string()])
+            .await
+            .unwrap();
+        let mut cache = read_cache(home.path()).await;
+        if let Some(entry) = cache.get_mut("ollama") {
+            entry.fetched_at = 0;
+        }
+        tokio::fs::write(
+            cache_path(home.path()),
+            serde_json::to_string(&cache).unwrap(),
+        )
+        .await
+        .unwrap();
+        let stale = discover_models(home.path(), "ollama", || async {
+            Err(io::Error::other("offline"))
+        })
+        .await
+        .unwrap();
+        assert_eq!(stale, vec!["old"]);
+
+        let missing = discover_models(home.path(), "other", || async {
+            Err(io::Error::other("offline"))
+        })
+        .await;
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn skips_non_chat_models() {
+        let ids = [
+            "gpt-5",
+            "text-embedding-3-small",
+            "whisper-1",
+            "gpt-4o-mini-tts",
+        ];
+        let chat: Vec<&str> = ids.into_iter().filter(|id| is_chat_model(id)).collect();
+        assert_eq!(chat, vec!["gpt-5"]);
+    }
+}
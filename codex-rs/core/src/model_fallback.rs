@@ -0,0 +1,325 @@
+//! Retrying failed turns on the models listed in `fallback_models`.
+//!
+//! Every [`ModelClient`](crate::client::ModelClient) carries a chain whose
+//! first entry is the session model. When a request fails in a way
+//! `fallback_policy` says to fail over on, the chain moves to its next entry
+//! and queues a [`ModelSwitch`] for the session to report. Each task starts
+//! again on the session model.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use codex_protocol::protocol::ModelFallbackItem;
+use reqwest::StatusCode;
+
+use crate::config::Config;
+use crate::config_types::ContextOverflowAction;
+use crate::config_types::FallbackAction;
+use crate::config_types::FallbackPolicy;
+use crate::error::CodexErr;
+use crate::model_provider_info::ModelProviderInfo;
+
+/// Error messages providers use when a request does not fit the context
+/// window, lowercased.
+const CONTEXT_OVERFLOW_MARKERS: &[&str] = &[
+    "context_length_exceeded",
+    "maximum context length",
+    "context window",
+    "prompt is too long",
+];
+
+/// An entry of `fallback_models`, resolved against `model_providers`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FallbackModel {
+    pub provider_id: String,
+    pub provider: ModelProviderInfo,
+    pub model: String,
+}
+
+/// Resolve `provider:model` entries. An entry whose prefix is not a known
+/// provider, such as `llama3:8b`, names a model on the session's provider.
+pub(crate) fn resolve_fallback_models(
+    entries: &[String],
+    session_provider_id: &str,
+    providers: &HashMap<String, ModelProviderInfo>,
+) -> std::io::Result<Vec<FallbackModel>> {
+    entries
+        .iter()
+        .map(|entry| {
+            let (provider_id, model) = match entry.split_once(':') {
+                Some((provider_id, model)) if providers.contains_key(provider_id) => {
+                    (provider_id, model)
+                }
+                _ => (session_provider_id, entry.as_str()),
+            };
+            let provider = providers.get(provider_id).ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Model provider `{provider_id}` not found"),
+                )
+            })?;
+            if model.trim().is_empty() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("fallback model `{entry}` does not name a model"),
+                ));
+            }
+            Ok(FallbackModel {
+                provider_id: provider_id.to_string(),
+                provider: provider.clone(),
+                model: model.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The kinds of failure `fallback_policy` has a say over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FailureClass {
+    RateLimit,
+    ServerError,
+    ContextOverflow,
+}
+
+impl FailureClass {
+    pub(crate) fn of(err: &CodexErr) -> Option<Self> {
+        match err {
+            CodexErr::RetryLimit(StatusCode::TOO_MANY_REQUESTS) => Some(Self::RateLimit),
+            CodexErr::RetryLimit(status) if status.is_server_error() => Some(Self::ServerError),
+            CodexErr::InternalServerError => Some(Self::ServerError),
+            CodexErr::UnexpectedStatus(StatusCode::PAYLOAD_TOO_LARGE, _) => {
+                Some(Self::ContextOverflow)
+            }
+            CodexErr::UnexpectedStatus(_, message) | CodexErr::Stream(message, _)
+                if is_context_overflow(message) =>
+            {
+                Some(Self::ContextOverflow)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FailureClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailureClass::RateLimit => write!(f, "rate limited"),
+            FailureClass::ServerError => write!(f, "server error"),
+            FailureClass::ContextOverflow => write!(f, "context window exceeded"),
+        }
+    }
+}
+
+fn is_context_overflow(message: &str) -> bool {
+    let message = message.to_lowercase();
+    CONTEXT_OVERFLOW_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+/// Whether `err` should be answered by compacting the conversation.
+pub(crate) fn should_compact(policy: &FallbackPolicy, err: &CodexErr) -> bool {
+    policy.context_overflow == ContextOverflowAction::Compact
+        && FailureClass::of(err) == Some(FailureClass::ContextOverflow)
+}
+
+/// A move to the next model of the chain, waiting to be reported.
+#[derive(Debug, Clone)]
+pub(crate) struct ModelSwitch {
+    pub model: String,
+    pub fallback: ModelFallbackItem,
+}
+
+#[derive(Debug)]
+pub(crate) struct FallbackTarget {
+    pub label: String,
+    pub config: Arc<Config>,
+    pub provider: ModelProviderInfo,
+}
+
+#[derive(Debug)]
+pub(crate) struct FallbackChain {
+    targets: Vec<FallbackTarget>,
+    policy: FallbackPolicy,
+    active: AtomicUsize,
+    switches: Mutex<Vec<ModelSwitch>>,
+}
+
+impl FallbackChain {
+    pub(crate) fn new(config: Arc<Config>, provider: ModelProviderInfo) -> Self {
+        let mut targets = vec![FallbackTarget {
+            label: format!("{}:{}", config.model_provider_id, config.model),
+            config: Arc::clone(&config),
+            provider,
+        }];
+        targets.extend(
+            config
+                .fallback_models
+                .iter()
+                .map(|fallback| FallbackTarget {
+                    label: format!("{}:{}", fallback.provider_id, fallback.model),
+                    config: Arc::new(fallback_config(&config, fallback)),
+                    provider: fallback.provider.clone(),
+                }),
+        );
+        Self {
+            targets,
+            policy: config.fallback_policy,
+            active: AtomicUsize::new(0),
+            switches: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn active(&self) -> &FallbackTarget {
+        let index = self.active.load(Ordering::Relaxed);
+        &self.targets[index.min(self.targets.len() - 1)]
+    }
+
+    /// Move to the next model if the policy fails over on `err`. Returns
+    /// whether it did.
+    pub(crate) fn fail_over(&self, err: &CodexErr) -> bool {
+        let Some(class) = FailureClass::of(err) else {
+            return false;
+        };
+        let fail_over = match class {
+            FailureClass::RateLimit => self.policy.rate_limit == FallbackAction::FailOver,
+            FailureClass::ServerError => self.policy.server_error == FallbackAction::FailOver,
+            FailureClass::ContextOverflow => {
+                self.policy.context_overflow == ContextOverflowAction::FailOver
+            }
+        };
+        if !fail_over {
+            return false;
+        }
+
+        let index = self.active.load(Ordering::Relaxed);
+        let (Some(from), Some(to)) = (self.targets.get(index), self.targets.get(index + 1)) else {
+            return false;
+        };
+        if self
+            .active
+            .compare_exchange(index, index + 1, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            // Another request sharing this chain already moved on.
+            return true;
+        }
+        if let Ok(mut switches) = self.switches.lock() {
+            switches.push(ModelSwitch {
+                model: to.config.model.clone(),
+                fallback: ModelFallbackItem {
+                    from: from.label.clone(),
+                    to: to.label.clone(),
+                    reason: format!("{class}: {err}"),
+                },
+            });
+        }
+        true
+    }
+
+    /// Go back to the session model.
+    pub(crate) fn reset(&self) {
+        self.active.store(0, Ordering::Relaxed);
+    }
+
+    pub(crate) fn take_switches(&self) -> Vec<ModelSwitch> {
+        self.switches
+            .lock()
+            .map(|mut switches| std::mem::take(&mut *switches))
+            .unwrap_or_default()
+    }
+}
+
+/// The session config with the model settings of `fallback` swapped in.
+fn fallback_config(config: &Config, fallback: &FallbackModel) -> Config {
+    let model_family = config.model_family_or_default(&fallback.model);
+    let model_info = config.model_info_for(&model_family);
+    let mut fallback_config = config.clone();
+    fallback_config.model = fallback.model.clone();
+    fallback_config.model_context_window = model_info.as_ref().map(|info| info.context_window);
+    fallback_config.model_max_output_tokens =
+        model_info.as_ref().map(|info| info.max_output_tokens);
+    fallback_config.model_auto_compact_token_limit =
+        model_info.and_then(|info| info.auto_compact_token_limit);
+    fallback_config.model_family = model_family;
+    fallback_config.model_provider_id = fallback.provider_id.clone();
+    fallback_config.model_provider = fallback.provider.clone();
+    fallback_config.fallback_models = Vec::new();
+    fallback_config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_provider_info::built_in_model_providers;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn resolves_provider_prefixes() {
+        let providers = built_in_model_providers();
+        let entries = [
+            "oss:gpt-oss:20b".to_string(),
+            "gpt-4.1".to_string(),
+            "llama3:8b".to_string(),
+        ];
+        let resolved = resolve_fallback_models(&entries, "openai", &providers)
+            .unwrap_or_else(|err| panic!("resolve failed: {err}"));
+        let labels: Vec<String> = resolved
+            .iter()
+            .map(|fallback| format!("{}:{}", fallback.provider_id, fallback.model))
+            .collect();
+        assert_eq!(
+            labels,
+            vec!["oss:gpt-oss:20b", "openai:gpt-4.1", "openai:llama3:8b"]
+        );
+
+        let err = resolve_fallback_models(&["openai:".to_string()], "openai", &providers)
+            .err()
+            .map(|err| err.to_string());
+        assert_eq!(
+            err.as_deref(),
+            Some("fallback model `openai:` does not name a model")
+        );
+    }
+
+    #[test]
+    fn classifies_failures() {
+        let cases = [
+            (
+                CodexErr::RetryLimit(StatusCode::TOO_MANY_REQUESTS),
+                Some(FailureClass::RateLimit),
+            ),
+            (
+                CodexErr::RetryLimit(StatusCode::BAD_GATEWAY),
+                Some(FailureClass::ServerError),
+            ),
+            (
+                CodexErr::InternalServerError,
+                Some(FailureClass::ServerError),
+            ),
+            (
+                CodexErr::UnexpectedStatus(
+                    StatusCode::BAD_REQUEST,
+                    r#"{"error":{"code":"context_length_exceeded"}}"#.to_string(),
+                ),
+                Some(FailureClass::ContextOverflow),
+            ),
+            (
+                CodexErr::Stream("prompt is too long: 210000 tokens".to_string(), None),
+                Some(FailureClass::ContextOverflow),
+            ),
+            (
+                CodexErr::UnexpectedStatus(StatusCode::BAD_REQUEST, "bad tool".to_string()),
+                None,
+            ),
+            (CodexErr::Stream("stream closed".to_string(), None), None),
+        ];
+        for (err, expected) in cases {
+            assert_eq!(FailureClass::of(&err), expected, "{err}");
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::config_types::ApplyPatchFormat;
+use crate::config_types::ModelCapabilities;
 use crate::config_types::ReasoningSummaryFormat;
 use crate::tool_apply_patch::ApplyPatchToolType;
 
@@ -39,6 +41,9 @@ pub struct ModelFamily {
     /// a tool call instead of just a bash command
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
 
+    /// False if the model cannot call tools, in which case none are sent.
+    pub supports_tools: bool,
+
     // Instructions to use for querying the model
     pub base_instructions: String,
 }
@@ -56,6 +61,7 @@ macro_rules! model_family {
             reasoning_summary_format: ReasoningSummaryFormat::None,
             uses_local_shell_tool: false,
             apply_patch_tool_type: None,
+            supports_tools: true,
             base_instructions: BASE_INSTRUCTIONS.to_string(),
         };
         // apply overrides
@@ -66,6 +72,32 @@ macro_rules! model_family {
     }};
 }
 
+impl ModelFamily {
+    /// This family with the user's `[model_capabilities]` entry applied.
+    pub fn with_capabilities(mut self, capabilities: &ModelCapabilities) -> Self {
+        if let Some(supports_tools) = capabilities.supports_tools {
+            self.supports_tools = supports_tools;
+        }
+        if let Some(supports_reasoning) = capabilities.supports_reasoning {
+            self.supports_reasoning_summaries = supports_reasoning;
+        }
+        match capabilities.apply_patch_format {
+            Some(ApplyPatchFormat::Freeform) => {
+                self.apply_patch_tool_type = Some(ApplyPatchToolType::Freeform);
+            }
+            Some(ApplyPatchFormat::Function) => {
+                self.apply_patch_tool_type = Some(ApplyPatchToolType::Function);
+            }
+            Some(ApplyPatchFormat::Shell) => {
+                self.apply_patch_tool_type = None;
+                self.needs_special_apply_patch_instructions = true;
+            }
+            None => {}
+        }
+        self
+    }
+}
+
 /// Returns a `ModelFamily` for the given model slug, or `None` if the slug
 /// does not match any known model family.
 pub fn find_family_for_model(slug: &str) -> Option<ModelFamily> {
@@ -106,6 +138,14 @@ pub fn find_family_for_model(slug: &str) -> Option<ModelFamily> {
             reasoning_summary_format: ReasoningSummaryFormat::Experimental,
             base_instructions: GPT_5_CODEX_INSTRUCTIONS.to_string(),
         )
+    } else if slug.starts_with("claude-") {
+        // Extended thinking arrived with Claude 3.7.
+        let supports_thinking = !slug.starts_with("claude-3-") || slug.starts_with("claude-3-7");
+        model_family!(
+            slug, "claude",
+            supports_reasoning_summaries: supports_thinking,
+            apply_patch_tool_type: Some(ApplyPatchToolType::Function),
+        )
     } else if slug.starts_with("gpt-5") {
         model_family!(
             slug, "gpt-5",
@@ -126,6 +166,7 @@ pub fn derive_default_model_family(model: &str) -> ModelFamily {
         reasoning_summary_format: ReasoningSummaryFormat::None,
         uses_local_shell_tool: false,
         apply_patch_tool_type: None,
+        supports_tools: true,
         base_instructions: BASE_INSTRUCTIONS.to_string(),
     }
 }
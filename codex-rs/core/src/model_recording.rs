@@ -0,0 +1,465 @@
+//! Record model traffic to a directory and replay it later.
+//!
+//! Each conversation numbers its model requests from 1. In record mode the
+//! request body is written to `turn-NNNN.request.json` and the raw SSE
+//! response to `turn-NNNN.sse`. In replay mode no network request is made:
+//! the response recorded for the same turn is streamed back, provided its
+//! request hash matches. When it does not, any recorded turn with a matching
+//! hash is used instead, so reordered requests still replay; failing that,
+//! the turn with the same index is replayed with a warning.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use bytes::Bytes;
+use futures::Stream;
+use futures::StreamExt;
+use regex_lite::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use sha1::Digest;
+use sha1::Sha1;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::error::CodexErr;
+use crate::error::Result;
+
+/// Whether model traffic is recorded to or replayed from a directory.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RecordingMode {
+    /// Store each request and its raw SSE response in this directory.
+    Record(PathBuf),
+    /// Serve responses recorded earlier from this directory instead of
+    /// calling the provider.
+    Replay(PathBuf),
+}
+
+/// Where model traffic is recorded to or replayed from, along with the turn
+/// counter of the conversation using it. Clones share the counter, so
+/// compaction and subagent requests made with a copy of the conversation's
+/// config get their own turns.
+#[derive(Debug, Clone)]
+pub struct ModelStreamRecording {
+    mode: RecordingMode,
+    next_turn: Arc<AtomicUsize>,
+}
+
+impl PartialEq for ModelStreamRecording {
+    fn eq(&self, other: &Self) -> bool {
+        self.mode == other.mode
+    }
+}
+
+/// Request fields that change between otherwise identical runs and are
+/// therefore left out of the request hash.
+const VOLATILE_REQUEST_FIELDS: &[&str] = &["prompt_cache_key"];
+
+/// Replaces the working directory in hashed requests.
+const CWD_PLACEHOLDER: &str = "<cwd>";
+
+static ENVIRONMENT_CWD: LazyLock<Regex> = LazyLock::new(|| {
+    #[expect(clippy::expect_used)]
+    Regex::new(r"<cwd>([^<]+)</cwd>").expect("valid cwd regex")
+});
+
+static WALL_TIME: LazyLock<Regex> = LazyLock::new(|| {
+    #[expect(clippy::expect_used)]
+    Regex::new(r"Wall time: [0-9.]+ seconds").expect("valid wall time regex")
+});
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedRequest {
+    turn: usize,
+    hash: String,
+    body: Value,
+}
+
+/// How a single model request is served under a [`ModelStreamRecording`].
+pub(crate) enum ModelTurn {
+    /// Replay mode: the recorded SSE response to stream back.
+    Replayed(Bytes),
+    /// Record mode: the request was saved; record the response under `turn`.
+    Recording { dir: PathBuf, turn: usize },
+}
+
+impl ModelStreamRecording {
+    pub fn record(dir: PathBuf) -> Self {
+        Self::new(RecordingMode::Record(dir))
+    }
+
+    pub fn replay(dir: PathBuf) -> Self {
+        Self::new(RecordingMode::Replay(dir))
+    }
+
+    fn new(mode: RecordingMode) -> Self {
+        Self {
+            mode,
+            next_turn: Arc::new(AtomicUsize::new(1)),
+        }
+    }
+
+    pub fn mode(&self) -> &RecordingMode {
+        &self.mode
+    }
+
+    pub fn dir(&self) -> &Path {
+        match &self.mode {
+            RecordingMode::Record(dir) | RecordingMode::Replay(dir) => dir,
+        }
+    }
+
+    /// The same recording with its own turn counter, for a new conversation.
+    pub fn for_new_conversation(&self) -> Self {
+        Self::new(self.mode.clone())
+    }
+
+    /// Claim a turn for the request `body`. Failing to save a request only
+    /// logs a warning; failing to find a recorded response is an error.
+    pub(crate) async fn begin_turn(&self, body: &Value) -> Result<ModelTurn> {
+        let turn = self.next_turn.fetch_add(1, Ordering::Relaxed);
+        match &self.mode {
+            RecordingMode::Replay(dir) => {
+                Ok(ModelTurn::Replayed(replay_response(dir, turn, body).await?))
+            }
+            RecordingMode::Record(dir) => {
+                if let Err(err) = record_request(dir, turn, body).await {
+                    warn!("failed to record model request to {}: {err}", dir.display());
+                }
+                Ok(ModelTurn::Recording {
+                    dir: dir.clone(),
+                    turn,
+                })
+            }
+        }
+    }
+}
+
+/// Hash of the request body, ignoring [`VOLATILE_REQUEST_FIELDS`], tool call
+/// timings and the working directory named in the environment context.
+pub fn request_hash(body: &Value) -> String {
+    let mut body = body.clone();
+    if let Some(object) = body.as_object_mut() {
+        for field in VOLATILE_REQUEST_FIELDS {
+            object.remove(*field);
+        }
+    }
+    let cwd = environment_cwd(&body);
+    normalize_strings(&mut body, cwd.as_deref());
+    let mut hasher = Sha1::new();
+    hasher.update(body.to_string().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// The `<cwd>` of the first environment context in the request, unless it
+/// is too short to replace safely.
+fn environment_cwd(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => ENVIRONMENT_CWD
+            .captures(text)
+            .map(|captures| captures[1].to_string())
+            .filter(|cwd| cwd.len() > 1),
+        Value::Array(items) => items.iter().find_map(environment_cwd),
+        Value::Object(map) => map.values().find_map(environment_cwd),
+        _ => None,
+    }
+}
+
+fn normalize_strings(value: &mut Value, cwd: Option<&str>) {
+    match value {
+        Value::String(text) => *text = normalize_text(text, cwd),
+        Value::Array(items) => {
+            for item in items {
+                normalize_strings(item, cwd);
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                normalize_strings(item, cwd);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Zero the timings reported in tool output and replace the working
+/// directory with [`CWD_PLACEHOLDER`].
+fn normalize_text(text: &str, cwd: Option<&str>) -> String {
+    let mut text = match serde_json::from_str::<Value>(text) {
+        Ok(mut output) => match output
+            .get_mut("metadata")
+            .and_then(|metadata| metadata.get_mut("duration_seconds"))
+        {
+            Some(duration) => {
+                *duration = Value::from(0);
+                output.to_string()
+            }
+            None => text.to_string(),
+        },
+        Err(_) => text.to_string(),
+    };
+    if text.contains("Wall time: ") {
+        text = WALL_TIME
+            .replace_all(&text, "Wall time: 0 seconds")
+            .into_owned();
+    }
+    match cwd {
+        Some(cwd) => text.replace(cwd, CWD_PLACEHOLDER),
+        None => text,
+    }
+}
+
+fn request_path(dir: &Path, turn: usize) -> PathBuf {
+    dir.join(format!("turn-{turn:04}.request.json"))
+}
+
+fn response_path(dir: &Path, turn: usize) -> PathBuf {
+    dir.join(format!("turn-{turn:04}.sse"))
+}
+
+/// Write the request for `turn` and truncate any response left from an
+/// earlier recording.
+async fn record_request(dir: &Path, turn: usize, body: &Value) -> std::io::Result<()> {
+    tokio::fs::create_dir_all(dir).await?;
+    let recorded = RecordedRequest {
+        turn,
+        hash: request_hash(body),
+        body: body.clone(),
+    };
+    tokio::fs::write(
+        request_path(dir, turn),
+        serde_json::to_string_pretty(&recorded)?,
+    )
+    .await?;
+    tokio::fs::File::create(response_path(dir, turn)).await?;
+    Ok(())
+}
+
+/// Pass `stream` through unchanged while appending every chunk to the
+/// response file for `turn`.
+pub(crate) async fn record_response<S>(
+    stream: S,
+    dir: &Path,
+    turn: usize,
+) -> impl Stream<Item = Result<Bytes>> + Unpin + use<S>
+where
+    S: Stream<Item = Result<Bytes>> + Unpin,
+{
+    let path = Arc::new(response_path(dir, turn));
+    let file = tokio::fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path.as_ref())
+        .await
+        .map_err(|err| {
+            warn!(
+                "failed to record model response to {}: {err}",
+                path.display()
+            )
+        })
+        .ok();
+    let file = Arc::new(Mutex::new(file));
+    Box::pin(stream.then(move |chunk| {
+        let file = Arc::clone(&file);
+        let path = Arc::clone(&path);
+        async move {
+            let mut file = file.lock().await;
+            if let (Some(out), Ok(bytes)) = (file.as_mut(), &chunk)
+                && let Err(err) = out.write_all(bytes).await
+            {
+                warn!(
+                    "failed to record model response to {}: {err}",
+                    path.display()
+                );
+                *file = None;
+            }
+            chunk
+        }
+    }))
+}
+
+/// The recorded response for a request, matched by request hash, preferring
+/// the same turn index.
+async fn replay_response(dir: &Path, turn: usize, body: &Value) -> Result<Bytes> {
+    let hash = request_hash(body);
+    let same_turn = read_recorded_request(&request_path(dir, turn)).await;
+    let matched = match &same_turn {
+        Some(recorded) if recorded.hash == hash => turn,
+        _ => match find_turn_by_hash(dir, &hash).await? {
+            Some(matched) => {
+                warn!("replaying recorded turn {matched} for model request {turn}");
+                matched
+            }
+            None if same_turn.is_some() => {
+                warn!(
+                    "model request {turn} differs from the recording in {}; replaying turn {turn} anyway",
+                    dir.display()
+                );
+                turn
+            }
+            None => {
+                return Err(CodexErr::Replay(format!(
+                    "no recorded response in {} matches model request {turn} (hash {hash})",
+                    dir.display()
+                )));
+            }
+        },
+    };
+    Ok(Bytes::from(
+        tokio::fs::read(response_path(dir, matched)).await?,
+    ))
+}
+
+async fn read_recorded_request(path: &Path) -> Option<RecordedRequest> {
+    let contents = tokio::fs::read_to_string(path).await.ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+async fn find_turn_by_hash(dir: &Path, hash: &str) -> Result<Option<usize>> {
+    let mut turns = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let is_request = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(".request.json"));
+        if is_request
+            && let Some(recorded) = read_recorded_request(&path).await
+            && recorded.hash == hash
+        {
+            turns.push(recorded.turn);
+        }
+    }
+    Ok(turns.into_iter().min())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    async fn collect(stream: impl Stream<Item = Result<Bytes>> + Unpin) -> Vec<u8> {
+        stream
+            .map(|chunk| chunk.expect("chunk").to_vec())
+            .concat()
+            .await
+    }
+
+    #[test]
+    fn request_hash_ignores_volatile_fields() {
+        let a = json!({ "model": "gpt-5", "input": [], "prompt_cache_key": "one" });
+        let b = json!({ "model": "gpt-5", "input": [], "prompt_cache_key": "two" });
+        let c = json!({ "model": "gpt-5", "input": ["hi"], "prompt_cache_key": "one" });
+        assert_eq!(request_hash(&a), request_hash(&b));
+        assert_ne!(request_hash(&a), request_hash(&c));
+    }
+
+    #[test]
+    fn request_hash_ignores_tool_timings_and_cwd() {
+        let request = |cwd: &str, duration: f64, output: &str| {
+            let tool_output = json!({
+                "output": format!("{output} in {cwd}/src\n"),
+                "metadata": { "exit_code": 0, "duration_seconds": duration },
+            });
+            json!({
+                "input": [
+                    {
+                        "type": "message",
+                        "role": "user",
+                        "content": [{
+                            "type": "input_text",
+                            "text": format!("<environment_context>\n  <cwd>{cwd}</cwd>\n</environment_context>"),
+                        }],
+                    },
+                    {
+                        "type": "function_call_output",
+                        "call_id": "call-1",
+                        "output": tool_output.to_string(),
+                    },
+                    {
+                        "type": "function_call_output",
+                        "call_id": "call-2",
+                        "output": format!("Wall time: {duration:.3} seconds\nOutput:\nok"),
+                    },
+                ],
+            })
+        };
+        assert_eq!(
+            request_hash(&request("/tmp/one", 0.1, "built")),
+            request_hash(&request("/tmp/two", 2.4, "built"))
+        );
+        assert_ne!(
+            request_hash(&request("/tmp/one", 0.1, "built")),
+            request_hash(&request("/tmp/one", 0.1, "failed"))
+        );
+    }
+
+    #[tokio::test]
+    async fn replays_what_was_recorded_by_turn_and_hash() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let first = json!({ "input": ["first"] });
+        let second = json!({ "input": ["second"] });
+
+        for (turn, body, sse) in [(1, &first, "data: one\n\n"), (2, &second, "data: two\n\n")] {
+            record_request(dir.path(), turn, body)
+                .await
+                .expect("record request");
+            let upstream = futures::stream::iter([Ok(Bytes::from(sse))]);
+            let recorded = collect(record_response(upstream, dir.path(), turn).await).await;
+            assert_eq!(recorded, sse.as_bytes());
+        }
+
+        let replayed = replay_response(dir.path(), 2, &second)
+            .await
+            .expect("replay");
+        assert_eq!(replayed, "data: two\n\n");
+
+        // Out-of-order requests are matched by hash.
+        let replayed = replay_response(dir.path(), 2, &first)
+            .await
+            .expect("replay");
+        assert_eq!(replayed, "data: one\n\n");
+
+        // Without a hash match the same turn is replayed.
+        let replayed = replay_response(dir.path(), 1, &json!({ "input": ["changed"] }))
+            .await
+            .expect("replay");
+        assert_eq!(replayed, "data: one\n\n");
+
+        let err = replay_response(dir.path(), 3, &json!({ "input": ["other"] }))
+            .await
+            .expect_err("no match");
+        assert!(matches!(err, CodexErr::Replay(_)));
+    }
+
+    #[tokio::test]
+    async fn conversations_number_their_turns_separately() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let recording = ModelStreamRecording::record(dir.path().to_path_buf());
+        let same_conversation = recording.clone();
+        let other_conversation = recording.for_new_conversation();
+
+        let turn = |model_turn: ModelTurn| match model_turn {
+            ModelTurn::Recording { turn, .. } => turn,
+            ModelTurn::Replayed(_) => panic!("expected a recorded turn"),
+        };
+        let body = json!({});
+        assert_eq!(turn(recording.begin_turn(&body).await.expect("turn")), 1);
+        assert_eq!(
+            turn(same_conversation.begin_turn(&body).await.expect("turn")),
+            2
+        );
+        assert_eq!(
+            turn(other_conversation.begin_turn(&body).await.expect("turn")),
+            1
+        );
+    }
+}
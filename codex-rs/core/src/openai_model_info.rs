@@ -1,10 +1,7 @@
+use crate::cost::ModelPricing;
 use crate::model_family::ModelFamily;
 
 /// Metadata about a model, particularly OpenAI models.
-/// We may want to consider including details like the pricing for
-/// input tokens, output tokens, etc., though users will need to be able to
-/// override this in config.toml, as this information can get out of date.
-/// Though this would help present more accurate pricing information in the UI.
 #[derive(Debug)]
 pub(crate) struct ModelInfo {
     /// Size of the context window in tokens.
@@ -18,7 +15,7 @@ pub(crate) struct ModelInfo {
 }
 
 impl ModelInfo {
-    const fn new(context_window: u64, max_output_tokens: u64) -> Self {
+    pub(crate) const fn new(context_window: u64, max_output_tokens: u64) -> Self {
         Self {
             context_window,
             max_output_tokens,
@@ -65,6 +62,48 @@ pub(crate) fn get_model_info(model_family: &ModelFamily) -> Option<ModelInfo> {
 
         _ if slug.starts_with("codex-") => Some(ModelInfo::new(272_000, 128_000)),
 
+        // https://docs.anthropic.com/en/docs/about-claude/models/overview
+        _ if slug.starts_with("claude-opus-4") => Some(ModelInfo::new(200_000, 32_000)),
+        _ if slug.starts_with("claude-sonnet-4") || slug.starts_with("claude-3-7-sonnet") => {
+            Some(ModelInfo::new(200_000, 64_000))
+        }
+        _ if slug.starts_with("claude-") => Some(ModelInfo::new(200_000, 8_192)),
+
+        _ => None,
+    }
+}
+
+/// List prices for known models. These go out of date; `[pricing]` in
+/// config.toml overrides them.
+pub(crate) fn get_model_pricing(model_family: &ModelFamily) -> Option<ModelPricing> {
+    let slug = model_family.slug.as_str();
+    match slug {
+        // https://platform.openai.com/docs/pricing
+        "gpt-5-mini" => Some(ModelPricing::new(0.25, 0.025, 2.0)),
+        "gpt-5-nano" => Some(ModelPricing::new(0.05, 0.005, 0.4)),
+        "codex-mini-latest" => Some(ModelPricing::new(1.5, 0.375, 6.0)),
+        "o3" => Some(ModelPricing::new(2.0, 0.5, 8.0)),
+        "o4-mini" => Some(ModelPricing::new(1.1, 0.275, 4.4)),
+        "gpt-4.1" | "gpt-4.1-2025-04-14" => Some(ModelPricing::new(2.0, 0.5, 8.0)),
+        "gpt-4.1-mini" => Some(ModelPricing::new(0.4, 0.1, 1.6)),
+        "gpt-4o-mini" => Some(ModelPricing::new(0.15, 0.075, 0.6)),
+        "gpt-4o" | "gpt-4o-2024-08-06" | "gpt-4o-2024-11-20" => {
+            Some(ModelPricing::new(2.5, 1.25, 10.0))
+        }
+        "gpt-3.5-turbo" => Some(ModelPricing {
+            input: 0.5,
+            cached_input: None,
+            output: 1.5,
+        }),
+        _ if slug.starts_with("gpt-5") => Some(ModelPricing::new(1.25, 0.125, 10.0)),
+
+        // https://docs.anthropic.com/en/docs/about-claude/pricing
+        _ if slug.starts_with("claude-opus-4") => Some(ModelPricing::new(15.0, 1.5, 75.0)),
+        _ if slug.starts_with("claude-sonnet-4") || slug.starts_with("claude-3-7-sonnet") => {
+            Some(ModelPricing::new(3.0, 0.3, 15.0))
+        }
+        _ if slug.starts_with("claude-3-5-haiku") => Some(ModelPricing::new(0.8, 0.08, 4.0)),
+
         _ => None,
     }
 }
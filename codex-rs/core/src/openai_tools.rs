@@ -291,6 +291,7 @@ pub(crate) struct ToolsConfig {
     pub web_search_request: bool,
     pub include_view_image_tool: bool,
     pub experimental_unified_exec_tool: bool,
+    pub supports_tools: bool,
 }
 
 pub(crate) struct ToolsConfigParams<'a> {
@@ -350,6 +351,7 @@ impl ToolsConfig {
             web_search_request: *include_web_search_request,
             include_view_image_tool: *include_view_image_tool,
             experimental_unified_exec_tool: *experimental_unified_exec_tool,
+            supports_tools: model_family.supports_tools,
         }
     }
 }
@@ -650,6 +652,36 @@ pub(crate) fn create_tools_json_for_chat_completions_api(
     Ok(tools_json)
 }
 
+/// Returns JSON values that are compatible with tool use in the Anthropic
+/// Messages API:
+/// https://docs.anthropic.com/en/docs/agents-and-tools/tool-use/implement-tool-use
+/// Freeform tools become functions that take their input as a single string.
+pub(crate) fn create_tools_json_for_anthropic_api(tools: &[OpenAiTool]) -> Vec<serde_json::Value> {
+    tools
+        .iter()
+        .filter_map(|tool| match tool {
+            OpenAiTool::Function(tool) => Some(json!({
+                "name": tool.name,
+                "description": tool.description,
+                "input_schema": tool.parameters,
+            })),
+            OpenAiTool::Freeform(tool) => Some(json!({
+                "name": tool.name,
+                "description": format!(
+                    "{}\n\n`input` must follow this {} grammar:\n{}",
+                    tool.description, tool.format.syntax, tool.format.definition
+                ),
+                "input_schema": {
+                    "type": "object",
+                    "properties": {"input": {"type": "string"}},
+                    "required": ["input"],
+                },
+            })),
+            OpenAiTool::LocalShell {} | OpenAiTool::WebSearch {} => None,
+        })
+        .collect()
+}
+
 pub(crate) fn mcp_tool_to_openai_tool(
     fully_qualified_name: String,
     tool: mcp_types::Tool,
@@ -809,6 +841,10 @@ pub(crate) fn get_openai_tools(
 ) -> Vec<OpenAiTool> {
     let mut tools: Vec<OpenAiTool> = Vec::new();
 
+    if !config.supports_tools {
+        return tools;
+    }
+
     if config.experimental_unified_exec_tool {
         tools.push(create_unified_exec_tool());
     } else {
@@ -880,6 +916,8 @@ pub(crate) fn get_openai_tools(
 
 #[cfg(test)]
 mod tests {
+    use crate::config_types::ModelCapabilities;
+    use crate::model_family::derive_default_model_family;
     use crate::model_family::find_family_for_model;
     use crate::subagents::SubagentConfig;
     use crate::subagents::SubagentDefinition;
@@ -940,6 +978,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_openai_tools_without_tool_support() {
+        let model_family =
+            derive_default_model_family("llama3:8b").with_capabilities(&ModelCapabilities {
+                supports_tools: Some(false),
+                ..Default::default()
+            });
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::ReadOnly,
+            include_plan_tool: true,
+            include_apply_patch_tool: true,
+            include_web_search_request: true,
+            use_streamable_shell_tool: false,
+            include_view_image_tool: true,
+            experimental_unified_exec_tool: false,
+        });
+        let tools = get_openai_tools(&config, Some(HashMap::new()), None);
+
+        assert_eq_tool_names(&tools, &[]);
+    }
+
     #[test]
     fn test_get_openai_tools_default_shell() {
         let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
@@ -1527,4 +1588,36 @@ mod tests {
 
         assert_eq!(description, "Runs a shell command and returns its output.");
     }
+
+    #[test]
+    fn anthropic_tools_keep_functions_and_wrap_freeform_input() {
+        let tools = vec![
+            create_apply_patch_freeform_tool(),
+            OpenAiTool::LocalShell {},
+            OpenAiTool::WebSearch {},
+            super::create_shell_tool_for_sandbox(&SandboxPolicy::DangerFullAccess),
+        ];
+
+        let tools_json = create_tools_json_for_anthropic_api(&tools);
+        assert_eq!(tools_json.len(), 2);
+        assert_eq!(tools_json[0]["name"], "apply_patch");
+        assert_eq!(
+            tools_json[0]["input_schema"],
+            json!({
+                "type": "object",
+                "properties": {"input": {"type": "string"}},
+                "required": ["input"],
+            })
+        );
+        assert!(
+            tools_json[0]["description"]
+                .as_str()
+                .is_some_and(|description| description.contains("grammar")),
+            "{}",
+            tools_json[0]
+        );
+        assert_eq!(tools_json[1]["name"], "shell");
+        assert_eq!(tools_json[1]["input_schema"]["type"], "object");
+        assert!(tools_json[1].get("type").is_none());
+    }
 }
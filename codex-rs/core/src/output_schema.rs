@@ -0,0 +1,377 @@
+//! Structured final output: a JSON Schema the last agent message must match.
+//!
+//! The schema is sent to the model as `text.format` on the Responses API and
+//! as a [`FINAL_OUTPUT_TOOL_NAME`] tool call on Chat Completions and
+//! Anthropic, forced on the final turn.
+//! [`validate_output`] then checks the final message locally. Only the subset
+//! of JSON Schema that structured outputs use is understood: `type`,
+//! `properties`, `required`, `additionalProperties`, `items`, `enum`,
+//! `const`, numeric and length bounds, `pattern`, `anyOf`/`oneOf`/`allOf`
+//! and local `$ref`s. Unknown keywords are ignored.
+
+use regex_lite::Regex;
+use serde_json::Map;
+use serde_json::Value;
+
+/// Name of the function tool Chat Completions providers must call with the
+/// final answer when an output schema is set.
+pub const FINAL_OUTPUT_TOOL_NAME: &str = "final_output";
+
+/// Sent, without being recorded, on the turn that forces
+/// [`FINAL_OUTPUT_TOOL_NAME`] after the model replied in plain text.
+pub(crate) const FINAL_OUTPUT_REQUEST: &str =
+    "Submit your final answer now by calling the final_output tool.";
+
+/// Name given to the schema in the Responses API `text.format`.
+pub(crate) const OUTPUT_SCHEMA_NAME: &str = "codex_output_schema";
+
+/// Parse `message` as JSON and validate it against `schema`. Returns one
+/// human-readable error per violation.
+pub fn validate_output(schema: &Value, message: &str) -> Result<Value, Vec<String>> {
+    let instance = parse_output(message).map_err(|err| vec![err])?;
+    let mut errors = Vec::new();
+    Validator { root: schema }.check(schema, &instance, "$", &mut errors);
+    if errors.is_empty() {
+        Ok(instance)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parse the final message as JSON, tolerating a surrounding Markdown code
+/// fence.
+fn parse_output(message: &str) -> Result<Value, String> {
+    let trimmed = message.trim();
+    let unfenced = trimmed
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map(|body| {
+            let body = body.strip_prefix("json").unwrap_or(body);
+            body.trim()
+        })
+        .unwrap_or(trimmed);
+    serde_json::from_str(unfenced).map_err(|err| format!("output is not valid JSON: {err}"))
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+}
+
+impl<'a> Validator<'a> {
+    fn check(&self, schema: &'a Value, instance: &Value, path: &str, errors: &mut Vec<String>) {
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                errors.push(format!("{path}: no value is allowed here"));
+                return;
+            }
+            Value::Object(schema) => schema,
+            _ => return,
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match self.resolve(reference) {
+                Some(target) => self.check(target, instance, path, errors),
+                None => errors.push(format!("{path}: cannot resolve $ref {reference}")),
+            }
+        }
+
+        if let Some(expected) = schema.get("type")
+            && !type_matches(expected, instance)
+        {
+            errors.push(format!(
+                "{path}: expected {}, got {}",
+                describe_type(expected),
+                type_name(instance)
+            ));
+            return;
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+            && !allowed.contains(instance)
+        {
+            errors.push(format!(
+                "{path}: {instance} is not one of {}",
+                Value::Array(allowed.clone())
+            ));
+        }
+        if let Some(expected) = schema.get("const")
+            && expected != instance
+        {
+            errors.push(format!("{path}: expected {expected}, got {instance}"));
+        }
+
+        match instance {
+            Value::Object(object) => self.check_object(schema, object, path, errors),
+            Value::Array(items) => self.check_array(schema, items, path, errors),
+            Value::String(text) => check_string(schema, text, path, errors),
+            Value::Number(number) => {
+                if let Some(number) = number.as_f64() {
+                    check_number(schema, number, path, errors);
+                }
+            }
+            Value::Bool(_) | Value::Null => {}
+        }
+
+        self.check_combinators(schema, instance, path, errors);
+    }
+
+    fn check_object(
+        &self,
+        schema: &'a Map<String, Value>,
+        object: &Map<String, Value>,
+        path: &str,
+        errors: &mut Vec<String>,
+    ) {
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(key) {
+                    errors.push(format!("{path}: missing required property \"{key}\""));
+                }
+            }
+        }
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (key, value) in object {
+            let child = format!("{path}.{key}");
+            match properties.and_then(|properties| properties.get(key)) {
+                Some(property) => self.check(property, value, &child, errors),
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        errors.push(format!("{path}: unexpected property \"{key}\""));
+                    }
+                    Some(additional) => self.check(additional, value, &child, errors),
+                    None => {}
+                },
+            }
+        }
+    }
+
+    fn check_array(
+        &self,
+        schema: &'a Map<String, Value>,
+        items: &[Value],
+        path: &str,
+        errors: &mut Vec<String>,
+    ) {
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64)
+            && (items.len() as u64) < min
+        {
+            errors.push(format!("{path}: expected at least {min} items"));
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64)
+            && (items.len() as u64) > max
+        {
+            errors.push(format!("{path}: expected at most {max} items"));
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (index, item) in items.iter().enumerate() {
+                self.check(item_schema, item, &format!("{path}[{index}]"), errors);
+            }
+        }
+    }
+
+    fn check_combinators(
+        &self,
+        schema: &'a Map<String, Value>,
+        instance: &Value,
+        path: &str,
+        errors: &mut Vec<String>,
+    ) {
+        if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+            for sub in all {
+                self.check(sub, instance, path, errors);
+            }
+        }
+        if let Some(any) = schema.get("anyOf").and_then(Value::as_array)
+            && !any.iter().any(|sub| self.is_valid(sub, instance, path))
+        {
+            errors.push(format!("{path}: does not match any schema in anyOf"));
+        }
+        if let Some(one) = schema.get("oneOf").and_then(Value::as_array) {
+            let matches = one
+                .iter()
+                .filter(|sub| self.is_valid(sub, instance, path))
+                .count();
+            if matches != 1 {
+                errors.push(format!(
+                    "{path}: expected exactly one schema in oneOf to match, {matches} did"
+                ));
+            }
+        }
+    }
+
+    fn is_valid(&self, schema: &'a Value, instance: &Value, path: &str) -> bool {
+        let mut errors = Vec::new();
+        self.check(schema, instance, path, &mut errors);
+        errors.is_empty()
+    }
+
+    /// Resolve a local reference such as `#/$defs/Item`.
+    fn resolve(&self, reference: &str) -> Option<&'a Value> {
+        let pointer = reference.strip_prefix('#')?;
+        self.root.pointer(pointer)
+    }
+}
+
+fn check_string(schema: &Map<String, Value>, text: &str, path: &str, errors: &mut Vec<String>) {
+    let len = text.chars().count() as u64;
+    if let Some(min) = schema.get("minLength").and_then(Value::as_u64)
+        && len < min
+    {
+        errors.push(format!("{path}: expected at least {min} characters"));
+    }
+    if let Some(max) = schema.get("maxLength").and_then(Value::as_u64)
+        && len > max
+    {
+        errors.push(format!("{path}: expected at most {max} characters"));
+    }
+    if let Some(pattern) = schema.get("pattern").and_then(Value::as_str)
+        && let Ok(regex) = Regex::new(pattern)
+        && !regex.is_match(text)
+    {
+        errors.push(format!("{path}: does not match pattern {pattern}"));
+    }
+}
+
+fn check_number(schema: &Map<String, Value>, number: f64, path: &str, errors: &mut Vec<String>) {
+    let bound = |key: &str| schema.get(key).and_then(Value::as_f64);
+    if let Some(min) = bound("minimum")
+        && number < min
+    {
+        errors.push(format!("{path}: {number} is less than {min}"));
+    }
+    if let Some(max) = bound("maximum")
+        && number > max
+    {
+        errors.push(format!("{path}: {number} is greater than {max}"));
+    }
+    if let Some(min) = bound("exclusiveMinimum")
+        && number <= min
+    {
+        errors.push(format!("{path}: {number} is not greater than {min}"));
+    }
+    if let Some(max) = bound("exclusiveMaximum")
+        && number >= max
+    {
+        errors.push(format!("{path}: {number} is not less than {max}"));
+    }
+}
+
+fn type_matches(expected: &Value, instance: &Value) -> bool {
+    match expected {
+        Value::String(name) => is_type(name, instance),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|name| is_type(name, instance)),
+        _ => true,
+    }
+}
+
+fn is_type(name: &str, instance: &Value) -> bool {
+    match name {
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "boolean" => instance.is_boolean(),
+        "null" => instance.is_null(),
+        "number" => instance.is_number(),
+        "integer" => instance
+            .as_f64()
+            .is_some_and(|number| number.fract() == 0.0),
+        _ => true,
+    }
+}
+
+fn describe_type(expected: &Value) -> String {
+    match expected {
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        other => other.as_str().unwrap_or("any").to_string(),
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+        Value::Number(_) => "number",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "summary": { "type": "string", "minLength": 1 },
+                "files": { "type": "array", "items": { "$ref": "#/$defs/file" } },
+                "status": { "enum": ["ok", "failed"] }
+            },
+            "required": ["summary", "status"],
+            "additionalProperties": false,
+            "$defs": {
+                "file": {
+                    "type": "object",
+                    "properties": { "path": { "type": "string" }, "lines": { "type": "integer", "minimum": 0 } },
+                    "required": ["path"]
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn accepts_conforming_output_in_a_code_fence() {
+        let message = "```json\n{\"summary\": \"done\", \"status\": \"ok\", \"files\": [{\"path\": \"a.rs\", \"lines\": 3}]}\n```";
+        let value = validate_output(&schema(), message).expect("valid");
+        assert_eq!(value["status"], "ok");
+    }
+
+    #[test]
+    fn reports_each_violation_with_its_path() {
+        let message =
+            r#"{"summary": "", "status": "maybe", "files": [{"lines": -1.5}], "extra": 1}"#;
+        let mut errors = validate_output(&schema(), message).expect_err("invalid");
+        // Property order depends on serde_json's `preserve_order` feature.
+        errors.sort();
+        assert_eq!(
+            errors,
+            vec![
+                "$.files[0].lines: expected integer, got number".to_string(),
+                "$.files[0]: missing required property \"path\"".to_string(),
+                "$.status: \"maybe\" is not one of [\"ok\",\"failed\"]".to_string(),
+                "$.summary: expected at least 1 characters".to_string(),
+                "$: unexpected property \"extra\"".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_non_json_output() {
+        let errors = validate_output(&schema(), "All done!").expect_err("invalid");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("output is not valid JSON"));
+    }
+
+    #[test]
+    fn any_of_and_one_of() {
+        let schema = json!({ "anyOf": [{ "type": "string" }, { "type": "null" }] });
+        assert!(validate_output(&schema, "null").is_ok());
+        assert!(validate_output(&schema, "1").is_err());
+
+        let schema = json!({ "oneOf": [{ "type": "number" }, { "type": "integer" }] });
+        assert!(validate_output(&schema, "1.5").is_ok());
+        assert!(validate_output(&schema, "1").is_err());
+    }
+}
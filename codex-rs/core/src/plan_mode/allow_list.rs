@@ -184,19 +184,15 @@ fn shell_command_candidates(command: &[String]) -> Vec<String> {
     candidates
 }
 
-fn strip_shell_wrapper<'a>(command: &'a [String]) -> Option<&'a [String]> {
+fn strip_shell_wrapper(command: &[String]) -> Option<&[String]> {
     if command.is_empty() {
         return None;
     }
 
     let mut slice = command;
     loop {
-        let Some(first) = slice.first() else {
-            return None;
-        };
-        let Some(exe_name) = Path::new(first).file_name().and_then(|os| os.to_str()) else {
-            return None;
-        };
+        let first = slice.first()?;
+        let exe_name = Path::new(first).file_name().and_then(|os| os.to_str())?;
         let exe_name = exe_name.to_ascii_lowercase();
 
         if exe_name == "env" || exe_name == "env.exe" {
@@ -0,0 +1,237 @@
+//! Helpers shared by the `/review` slash command and `codex review` for
+//! turning a slice of git history into a [`ReviewRequest`] and interpreting
+//! the resulting [`ReviewOutputEvent`].
+
+use std::path::Path;
+
+use crate::git_info::get_git_repo_root;
+use crate::git_info::git_branch_exists;
+use crate::git_info::git_diff_for_commit;
+use crate::git_info::git_diff_staged;
+use crate::git_info::git_diff_to_base;
+use crate::git_info::git_diff_to_remote;
+use crate::protocol::ReviewFinding;
+use crate::protocol::ReviewOutputEvent;
+use crate::protocol::ReviewRequest;
+
+/// Findings at or above this priority (numerically at or below) fail CI runs.
+pub const BLOCKING_PRIORITY_THRESHOLD: i32 = 1;
+
+/// Which changes a review should look at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewTarget {
+    /// The working tree against the closest commit that exists on a remote.
+    Remote,
+    /// The working tree against the merge base with the given branch.
+    Base(String),
+    /// The changes introduced by a single commit.
+    Commit(String),
+    /// The changes currently staged in the index.
+    Staged,
+}
+
+impl ReviewTarget {
+    /// Interpret a free-form `/review` argument. An empty argument reviews
+    /// the working tree against the remote, `staged` reviews the index, a
+    /// branch name reviews against that branch, and anything else is treated
+    /// as a commit.
+    pub async fn resolve(cwd: &Path, arg: &str) -> Self {
+        let arg = arg.trim();
+        if arg.is_empty() {
+            return Self::Remote;
+        }
+        if arg.eq_ignore_ascii_case("staged") {
+            return Self::Staged;
+        }
+        if git_branch_exists(cwd, arg).await {
+            Self::Base(arg.to_string())
+        } else {
+            Self::Commit(arg.to_string())
+        }
+    }
+
+    /// Short human-readable description, used as the review's
+    /// `user_facing_hint`.
+    pub fn description(&self) -> String {
+        match self {
+            Self::Remote => "current changes".to_string(),
+            Self::Base(base) => format!("changes against {base}"),
+            Self::Commit(commit) => format!("commit {commit}"),
+            Self::Staged => "staged changes".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ReviewTargetError {
+    #[error("not inside a git repository")]
+    NotAGitRepository,
+    #[error("failed to compute the diff for {0}")]
+    DiffFailed(String),
+    #[error("no changes to review for {0}")]
+    NoChanges(String),
+}
+
+/// Compute the diff for `target` and wrap it in a [`ReviewRequest`].
+pub async fn build_review_request(
+    cwd: &Path,
+    target: &ReviewTarget,
+) -> Result<ReviewRequest, ReviewTargetError> {
+    if get_git_repo_root(cwd).is_none() {
+        return Err(ReviewTargetError::NotAGitRepository);
+    }
+
+    let description = target.description();
+    let diff = match target {
+        ReviewTarget::Remote => git_diff_to_remote(cwd).await.map(|d| d.diff),
+        ReviewTarget::Base(base) => git_diff_to_base(cwd, base).await.map(|d| d.diff),
+        ReviewTarget::Commit(commit) => git_diff_for_commit(cwd, commit).await,
+        ReviewTarget::Staged => git_diff_staged(cwd).await,
+    }
+    .ok_or_else(|| ReviewTargetError::DiffFailed(description.clone()))?;
+
+    if diff.trim().is_empty() {
+        return Err(ReviewTargetError::NoChanges(description));
+    }
+
+    Ok(ReviewRequest {
+        prompt: format!(
+            "Review the {description} in this repository. The diff to review is below.\n\n```diff\n{diff}\n```"
+        ),
+        user_facing_hint: description,
+    })
+}
+
+/// Render a priority as the conventional `P0`..`P3` label.
+pub fn priority_label(priority: i32) -> String {
+    format!("P{priority}")
+}
+
+/// True when the finding should block a merge.
+pub fn is_blocking_finding(finding: &ReviewFinding) -> bool {
+    finding.priority <= BLOCKING_PRIORITY_THRESHOLD
+}
+
+/// True when any finding in `output` is P0 or P1.
+pub fn has_blocking_findings(output: &ReviewOutputEvent) -> bool {
+    output.findings.iter().any(is_blocking_finding)
+}
+
+/// Findings ordered from most to least severe, breaking ties by confidence.
+pub fn sorted_findings(output: &ReviewOutputEvent) -> Vec<&ReviewFinding> {
+    let mut findings: Vec<&ReviewFinding> = output.findings.iter().collect();
+    findings.sort_by(|a, b| {
+        a.priority
+            .cmp(&b.priority)
+            .then_with(|| b.confidence_score.total_cmp(&a.confidence_score))
+    });
+    findings
+}
+
+/// Build the follow-up prompt that asks the agent to address `findings`.
+pub fn fix_findings_prompt(findings: &[&ReviewFinding]) -> String {
+    let mut prompt = String::from("Fix these review findings:\n");
+    for (idx, finding) in findings.iter().enumerate() {
+        let location = &finding.code_location;
+        prompt.push_str(&format!(
+            "\n{}. [{}] {} ({}:{}-{})\n",
+            idx + 1,
+            priority_label(finding.priority),
+            finding.title,
+            location.absolute_file_path.display(),
+            location.line_range.start,
+            location.line_range.end,
+        ));
+        for line in finding.body.trim().lines() {
+            prompt.push_str(&format!("   {line}\n"));
+        }
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ReviewCodeLocation;
+    use crate::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn finding(title: &str, priority: i32, confidence_score: f32) -> ReviewFinding {
+        ReviewFinding {
+            title: title.to_string(),
+            body: String::new(),
+            confidence_score,
+            priority,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/tmp/file.rs"),
+                line_range: ReviewLineRange { start: 1, end: 2 },
+            },
+        }
+    }
+
+    #[test]
+    fn sorts_findings_by_priority_then_confidence() {
+        let output = ReviewOutputEvent {
+            findings: vec![
+                finding("minor", 3, 0.9),
+                finding("major-low", 1, 0.4),
+                finding("major-high", 1, 0.8),
+            ],
+            ..Default::default()
+        };
+        let titles: Vec<&str> = sorted_findings(&output)
+            .into_iter()
+            .map(|f| f.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["major-high", "major-low", "minor"]);
+        assert!(has_blocking_findings(&output));
+    }
+
+    #[test]
+    fn low_priority_findings_do_not_block() {
+        let output = ReviewOutputEvent {
+            findings: vec![finding("nit", 2, 0.9), finding("style", 3, 0.5)],
+            ..Default::default()
+        };
+        assert!(!has_blocking_findings(&output));
+    }
+
+    #[test]
+    fn fix_prompt_lists_findings_with_locations() {
+        let mut crash = finding("Crash", 0, 0.9);
+        crash.body = "Unwrap on None.".to_string();
+        let nit = finding("Nit", 3, 0.2);
+        assert_eq!(
+            fix_findings_prompt(&[&crash, &nit]),
+            "Fix these review findings:\n\n1. [P0] Crash (/tmp/file.rs:1-2)\n   Unwrap on None.\n\n2. [P3] Nit (/tmp/file.rs:1-2)\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn build_review_request_requires_git_repository() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let err = build_review_request(temp_dir.path(), &ReviewTarget::Staged)
+            .await
+            .expect_err("non-git directory should fail");
+        assert_eq!(err, ReviewTargetError::NotAGitRepository);
+    }
+
+    #[tokio::test]
+    async fn resolve_recognizes_staged_and_commits() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        assert_eq!(
+            ReviewTarget::resolve(temp_dir.path(), "").await,
+            ReviewTarget::Remote
+        );
+        assert_eq!(
+            ReviewTarget::resolve(temp_dir.path(), "staged").await,
+            ReviewTarget::Staged
+        );
+        assert_eq!(
+            ReviewTarget::resolve(temp_dir.path(), "abc1234").await,
+            ReviewTarget::Commit("abc1234".to_string())
+        );
+    }
+}
@@ -0,0 +1,870 @@
+//! Readable exports of recorded sessions.
+//!
+//! A rollout is reduced to a [`Transcript`] (messages, reasoning summaries,
+//! commands with their output, and file changes) which can then be rendered
+//! as Markdown, a standalone HTML page, or JSON.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use regex_lite::Regex;
+use serde::Serialize;
+
+use super::recorder::RolloutRecorder;
+use crate::protocol::EventMsg;
+use crate::protocol::InputMessageKind;
+use codex_apply_patch::split_file_mode;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ForkedFrom;
+use codex_protocol::protocol::InitialHistory;
+use codex_protocol::protocol::RolloutItem;
+
+/// Output lines kept from each end of a command's output.
+const OUTPUT_HEAD_LINES: usize = 10;
+const OUTPUT_TAIL_LINES: usize = 10;
+const REDACTED: &str = "[REDACTED]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "html" => Ok(ExportFormat::Html),
+            "json" => Ok(ExportFormat::Json),
+            other => Err(format!(
+                "unknown export format `{other}`; expected md, html or json"
+            )),
+        }
+    }
+}
+
+/// How a file was changed by a patch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeKind {
+    Added,
+    Edited,
+    Deleted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PatchedFile {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_path: Option<String>,
+    pub kind: FileChangeKind,
+    pub added: usize,
+    pub removed: usize,
+    /// Hunks in unified-diff style (`@@`, ` `, `+`, `-` prefixed lines).
+    pub diff: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptEntry {
+    User {
+        text: String,
+    },
+    Agent {
+        text: String,
+    },
+    Reasoning {
+        text: String,
+    },
+    Command {
+        command: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        exit_code: Option<i32>,
+        output: String,
+    },
+    Patch {
+        files: Vec<PatchedFile>,
+    },
+    Compacted {
+        summary: String,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Transcript {
+    pub id: Option<String>,
+    pub timestamp: Option<String>,
+    pub cwd: Option<PathBuf>,
+    pub git_branch: Option<String>,
+    pub forked_from: Option<ForkedFrom>,
+    pub models: Vec<String>,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+impl Transcript {
+    /// Read and reduce the rollout at `path`.
+    pub async fn load(path: &Path) -> std::io::Result<Self> {
+        match RolloutRecorder::get_rollout_history(path).await? {
+            InitialHistory::Resumed(resumed) => Ok(Self::from_rollout(&resumed.history)),
+            InitialHistory::Forked(items) => Ok(Self::from_rollout(&items)),
+            InitialHistory::New => Ok(Self::default()),
+        }
+    }
+
+    pub fn from_rollout(items: &[RolloutItem]) -> Self {
+        let mut transcript = Transcript::default();
+        // Command entries waiting for their output, by call id.
+        let mut pending_commands: HashMap<String, usize> = HashMap::new();
+        for item in items {
+            match item {
+                RolloutItem::SessionMeta(meta_line) => {
+                    if transcript.id.is_none() {
+                        transcript.id = Some(meta_line.meta.id.to_string());
+                        transcript.timestamp = Some(meta_line.meta.timestamp.clone());
+                        transcript.cwd = Some(meta_line.meta.cwd.clone());
+                        transcript.git_branch =
+                            meta_line.git.as_ref().and_then(|git| git.branch.clone());
+                        transcript.forked_from = meta_line.forked_from.clone();
+                    }
+                }
+                RolloutItem::TurnContext(turn_context) => {
+                    if !transcript.models.contains(&turn_context.model) {
+                        transcript.models.push(turn_context.model.clone());
+                    }
+                }
+                RolloutItem::Compacted(compacted) => {
+                    transcript.entries.push(TranscriptEntry::Compacted {
+                        summary: compacted.message.clone(),
+                    });
+                }
+                RolloutItem::EventMsg(EventMsg::UserMessage(event)) => {
+                    if matches!(event.kind, None | Some(InputMessageKind::Plain)) {
+                        transcript.entries.push(TranscriptEntry::User {
+                            text: event.message.clone(),
+                        });
+                    }
+                }
+                RolloutItem::EventMsg(EventMsg::AgentMessage(event)) => {
+                    transcript.entries.push(TranscriptEntry::Agent {
+                        text: event.message.clone(),
+                    });
+                }
+                RolloutItem::EventMsg(EventMsg::AgentReasoning(event)) => {
+                    transcript.entries.push(TranscriptEntry::Reasoning {
+                        text: event.text.clone(),
+                    });
+                }
+                RolloutItem::EventMsg(_) => {}
+                RolloutItem::ResponseItem(item) => {
+                    transcript.push_response_item(item, &mut pending_commands);
+                }
+            }
+        }
+        transcript
+    }
+
+    fn push_response_item(
+        &mut self,
+        item: &ResponseItem,
+        pending_commands: &mut HashMap<String, usize>,
+    ) {
+        match item {
+            ResponseItem::LocalShellCall {
+                call_id,
+                action: LocalShellAction::Exec(exec),
+                ..
+            } => self.push_command(exec.command.join(" "), call_id.clone(), pending_commands),
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                let Ok(args) = serde_json::from_str::<serde_json::Value>(arguments) else {
+                    return;
+                };
+                if name == "apply_patch" {
+                    if let Some(patch) = args.get("input").and_then(|v| v.as_str()) {
+                        self.push_patch(patch);
+                    }
+                } else if let Some(command) = args.get("command").and_then(|v| v.as_array()) {
+                    let command: Vec<&str> = command.iter().filter_map(|v| v.as_str()).collect();
+                    self.push_command(command.join(" "), Some(call_id.clone()), pending_commands);
+                } else if let Some(cmd) = args.get("cmd").and_then(|v| v.as_str()) {
+                    self.push_command(cmd.to_string(), Some(call_id.clone()), pending_commands);
+                }
+            }
+            ResponseItem::CustomToolCall { name, input, .. } if name == "apply_patch" => {
+                self.push_patch(input);
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                let Some(idx) = pending_commands.remove(call_id) else {
+                    return;
+                };
+                if let Some(TranscriptEntry::Command {
+                    exit_code,
+                    output: command_output,
+                    ..
+                }) = self.entries.get_mut(idx)
+                {
+                    let (text, code) = parse_exec_output(&output.content);
+                    *command_output = truncate_output(&text);
+                    *exit_code = code;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_command(
+        &mut self,
+        command: String,
+        call_id: Option<String>,
+        pending_commands: &mut HashMap<String, usize>,
+    ) {
+        if let Some(call_id) = call_id {
+            pending_commands.insert(call_id, self.entries.len());
+        }
+        self.entries.push(TranscriptEntry::Command {
+            command,
+            exit_code: None,
+            output: String::new(),
+        });
+    }
+
+    fn push_patch(&mut self, patch: &str) {
+        let files = parse_patch_files(patch);
+        if !files.is_empty() {
+            self.entries.push(TranscriptEntry::Patch { files });
+        }
+    }
+
+    /// Replace env-var-like secrets and well-known token shapes everywhere
+    /// in the transcript.
+    pub fn redact(&mut self) {
+        for entry in &mut self.entries {
+            match entry {
+                TranscriptEntry::User { text }
+                | TranscriptEntry::Agent { text }
+                | TranscriptEntry::Reasoning { text }
+                | TranscriptEntry::Compacted { summary: text } => *text = redact_secrets(text),
+                TranscriptEntry::Command {
+                    command, output, ..
+                } => {
+                    *command = redact_secrets(command);
+                    *output = redact_secrets(output);
+                }
+                TranscriptEntry::Patch { files } => {
+                    for file in files {
+                        file.diff = redact_secrets(&file.diff);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn render(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Markdown => self.to_markdown(),
+            ExportFormat::Html => self.to_html(),
+            ExportFormat::Json => serde_json::to_string_pretty(self).unwrap_or_default(),
+        }
+    }
+
+    fn details(&self) -> Vec<(&'static str, String)> {
+        let mut details = Vec::new();
+        if let Some(timestamp) = &self.timestamp {
+            details.push(("Started", timestamp.clone()));
+        }
+        if let Some(cwd) = &self.cwd {
+            details.push(("Directory", cwd.display().to_string()));
+        }
+        if let Some(branch) = &self.git_branch {
+            details.push(("Branch", branch.clone()));
+        }
+        if let Some(forked_from) = &self.forked_from {
+            details.push((
+                "Forked from",
+                format!(
+                    "{} after turn {}",
+                    forked_from.conversation_id, forked_from.turn
+                ),
+            ));
+        }
+        if !self.models.is_empty() {
+            details.push(("Model", self.models.join(", ")));
+        }
+        details
+    }
+
+    fn title(&self) -> String {
+        match &self.id {
+            Some(id) => format!("Codex session {id}"),
+            None => "Codex session".to_string(),
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", self.title());
+        for (label, value) in self.details() {
+            let _ = writeln!(out, "- {label}: `{value}`");
+        }
+        for entry in &self.entries {
+            out.push('\n');
+            match entry {
+                TranscriptEntry::User { text } => {
+                    let _ = writeln!(out, "## User\n\n{}", text.trim());
+                }
+                TranscriptEntry::Agent { text } => {
+                    let _ = writeln!(out, "## Codex\n\n{}", text.trim());
+                }
+                TranscriptEntry::Reasoning { text } => {
+                    for line in text.trim().lines() {
+                        let _ = writeln!(out, "> {line}");
+                    }
+                }
+                TranscriptEntry::Command {
+                    command,
+                    exit_code,
+                    output,
+                } => {
+                    let _ = write!(out, "**Ran** `{}`", command.replace('`', "'"));
+                    if let Some(code) = exit_code {
+                        let _ = write!(out, " (exit {code})");
+                    }
+                    out.push('\n');
+                    if !output.trim().is_empty() {
+                        push_fenced(&mut out, "text", output.trim_end());
+                    }
+                }
+                TranscriptEntry::Patch { files } => {
+                    for (idx, file) in files.iter().enumerate() {
+                        if idx > 0 {
+                            out.push('\n');
+                        }
+                        let _ = writeln!(
+                            out,
+                            "**{}** `{}` (+{} -{})",
+                            file_label(file),
+                            file_path_label(file),
+                            file.added,
+                            file.removed
+                        );
+                        if !file.diff.is_empty() {
+                            push_fenced(&mut out, "diff", file.diff.trim_end());
+                        }
+                    }
+                }
+                TranscriptEntry::Compacted { summary } => {
+                    let _ = writeln!(
+                        out,
+                        "## Summary of earlier conversation\n\n{}",
+                        summary.trim()
+                    );
+                }
+            }
+        }
+        out
+    }
+
+    fn to_html(&self) -> String {
+        let mut out = String::new();
+        let title = escape_html(&self.title());
+        let _ = write!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{HTML_STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+        let details = self.details();
+        if !details.is_empty() {
+            out.push_str("<ul class=\"details\">\n");
+            for (label, value) in details {
+                let _ = writeln!(
+                    out,
+                    "<li>{label}: <code>{}</code></li>",
+                    escape_html(&value)
+                );
+            }
+            out.push_str("</ul>\n");
+        }
+        for entry in &self.entries {
+            match entry {
+                TranscriptEntry::User { text } => {
+                    let _ = writeln!(
+                        out,
+                        "<section class=\"user\"><h2>User</h2><pre class=\"message\">{}</pre></section>",
+                        escape_html(text.trim())
+                    );
+                }
+                TranscriptEntry::Agent { text } => {
+                    let _ = writeln!(
+                        out,
+                        "<section class=\"agent\"><h2>Codex</h2><pre class=\"message\">{}</pre></section>",
+                        escape_html(text.trim())
+                    );
+                }
+                TranscriptEntry::Reasoning { text } => {
+                    let _ = writeln!(
+                        out,
+                        "<blockquote class=\"reasoning\">{}</blockquote>",
+                        escape_html(text.trim())
+                    );
+                }
+                TranscriptEntry::Command {
+                    command,
+                    exit_code,
+                    output,
+                } => {
+                    let _ = write!(
+                        out,
+                        "<div class=\"command\"><p><strong>Ran</strong> <code>{}</code>",
+                        escape_html(command)
+                    );
+                    if let Some(code) = exit_code {
+                        let class = if *code == 0 { "ok" } else { "failed" };
+                        let _ = write!(out, " <span class=\"{class}\">(exit {code})</span>");
+                    }
+                    out.push_str("</p>");
+                    if !output.trim().is_empty() {
+                        let _ = write!(out, "<pre>{}</pre>", escape_html(output.trim_end()));
+                    }
+                    out.push_str("</div>\n");
+                }
+                TranscriptEntry::Patch { files } => {
+                    for file in files {
+                        let _ = write!(
+                            out,
+                            "<div class=\"patch\"><p><strong>{}</strong> <code>{}</code> <span class=\"add\">+{}</span> <span class=\"del\">-{}</span></p><pre class=\"diff\">",
+                            file_label(file),
+                            escape_html(&file_path_label(file)),
+                            file.added,
+                            file.removed
+                        );
+                        for line in file.diff.lines() {
+                            let class = match line.chars().next() {
+                                Some('+') => "add",
+                                Some('-') => "del",
+                                Some('@') => "hunk",
+                                _ => "ctx",
+                            };
+                            let _ = writeln!(
+                                out,
+                                "<span class=\"{class}\">{}</span>",
+                                escape_html(line)
+                            );
+                        }
+                        out.push_str("</pre></div>\n");
+                    }
+                }
+                TranscriptEntry::Compacted { summary } => {
+                    let _ = writeln!(
+                        out,
+                        "<section class=\"summary\"><h2>Summary of earlier conversation</h2><pre class=\"message\">{}</pre></section>",
+                        escape_html(summary.trim())
+                    );
+                }
+            }
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+const HTML_STYLE: &str = "body { font-family: -apple-system, BlinkMacSystemFont, \"Segoe UI\", sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; color: #1f2328; }
+pre { background: #f6f8fa; padding: 0.75rem; overflow-x: auto; }
+pre.message { background: none; padding: 0; white-space: pre-wrap; font-family: inherit; }
+blockquote.reasoning { color: #59636e; border-left: 3px solid #d1d9e0; margin: 0.5rem 0; padding-left: 0.75rem; white-space: pre-wrap; }
+pre.diff span { display: block; }
+.add { color: #1a7f37; }
+.del { color: #d1242f; }
+.hunk { color: #8250df; }
+.ok { color: #1a7f37; }
+.failed { color: #d1242f; }
+";
+
+fn file_label(file: &PatchedFile) -> &'static str {
+    match file.kind {
+        FileChangeKind::Added => "Added",
+        FileChangeKind::Edited => "Edited",
+        FileChangeKind::Deleted => "Deleted",
+    }
+}
+
+fn file_path_label(file: &PatchedFile) -> String {
+    match &file.move_path {
+        Some(dest) => format!("{} → {dest}", file.path),
+        None => file.path.clone(),
+    }
+}
+
+/// Append a fenced code block, lengthening the fence if the content
+/// contains backtick runs of its own.
+fn push_fenced(out: &mut String, lang: &str, content: &str) {
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    let _ = writeln!(out, "{fence}{lang}\n{content}\n{fence}");
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Shell tool output is JSON `{"output": ..., "metadata": {"exit_code": ..}}`;
+/// anything else is taken verbatim.
+fn parse_exec_output(content: &str) -> (String, Option<i32>) {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+        return (content.to_string(), None);
+    };
+    let Some(output) = value.get("output").and_then(|v| v.as_str()) else {
+        return (content.to_string(), None);
+    };
+    let exit_code = value
+        .get("metadata")
+        .and_then(|m| m.get("exit_code"))
+        .and_then(serde_json::Value::as_i64)
+        .and_then(|code| i32::try_from(code).ok());
+    (output.to_string(), exit_code)
+}
+
+fn truncate_output(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().collect();
+    if lines.len() <= OUTPUT_HEAD_LINES + OUTPUT_TAIL_LINES {
+        return output.to_string();
+    }
+    let omitted = lines.len() - OUTPUT_HEAD_LINES - OUTPUT_TAIL_LINES;
+    format!(
+        "{}\n… {omitted} lines omitted …\n{}",
+        lines[..OUTPUT_HEAD_LINES].join("\n"),
+        lines[lines.len() - OUTPUT_TAIL_LINES..].join("\n")
+    )
+}
+
+/// Split an `apply_patch` body into per-file diffs. The patch grammar's hunk
+/// lines already use unified-diff prefixes, so they are kept as-is. The base64
+/// contents of binary files are left out.
+fn parse_patch_files(patch: &str) -> Vec<PatchedFile> {
+    let mut files: Vec<PatchedFile> = Vec::new();
+    let mut in_binary_file = false;
+    for line in patch.lines() {
+        let header = [
+            ("*** Add File: ", FileChangeKind::Added),
+            ("*** Add Binary File: ", FileChangeKind::Added),
+            ("*** Update File: ", FileChangeKind::Edited),
+            ("*** Replace Binary File: ", FileChangeKind::Edited),
+            ("*** Delete File: ", FileChangeKind::Deleted),
+        ]
+        .into_iter()
+        .find_map(|(marker, kind)| {
+            line.strip_prefix(marker)
+                .map(|header| (split_file_mode(header).0, kind))
+        });
+        if let Some((path, kind)) = header {
+            in_binary_file = line.contains(" Binary File: ");
+            files.push(PatchedFile {
+                path: path.trim().to_string(),
+                move_path: None,
+                kind,
+                added: 0,
+                removed: 0,
+                diff: String::new(),
+            });
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        if let Some(dest) = line.strip_prefix("*** Move to: ") {
+            file.move_path = Some(dest.trim().to_string());
+            continue;
+        }
+        if line.starts_with("*** ") || in_binary_file {
+            // `*** End Patch`, `*** End of File`, or base64 contents.
+            continue;
+        }
+        match line.chars().next() {
+            Some('+') => file.added += 1,
+            Some('-') => file.removed += 1,
+            _ => {}
+        }
+        file.diff.push_str(line);
+        file.diff.push('\n');
+    }
+    files
+}
+
+/// Candidate `name = value` / `"name": "value"` pairs; [`is_secret_name`]
+/// decides whether the name really is a secret.
+static SECRET_ASSIGNMENT: LazyLock<Regex> = LazyLock::new(|| {
+    #[expect(clippy::expect_used)]
+    Regex::new(
+        r#"(?i)(["']?)\b([a-z0-9_.-]*(?:key|token|secret|password|passwd|credentials?)[a-z0-9_]*)(["']?)(\s*[=:]\s*)("[^"]*"|'[^']*'|[^\s"',}]+)"#,
+    )
+    .expect("valid secret assignment regex")
+});
+
+/// Words that make a name a secret, e.g. `OPENAI_API_KEY`, `apiKey`,
+/// `client-secret` or `db_password`.
+const SECRET_NAME_WORDS: &[&str] = &[
+    "key",
+    "apikey",
+    "token",
+    "secret",
+    "password",
+    "passwd",
+    "credential",
+    "credentials",
+];
+
+static SECRET_TOKENS: LazyLock<Regex> = LazyLock::new(|| {
+    #[expect(clippy::expect_used)]
+    Regex::new(
+        r"\b(?:sk-[A-Za-z0-9_-]{16,}|gh[pousr]_[A-Za-z0-9]{20,}|github_pat_[A-Za-z0-9_]{20,}|AKIA[0-9A-Z]{16}|xox[abprs]-[A-Za-z0-9-]{10,})",
+    )
+    .expect("valid secret token regex")
+});
+
+static BEARER_TOKEN: LazyLock<Regex> = LazyLock::new(|| {
+    #[expect(clippy::expect_used)]
+    Regex::new(r"(?i)\b(bearer\s+)[A-Za-z0-9._~+/-]{8,}=*").expect("valid bearer regex")
+});
+
+/// Mask values assigned to secret-looking names (`OPENAI_API_KEY=...`,
+/// `DB_PASSWORD: ...`, `"apiKey": "..."`), bearer tokens, and common API key
+/// formats.
+pub fn redact_secrets(text: &str) -> String {
+    let text = SECRET_ASSIGNMENT.replace_all(text, |caps: &regex_lite::Captures<'_>| {
+        let name = &caps[2];
+        if !is_secret_name(name) {
+            return caps[0].to_string();
+        }
+        let value = match caps[5].chars().next() {
+            Some(quote @ ('"' | '\'')) => format!("{quote}{REDACTED}{quote}"),
+            _ => REDACTED.to_string(),
+        };
+        format!("{}{name}{}{}{value}", &caps[1], &caps[3], &caps[4])
+    });
+    let text = BEARER_TOKEN.replace_all(&text, format!("${{1}}{REDACTED}").as_str());
+    SECRET_TOKENS.replace_all(&text, REDACTED).into_owned()
+}
+
+/// Whether one of the words of `name`, split at `_`, `-`, `.` and camelCase
+/// boundaries, is in [`SECRET_NAME_WORDS`].
+fn is_secret_name(name: &str) -> bool {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut previous_lowercase = false;
+    for ch in name.chars() {
+        if !ch.is_ascii_alphanumeric() || (ch.is_ascii_uppercase() && previous_lowercase) {
+            words.push(std::mem::take(&mut word));
+        }
+        if ch.is_ascii_alphanumeric() {
+            word.push(ch.to_ascii_lowercase());
+        }
+        previous_lowercase = ch.is_ascii_lowercase() || ch.is_ascii_digit();
+    }
+    words.push(word);
+    words
+        .iter()
+        .any(|word| SECRET_NAME_WORDS.contains(&word.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn rollout() -> Vec<RolloutItem> {
+        let lines = [
+            json!({
+                "type": "session_meta",
+                "payload": {
+                    "id": "00000000-0000-0000-0000-000000000001",
+                    "timestamp": "2025-01-02T00:00:00.000Z",
+                    "cwd": "/repo",
+                    "originator": "codex_cli_rs",
+                    "cli_version": "0.0.0",
+                    "instructions": null,
+                    "git": { "branch": "main" },
+                },
+            }),
+            json!({
+                "type": "turn_context",
+                "payload": {
+                    "cwd": "/repo",
+                    "approval_policy": "on-request",
+                    "sandbox_policy": { "mode": "read-only" },
+                    "model": "gpt-5",
+                    "summary": "auto",
+                },
+            }),
+            json!({
+                "type": "event_msg",
+                "payload": { "type": "user_message", "message": "Run the tests with OPENAI_API_KEY=sk-abcdefghijklmnopqrstuv" },
+            }),
+            json!({
+                "type": "event_msg",
+                "payload": { "type": "agent_reasoning", "text": "Running the suite first." },
+            }),
+            json!({
+                "type": "response_item",
+                "payload": {
+                    "type": "function_call",
+                    "name": "shell",
+                    "arguments": json!({ "command": ["cargo", "test"] }).to_string(),
+                    "call_id": "call-1",
+                },
+            }),
+            json!({
+                "type": "response_item",
+                "payload": {
+                    "type": "function_call_output",
+                    "call_id": "call-1",
+                    "output": json!({
+                        "output": "test a ... FAILED\n",
+                        "metadata": { "exit_code": 101, "duration_seconds": 1.0 },
+                    }).to_string(),
+                },
+            }),
+            json!({
+                "type": "response_item",
+                "payload": {
+                    "type": "custom_tool_call",
+                    "name": "apply_patch",
+                    "call_id": "call-2",
+                    "input": "*** Begin Patch\n*** Update File: src/lib.rs\n@@ fn a()\n-    1\n+    2\n*** Add File: NOTES.md\n+fixed\n*** End Patch",
+                },
+            }),
+            json!({
+                "type": "event_msg",
+                "payload": { "type": "agent_message", "message": "Fixed `a`." },
+            }),
+        ];
+        lines
+            .into_iter()
+            .map(|mut line| {
+                line["timestamp"] = json!("2025-01-02T00:00:00.000Z");
+                serde_json::from_value::<codex_protocol::protocol::RolloutLine>(line)
+                    .expect("rollout line")
+                    .item
+            })
+            .collect()
+    }
+
+    #[test]
+    fn renders_markdown_with_commands_and_diffs() {
+        let transcript = Transcript::from_rollout(&rollout());
+        assert_eq!(
+            transcript.render(ExportFormat::Markdown),
+            "# Codex session 00000000-0000-0000-0000-000000000001
+
+- Started: `2025-01-02T00:00:00.000Z`
+- Directory: `/repo`
+- Branch: `main`
+- Model: `gpt-5`
+
+## User
+
+Run the tests with OPENAI_API_KEY=sk-abcdefghijklmnopqrstuv
+
+> Running the suite first.
+
+**Ran** `cargo test` (exit 101)
+```text
+test a ... FAILED
+```
+
+**Edited** `src/lib.rs` (+1 -1)
+```diff
+@@ fn a()
+-    1
++    2
+```
+
+**Added** `NOTES.md` (+1 -0)
+```diff
++fixed
+```
+
+## Codex
+
+Fixed `a`.
+"
+        );
+    }
+
+    #[test]
+    fn redacts_secrets_in_every_entry() {
+        let mut transcript = Transcript::from_rollout(&rollout());
+        transcript.redact();
+        assert_eq!(
+            transcript.entries[0],
+            TranscriptEntry::User {
+                text: "Run the tests with OPENAI_API_KEY=[REDACTED]".to_string()
+            }
+        );
+        assert_eq!(
+            redact_secrets(
+                "curl -H 'Authorization: Bearer abc.def.ghi123' token ghp_abcdefghijklmnopqrstuvwxyz DB_PASSWORD: \"hunter2\" keep=me"
+            ),
+            "curl -H 'Authorization: Bearer [REDACTED]' token [REDACTED] DB_PASSWORD: \"[REDACTED]\" keep=me"
+        );
+    }
+
+    #[test]
+    fn redacts_quoted_lowercase_and_camel_case_names() {
+        assert_eq!(
+            redact_secrets(r#"{"api_key": "live-123", "apiKey": 'abc', "user": "me"}"#),
+            r#"{"api_key": "[REDACTED]", "apiKey": '[REDACTED]', "user": "me"}"#
+        );
+        assert_eq!(
+            redact_secrets("password=hunter2 clientSecret: s3cr3t x-api-key: abc123"),
+            "password=[REDACTED] clientSecret: [REDACTED] x-api-key: [REDACTED]"
+        );
+        assert_eq!(
+            redact_secrets("Github_Token = 'abc def'"),
+            "Github_Token = '[REDACTED]'"
+        );
+    }
+
+    #[test]
+    fn keeps_names_that_only_contain_secret_words() {
+        let text = "max_tokens: 100 monkey=banana keyboard=us";
+        assert_eq!(redact_secrets(text), text);
+    }
+
+    #[test]
+    fn html_escapes_and_truncates_long_output() {
+        let output = (1..=30).map(|i| format!("line {i}")).collect::<Vec<_>>();
+        let mut transcript = Transcript::default();
+        transcript.entries.push(TranscriptEntry::Command {
+            command: "echo '<b>'".to_string(),
+            exit_code: Some(0),
+            output: truncate_output(&output.join("\n")),
+        });
+        let html = transcript.render(ExportFormat::Html);
+        assert!(html.contains("<code>echo '&lt;b&gt;'</code> <span class=\"ok\">(exit 0)</span>"));
+        assert!(html.contains("line 10\n… 10 lines omitted …\nline 21"));
+        assert!(!html.contains("line 11\n"));
+    }
+}
@@ -4,6 +4,7 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use codex_file_search as file_search;
+use codex_protocol::mcp_protocol::ConversationId;
 use std::num::NonZero;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
@@ -198,6 +199,34 @@ async fn traverse_directories_for_paths(
     })
 }
 
+/// Every rollout file under the sessions directory as `(timestamp, id, path)`,
+/// newest first. Unlike [`get_conversations`] this is not capped and does not
+/// skip sessions without user messages, so it suits housekeeping.
+pub(crate) async fn all_rollout_files(
+    codex_home: &Path,
+) -> io::Result<Vec<(OffsetDateTime, Uuid, PathBuf)>> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for (_year, year_path) in collect_dirs_desc(&root, |s| s.parse::<u16>().ok()).await? {
+        for (_month, month_path) in collect_dirs_desc(&year_path, |s| s.parse::<u8>().ok()).await? {
+            for (_day, day_path) in collect_dirs_desc(&month_path, |s| s.parse::<u8>().ok()).await?
+            {
+                let mut day_files = collect_files(&day_path, |name_str, path| {
+                    parse_timestamp_uuid_from_filename(name_str)
+                        .map(|(ts, id)| (ts, id, path.to_path_buf()))
+                })
+                .await?;
+                day_files.sort_by_key(|(ts, id, _path)| (Reverse(*ts), Reverse(*id)));
+                files.extend(day_files);
+            }
+        }
+    }
+    Ok(files)
+}
+
 /// Pagination cursor token format: "<file_ts>|<uuid>" where `file_ts` matches the
 /// filename timestamp portion (YYYY-MM-DDThh-mm-ss) used in rollout filenames.
 /// The cursor orders files by timestamp desc, then UUID desc.
@@ -269,6 +298,12 @@ where
     Ok(collected)
 }
 
+/// The id of the session recorded in the rollout file at `path`.
+pub(crate) fn conversation_id_from_path(path: &Path) -> Option<ConversationId> {
+    let name = path.file_name()?.to_str()?;
+    parse_timestamp_uuid_from_filename(name).map(|(_, uuid)| ConversationId::from(uuid))
+}
+
 fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
     // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl
     let core = name.strip_prefix("rollout-")?.strip_suffix(".jsonl")?;
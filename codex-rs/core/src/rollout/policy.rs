@@ -75,10 +75,13 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::PlanUpdate(_)
         | EventMsg::ShutdownComplete
         | EventMsg::ConversationPath(_)
+        | EventMsg::CheckpointRestored(_)
+        | EventMsg::ContextPinned(_)
         | EventMsg::HookListResponse(_)
         | EventMsg::HookExecLogResponse(_)
         | EventMsg::HookValidationResult(_)
         | EventMsg::HookReloadResult(_)
-        | EventMsg::SubagentApprovalRequest(_) => false,
+        | EventMsg::SubagentApprovalRequest(_)
+        | EventMsg::CostLimitApprovalRequest(_) => false,
     }
 }
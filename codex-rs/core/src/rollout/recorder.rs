@@ -25,10 +25,13 @@ use super::list::ConversationsPage;
 use super::list::Cursor;
 use super::list::get_conversations;
 use super::policy::is_persisted_response_item;
+use super::retention::SessionRetention;
+use super::retention::enforce_retention;
 use crate::config::Config;
 use crate::default_client::ORIGINATOR;
 use crate::git_info::collect_git_info;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ForkedFrom;
 use codex_protocol::protocol::InitialHistory;
 use codex_protocol::protocol::ResumedHistory;
 use codex_protocol::protocol::RolloutItem;
@@ -69,6 +72,7 @@ pub enum RolloutRecorderParams {
     Create {
         conversation_id: ConversationId,
         instructions: Option<String>,
+        forked_from: Option<ForkedFrom>,
     },
     Resume {
         path: PathBuf,
@@ -91,6 +95,21 @@ impl RolloutRecorderParams {
         Self::Create {
             conversation_id,
             instructions,
+            forked_from: None,
+        }
+    }
+
+    /// Like [`RolloutRecorderParams::new`], but records the session it was
+    /// forked from in the session meta line.
+    pub fn fork(
+        conversation_id: ConversationId,
+        instructions: Option<String>,
+        forked_from: ForkedFrom,
+    ) -> Self {
+        Self::Create {
+            conversation_id,
+            instructions,
+            forked_from: Some(forked_from),
         }
     }
 
@@ -117,6 +136,7 @@ impl RolloutRecorder {
             RolloutRecorderParams::Create {
                 conversation_id,
                 instructions,
+                forked_from,
             } => {
                 let LogFileInfo {
                     file,
@@ -136,26 +156,43 @@ impl RolloutRecorder {
                 (
                     tokio::fs::File::from_std(file),
                     path,
-                    Some(SessionMeta {
-                        id: session_id,
-                        timestamp,
-                        cwd: config.cwd.clone(),
-                        originator: ORIGINATOR.value.clone(),
-                        cli_version: env!("CARGO_PKG_VERSION").to_string(),
-                        instructions,
+                    Some(SessionMetaLine {
+                        meta: SessionMeta {
+                            id: session_id,
+                            timestamp,
+                            cwd: config.cwd.clone(),
+                            originator: ORIGINATOR.value.clone(),
+                            cli_version: env!("CARGO_PKG_VERSION").to_string(),
+                            instructions,
+                        },
+                        git: None,
+                        forked_from,
                     }),
                 )
             }
-            RolloutRecorderParams::Resume { path } => (
-                tokio::fs::OpenOptions::new()
+            RolloutRecorderParams::Resume { path } => {
+                let file = tokio::fs::OpenOptions::new()
                     .append(true)
                     .open(&path)
-                    .await?,
-                path,
-                None,
-            ),
+                    .await?
+                    .into_std()
+                    .await;
+                lock_for_writing(&file);
+                (tokio::fs::File::from_std(file), path, None)
+            }
         };
 
+        // Starting a new session is when the `[history]` retention policy is
+        // applied; it runs in the background and never removes this session.
+        let retention = SessionRetention::from_history(&config.history);
+        if meta.is_some() && !retention.is_unbounded() {
+            tokio::task::spawn(enforce_retention(
+                config.codex_home.clone(),
+                retention,
+                Some(rollout_path.clone()),
+            ));
+        }
+
         // Clone the cwd for the spawned task to collect git info asynchronously
         let cwd = config.cwd.clone();
 
@@ -336,6 +373,7 @@ fn create_log_file(
         .append(true)
         .create(true)
         .open(&path)?;
+    lock_for_writing(&file);
 
     Ok(LogFileInfo {
         file,
@@ -345,21 +383,30 @@ fn create_log_file(
     })
 }
 
+/// Hold a lock on the rollout for as long as this process writes to it, so
+/// retention in other processes leaves the session alone (see
+/// [`super::retention::session_in_use`]). Windows locks are mandatory and
+/// would keep readers out, so the lock is only taken on Unix.
+fn lock_for_writing(file: &std::fs::File) {
+    #[cfg(unix)]
+    if let Err(e) = file.try_lock() {
+        warn!("failed to lock rollout file: {e}");
+    }
+    #[cfg(not(unix))]
+    let _ = file;
+}
+
 async fn rollout_writer(
     file: tokio::fs::File,
     mut rx: mpsc::Receiver<RolloutCmd>,
-    mut meta: Option<SessionMeta>,
+    mut meta: Option<SessionMetaLine>,
     cwd: std::path::PathBuf,
 ) -> std::io::Result<()> {
     let mut writer = JsonlWriter { file };
 
     // If we have a meta, collect git info asynchronously and write meta first
-    if let Some(session_meta) = meta.take() {
-        let git_info = collect_git_info(&cwd).await;
-        let session_meta_line = SessionMetaLine {
-            meta: session_meta,
-            git: git_info,
-        };
+    if let Some(mut session_meta_line) = meta.take() {
+        session_meta_line.git = collect_git_info(&cwd).await;
 
         // Write the SessionMeta as the first item in the file, wrapped in a rollout line
         writer
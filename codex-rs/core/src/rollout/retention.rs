@@ -0,0 +1,386 @@
+//! Housekeeping for recorded sessions: removing or archiving rollout files
+//! and enforcing the `[history]` retention policy.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+use tracing::info;
+use tracing::warn;
+
+use super::ARCHIVED_SESSIONS_SUBDIR;
+use super::SESSIONS_SUBDIR;
+use super::list::all_rollout_files;
+use super::list::conversation_id_from_path;
+use super::search::SessionIndex;
+use crate::checkpoints::remove_checkpoints;
+use crate::config_types::History;
+
+/// Sessions whose rollout was written more recently than this may still be
+/// running, so retention leaves them alone.
+const RECENT_ACTIVITY: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Which sessions to keep. A session is pruned when it falls outside the
+/// newest `max_sessions` or is older than `max_age`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionRetention {
+    pub max_sessions: Option<usize>,
+    pub max_age: Option<Duration>,
+}
+
+impl SessionRetention {
+    pub fn from_history(history: &History) -> Self {
+        Self {
+            max_sessions: history.max_sessions,
+            max_age: history.max_age,
+        }
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.max_sessions.is_none() && self.max_age.is_none()
+    }
+}
+
+/// A rollout file and the time its session started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionFile {
+    pub path: PathBuf,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Rollout files the policy would remove, newest first.
+pub async fn prunable_sessions(
+    codex_home: &Path,
+    retention: &SessionRetention,
+    now: DateTime<Utc>,
+) -> io::Result<Vec<SessionFile>> {
+    if retention.is_unbounded() {
+        return Ok(Vec::new());
+    }
+    let files = all_rollout_files(codex_home)
+        .await?
+        .into_iter()
+        .filter_map(|(ts, _id, path)| {
+            let started_at = DateTime::<Utc>::from_timestamp(ts.unix_timestamp(), 0)?;
+            Some(SessionFile { path, started_at })
+        })
+        .collect::<Vec<_>>();
+    Ok(select_for_pruning(files, retention, now))
+}
+
+/// `files` must be ordered newest first.
+fn select_for_pruning(
+    files: Vec<SessionFile>,
+    retention: &SessionRetention,
+    now: DateTime<Utc>,
+) -> Vec<SessionFile> {
+    files
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, file)| {
+            retention.max_sessions.is_some_and(|max| *idx >= max)
+                || retention
+                    .max_age
+                    .is_some_and(|max_age| now - file.started_at > max_age)
+        })
+        .map(|(_, file)| file)
+        .collect()
+}
+
+/// Delete a rollout file and the session's workspace checkpoints. Only files
+/// inside `$CODEX_HOME/sessions` are accepted.
+pub async fn remove_session(codex_home: &Path, path: &Path) -> io::Result<()> {
+    let path = session_path_in(codex_home, path).await?;
+    delete_session(codex_home, &path).await
+}
+
+/// Move a rollout file to `$CODEX_HOME/archived_sessions`, as the MCP
+/// `archiveConversation` request does. Returns the new location.
+pub async fn archive_session(codex_home: &Path, path: &Path) -> io::Result<PathBuf> {
+    let path = session_path_in(codex_home, path).await?;
+    let Some(file_name) = path.file_name() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("rollout path `{}` missing file name", path.display()),
+        ));
+    };
+    let archive_folder = codex_home.join(ARCHIVED_SESSIONS_SUBDIR);
+    tokio::fs::create_dir_all(&archive_folder).await?;
+    let destination = archive_folder.join(file_name);
+    tokio::fs::rename(&path, &destination).await?;
+    forget_in_index(codex_home, &path).await;
+    Ok(destination)
+}
+
+/// Whether the session at `path` may still be running: its rollout was
+/// written within the last hour or another process holds its writer lock.
+pub async fn session_in_use(path: &Path) -> bool {
+    let Ok(metadata) = tokio::fs::metadata(path).await else {
+        return false;
+    };
+    // A modification time in the future also counts as recent.
+    if let Ok(modified) = metadata.modified()
+        && modified.elapsed().map_or(true, |age| age < RECENT_ACTIVITY)
+    {
+        return true;
+    }
+    let Ok(file) = tokio::fs::File::open(path).await else {
+        return false;
+    };
+    let file = file.into_std().await;
+    matches!(file.try_lock(), Err(std::fs::TryLockError::WouldBlock))
+}
+
+/// Remove every session the `[history]` policy no longer keeps, except
+/// `current` (the session being started) and sessions that may still be in
+/// use. Failures are logged, not returned, so housekeeping never blocks a
+/// session.
+pub async fn enforce_retention(
+    codex_home: PathBuf,
+    retention: SessionRetention,
+    current: Option<PathBuf>,
+) -> Vec<PathBuf> {
+    let prunable = match prunable_sessions(&codex_home, &retention, Utc::now()).await {
+        Ok(prunable) => prunable,
+        Err(err) => {
+            warn!("failed to list sessions for retention: {err}");
+            return Vec::new();
+        }
+    };
+    let mut removed = Vec::new();
+    for file in prunable {
+        if current.as_deref() == Some(file.path.as_path()) || session_in_use(&file.path).await {
+            continue;
+        }
+        match delete_session(&codex_home, &file.path).await {
+            Ok(()) => removed.push(file.path),
+            Err(err) => warn!("failed to remove {}: {err}", file.path.display()),
+        }
+    }
+    if !removed.is_empty() {
+        info!(
+            "removed {} session(s) past the retention policy",
+            removed.len()
+        );
+    }
+    removed
+}
+
+/// Delete a rollout along with its checkpoints and search-index entry.
+async fn delete_session(codex_home: &Path, path: &Path) -> io::Result<()> {
+    tokio::fs::remove_file(path).await?;
+    if let Some(conversation_id) = conversation_id_from_path(path) {
+        remove_checkpoints(codex_home, conversation_id).await?;
+    }
+    forget_in_index(codex_home, path).await;
+    Ok(())
+}
+
+/// The index drops missing rollouts on its next refresh anyway, so failing to
+/// update it here is only logged.
+async fn forget_in_index(codex_home: &Path, path: &Path) {
+    let codex_home = codex_home.to_path_buf();
+    let path = path.to_path_buf();
+    let result =
+        tokio::task::spawn_blocking(move || SessionIndex::forget(&codex_home, &path)).await;
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => warn!("failed to update the session search index: {err}"),
+        Err(err) => warn!("failed to update the session search index: {err}"),
+    }
+}
+
+/// Reject paths outside the sessions directory so callers cannot be tricked
+/// into deleting or moving arbitrary files.
+async fn session_path_in(codex_home: &Path, path: &Path) -> io::Result<PathBuf> {
+    let sessions_dir = tokio::fs::canonicalize(codex_home.join(SESSIONS_SUBDIR)).await?;
+    let path = tokio::fs::canonicalize(path).await?;
+    if !path.starts_with(&sessions_dir) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "rollout path `{}` must be in sessions directory",
+                path.display()
+            ),
+        ));
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn write_session(codex_home: &Path, day: u32, id: u32) -> PathBuf {
+        let dir = codex_home
+            .join(SESSIONS_SUBDIR)
+            .join(format!("2025/01/{day:02}"));
+        std::fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join(format!(
+            "rollout-2025-01-{day:02}T12-00-00-00000000-0000-0000-0000-{id:012}.jsonl"
+        ));
+        std::fs::write(&path, "{}\n").expect("write rollout");
+        set_idle(&path);
+        path
+    }
+
+    /// Backdate the rollout so it no longer counts as recently written.
+    fn set_idle(path: &Path) {
+        let file = std::fs::File::options()
+            .write(true)
+            .open(path)
+            .expect("open rollout");
+        file.set_modified(std::time::SystemTime::now() - RECENT_ACTIVITY * 2)
+            .expect("set mtime");
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-01-10T12:00:00Z")
+            .expect("timestamp")
+            .with_timezone(&Utc)
+    }
+
+    #[tokio::test]
+    async fn prunes_beyond_max_sessions_or_max_age() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let oldest = write_session(codex_home.path(), 1, 1);
+        let older = write_session(codex_home.path(), 5, 2);
+        write_session(codex_home.path(), 8, 3);
+        write_session(codex_home.path(), 9, 4);
+
+        let paths = |files: Vec<SessionFile>| -> Vec<PathBuf> {
+            files.into_iter().map(|file| file.path).collect()
+        };
+        let keep_three = SessionRetention {
+            max_sessions: Some(3),
+            max_age: None,
+        };
+        assert_eq!(
+            paths(
+                prunable_sessions(codex_home.path(), &keep_three, now())
+                    .await
+                    .expect("prunable")
+            ),
+            vec![oldest.clone()]
+        );
+
+        let keep_week = SessionRetention {
+            max_sessions: Some(10),
+            max_age: Duration::try_days(7),
+        };
+        assert_eq!(
+            paths(
+                prunable_sessions(codex_home.path(), &keep_week, now())
+                    .await
+                    .expect("prunable")
+            ),
+            vec![oldest.clone()]
+        );
+
+        let keep_two_within_week = SessionRetention {
+            max_sessions: Some(2),
+            max_age: Duration::try_days(7),
+        };
+        assert_eq!(
+            paths(
+                prunable_sessions(codex_home.path(), &keep_two_within_week, now())
+                    .await
+                    .expect("prunable")
+            ),
+            vec![older, oldest]
+        );
+    }
+
+    #[tokio::test]
+    async fn enforce_retention_spares_current_session() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let old = write_session(codex_home.path(), 1, 1);
+        let current = write_session(codex_home.path(), 2, 2);
+
+        let removed = enforce_retention(
+            codex_home.path().to_path_buf(),
+            SessionRetention {
+                max_sessions: Some(0),
+                max_age: None,
+            },
+            Some(current.clone()),
+        )
+        .await;
+        assert_eq!(removed, vec![old.clone()]);
+        assert!(!old.exists());
+        assert!(current.exists());
+    }
+
+    #[tokio::test]
+    async fn enforce_retention_spares_sessions_in_use() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let idle = write_session(codex_home.path(), 1, 1);
+        let recent = write_session(codex_home.path(), 2, 2);
+        std::fs::write(&recent, "{}\n{}\n").expect("append");
+        let locked = write_session(codex_home.path(), 3, 3);
+        let writer = std::fs::File::open(&locked).expect("open rollout");
+        writer.lock().expect("lock rollout");
+
+        let removed = enforce_retention(
+            codex_home.path().to_path_buf(),
+            SessionRetention {
+                max_sessions: Some(0),
+                max_age: None,
+            },
+            None,
+        )
+        .await;
+        assert_eq!(removed, vec![idle]);
+        assert!(recent.exists());
+        assert!(locked.exists());
+    }
+
+    #[tokio::test]
+    async fn remove_session_deletes_its_checkpoints() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let session = write_session(codex_home.path(), 1, 7);
+        let checkpoints = codex_home
+            .path()
+            .join("checkpoints")
+            .join("00000000-0000-0000-0000-000000000007");
+        std::fs::create_dir_all(&checkpoints).expect("create dir");
+        std::fs::write(checkpoints.join("checkpoints.json"), "[]").expect("write");
+
+        remove_session(codex_home.path(), &session)
+            .await
+            .expect("remove");
+        assert!(!session.exists());
+        assert!(!checkpoints.exists());
+    }
+
+    #[tokio::test]
+    async fn archive_moves_only_files_inside_sessions_dir() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let session = write_session(codex_home.path(), 1, 1);
+
+        let archived = archive_session(codex_home.path(), &session)
+            .await
+            .expect("archive");
+        assert!(!session.exists());
+        assert_eq!(
+            archived,
+            codex_home
+                .path()
+                .join(ARCHIVED_SESSIONS_SUBDIR)
+                .join(session.file_name().expect("file name"))
+        );
+
+        let outside = codex_home.path().join("config.toml");
+        std::fs::write(&outside, "").expect("write");
+        let err = remove_session(codex_home.path(), &outside)
+            .await
+            .expect_err("outside sessions dir");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(outside.exists());
+    }
+}
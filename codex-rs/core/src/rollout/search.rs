@@ -0,0 +1,830 @@
+//! Full-text search over recorded session rollouts.
+//!
+//! Searchable text (user and agent messages, commands run and files patched)
+//! is extracted from each rollout into an index stored next to the rollouts
+//! at `$CODEX_HOME/sessions/search_index.json`. Rollouts are append-only, so
+//! the index remembers how many bytes of each file it has consumed and only
+//! reads what was appended since the last search.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::io::{self};
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use tempfile::NamedTempFile;
+
+use super::SESSIONS_SUBDIR;
+use crate::protocol::EventMsg;
+use crate::protocol::InputMessageKind;
+use codex_apply_patch::split_file_mode;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+
+const INDEX_FILE: &str = "search_index.json";
+/// Held exclusively while the index is read, refreshed and written back, so
+/// concurrent `codex sessions search` runs do not clobber each other.
+const INDEX_LOCK_FILE: &str = "search_index.lock";
+const INDEX_VERSION: u32 = 1;
+/// Longest text kept per indexed entry; pasted files are mostly noise.
+const MAX_ENTRY_CHARS: usize = 4000;
+/// Characters of context shown on each side of a match.
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+const MAX_SNIPPETS_PER_HIT: usize = 3;
+
+/// Where a piece of searchable text came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchEntryKind {
+    User,
+    Agent,
+    Command,
+    Patch,
+}
+
+impl SearchEntryKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchEntryKind::User => "user",
+            SearchEntryKind::Agent => "codex",
+            SearchEntryKind::Command => "exec",
+            SearchEntryKind::Patch => "patch",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct IndexedEntry {
+    kind: SearchEntryKind,
+    text: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct IndexedSession {
+    id: Option<String>,
+    timestamp: Option<String>,
+    cwd: Option<PathBuf>,
+    git_branch: Option<String>,
+    models: Vec<String>,
+    entries: Vec<IndexedEntry>,
+    /// Bytes of the rollout already consumed.
+    indexed_len: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexFile {
+    version: u32,
+    /// Keyed by rollout path relative to the sessions directory.
+    sessions: BTreeMap<String, IndexedSession>,
+}
+
+/// Filters and terms for [`SessionIndex::search`]. Every term must occur
+/// (case-insensitively) somewhere in the session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSearchQuery {
+    pub terms: Vec<String>,
+    /// Only sessions whose cwd is this directory or below it.
+    pub cwd: Option<PathBuf>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Substring of a model used in the session.
+    pub model: Option<String>,
+    pub git_branch: Option<String>,
+}
+
+impl SessionSearchQuery {
+    /// Parse free text where `cwd:`, `model:`, `branch:`, `since:` and
+    /// `until:` tokens set filters and everything else is a search term.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut query = SessionSearchQuery::default();
+        for token in input.split_whitespace() {
+            match token.split_once(':') {
+                Some(("cwd", value)) if !value.is_empty() => {
+                    query.cwd = Some(PathBuf::from(value));
+                }
+                Some(("model", value)) if !value.is_empty() => {
+                    query.model = Some(value.to_string());
+                }
+                Some(("branch", value)) if !value.is_empty() => {
+                    query.git_branch = Some(value.to_string());
+                }
+                Some(("since", value)) if !value.is_empty() => {
+                    query.since = Some(parse_time_bound(value, Utc::now(), false)?);
+                }
+                Some(("until", value)) if !value.is_empty() => {
+                    query.until = Some(parse_time_bound(value, Utc::now(), true)?);
+                }
+                _ => query.terms.push(token.to_string()),
+            }
+        }
+        Ok(query)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &SessionSearchQuery::default()
+    }
+}
+
+/// A matching piece of a session, trimmed around the first matching term.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchSnippet {
+    pub kind: SearchEntryKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionSearchHit {
+    /// Absolute path to the rollout file.
+    pub path: PathBuf,
+    pub id: Option<String>,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub cwd: Option<PathBuf>,
+    pub git_branch: Option<String>,
+    pub models: Vec<String>,
+    /// First message the user sent, for previews.
+    pub first_user_message: Option<String>,
+    pub snippets: Vec<SearchSnippet>,
+}
+
+/// In-memory copy of the on-disk search index.
+pub struct SessionIndex {
+    sessions_dir: PathBuf,
+    index: IndexFile,
+}
+
+impl SessionIndex {
+    /// Load the index, bring it up to date with the rollouts on disk and save
+    /// it back if anything changed. Performs blocking file I/O.
+    pub fn open(codex_home: &Path) -> io::Result<Self> {
+        // Released when `_lock` is dropped, after the index is saved.
+        let (_lock, mut this) = Self::lock_and_load(codex_home)?;
+        if this.refresh()? {
+            this.save()?;
+        }
+        Ok(this)
+    }
+
+    /// Drop the entry for the rollout at `rollout_path`, e.g. once the
+    /// session has been deleted. Performs blocking file I/O.
+    pub(crate) fn forget(codex_home: &Path, rollout_path: &Path) -> io::Result<()> {
+        let (_lock, mut this) = Self::lock_and_load(codex_home)?;
+        let canonical_dir = std::fs::canonicalize(&this.sessions_dir)?;
+        let relative = rollout_path
+            .strip_prefix(&this.sessions_dir)
+            .or_else(|_| rollout_path.strip_prefix(&canonical_dir));
+        let Ok(relative) = relative else {
+            return Ok(());
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if this.index.sessions.remove(&relative).is_some() {
+            this.save()?;
+        }
+        Ok(())
+    }
+
+    /// Take the index lock and read the index. The lock is held until the
+    /// returned file is dropped.
+    fn lock_and_load(codex_home: &Path) -> io::Result<(File, Self)> {
+        let sessions_dir = codex_home.join(SESSIONS_SUBDIR);
+        std::fs::create_dir_all(&sessions_dir)?;
+        let lock = File::create(sessions_dir.join(INDEX_LOCK_FILE))?;
+        lock.lock()?;
+        let index = std::fs::read_to_string(sessions_dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|contents| serde_json::from_str::<IndexFile>(&contents).ok())
+            .filter(|index| index.version == INDEX_VERSION)
+            .unwrap_or_else(|| IndexFile {
+                version: INDEX_VERSION,
+                sessions: BTreeMap::new(),
+            });
+        Ok((
+            lock,
+            Self {
+                sessions_dir,
+                index,
+            },
+        ))
+    }
+
+    /// Number of indexed sessions.
+    pub fn len(&self) -> usize {
+        self.index.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.sessions.is_empty()
+    }
+
+    /// Matching sessions, newest first, at most `limit` of them.
+    pub fn search(&self, query: &SessionSearchQuery, limit: usize) -> Vec<SessionSearchHit> {
+        let terms: Vec<String> = query.terms.iter().map(|t| t.to_lowercase()).collect();
+        let mut hits: Vec<SessionSearchHit> = self
+            .index
+            .sessions
+            .iter()
+            .filter_map(|(relative, session)| self.match_session(relative, session, query, &terms))
+            .collect();
+        hits.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then(b.path.cmp(&a.path)));
+        hits.truncate(limit);
+        hits
+    }
+
+    fn match_session(
+        &self,
+        relative: &str,
+        session: &IndexedSession,
+        query: &SessionSearchQuery,
+        terms: &[String],
+    ) -> Option<SessionSearchHit> {
+        let timestamp = session
+            .timestamp
+            .as_deref()
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|ts| ts.with_timezone(&Utc));
+        if let Some(cwd) = &query.cwd
+            && !session.cwd.as_ref().is_some_and(|c| c.starts_with(cwd))
+        {
+            return None;
+        }
+        if let Some(branch) = &query.git_branch
+            && session.git_branch.as_ref() != Some(branch)
+        {
+            return None;
+        }
+        if let Some(model) = &query.model {
+            let model = model.to_lowercase();
+            if !session
+                .models
+                .iter()
+                .any(|m| m.to_lowercase().contains(&model))
+            {
+                return None;
+            }
+        }
+        if query.since.is_some() || query.until.is_some() {
+            let ts = timestamp?;
+            if query.since.is_some_and(|since| ts < since)
+                || query.until.is_some_and(|until| ts >= until)
+            {
+                return None;
+            }
+        }
+
+        let lowered: Vec<String> = session
+            .entries
+            .iter()
+            .map(|entry| entry.text.to_lowercase())
+            .collect();
+        if !terms
+            .iter()
+            .all(|term| lowered.iter().any(|text| text.contains(term)))
+        {
+            return None;
+        }
+        let snippets = session
+            .entries
+            .iter()
+            .zip(&lowered)
+            .filter_map(|(entry, lower)| {
+                let (term_start, term_len) = terms
+                    .iter()
+                    .filter_map(|term| lower.find(term.as_str()).map(|idx| (idx, term.len())))
+                    .min()?;
+                Some(SearchSnippet {
+                    kind: entry.kind,
+                    text: snippet(&entry.text, lower, term_start, term_len),
+                })
+            })
+            .take(MAX_SNIPPETS_PER_HIT)
+            .collect();
+
+        Some(SessionSearchHit {
+            path: self.sessions_dir.join(relative),
+            id: session.id.clone(),
+            timestamp,
+            cwd: session.cwd.clone(),
+            git_branch: session.git_branch.clone(),
+            models: session.models.clone(),
+            first_user_message: session
+                .entries
+                .iter()
+                .find(|entry| entry.kind == SearchEntryKind::User)
+                .map(|entry| entry.text.clone()),
+            snippets,
+        })
+    }
+
+    /// Index new rollouts and appended lines, and drop deleted rollouts.
+    /// Returns whether the index changed.
+    fn refresh(&mut self) -> io::Result<bool> {
+        let mut changed = false;
+        let mut seen = HashSet::new();
+        for path in rollout_files(&self.sessions_dir)? {
+            let Ok(relative) = path.strip_prefix(&self.sessions_dir) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            let session = self.index.sessions.entry(relative.clone()).or_default();
+            changed |= index_appended_lines(&path, session)?;
+            seen.insert(relative);
+        }
+        let before = self.index.sessions.len();
+        self.index
+            .sessions
+            .retain(|relative, _| seen.contains(relative));
+        Ok(changed || before != self.index.sessions.len())
+    }
+
+    /// Write the index through a uniquely named temporary file. Callers hold
+    /// the index lock.
+    fn save(&self) -> io::Result<()> {
+        let json = serde_json::to_string(&self.index).map_err(io::Error::other)?;
+        let mut tmp = NamedTempFile::new_in(&self.sessions_dir)?;
+        tmp.write_all(json.as_bytes())?;
+        tmp.persist(self.sessions_dir.join(INDEX_FILE))
+            .map_err(|err| err.error)?;
+        Ok(())
+    }
+}
+
+/// All `rollout-*.jsonl` files under the `YYYY/MM/DD` sessions tree.
+fn rollout_files(sessions_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    fn subdirs(dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut dirs = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                dirs.push(entry.path());
+            }
+        }
+        Ok(dirs)
+    }
+
+    if !sessions_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for year in subdirs(sessions_dir)? {
+        for month in subdirs(&year)? {
+            for day in subdirs(&month)? {
+                for entry in std::fs::read_dir(&day)? {
+                    let entry = entry?;
+                    let name = entry.file_name();
+                    let name = name.to_string_lossy();
+                    if entry.file_type()?.is_file()
+                        && name.starts_with("rollout-")
+                        && name.ends_with(".jsonl")
+                    {
+                        files.push(entry.path());
+                    }
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Index the complete lines appended to `path` since the last refresh.
+fn index_appended_lines(path: &Path, session: &mut IndexedSession) -> io::Result<bool> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    if len == session.indexed_len {
+        return Ok(false);
+    }
+    if len < session.indexed_len {
+        // Rewritten rather than appended to; start over.
+        *session = IndexedSession::default();
+    }
+    file.seek(SeekFrom::Start(session.indexed_len))?;
+    let mut appended = Vec::new();
+    file.read_to_end(&mut appended)?;
+    // Leave a partially written last line for the next refresh.
+    let Some(complete) = appended.iter().rposition(|b| *b == b'\n').map(|i| i + 1) else {
+        return Ok(false);
+    };
+    for line in String::from_utf8_lossy(&appended[..complete]).lines() {
+        if let Ok(line) = serde_json::from_str::<RolloutLine>(line.trim()) {
+            index_item(session, line.item);
+        }
+    }
+    session.indexed_len += complete as u64;
+    Ok(true)
+}
+
+fn index_item(session: &mut IndexedSession, item: RolloutItem) {
+    match item {
+        RolloutItem::SessionMeta(meta_line) => {
+            session.id = Some(meta_line.meta.id.to_string());
+            session.timestamp = Some(meta_line.meta.timestamp);
+            session.cwd = Some(meta_line.meta.cwd);
+            session.git_branch = meta_line.git.and_then(|git| git.branch);
+        }
+        RolloutItem::TurnContext(turn_context) => {
+            if !session.models.contains(&turn_context.model) {
+                session.models.push(turn_context.model);
+            }
+        }
+        RolloutItem::EventMsg(EventMsg::UserMessage(event)) => {
+            if matches!(event.kind, None | Some(InputMessageKind::Plain)) {
+                push_entry(session, SearchEntryKind::User, &event.message);
+            }
+        }
+        RolloutItem::EventMsg(EventMsg::AgentMessage(event)) => {
+            push_entry(session, SearchEntryKind::Agent, &event.message);
+        }
+        RolloutItem::ResponseItem(item) => index_response_item(session, item),
+        RolloutItem::EventMsg(_) | RolloutItem::Compacted(_) => {}
+    }
+}
+
+fn index_response_item(session: &mut IndexedSession, item: ResponseItem) {
+    match item {
+        ResponseItem::LocalShellCall {
+            action: LocalShellAction::Exec(exec),
+            ..
+        } => push_entry(session, SearchEntryKind::Command, &exec.command.join(" ")),
+        ResponseItem::FunctionCall {
+            name, arguments, ..
+        } => {
+            let Ok(args) = serde_json::from_str::<serde_json::Value>(&arguments) else {
+                return;
+            };
+            if name == "apply_patch" {
+                if let Some(patch) = args.get("input").and_then(|v| v.as_str()) {
+                    push_patched_paths(session, patch);
+                }
+            } else if let Some(command) = args.get("command").and_then(|v| v.as_array()) {
+                let command: Vec<&str> = command.iter().filter_map(|v| v.as_str()).collect();
+                push_entry(session, SearchEntryKind::Command, &command.join(" "));
+            } else if let Some(cmd) = args.get("cmd").and_then(|v| v.as_str()) {
+                push_entry(session, SearchEntryKind::Command, cmd);
+            }
+        }
+        ResponseItem::CustomToolCall { name, input, .. } if name == "apply_patch" => {
+            push_patched_paths(session, &input);
+        }
+        _ => {}
+    }
+}
+
+fn push_patched_paths(session: &mut IndexedSession, patch: &str) {
+    for line in patch.lines() {
+        let path = [
+            "*** Add File: ",
+            "*** Update File: ",
+            "*** Delete File: ",
+            "*** Add Binary File: ",
+            "*** Replace Binary File: ",
+            "*** Move to: ",
+        ]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+        .map(|header| split_file_mode(header).0);
+        if let Some(path) = path {
+            push_entry(session, SearchEntryKind::Patch, path);
+        }
+    }
+}
+
+fn push_entry(session: &mut IndexedSession, kind: SearchEntryKind, text: &str) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    let text = match text.char_indices().nth(MAX_ENTRY_CHARS) {
+        Some((idx, _)) => &text[..idx],
+        None => text,
+    };
+    session.entries.push(IndexedEntry {
+        kind,
+        text: text.to_string(),
+    });
+}
+
+/// Single-line excerpt of `text` around the match at byte `start` of its
+/// lowercase form.
+fn snippet(text: &str, lower: &str, start: usize, len: usize) -> String {
+    // Lowercasing can change byte lengths; fall back to the head of the text.
+    let (start, end) = if lower.len() == text.len() {
+        (start, start + len)
+    } else {
+        (0, 0)
+    };
+    let before: String = {
+        let mut chars: Vec<char> = text[..start]
+            .chars()
+            .rev()
+            .take(SNIPPET_CONTEXT_CHARS)
+            .collect();
+        chars.reverse();
+        chars.into_iter().collect()
+    };
+    let after: String = text[end..]
+        .chars()
+        .take(SNIPPET_CONTEXT_CHARS + SNIPPET_CONTEXT_CHARS.saturating_sub(before.len()))
+        .collect();
+    let mut out = String::new();
+    if before.len() < text[..start].len() {
+        out.push('…');
+    }
+    out.push_str(&before);
+    out.push_str(&text[start..end]);
+    out.push_str(&after);
+    if end + after.len() < text.len() {
+        out.push('…');
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parse a duration such as `30d`, `12h`, `2w` or `45m`.
+pub fn parse_age(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let unit = value.chars().last()?;
+    let amount: i64 = value[..value.len() - unit.len_utf8()].parse().ok()?;
+    match unit {
+        'm' => Duration::try_minutes(amount),
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
+/// Parse `YYYY-MM-DD` or an age like `7d` (meaning that long before `now`).
+/// With `end_of_day`, a date means the end of that day.
+pub fn parse_time_bound(
+    value: &str,
+    now: DateTime<Utc>,
+    end_of_day: bool,
+) -> Result<DateTime<Utc>, String> {
+    if let Some(age) = parse_age(value) {
+        return Ok(now - age);
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("invalid date `{value}`; expected YYYY-MM-DD or an age like 7d"))?;
+    let date = if end_of_day {
+        date.succ_opt().unwrap_or(date)
+    } else {
+        date
+    };
+    Ok(date.and_time(chrono::NaiveTime::MIN).and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn write_rollout(codex_home: &Path, name: &str, lines: &[serde_json::Value]) -> PathBuf {
+        let dir = codex_home.join(SESSIONS_SUBDIR).join("2025/01/02");
+        std::fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join(name);
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .expect("open rollout");
+        for line in lines {
+            writeln!(file, "{line}").expect("write line");
+        }
+        path
+    }
+
+    fn meta(id: &str, timestamp: &str, cwd: &str, branch: &str) -> serde_json::Value {
+        json!({
+            "timestamp": timestamp,
+            "type": "session_meta",
+            "payload": {
+                "id": id,
+                "timestamp": timestamp,
+                "cwd": cwd,
+                "originator": "codex_cli_rs",
+                "cli_version": "0.0.0",
+                "instructions": null,
+                "git": { "branch": branch },
+            },
+        })
+    }
+
+    fn event(kind: &str, message: &str) -> serde_json::Value {
+        json!({
+            "timestamp": "2025-01-02T00:00:00.000Z",
+            "type": "event_msg",
+            "payload": { "type": kind, "message": message },
+        })
+    }
+
+    fn shell_call(command: &[&str]) -> serde_json::Value {
+        json!({
+            "timestamp": "2025-01-02T00:00:00.000Z",
+            "type": "response_item",
+            "payload": {
+                "type": "function_call",
+                "name": "shell",
+                "arguments": json!({ "command": command }).to_string(),
+                "call_id": "call-1",
+            },
+        })
+    }
+
+    fn turn_context(model: &str) -> serde_json::Value {
+        json!({
+            "timestamp": "2025-01-02T00:00:00.000Z",
+            "type": "turn_context",
+            "payload": {
+                "cwd": "/repo",
+                "approval_policy": "on-request",
+                "sandbox_policy": { "mode": "read-only" },
+                "model": model,
+                "summary": "auto",
+            },
+        })
+    }
+
+    const FIRST_ID: &str = "00000000-0000-0000-0000-000000000001";
+    const SECOND_ID: &str = "00000000-0000-0000-0000-000000000002";
+
+    fn seed(codex_home: &Path) -> PathBuf {
+        write_rollout(
+            codex_home,
+            &format!("rollout-2025-01-01T00-00-00-{FIRST_ID}.jsonl"),
+            &[
+                meta(FIRST_ID, "2025-01-01T00:00:00.000Z", "/repo/api", "main"),
+                turn_context("gpt-5"),
+                event("user_message", "Fix the flaky DB migration test"),
+                shell_call(&["cargo", "test", "-p", "migrations"]),
+                event(
+                    "agent_message",
+                    "The migration test now waits for the lock.",
+                ),
+            ],
+        );
+        write_rollout(
+            codex_home,
+            &format!("rollout-2025-01-02T00-00-00-{SECOND_ID}.jsonl"),
+            &[
+                meta(SECOND_ID, "2025-01-02T00:00:00.000Z", "/other", "feature"),
+                turn_context("o3"),
+                event("user_message", "Write docs for the CLI"),
+            ],
+        )
+    }
+
+    #[test]
+    fn search_matches_all_terms_and_filters() {
+        let codex_home = TempDir::new().expect("tempdir");
+        seed(codex_home.path());
+        let index = SessionIndex::open(codex_home.path()).expect("open index");
+        assert_eq!(index.len(), 2);
+
+        let hits = index.search(&SessionSearchQuery::parse("migration cargo").unwrap(), 10);
+        assert_eq!(hits.len(), 1);
+        let hit = &hits[0];
+        assert_eq!(hit.id.as_deref(), Some(FIRST_ID));
+        assert_eq!(hit.git_branch.as_deref(), Some("main"));
+        assert_eq!(hit.models, vec!["gpt-5".to_string()]);
+        assert_eq!(
+            hit.first_user_message.as_deref(),
+            Some("Fix the flaky DB migration test")
+        );
+        assert_eq!(
+            hit.snippets
+                .iter()
+                .map(|s| (s.kind, s.text.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (SearchEntryKind::User, "Fix the flaky DB migration test"),
+                (SearchEntryKind::Command, "cargo test -p migrations"),
+                (
+                    SearchEntryKind::Agent,
+                    "The migration test now waits for the lock."
+                ),
+            ]
+        );
+
+        let ids = |query: &str| -> Vec<Option<String>> {
+            index
+                .search(&SessionSearchQuery::parse(query).unwrap(), 10)
+                .into_iter()
+                .map(|hit| hit.id)
+                .collect()
+        };
+        assert_eq!(
+            ids(""),
+            vec![Some(SECOND_ID.to_string()), Some(FIRST_ID.to_string())]
+        );
+        assert_eq!(ids("cwd:/repo"), vec![Some(FIRST_ID.to_string())]);
+        assert_eq!(ids("model:O3"), vec![Some(SECOND_ID.to_string())]);
+        assert_eq!(ids("branch:main docs"), Vec::<Option<String>>::new());
+        assert_eq!(ids("since:2025-01-02"), vec![Some(SECOND_ID.to_string())]);
+        assert_eq!(ids("until:2025-01-01"), vec![Some(FIRST_ID.to_string())]);
+    }
+
+    #[test]
+    fn refresh_indexes_appended_lines_and_drops_deleted_rollouts() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let second = seed(codex_home.path());
+        SessionIndex::open(codex_home.path()).expect("open index");
+
+        write_rollout(
+            codex_home.path(),
+            second.file_name().and_then(|n| n.to_str()).expect("name"),
+            &[event("user_message", "also cover the sandbox flags")],
+        );
+        std::fs::remove_file(codex_home.path().join(SESSIONS_SUBDIR).join(format!(
+            "2025/01/02/rollout-2025-01-01T00-00-00-{FIRST_ID}.jsonl"
+        )))
+        .expect("remove");
+
+        let index = SessionIndex::open(codex_home.path()).expect("reopen index");
+        assert_eq!(index.len(), 1);
+        let hits = index.search(&SessionSearchQuery::parse("sandbox").unwrap(), 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id.as_deref(), Some(SECOND_ID));
+        assert_eq!(
+            hits[0].first_user_message.as_deref(),
+            Some("Write docs for the CLI")
+        );
+    }
+
+    #[test]
+    fn concurrent_opens_leave_a_readable_index() {
+        let codex_home = TempDir::new().expect("tempdir");
+        seed(codex_home.path());
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let codex_home = codex_home.path().to_path_buf();
+                std::thread::spawn(move || SessionIndex::open(&codex_home).map(|index| index.len()))
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().expect("thread").expect("open index"), 2);
+        }
+
+        let sessions_dir = codex_home.path().join(SESSIONS_SUBDIR);
+        let contents = std::fs::read_to_string(sessions_dir.join(INDEX_FILE)).expect("index");
+        assert!(serde_json::from_str::<IndexFile>(&contents).is_ok());
+        let stray: Vec<_> = std::fs::read_dir(&sessions_dir)
+            .expect("read dir")
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| name.starts_with(".tmp"))
+            .collect();
+        assert_eq!(stray, Vec::<String>::new());
+    }
+
+    #[test]
+    fn forget_drops_only_the_given_session() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let second = seed(codex_home.path());
+        SessionIndex::open(codex_home.path()).expect("open index");
+
+        SessionIndex::forget(codex_home.path(), &second).expect("forget");
+
+        let sessions_dir = codex_home.path().join(SESSIONS_SUBDIR);
+        let contents = std::fs::read_to_string(sessions_dir.join(INDEX_FILE)).expect("index");
+        let index: IndexFile = serde_json::from_str(&contents).expect("parse index");
+        let ids: Vec<_> = index
+            .sessions
+            .values()
+            .filter_map(|session| session.id.as_deref())
+            .collect();
+        assert_eq!(ids, vec![FIRST_ID]);
+    }
+
+    #[test]
+    fn parses_ages_and_dates() {
+        let now = DateTime::parse_from_rfc3339("2025-01-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            parse_time_bound("7d", now, false).unwrap().to_rfc3339(),
+            "2025-01-03T12:00:00+00:00"
+        );
+        assert_eq!(
+            parse_time_bound("2025-01-05", now, true)
+                .unwrap()
+                .to_rfc3339(),
+            "2025-01-06T00:00:00+00:00"
+        );
+        assert!(parse_time_bound("yesterday", now, false).is_err());
+        assert_eq!(parse_age("2w"), Duration::try_weeks(2));
+    }
+}
@@ -99,11 +99,9 @@ impl SubagentDefinition {
                     normalized.push('-');
                     seen_separator = true;
                 }
-            } else {
-                if !normalized.is_empty() && !seen_separator {
-                    normalized.push('-');
-                    seen_separator = true;
-                }
+            } else if !normalized.is_empty() && !seen_separator {
+                normalized.push('-');
+                seen_separator = true;
             }
         }
 
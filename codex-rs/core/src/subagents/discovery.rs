@@ -64,7 +64,7 @@ pub fn discover_from_source(source: DiscoverySource) -> DiscoveryOutcome {
             Ok(e) => e,
             Err(err) => {
                 outcome.events.push(DiscoveryEvent {
-                    message: format!("Failed to traverse directory: {}", err),
+                    message: format!("Failed to traverse directory: {err}"),
                 });
                 continue;
             }
@@ -104,7 +104,7 @@ pub fn discover_from_source(source: DiscoverySource) -> DiscoveryOutcome {
     outcome
 }
 
-pub fn detect_scope(path: &PathBuf) -> SubagentScope {
+pub fn detect_scope(path: &Path) -> SubagentScope {
     if let Some(home) = dirs::home_dir() {
         let user_root = home.join(".codex/agents");
         if path.starts_with(&user_root) {
@@ -9,14 +9,12 @@ use uuid::Uuid;
 use crate::AuthManager;
 use crate::codex::{Codex, CodexSpawnOk};
 use crate::config::Config;
-use crate::model_family::find_family_for_model;
-use crate::openai_model_info::get_model_info;
 use crate::subagents::config::SubagentConfig;
 use crate::subagents::definition::SubagentScope;
 use crate::subagents::invocation::InvocationSession;
 use crate::subagents::record::SubagentRecord;
 use crate::subagents::runner::{PreparedSubagentInvocation, SubagentInvocationError};
-use codex_protocol::protocol::{Event, EventMsg, InitialHistory, InputItem, Op};
+use codex_protocol::protocol::{Event, EventMsg, InitialHistory, InputItem, Op, ReviewDecision};
 
 #[derive(Serialize)]
 struct TranscriptPayload {
@@ -43,6 +41,7 @@ fn storage_dir(base_dir: &Path, record: &SubagentRecord) -> PathBuf {
         .join("sessions")
 }
 
+#[allow(clippy::result_large_err)]
 fn persist_transcript(
     dir: &Path,
     payload: &TranscriptPayload,
@@ -98,6 +97,29 @@ fn artifact_uri(name: &str) -> PathBuf {
     PathBuf::from(format!("agents://{name}/sessions/latest"))
 }
 
+/// A subagent has no user to ask, so anything its approval policy would
+/// prompt for is declined and reported back to it as denied.
+async fn decline_approval_request(codex: &Codex, event: &Event) {
+    let op = match &event.msg {
+        EventMsg::ExecApprovalRequest(_) => Op::ExecApproval {
+            id: event.id.clone(),
+            decision: ReviewDecision::Denied,
+        },
+        EventMsg::ApplyPatchApprovalRequest(_) => Op::PatchApproval {
+            id: event.id.clone(),
+            decision: ReviewDecision::Denied,
+        },
+        EventMsg::CostLimitApprovalRequest(request) => Op::CostLimitApproval {
+            id: request.id.clone(),
+            decision: ReviewDecision::Denied,
+        },
+        _ => return,
+    };
+    if let Err(err) = codex.submit(op).await {
+        tracing::warn!("failed to decline subagent approval request: {err}");
+    }
+}
+
 fn capture_agent_output(
     msg: &EventMsg,
     message_buffer: &mut Option<String>,
@@ -134,6 +156,8 @@ async fn spawn_subagent_codex(
     auth_manager: Arc<AuthManager>,
 ) -> Result<CodexSpawnOk, SubagentInvocationError> {
     config.subagents = SubagentConfig::disabled();
+    // The output schema applies to the parent's final answer only.
+    config.output_schema = None;
     Codex::spawn(config, auth_manager, InitialHistory::New)
         .await
         .map_err(|err| SubagentInvocationError::ExecutionFailed(err.to_string()))
@@ -164,11 +188,9 @@ pub async fn execute_subagent_invocation(
     let mut config = base_config.clone();
     if let Some(model) = resolved_model.clone() {
         config.model = model.clone();
-        if let Some(family) = find_family_for_model(&model) {
-            config.model_family = family.clone();
-            if let Some(info) = get_model_info(&family) {
-                config.model_context_window = Some(info.context_window);
-            }
+        config.model_family = base_config.model_family_or_default(&model);
+        if let Some(info) = base_config.model_info_for(&config.model_family) {
+            config.model_context_window = Some(info.context_window);
         }
     }
 
@@ -226,6 +248,7 @@ pub async fn execute_subagent_invocation(
         } else {
             None
         };
+        decline_approval_request(&codex, &event).await;
         let should_break = capture_agent_output(&event.msg, &mut message_buffer, &mut last_message);
         transcript.push(event);
         if let Some(message) = error_message {
@@ -267,12 +290,15 @@ pub async fn execute_subagent_invocation(
             prepared.session.subagent_name
         ))
     });
-    prepared.session.detail_artifacts = vec![artifact_uri(&prepared.session.subagent_name)];
+    prepared.session.detail_artifacts = vec![
+        artifact_uri(&prepared.session.subagent_name),
+        artifact_path.clone(),
+    ];
     prepared.session.resolved_model = resolved_model;
     prepared
         .session
         .execution_log
-        .push(format!("instructions: {}", instruction_preview));
+        .push(format!("instructions: {instruction_preview}"));
     prepared
         .session
         .execution_log
@@ -356,6 +382,7 @@ mod tests {
         let should_break = capture_agent_output(
             &EventMsg::TaskComplete(TaskCompleteEvent {
                 last_agent_message: None,
+                budget_exhausted: None,
             }),
             &mut buffer,
             &mut last,
@@ -372,6 +399,7 @@ mod tests {
         let should_break = capture_agent_output(
             &EventMsg::TaskComplete(TaskCompleteEvent {
                 last_agent_message: Some("final".into()),
+                budget_exhausted: None,
             }),
             &mut buffer,
             &mut last,
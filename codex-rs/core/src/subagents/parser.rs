@@ -120,8 +120,7 @@ fn trim_bom(input: &str) -> &str {
 }
 
 fn extract_frontmatter(contents: &str) -> Result<(String, &str), SubagentParserError> {
-    let trimmed =
-        contents.trim_start_matches(|ch: char| ch == '\n' || ch == '\r' || ch == ' ' || ch == '\t');
+    let trimmed = contents.trim_start_matches(['\n', '\r', ' ', '\t']);
     if !trimmed.starts_with("---") {
         return Err(SubagentParserError::ParseError(
             "subagent definitions must start with `---` frontmatter".to_string(),
@@ -5,6 +5,7 @@ use crate::subagents::invocation::InvocationSession;
 use crate::subagents::record::SubagentRecord;
 use crate::subagents::record::SubagentStatus;
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, thiserror::Error)]
 pub enum SubagentInvocationError {
     #[error("subagents feature disabled")]
@@ -46,6 +47,7 @@ impl<'a> SubagentRunner<'a> {
         Self { config, inventory }
     }
 
+    #[allow(clippy::result_large_err)]
     pub fn invoke(
         &self,
         mut session: InvocationSession,
@@ -73,12 +75,12 @@ impl<'a> SubagentRunner<'a> {
         match record.status {
             SubagentStatus::Invalid => {
                 return Err(SubagentInvocationError::InvalidSubagent(
-                    record.definition.name.clone(),
+                    record.definition.name,
                 ));
             }
             SubagentStatus::Disabled => {
                 return Err(SubagentInvocationError::DisabledSubagent(
-                    record.definition.name.clone(),
+                    record.definition.name,
                 ));
             }
             SubagentStatus::Active => {}
@@ -87,7 +89,7 @@ impl<'a> SubagentRunner<'a> {
         if matches!(self.config.discovery, SubagentDiscoveryMode::Auto) && !session.confirmed {
             return Err(SubagentInvocationError::ConfirmationRequired {
                 subagent: record.definition.name.clone(),
-                record: record.clone(),
+                record,
                 session,
             });
         }
@@ -95,7 +97,7 @@ impl<'a> SubagentRunner<'a> {
         for tool in &session.requested_tools {
             if !record.allows_tool(tool) {
                 return Err(SubagentInvocationError::ToolNotAllowed {
-                    subagent: record.definition.name.clone(),
+                    subagent: record.definition.name,
                     tool: tool.clone(),
                 });
             }
@@ -64,6 +64,9 @@ Each operation starts with one of three headers:
 *** Update File: <path> - patch an existing file in place (optionally with a rename).
 
 May be immediately followed by *** Move to: <new path> if you want to rename the file.
+
+The Add File and Update File headers may end with a mode, e.g. `*** Add File: run.sh (mode 755)`, to set the file's permissions; an Update File hunk with a mode needs no @@ hunks if only the mode changes.
+To add or replace a binary file, use `*** Add Binary File: <path>` or `*** Replace Binary File: <path>` followed by the file's contents in base64, on one or more + lines.
 Then one or more “hunks”, each introduced by @@ (optionally followed by a hunk header).
 Within a hunk each line starts with:
 
@@ -89,10 +92,12 @@ The full grammar definition is below:
 Patch := Begin { FileOp } End
 Begin := "*** Begin Patch" NEWLINE
 End := "*** End Patch" NEWLINE
-FileOp := AddFile | DeleteFile | UpdateFile
-AddFile := "*** Add File: " path NEWLINE { "+" line NEWLINE }
+FileOp := AddFile | DeleteFile | UpdateFile | BinaryFile
+AddFile := "*** Add File: " path [ Mode ] NEWLINE { "+" line NEWLINE }
 DeleteFile := "*** Delete File: " path NEWLINE
-UpdateFile := "*** Update File: " path NEWLINE [ MoveTo ] { Hunk }
+UpdateFile := "*** Update File: " path [ Mode ] NEWLINE [ MoveTo ] { Hunk }
+BinaryFile := ( "*** Add Binary File: " | "*** Replace Binary File: " ) path [ Mode ] NEWLINE { "+" base64 NEWLINE }
+Mode := " (mode " octalDigits ")"
 MoveTo := "*** Move to: " newPath NEWLINE
 Hunk := "@@" [ header ] NEWLINE { HunkLine } [ "*** End of File" NEWLINE ]
 HunkLine := (" " | "-" | "+") text NEWLINE
@@ -0,0 +1,300 @@
+use std::sync::Arc;
+
+use codex_core::ContentItem;
+use codex_core::ModelClient;
+use codex_core::ModelProviderInfo;
+use codex_core::Prompt;
+use codex_core::ResponseEvent;
+use codex_core::ResponseItem;
+use codex_core::WireApi;
+use codex_core::model_family::find_family_for_model;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use codex_protocol::config_types::ReasoningEffort;
+use codex_protocol::mcp_protocol::ConversationId;
+use codex_protocol::models::ReasoningItemReasoningSummary;
+use core_test_support::load_default_config_for_test;
+use futures::StreamExt;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::header;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+const MODEL: &str = "claude-sonnet-4-20250514";
+
+fn network_disabled() -> bool {
+    std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok()
+}
+
+/// Streams one turn with `input` against a mock Messages API that replies
+/// with `sse_body`, returning the events and the JSON request body.
+async fn run_stream(input: Vec<ResponseItem>, sse_body: &str) -> (Vec<ResponseEvent>, Value) {
+    let server = MockServer::start().await;
+
+    let template = ResponseTemplate::new(200)
+        .insert_header("content-type", "text/event-stream")
+        .set_body_raw(sse_body.to_string(), "text/event-stream");
+
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(header("anthropic-version", "2023-06-01"))
+        .respond_with(template)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = ModelProviderInfo {
+        name: "mock".into(),
+        base_url: Some(format!("{}/v1", server.uri())),
+        env_key: None,
+        env_key_instructions: None,
+        wire_api: WireApi::Anthropic,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(5_000),
+        requires_openai_auth: false,
+    };
+
+    let codex_home = match TempDir::new() {
+        Ok(dir) => dir,
+        Err(e) => panic!("failed to create TempDir: {e}"),
+    };
+    let mut config = load_default_config_for_test(&codex_home);
+    config.model = MODEL.to_string();
+    let Some(model_family) = find_family_for_model(MODEL) else {
+        panic!("no model family for {MODEL}");
+    };
+    config.model_family = model_family;
+    config.model_max_output_tokens = Some(64_000);
+    config.model_provider_id = provider.name.clone();
+    config.model_provider = provider.clone();
+    let summary = config.model_reasoning_summary;
+    let config = Arc::new(config);
+
+    let client = ModelClient::new(
+        Arc::clone(&config),
+        None,
+        provider,
+        Some(ReasoningEffort::Low),
+        summary,
+        ConversationId::new(),
+    );
+
+    let mut prompt = Prompt::default();
+    prompt.input = input;
+
+    let mut stream = match client.stream(&prompt).await {
+        Ok(s) => s,
+        Err(e) => panic!("stream messages failed: {e}"),
+    };
+    let mut events = Vec::new();
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(ev) => events.push(ev),
+            Err(e) => panic!("stream event error: {e}"),
+        }
+    }
+
+    let requests = match server.received_requests().await {
+        Some(reqs) => reqs,
+        None => panic!("request not made"),
+    };
+    let body = match requests[0].body_json() {
+        Ok(v) => v,
+        Err(e) => panic!("invalid json body: {e}"),
+    };
+    (events, body)
+}
+
+fn sse(events: &[Value]) -> String {
+    events
+        .iter()
+        .map(|event| {
+            format!(
+                "event: {}\ndata: {event}\n\n",
+                event["type"].as_str().unwrap_or("")
+            )
+        })
+        .collect()
+}
+
+fn user_message(text: &str) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: text.to_string(),
+        }],
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn streams_thinking_text_and_tool_use_as_response_events() {
+    if network_disabled() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let body = sse(&[
+        json!({"type": "message_start", "message": {"id": "msg_1", "usage": {
+            "input_tokens": 10, "cache_creation_input_tokens": 0,
+            "cache_read_input_tokens": 90, "output_tokens": 1,
+        }}}),
+        json!({"type": "content_block_start", "index": 0, "content_block": {"type": "thinking", "thinking": ""}}),
+        json!({"type": "content_block_delta", "index": 0, "delta": {"type": "thinking_delta", "thinking": "List the files."}}),
+        json!({"type": "content_block_delta", "index": 0, "delta": {"type": "signature_delta", "signature": "sig"}}),
+        json!({"type": "content_block_stop", "index": 0}),
+        json!({"type": "content_block_start", "index": 1, "content_block": {"type": "text", "text": ""}}),
+        json!({"type": "ping"}),
+        json!({"type": "content_block_delta", "index": 1, "delta": {"type": "text_delta", "text": "Listing"}}),
+        json!({"type": "content_block_stop", "index": 1}),
+        json!({"type": "content_block_start", "index": 2, "content_block": {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {}}}),
+        json!({"type": "content_block_delta", "index": 2, "delta": {"type": "input_json_delta", "partial_json": "{\"command\":"}}),
+        json!({"type": "content_block_delta", "index": 2, "delta": {"type": "input_json_delta", "partial_json": "[\"ls\"]}"}}),
+        json!({"type": "content_block_stop", "index": 2}),
+        json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"}, "usage": {"output_tokens": 25}}),
+        json!({"type": "message_stop"}),
+    ]);
+
+    let (events, _) = run_stream(vec![user_message("hello")], &body).await;
+    assert_eq!(events.len(), 7, "unexpected events: {events:?}");
+
+    assert!(matches!(events[0], ResponseEvent::Created));
+    match &events[1] {
+        ResponseEvent::ReasoningSummaryDelta(text) => assert_eq!(text, "List the files."),
+        other => panic!("expected thinking delta, got {other:?}"),
+    }
+    match &events[2] {
+        ResponseEvent::OutputItemDone(ResponseItem::Reasoning {
+            summary,
+            encrypted_content,
+            ..
+        }) => {
+            assert_eq!(
+                summary,
+                &vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: "List the files.".to_string()
+                }]
+            );
+            assert_eq!(encrypted_content.as_deref(), Some("anthropic:sig"));
+        }
+        other => panic!("expected reasoning item, got {other:?}"),
+    }
+    match &events[3] {
+        ResponseEvent::OutputTextDelta(text) => assert_eq!(text, "Listing"),
+        other => panic!("expected text delta, got {other:?}"),
+    }
+    match &events[4] {
+        ResponseEvent::OutputItemDone(ResponseItem::Message { role, content, .. }) => {
+            assert_eq!(role, "assistant");
+            assert_eq!(
+                content,
+                &vec![ContentItem::OutputText {
+                    text: "Listing".to_string()
+                }]
+            );
+        }
+        other => panic!("expected message item, got {other:?}"),
+    }
+    match &events[5] {
+        ResponseEvent::OutputItemDone(ResponseItem::FunctionCall {
+            name,
+            arguments,
+            call_id,
+            ..
+        }) => {
+            assert_eq!(name, "shell");
+            assert_eq!(arguments, "{\"command\":[\"ls\"]}");
+            assert_eq!(call_id, "toolu_1");
+        }
+        other => panic!("expected function call, got {other:?}"),
+    }
+    match &events[6] {
+        ResponseEvent::Completed {
+            response_id,
+            token_usage: Some(usage),
+        } => {
+            assert_eq!(response_id, "msg_1");
+            assert_eq!(usage.input_tokens, 100);
+            assert_eq!(usage.cached_input_tokens, 90);
+            assert_eq!(usage.output_tokens, 25);
+            assert_eq!(usage.total_tokens, 125);
+        }
+        other => panic!("expected completed with usage, got {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn request_replays_history_with_thinking_and_cache_breakpoints() {
+    if network_disabled() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let body = sse(&[
+        json!({"type": "message_start", "message": {"id": "msg_2", "usage": {"input_tokens": 1, "output_tokens": 1}}}),
+        json!({"type": "message_stop"}),
+    ]);
+    let input = vec![
+        user_message("list files"),
+        ResponseItem::Reasoning {
+            id: String::new(),
+            summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                text: "List the files.".to_string(),
+            }],
+            content: None,
+            encrypted_content: Some("anthropic:sig".to_string()),
+        },
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: "{\"command\":[\"ls\"]}".to_string(),
+            call_id: "toolu_1".to_string(),
+        },
+        ResponseItem::FunctionCallOutput {
+            call_id: "toolu_1".to_string(),
+            output: codex_protocol::models::FunctionCallOutputPayload {
+                content: "README.md".to_string(),
+                success: Some(true),
+            },
+        },
+    ];
+
+    let (_, request) = run_stream(input, &body).await;
+
+    assert_eq!(request["model"], MODEL);
+    assert_eq!(request["max_tokens"], 64_000);
+    assert_eq!(request["stream"], true);
+    assert_eq!(
+        request["thinking"],
+        json!({"type": "enabled", "budget_tokens": 4_096})
+    );
+    assert_eq!(request["system"][0]["cache_control"]["type"], "ephemeral");
+    assert_eq!(
+        request["messages"],
+        json!([
+            {"role": "user", "content": [{"type": "text", "text": "list files"}]},
+            {"role": "assistant", "content": [
+                {"type": "thinking", "thinking": "List the files.", "signature": "sig"},
+                {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {"command": ["ls"]}},
+            ]},
+            {"role": "user", "content": [{
+                "type": "tool_result",
+                "tool_use_id": "toolu_1",
+                "content": "README.md",
+                "cache_control": {"type": "ephemeral"},
+            }]},
+        ])
+    );
+}
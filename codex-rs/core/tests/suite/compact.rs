@@ -89,7 +89,7 @@ pub(super) fn ev_assistant_message(id: &str, text: &str) -> Value {
     })
 }
 
-fn ev_function_call(call_id: &str, name: &str, arguments: &str) -> Value {
+pub(super) fn ev_function_call(call_id: &str, name: &str, arguments: &str) -> Value {
     serde_json::json!({
         "type": "response.output_item.done",
         "item": {
@@ -218,7 +218,10 @@ async fn summarize_context_three_requests_and_instructions() {
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
     // 2) Summarize – second hit with summarization instructions.
-    codex.submit(Op::Compact).await.unwrap();
+    codex
+        .submit(Op::Compact { instructions: None })
+        .await
+        .unwrap();
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
     // 3) Next user input – third hit; history should include only the summary.
@@ -366,6 +369,184 @@ async fn summarize_context_three_requests_and_instructions() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn compact_with_instructions_uses_compact_model_and_records_them() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    const FOCUS: &str = "focus on the DB migration work";
+
+    let server = start_mock_server().await;
+    let first_matcher = |req: &wiremock::Request| {
+        let body = std::str::from_utf8(&req.body).unwrap_or("");
+        body.contains("\"text\":\"hello world\"")
+            && !body.contains(&format!("\"text\":\"{SUMMARIZE_TRIGGER}\""))
+    };
+    mount_sse_once(
+        &server,
+        first_matcher,
+        sse(vec![
+            ev_assistant_message("m1", FIRST_REPLY),
+            ev_completed("r1"),
+        ]),
+    )
+    .await;
+    let second_matcher = |req: &wiremock::Request| {
+        let body = std::str::from_utf8(&req.body).unwrap_or("");
+        body.contains(&format!("\"text\":\"{SUMMARIZE_TRIGGER}\""))
+    };
+    mount_sse_once(
+        &server,
+        second_matcher,
+        sse(vec![
+            ev_assistant_message("m2", SUMMARY_TEXT),
+            ev_completed("r2"),
+        ]),
+    )
+    .await;
+
+    let model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = model_provider;
+    config.compact_model = Some("gpt-4.1-mini".to_string());
+    let session_model = config.model.clone();
+    let conversation_manager = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"));
+    let NewConversation {
+        conversation: codex,
+        session_configured,
+        ..
+    } = conversation_manager.new_conversation(config).await.unwrap();
+    let rollout_path = session_configured.rollout_path;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "hello world".into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex
+        .submit(Op::Compact {
+            instructions: Some(format!("  {FOCUS}\n")),
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2, "expected exactly two requests");
+    let body1 = requests[0].body_json::<serde_json::Value>().unwrap();
+    let body2 = requests[1].body_json::<serde_json::Value>().unwrap();
+    assert_eq!(body1["model"].as_str(), Some(session_model.as_str()));
+    assert_eq!(body2["model"].as_str(), Some("gpt-4.1-mini"));
+    let instructions = body2["instructions"].as_str().unwrap();
+    assert!(
+        instructions.contains("You have exceeded the maximum number of tokens"),
+        "summarization prompt missing: {instructions}"
+    );
+    assert!(
+        instructions.ends_with(&format!("{FOCUS}\n")),
+        "user guidance missing: {instructions}"
+    );
+
+    codex.submit(Op::Shutdown).await.unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::ShutdownComplete)).await;
+
+    let text = std::fs::read_to_string(&rollout_path).unwrap();
+    let compacted: Vec<_> = text
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RolloutLine>(line).ok())
+        .filter_map(|entry| match entry.item {
+            RolloutItem::Compacted(item) => Some(item),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(compacted.len(), 1, "expected one Compacted entry");
+    assert_eq!(compacted[0].message, SUMMARY_TEXT);
+    assert_eq!(compacted[0].model.as_deref(), Some("gpt-4.1-mini"));
+    assert_eq!(compacted[0].instructions.as_deref(), Some(FOCUS));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn compact_with_instructions_is_rejected_while_a_task_runs() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = start_mock_server().await;
+    // Keep the first turn running while the compact is submitted.
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(
+            sse_response(sse(vec![
+                ev_assistant_message("m1", FIRST_REPLY),
+                ev_completed("r1"),
+            ]))
+            .set_delay(std::time::Duration::from_millis(500)),
+        )
+        .mount(&server)
+        .await;
+
+    let model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = model_provider;
+    let conversation_manager = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"));
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "hello world".into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskStarted(_))).await;
+    codex
+        .submit(Op::Compact {
+            instructions: Some("keep the file names".to_string()),
+        })
+        .await
+        .unwrap();
+
+    let error = wait_for_event(&codex, |ev| matches!(ev, EventMsg::Error(_))).await;
+    let EventMsg::Error(ErrorEvent { message }) = error else {
+        unreachable!();
+    };
+    assert_eq!(
+        message,
+        "Cannot compact with instructions while a task is running."
+    );
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1, "the compact must not reach the model");
+    let body = requests[0].body_json::<serde_json::Value>().unwrap();
+    assert!(!body.to_string().contains(SUMMARIZE_TRIGGER));
+}
+
 // Windows CI only: bump to 4 workers to prevent SSE/event starvation and test timeouts.
 #[cfg_attr(windows, tokio::test(flavor = "multi_thread", worker_threads = 4))]
 #[cfg_attr(not(windows), tokio::test(flavor = "multi_thread", worker_threads = 2))]
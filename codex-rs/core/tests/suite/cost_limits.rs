@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use codex_core::CodexAuth;
+use codex_core::ConversationManager;
+use codex_core::ModelProviderInfo;
+use codex_core::WireApi;
+use codex_core::cost::CostLimits;
+use codex_core::cost::ModelPricing;
+use codex_core::protocol::BudgetExhausted;
+use codex_core::protocol::CostLimit;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use core_test_support::load_default_config_for_test;
+use core_test_support::load_sse_fixture_with_id_from_str;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+/// A completed response that used a million uncached input tokens.
+const COMPLETED_WITH_USAGE: &str = r#"[
+  {
+    "type": "response.completed",
+    "response": {
+      "id": "__ID__",
+      "usage": {
+        "input_tokens": 1000000,
+        "input_tokens_details": null,
+        "output_tokens": 0,
+        "output_tokens_details": null,
+        "total_tokens": 1000000
+      },
+      "output": []
+    }
+  }
+]"#;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn session_cost_limit_asks_after_each_request() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            load_sse_fixture_with_id_from_str(COMPLETED_WITH_USAGE, "resp_1"),
+            "text/event-stream",
+        ))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider_id = "mock".to_string();
+    config.model_provider = ModelProviderInfo {
+        name: "mock".into(),
+        base_url: Some(format!("{}/v1", server.uri())),
+        env_key: Some("PATH".into()),
+        env_key_instructions: None,
+        wire_api: WireApi::Responses,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(2_000),
+        requires_openai_auth: false,
+    };
+    config.pricing = HashMap::from([(
+        "mock".to_string(),
+        HashMap::from([(
+            config.model.clone(),
+            ModelPricing {
+                input: 2.0,
+                cached_input: None,
+                output: 8.0,
+            },
+        )]),
+    )]);
+    config.cost_limits = CostLimits {
+        max_session_usd: Some(1.0),
+        max_daily_usd: None,
+    };
+
+    let conversation_manager =
+        ConversationManager::with_auth(CodexAuth::from_api_key("Test API Key"));
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    for (text, decision, spent_usd) in [
+        ("first", ReviewDecision::Approved, 2.0),
+        ("second", ReviewDecision::Denied, 4.0),
+    ] {
+        codex
+            .submit(Op::UserInput {
+                items: vec![InputItem::Text { text: text.into() }],
+            })
+            .await
+            .unwrap();
+        let EventMsg::TokenCount(token_count) =
+            wait_for_event(&codex, |ev| matches!(ev, EventMsg::TokenCount(_))).await
+        else {
+            unreachable!()
+        };
+        let cost = token_count.info.and_then(|info| info.cost);
+        assert_eq!(cost.map(|cost| cost.session_usd), Some(spent_usd));
+
+        // Every request costs $2, so each one reaches the limit: the first
+        // one the $1 limit, the second one the $3 it was raised to.
+        let EventMsg::CostLimitApprovalRequest(request) = wait_for_event(&codex, |ev| {
+            matches!(ev, EventMsg::CostLimitApprovalRequest(_))
+        })
+        .await
+        else {
+            unreachable!()
+        };
+        assert_eq!(request.limit, CostLimit::Session);
+        assert_eq!(request.limit_usd, 1.0);
+        assert_eq!(request.spent_usd, spent_usd);
+
+        codex
+            .submit(Op::CostLimitApproval {
+                id: request.id,
+                decision,
+            })
+            .await
+            .unwrap();
+        let EventMsg::TaskComplete(complete) =
+            wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await
+        else {
+            unreachable!()
+        };
+        let expected = (decision == ReviewDecision::Denied).then_some(BudgetExhausted::MaxCost);
+        assert_eq!(complete.budget_exhausted, expected);
+    }
+}
@@ -4,11 +4,15 @@ mod cli_stream;
 mod client;
 mod compact;
 mod compact_resume_fork;
+mod cost_limits;
 mod exec;
 mod exec_stream_events;
 mod fork_conversation;
 mod live_cli;
+mod model_fallback;
 mod model_overrides;
+mod model_recording;
+mod output_schema;
 mod plan_mode;
 mod prompt_caching;
 mod review;
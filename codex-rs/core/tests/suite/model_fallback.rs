@@ -0,0 +1,388 @@
+use super::compact::ev_assistant_message;
+use super::compact::ev_completed;
+use super::compact::sse;
+use super::compact::sse_response;
+use codex_core::CodexAuth;
+use codex_core::ConversationManager;
+use codex_core::ModelProviderInfo;
+use codex_core::WireApi;
+use codex_core::model_fallback::FallbackModel;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use core_test_support::load_default_config_for_test;
+use core_test_support::load_sse_fixture_with_id;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+fn sse_completed(id: &str) -> String {
+    load_sse_fixture_with_id("tests/fixtures/completed_template.json", id)
+}
+
+fn mock_provider(server: &MockServer, prefix: &str) -> ModelProviderInfo {
+    ModelProviderInfo {
+        name: format!("mock-{prefix}"),
+        base_url: Some(format!("{}/{prefix}/v1", server.uri())),
+        env_key: Some("PATH".into()),
+        env_key_instructions: None,
+        wire_api: WireApi::Responses,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(2_000),
+        requires_openai_auth: false,
+    }
+}
+
+/// A Messages API stream body; every event carries its data.
+fn anthropic_sse(events: &[Value]) -> String {
+    events
+        .iter()
+        .map(|event| {
+            format!(
+                "event: {}\ndata: {event}\n\n",
+                event["type"].as_str().unwrap_or_default()
+            )
+        })
+        .collect()
+}
+
+fn request_body(request: &wiremock::Request) -> Value {
+    match serde_json::from_slice(&request.body) {
+        Ok(body) => body,
+        Err(err) => panic!("request body is not JSON: {err}"),
+    }
+}
+
+fn request_models(requests: &[wiremock::Request]) -> Vec<(String, String)> {
+    requests
+        .iter()
+        .map(|request| {
+            let body = request_body(request);
+            (
+                request.url.path().to_string(),
+                body["model"].as_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn rate_limited_turn_fails_over_to_next_model() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/primary/v1/responses"))
+        .respond_with(ResponseTemplate::new(429))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/backup/v1/responses"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(sse_completed("resp_backup"), "text/event-stream"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider_id = "primary".to_string();
+    config.model_provider = mock_provider(&server, "primary");
+    config.fallback_models = vec![FallbackModel {
+        provider_id: "backup".to_string(),
+        provider: mock_provider(&server, "backup"),
+        model: "gpt-4.1".to_string(),
+    }];
+    let primary_model = config.model.clone();
+
+    let conversation_manager =
+        ConversationManager::with_auth(CodexAuth::from_api_key("Test API Key"));
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await
+        .unwrap();
+
+    let EventMsg::BackgroundEvent(event) =
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::BackgroundEvent(_))).await
+    else {
+        unreachable!()
+    };
+    assert!(
+        event
+            .message
+            .starts_with(&format!("primary:{primary_model} failed (rate limited: ")),
+        "{}",
+        event.message
+    );
+    assert!(
+        event.message.ends_with("; switching to backup:gpt-4.1"),
+        "{}",
+        event.message
+    );
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = server.received_requests().await.unwrap_or_default();
+    assert_eq!(
+        request_models(&requests),
+        vec![
+            ("/primary/v1/responses".to_string(), primary_model),
+            ("/backup/v1/responses".to_string(), "gpt-4.1".to_string()),
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn context_overflow_compacts_and_retries() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/primary/v1/responses"))
+        .respond_with(
+            ResponseTemplate::new(400).set_body_string(
+                serde_json::json!({
+                    "error": {
+                        "code": "context_length_exceeded",
+                        "message": "Your input exceeds the context window of this model."
+                    }
+                })
+                .to_string(),
+            ),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/primary/v1/responses"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(sse_completed("resp_ok"), "text/event-stream"),
+        )
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = mock_provider(&server, "primary");
+
+    let conversation_manager =
+        ConversationManager::with_auth(CodexAuth::from_api_key("Test API Key"));
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await
+        .unwrap();
+
+    let EventMsg::BackgroundEvent(event) =
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::BackgroundEvent(_))).await
+    else {
+        unreachable!()
+    };
+    assert!(
+        event
+            .message
+            .ends_with("; compacting the conversation and retrying."),
+        "{}",
+        event.message
+    );
+    // The compaction runs as its own task before the turn is retried.
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    let last = wait_for_event(&codex, |ev| {
+        matches!(ev, EventMsg::TaskComplete(_) | EventMsg::Error(_))
+    })
+    .await;
+    assert!(matches!(last, EventMsg::TaskComplete(_)), "{last:?}");
+
+    // The failed turn, the summarization request, then the retried turn.
+    let requests = server.received_requests().await.unwrap_or_default();
+    assert_eq!(requests.len(), 3);
+}
+
+/// Reasoning signatures are only valid for the wire API that issued them, so
+/// after failing over between wire APIs neither is sent the other's.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn failover_between_wire_apis_drops_foreign_reasoning() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    let responses_reasoning = json!({
+        "type": "response.output_item.done",
+        "item": {
+            "type": "reasoning",
+            "id": "rs_1",
+            "summary": [{"type": "summary_text", "text": "Think in OpenAI."}],
+            "encrypted_content": "gAAAA-openai",
+        }
+    });
+    // Turn 1 succeeds on the primary, turn 2 fails over, turn 3 starts again
+    // on the primary.
+    Mock::given(method("POST"))
+        .and(path("/primary/v1/responses"))
+        .respond_with(sse_response(sse(vec![
+            responses_reasoning,
+            ev_assistant_message("m1", "first"),
+            ev_completed("r1"),
+        ])))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/primary/v1/responses"))
+        .respond_with(ResponseTemplate::new(429))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/primary/v1/responses"))
+        .respond_with(sse_response(sse(vec![
+            ev_assistant_message("m3", "third"),
+            ev_completed("r3"),
+        ])))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/backup/v1/messages"))
+        .respond_with(sse_response(anthropic_sse(&[
+            json!({"type": "message_start", "message": {"id": "msg_1", "usage": {"input_tokens": 1, "output_tokens": 1}}}),
+            json!({"type": "content_block_start", "index": 0, "content_block": {"type": "thinking", "thinking": ""}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "thinking_delta", "thinking": "Think in Claude."}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "signature_delta", "signature": "sig-claude"}}),
+            json!({"type": "content_block_stop", "index": 0}),
+            json!({"type": "content_block_start", "index": 1, "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "content_block_delta", "index": 1, "delta": {"type": "text_delta", "text": "second"}}),
+            json!({"type": "content_block_stop", "index": 1}),
+            json!({"type": "message_delta", "delta": {"stop_reason": "end_turn"}, "usage": {"output_tokens": 1}}),
+            json!({"type": "message_stop"}),
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider_id = "primary".to_string();
+    config.model_provider = mock_provider(&server, "primary");
+    config.fallback_models = vec![FallbackModel {
+        provider_id: "backup".to_string(),
+        provider: ModelProviderInfo {
+            wire_api: WireApi::Anthropic,
+            ..mock_provider(&server, "backup")
+        },
+        model: "claude-sonnet-4-20250514".to_string(),
+    }];
+
+    let conversation_manager =
+        ConversationManager::with_auth(CodexAuth::from_api_key("Test API Key"));
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    for text in ["one", "two", "three"] {
+        codex
+            .submit(Op::UserInput {
+                items: vec![InputItem::Text { text: text.into() }],
+            })
+            .await
+            .unwrap();
+        let last = wait_for_event(&codex, |ev| {
+            matches!(ev, EventMsg::TaskComplete(_) | EventMsg::Error(_))
+        })
+        .await;
+        assert!(matches!(last, EventMsg::TaskComplete(_)), "{last:?}");
+    }
+
+    let requests = server.received_requests().await.unwrap_or_default();
+    let paths: Vec<&str> = requests.iter().map(|request| request.url.path()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "/primary/v1/responses",
+            "/primary/v1/responses",
+            "/backup/v1/messages",
+            "/primary/v1/responses",
+        ]
+    );
+
+    // The Messages API sees the OpenAI reasoning neither as thinking nor as
+    // redacted thinking.
+    let messages = request_body(&requests[2]);
+    let blocks: Vec<&Value> = messages["messages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|message| message["content"].as_array().into_iter().flatten())
+        .collect();
+    assert!(
+        blocks
+            .iter()
+            .all(|block| block["type"] != "thinking" && block["type"] != "redacted_thinking"),
+        "{messages}"
+    );
+
+    // Back on the Responses API, only its own reasoning is replayed.
+    let responses = request_body(&requests[3]);
+    let reasoning: Vec<&Value> = responses["input"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|item| item["type"] == "reasoning")
+        .collect();
+    assert_eq!(
+        reasoning
+            .iter()
+            .map(|item| item["encrypted_content"].as_str().unwrap_or_default())
+            .collect::<Vec<_>>(),
+        vec!["gAAAA-openai"]
+    );
+}
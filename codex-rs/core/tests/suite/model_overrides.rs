@@ -1,12 +1,24 @@
+use super::compact::ev_assistant_message;
+use super::compact::ev_completed;
+use super::compact::sse;
+use super::compact::sse_response;
 use codex_core::CodexAuth;
 use codex_core::ConversationManager;
+use codex_core::ModelProviderInfo;
+use codex_core::built_in_model_providers;
 use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
 use codex_core::protocol_config_types::ReasoningEffort;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
 use core_test_support::load_default_config_for_test;
 use core_test_support::wait_for_event;
 use pretty_assertions::assert_eq;
 use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
 
 const CONFIG_TOML: &str = "config.toml";
 
@@ -90,3 +102,69 @@ async fn override_turn_context_does_not_create_config_file() {
         "override should not create config.toml"
     );
 }
+
+/// A model the provider lists but Codex does not know gets a family of its
+/// own rather than the capabilities of the session's model.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn override_to_unknown_model_derives_its_family() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(sse_response(sse(vec![
+            ev_assistant_message("m1", "hi"),
+            ev_completed("r1"),
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let codex_home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&codex_home);
+    config.model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    // The session model supports reasoning summaries; the unknown one may not.
+    assert!(config.model_family.supports_reasoning_summaries);
+
+    let conversation_manager =
+        ConversationManager::with_auth(CodexAuth::from_api_key("Test API Key"));
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .expect("create conversation")
+        .conversation;
+
+    codex
+        .submit(Op::OverrideTurnContext {
+            cwd: None,
+            approval_policy: None,
+            sandbox_policy: None,
+            model: Some("acme-chat-1".to_string()),
+            effort: None,
+            summary: None,
+        })
+        .await
+        .expect("submit override");
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await
+        .expect("submit input");
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = server.received_requests().await.unwrap_or_default();
+    let body = requests[0].body_json::<serde_json::Value>().unwrap();
+    assert_eq!(body["model"], "acme-chat-1");
+    assert_eq!(body["reasoning"], serde_json::Value::Null);
+}
@@ -0,0 +1,220 @@
+//! Record a session's model traffic against a mock server, then replay it
+//! with no server at all.
+
+use super::compact::ev_assistant_message;
+use super::compact::ev_completed;
+use super::compact::ev_function_call;
+use super::compact::sse;
+use super::compact::sse_response;
+use codex_core::CodexAuth;
+use codex_core::ConversationManager;
+use codex_core::ModelProviderInfo;
+use codex_core::built_in_model_providers;
+use codex_core::config::Config;
+use codex_core::model_recording::ModelStreamRecording;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use core_test_support::load_default_config_for_test;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+/// Send each of `inputs` as its own user turn and collect the last agent
+/// message of every turn.
+#[expect(clippy::expect_used)]
+async fn run_turns(config: Config, inputs: &[&str]) -> Vec<Option<String>> {
+    let conversation_manager =
+        ConversationManager::with_auth(CodexAuth::from_api_key("Test API Key"));
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .expect("create conversation")
+        .conversation;
+    let mut answers = Vec::new();
+    for text in inputs {
+        codex
+            .submit(Op::UserInput {
+                items: vec![InputItem::Text {
+                    text: (*text).into(),
+                }],
+            })
+            .await
+            .expect("submit");
+        let mut last_message = None;
+        loop {
+            match wait_for_event(&codex, |_| true).await {
+                EventMsg::AgentMessage(ev) => last_message = Some(ev.message),
+                EventMsg::TaskComplete(_) => break,
+                EventMsg::Error(ev) => panic!("turn failed: {}", ev.message),
+                _ => {}
+            }
+        }
+        answers.push(last_message);
+    }
+    answers
+}
+
+/// Serve `bodies` in order, one per request.
+async fn mount_sse_sequence(server: &MockServer, bodies: Vec<String>) {
+    for body in bodies {
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(sse_response(body))
+            .up_to_n_times(1)
+            .mount(server)
+            .await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn replays_recorded_session_without_network() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    mount_sse_sequence(
+        &server,
+        vec![sse(vec![
+            ev_assistant_message("m1", "recorded answer"),
+            ev_completed("r1"),
+        ])],
+    )
+    .await;
+
+    let codex_home = TempDir::new().expect("tempdir");
+    let tape = TempDir::new().expect("tempdir");
+    let mut config = load_default_config_for_test(&codex_home);
+    config.model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+
+    let mut record_config = config.clone();
+    record_config.model_stream_recording =
+        Some(ModelStreamRecording::record(tape.path().to_path_buf()));
+    assert_eq!(
+        run_turns(record_config, &["hello"]).await,
+        vec![Some("recorded answer".to_string())]
+    );
+    assert!(tape.path().join("turn-0001.request.json").exists());
+    assert!(tape.path().join("turn-0001.sse").exists());
+    assert_eq!(
+        server.received_requests().await.unwrap_or_default().len(),
+        1
+    );
+
+    // Nothing listens on the replay provider's port.
+    drop(server);
+    let mut replay_config = config;
+    replay_config.model_stream_recording =
+        Some(ModelStreamRecording::replay(tape.path().to_path_buf()));
+    assert_eq!(
+        run_turns(replay_config, &["hello"]).await,
+        vec![Some("recorded answer".to_string())]
+    );
+}
+
+/// Tool output carries timings and the working directory, which differ
+/// between the recording and the replay; every turn must still replay the
+/// response recorded for it.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn replays_multi_turn_session_with_tool_calls() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let shell_call = |call_id: &str| {
+        ev_function_call(
+            call_id,
+            "shell",
+            &serde_json::json!({ "command": ["sh", "-c", "pwd && sleep 0.1"] }).to_string(),
+        )
+    };
+    let session = || {
+        vec![
+            sse(vec![shell_call("call-1"), ev_completed("r1")]),
+            sse(vec![
+                ev_assistant_message("m2", "first answer"),
+                ev_completed("r2"),
+            ]),
+            sse(vec![shell_call("call-2"), ev_completed("r3")]),
+            sse(vec![
+                ev_assistant_message("m4", "second answer"),
+                ev_completed("r4"),
+            ]),
+        ]
+    };
+    let server = MockServer::start().await;
+    mount_sse_sequence(&server, [session(), session()].concat()).await;
+
+    let codex_home = TempDir::new().expect("tempdir");
+    let mut config = load_default_config_for_test(&codex_home);
+    config.model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    config.approval_policy = AskForApproval::Never;
+    config.sandbox_policy = SandboxPolicy::DangerFullAccess;
+    let inputs = ["where am I?", "and now?"];
+    let expected = vec![
+        Some("first answer".to_string()),
+        Some("second answer".to_string()),
+    ];
+
+    // Record the same session twice, from different working directories.
+    let tapes = [
+        TempDir::new().expect("tempdir"),
+        TempDir::new().expect("tempdir"),
+    ];
+    let cwds = [
+        TempDir::new().expect("tempdir"),
+        TempDir::new().expect("tempdir"),
+    ];
+    for (tape, cwd) in tapes.iter().zip(&cwds) {
+        let mut record_config = config.clone();
+        record_config.cwd = cwd.path().to_path_buf();
+        record_config.model_stream_recording =
+            Some(ModelStreamRecording::record(tape.path().to_path_buf()));
+        assert_eq!(run_turns(record_config, &inputs).await, expected);
+    }
+    assert_eq!(
+        server.received_requests().await.unwrap_or_default().len(),
+        8
+    );
+    let recorded_hash = |tape: &TempDir, turn: usize| -> String {
+        let path = tape.path().join(format!("turn-{turn:04}.request.json"));
+        let contents = std::fs::read_to_string(path).expect("read request");
+        let request: serde_json::Value = serde_json::from_str(&contents).expect("parse request");
+        request["hash"].as_str().expect("hash").to_string()
+    };
+    for turn in 1..=4 {
+        assert_eq!(
+            recorded_hash(&tapes[0], turn),
+            recorded_hash(&tapes[1], turn),
+            "turn {turn}"
+        );
+    }
+
+    // Nothing listens on the replay provider's port.
+    drop(server);
+    let mut replay_config = config;
+    replay_config.cwd = cwds[1].path().to_path_buf();
+    replay_config.model_stream_recording =
+        Some(ModelStreamRecording::replay(tapes[0].path().to_path_buf()));
+    assert_eq!(run_turns(replay_config, &inputs).await, expected);
+}
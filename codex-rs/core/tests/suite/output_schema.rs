@@ -0,0 +1,120 @@
+use codex_core::CodexAuth;
+use codex_core::ConversationManager;
+use codex_core::ModelProviderInfo;
+use codex_core::WireApi;
+use codex_core::built_in_model_providers;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::TaskCompleteEvent;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use core_test_support::load_default_config_for_test;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::Request;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+const FINAL_OUTPUT_REQUEST: &str = "Submit your final answer now by calling the final_output tool.";
+
+fn chat_sse(chunks: &[serde_json::Value]) -> ResponseTemplate {
+    let mut body: String = chunks
+        .iter()
+        .map(|chunk| format!("data: {chunk}\n\n"))
+        .collect();
+    body.push_str("data: [DONE]\n\n");
+    ResponseTemplate::new(200)
+        .insert_header("content-type", "text/event-stream")
+        .set_body_raw(body, "text/event-stream")
+}
+
+fn asks_for_final_output(request: &Request) -> bool {
+    String::from_utf8_lossy(&request.body).contains(FINAL_OUTPUT_REQUEST)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn plain_text_reply_is_followed_by_a_forced_final_output_call() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(|request: &Request| !asks_for_final_output(request))
+        .respond_with(chat_sse(&[
+            json!({"choices": [{"delta": {"content": "The answer is 42."}}]}),
+            json!({"choices": [{"delta": {}, "finish_reason": "stop"}]}),
+        ]))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(asks_for_final_output)
+        .respond_with(chat_sse(&[
+            json!({"choices": [{"delta": {"tool_calls": [{
+                "id": "call-1",
+                "type": "function",
+                "function": {"name": "final_output", "arguments": "{\"answer\":42}"}
+            }]}}]}),
+            json!({"choices": [{"delta": {}, "finish_reason": "tool_calls"}]}),
+        ]))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        wire_api: WireApi::Chat,
+        ..built_in_model_providers()["openai"].clone()
+    };
+    config.output_schema = Some(json!({
+        "type": "object",
+        "properties": {"answer": {"type": "integer"}},
+        "required": ["answer"],
+    }));
+    let conversation_manager = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"));
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "what is the answer?".into(),
+            }],
+        })
+        .await
+        .unwrap();
+    let complete = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    let EventMsg::TaskComplete(TaskCompleteEvent {
+        last_agent_message, ..
+    }) = complete
+    else {
+        unreachable!();
+    };
+    assert_eq!(last_agent_message.as_deref(), Some("{\"answer\":42}"));
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    let first = requests[0].body_json::<serde_json::Value>().unwrap();
+    assert_eq!(first.get("tool_choice"), None);
+    let second = requests[1].body_json::<serde_json::Value>().unwrap();
+    assert_eq!(
+        second["tool_choice"],
+        json!({"type": "function", "function": {"name": "final_output"}})
+    );
+}
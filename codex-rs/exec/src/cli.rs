@@ -1,7 +1,11 @@
 use clap::Parser;
 use clap::ValueEnum;
 use codex_common::CliConfigOverrides;
+use codex_core::review::ReviewTarget;
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::review_format::ReviewFormat;
 
 #[derive(Parser, Debug)]
 #[command(version)]
@@ -63,10 +67,42 @@ pub struct Cli {
     #[arg(long = "json", default_value_t = false)]
     pub json: bool,
 
+    /// Record every model request and its streamed response to this
+    /// directory so the session can be replayed with `--replay`.
+    #[arg(long = "record", value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Serve model responses recorded with `--record` from this directory
+    /// instead of calling the model provider.
+    #[arg(long = "replay", value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+
     /// Specifies file where the last message from the agent should be written.
     #[arg(long = "output-last-message")]
     pub last_message_file: Option<PathBuf>,
 
+    /// Stop after this many model turns.
+    #[arg(long = "max-turns", value_name = "N")]
+    pub max_turns: Option<u64>,
+
+    /// Stop once the session has used this many tokens (input plus output).
+    #[arg(long = "max-tokens", value_name = "N")]
+    pub max_tokens: Option<u64>,
+
+    /// Stop after this much wall-clock time, e.g. `90`, `90s`, `15m` or `1h`.
+    #[arg(long = "timeout", value_name = "DURATION", value_parser = parse_timeout)]
+    pub timeout: Option<Duration>,
+
+    /// Stop after the model has made this many tool calls.
+    #[arg(long = "max-tool-calls", value_name = "N")]
+    pub max_tool_calls: Option<u64>,
+
+    /// JSON Schema file the agent's final message must conform to. The
+    /// model is asked once to fix a non-conforming answer; if it still does
+    /// not match, codex-exec exits with a non-zero status.
+    #[arg(long = "output-schema", value_name = "FILE")]
+    pub output_schema: Option<PathBuf>,
+
     /// Initial instructions for the agent. If not provided as an argument (or
     /// if `-` is used), instructions are read from stdin.
     #[arg(value_name = "PROMPT")]
@@ -77,6 +113,9 @@ pub struct Cli {
 pub enum Command {
     /// Resume a previous session by id or pick the most recent with --last.
     Resume(ResumeArgs),
+
+    /// Review the current changes, a branch, a commit, or the staged changes.
+    Review(ExecReviewArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -95,6 +134,45 @@ pub struct ResumeArgs {
     pub prompt: Option<String>,
 }
 
+#[derive(Parser, Debug)]
+pub struct ExecReviewArgs {
+    #[clap(flatten)]
+    pub target: ReviewArgs,
+
+    /// Print only the review result, rendered in this format.
+    #[arg(long = "review-format", value_enum)]
+    pub review_format: Option<ReviewFormat>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ReviewArgs {
+    /// Review the working tree against its merge base with this branch.
+    #[arg(long = "base", value_name = "BRANCH", conflicts_with_all = ["commit", "staged"])]
+    pub base: Option<String>,
+
+    /// Review the changes introduced by a single commit.
+    #[arg(long = "commit", value_name = "SHA", conflicts_with = "staged")]
+    pub commit: Option<String>,
+
+    /// Review only the changes staged in the index.
+    #[arg(long = "staged", default_value_t = false)]
+    pub staged: bool,
+}
+
+impl ReviewArgs {
+    pub fn target(&self) -> ReviewTarget {
+        if let Some(base) = &self.base {
+            ReviewTarget::Base(base.clone())
+        } else if let Some(commit) = &self.commit {
+            ReviewTarget::Commit(commit.clone())
+        } else if self.staged {
+            ReviewTarget::Staged
+        } else {
+            ReviewTarget::Remote
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
 #[value(rename_all = "kebab-case")]
 pub enum Color {
@@ -103,3 +181,20 @@ pub enum Color {
     #[default]
     Auto,
 }
+
+/// Parse a duration in seconds, optionally suffixed with `s`, `m` or `h`.
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid duration `{value}`; expected e.g. 90, 90s, 15m or 1h");
+    let value = value.trim();
+    let (amount, scale) = match value.chars().last() {
+        Some('s') => (&value[..value.len() - 1], 1),
+        Some('m') => (&value[..value.len() - 1], 60),
+        Some('h') => (&value[..value.len() - 1], 60 * 60),
+        _ => (value, 1),
+    };
+    let amount: u64 = amount.parse().map_err(|_| invalid())?;
+    amount
+        .checked_mul(scale)
+        .map(Duration::from_secs)
+        .ok_or_else(invalid)
+}
@@ -8,11 +8,14 @@ use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::CheckpointRestoredEvent;
+use codex_core::protocol::ContextPinnedEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::McpToolCallBeginEvent;
@@ -26,6 +29,7 @@ use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnDiffEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_protocol::num_format::format_usd;
 use codex_protocol::num_format::format_with_separators;
 use owo_colors::OwoColorize;
 use owo_colors::Style;
@@ -38,6 +42,7 @@ use std::time::Instant;
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
 use crate::event_processor::handle_last_message;
+use crate::review_format::format_review_text;
 use codex_common::create_config_summary_entries;
 
 /// This should be configurable. When used in CI, users may not want to impose
@@ -66,6 +71,7 @@ pub(crate) struct EventProcessorWithHumanOutput {
     reasoning_started: bool,
     raw_reasoning_started: bool,
     last_message_path: Option<PathBuf>,
+    cwd: PathBuf,
 }
 
 impl EventProcessorWithHumanOutput {
@@ -94,6 +100,7 @@ impl EventProcessorWithHumanOutput {
                 reasoning_started: false,
                 raw_reasoning_started: false,
                 last_message_path,
+                cwd: config.cwd.clone(),
             }
         } else {
             Self {
@@ -112,6 +119,7 @@ impl EventProcessorWithHumanOutput {
                 reasoning_started: false,
                 raw_reasoning_started: false,
                 last_message_path,
+                cwd: config.cwd.clone(),
             }
         }
     }
@@ -190,8 +198,20 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     self,
                     "{}",
                     format!(
-                        "Auto-denying subagent '{}' – interactive approval is not supported in CLI mode.",
-                        name
+                        "Auto-denying subagent '{name}' – interactive approval is not supported in CLI mode."
+                    )
+                    .style(self.dimmed)
+                );
+            }
+            EventMsg::CostLimitApprovalRequest(request) => {
+                ts_println!(
+                    self,
+                    "{}",
+                    format!(
+                        "{} cost limit of {} reached ({} spent) – stopping, interactive approval is not supported in CLI mode.",
+                        request.limit,
+                        format_usd(request.limit_usd),
+                        format_usd(request.spent_usd)
                     )
                     .style(self.dimmed)
                 );
@@ -202,7 +222,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::HookReloadResult(_) => {
                 // Hook inspection events are no-ops in the non-interactive CLI output.
             }
-            EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+            EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message, ..
+            }) => {
                 if let Some(output_file) = self.last_message_path.as_deref() {
                     handle_last_message(last_agent_message.as_deref(), output_file);
                 }
@@ -215,6 +237,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                         "tokens used: {}",
                         format_with_separators(usage_info.total_token_usage.blended_total())
                     );
+                    if let Some(cost) = usage_info.cost {
+                        ts_println!(self, "cost: {}", format_usd(cost.session_usd));
+                    }
                 }
             }
             EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta }) => {
@@ -418,24 +443,34 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 // it's easy to scan in the terminal output.
                 for (path, change) in changes.iter() {
                     match change {
-                        FileChange::Add { content } => {
+                        FileChange::Add { content, .. } => {
                             let header = format!(
-                                "{} {}",
+                                "{} {}{}",
                                 format_file_change(change),
-                                path.to_string_lossy()
+                                path.to_string_lossy(),
+                                format_file_mode(change)
                             );
                             println!("{}", header.style(self.magenta));
                             for line in content.lines() {
                                 println!("{}", line.style(self.green));
                             }
                         }
-                        FileChange::Delete { content } => {
+                        FileChange::Delete {
+                            content,
+                            binary_size,
+                        } => {
                             let header = format!(
                                 "{} {}",
                                 format_file_change(change),
                                 path.to_string_lossy()
                             );
                             println!("{}", header.style(self.magenta));
+                            if let Some(size) = binary_size {
+                                println!(
+                                    "{}",
+                                    format!("binary file, {size} bytes").style(self.dimmed)
+                                );
+                            }
                             for line in content.lines() {
                                 println!("{}", line.style(self.red));
                             }
@@ -443,16 +478,23 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                         FileChange::Update {
                             unified_diff,
                             move_path,
+                            ..
                         } => {
                             let header = if let Some(dest) = move_path {
                                 format!(
-                                    "{} {} -> {}",
+                                    "{} {} -> {}{}",
                                     format_file_change(change),
                                     path.to_string_lossy(),
-                                    dest.to_string_lossy()
+                                    dest.to_string_lossy(),
+                                    format_file_mode(change)
                                 )
                             } else {
-                                format!("{} {}", format_file_change(change), path.to_string_lossy())
+                                format!(
+                                    "{} {}{}",
+                                    format_file_change(change),
+                                    path.to_string_lossy(),
+                                    format_file_mode(change)
+                                )
                             };
                             println!("{}", header.style(self.magenta));
 
@@ -471,6 +513,19 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                                 }
                             }
                         }
+                        FileChange::Binary { size, .. } => {
+                            let header = format!(
+                                "{} {}{}",
+                                format_file_change(change),
+                                path.to_string_lossy(),
+                                format_file_mode(change)
+                            );
+                            println!("{}", header.style(self.magenta));
+                            println!(
+                                "{}",
+                                format!("binary file, {size} bytes").style(self.dimmed)
+                            );
+                        }
                     }
                 }
             }
@@ -586,8 +641,49 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
             EventMsg::ConversationPath(_) => {}
             EventMsg::UserMessage(_) => {}
-            EventMsg::EnteredReviewMode(_) => {}
-            EventMsg::ExitedReviewMode(_) => {}
+            EventMsg::EnteredReviewMode(request) => {
+                ts_println!(
+                    self,
+                    "{} {}",
+                    "review".style(self.magenta),
+                    request.user_facing_hint
+                );
+            }
+            EventMsg::ExitedReviewMode(ExitedReviewModeEvent { review_output }) => {
+                if let Some(output) = review_output {
+                    ts_println!(self, "{}", format_review_text(&output, &self.cwd));
+                }
+            }
+            EventMsg::ContextPinned(ContextPinnedEvent { pins }) => {
+                ts_println!(
+                    self,
+                    "{} {} note(s)",
+                    "pinned".style(self.magenta),
+                    pins.len()
+                );
+            }
+            EventMsg::CheckpointRestored(CheckpointRestoredEvent {
+                applied,
+                restored_paths,
+                conflicts,
+                ..
+            }) => {
+                if applied {
+                    ts_println!(self, "{}", "checkpoint restored".style(self.green));
+                    for path in restored_paths {
+                        println!("{}", path.display().style(self.dimmed));
+                    }
+                } else {
+                    ts_println!(
+                        self,
+                        "{} files changed since the checkpoint:",
+                        "checkpoint not restored:".style(self.red)
+                    );
+                    for path in conflicts {
+                        println!("{}", path.display().style(self.dimmed));
+                    }
+                }
+            }
         }
         CodexStatus::Running
     }
@@ -607,6 +703,23 @@ fn format_file_change(change: &FileChange) -> &'static str {
         FileChange::Update {
             move_path: None, ..
         } => "M",
+        FileChange::Binary { replace: false, .. } => "A",
+        FileChange::Binary { replace: true, .. } => "M",
+    }
+}
+
+fn format_file_mode(change: &FileChange) -> String {
+    match change {
+        FileChange::Add {
+            mode: Some(mode), ..
+        }
+        | FileChange::Update {
+            mode: Some(mode), ..
+        }
+        | FileChange::Binary {
+            mode: Some(mode), ..
+        } => format!(" (mode {mode:o})"),
+        _ => String::new(),
     }
 }
 
@@ -4,26 +4,52 @@ use std::path::PathBuf;
 use codex_core::config::Config;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::ReviewOutputEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use serde_json::json;
 
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
 use crate::event_processor::handle_last_message;
+use crate::review_format::ReviewFormat;
+use crate::review_format::format_review;
 use codex_common::create_config_summary_entries;
 
 pub(crate) struct EventProcessorWithJsonOutput {
     last_message_path: Option<PathBuf>,
+    /// When set, only the review result is printed, rendered in this format.
+    review_format: Option<ReviewFormat>,
+    cwd: PathBuf,
 }
 
 impl EventProcessorWithJsonOutput {
-    pub fn new(last_message_path: Option<PathBuf>) -> Self {
-        Self { last_message_path }
+    pub fn new(
+        last_message_path: Option<PathBuf>,
+        review_format: Option<ReviewFormat>,
+        cwd: PathBuf,
+    ) -> Self {
+        Self {
+            last_message_path,
+            review_format,
+            cwd,
+        }
+    }
+}
+
+impl EventProcessorWithJsonOutput {
+    fn print_review(&self, output: ReviewOutputEvent) {
+        if let Some(format) = self.review_format {
+            println!("{}", format_review(&output, format, &self.cwd));
+        }
     }
 }
 
 impl EventProcessor for EventProcessorWithJsonOutput {
     fn print_config_summary(&mut self, config: &Config, prompt: &str) {
+        if self.review_format.is_some() {
+            return;
+        }
         let entries = create_config_summary_entries(config)
             .into_iter()
             .map(|(key, value)| (key.to_string(), value))
@@ -45,13 +71,26 @@ impl EventProcessor for EventProcessorWithJsonOutput {
                 // Suppress streaming events in JSON mode.
                 CodexStatus::Running
             }
-            EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+            EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message, ..
+            }) => {
                 if let Some(output_file) = self.last_message_path.as_deref() {
                     handle_last_message(last_agent_message.as_deref(), output_file);
                 }
                 CodexStatus::InitiateShutdown
             }
             EventMsg::ShutdownComplete => CodexStatus::Shutdown,
+            EventMsg::ExitedReviewMode(ExitedReviewModeEvent { review_output })
+                if self.review_format.is_some() =>
+            {
+                self.print_review(review_output.unwrap_or_default());
+                CodexStatus::Running
+            }
+            EventMsg::Error(err) if self.review_format.is_some() => {
+                eprintln!("ERROR: {}", err.message);
+                CodexStatus::Running
+            }
+            _ if self.review_format.is_some() => CodexStatus::Running,
             _ => {
                 if let Ok(line) = serde_json::to_string(&event) {
                     println!("{line}");
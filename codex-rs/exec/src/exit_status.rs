@@ -0,0 +1,38 @@
+//! Exit codes of `codex exec` other than 0 (success), and propagating the
+//! exit status of a child process.
+
+/// The run was stopped by `--max-turns`, `--max-tokens`, `--timeout` or
+/// `--max-tool-calls` before the agent finished.
+pub const BUDGET_EXHAUSTED_EXIT_CODE: i32 = 3;
+
+/// A review finished and reported findings at or above the blocking priority
+/// (P0/P1). Distinct from 1, which covers every failure to run the review
+/// (including one that ends without a result), so CI can tell the two apart.
+pub const BLOCKING_FINDINGS_EXIT_CODE: i32 = 4;
+
+/// Exit with the status of a finished child process, mapping a terminating
+/// signal to `128 + signal` as shells do.
+#[cfg(unix)]
+pub fn handle_exit_status(status: std::process::ExitStatus) -> ! {
+    use std::os::unix::process::ExitStatusExt;
+
+    // Use ExitStatus to derive the exit code.
+    if let Some(code) = status.code() {
+        std::process::exit(code);
+    } else if let Some(signal) = status.signal() {
+        std::process::exit(128 + signal);
+    } else {
+        std::process::exit(1);
+    }
+}
+
+/// Exit with the status of a finished child process.
+#[cfg(windows)]
+pub fn handle_exit_status(status: std::process::ExitStatus) -> ! {
+    if let Some(code) = status.code() {
+        std::process::exit(code);
+    } else {
+        // Rare on Windows, but if it happens: use fallback code.
+        std::process::exit(1);
+    }
+}
@@ -2,30 +2,43 @@ mod cli;
 mod event_processor;
 mod event_processor_with_human_output;
 mod event_processor_with_json_output;
+pub mod exit_status;
+mod output_schema;
+mod review_format;
 
 use std::io::IsTerminal;
 use std::io::Read;
 use std::path::PathBuf;
 
 pub use cli::Cli;
+pub use cli::ReviewArgs;
 use codex_core::AuthManager;
 use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use codex_core::ConversationManager;
 use codex_core::NewConversation;
+use codex_core::budget::SessionBudget;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::git_info::get_git_repo_root;
+use codex_core::output_schema::validate_output;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::SubagentApprovalDecision;
 use codex_core::protocol::TaskCompleteEvent;
+use codex_core::review::build_review_request;
+use codex_core::review::has_blocking_findings;
 use codex_ollama::DEFAULT_OSS_MODEL;
 use codex_protocol::config_types::SandboxMode;
 use event_processor_with_human_output::EventProcessorWithHumanOutput;
 use event_processor_with_json_output::EventProcessorWithJsonOutput;
+pub use review_format::ReviewFormat;
+pub use review_format::format_review;
+pub use review_format::format_review_text;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
@@ -35,6 +48,11 @@ use tracing_subscriber::EnvFilter;
 use crate::cli::Command as ExecCommand;
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
+use crate::event_processor::handle_last_message;
+use crate::exit_status::BLOCKING_FINDINGS_EXIT_CODE;
+use crate::exit_status::BUDGET_EXHAUSTED_EXIT_CODE;
+use crate::output_schema::load_output_schema;
+use crate::output_schema::retry_prompt;
 use codex_core::find_conversation_path_by_id_str;
 
 pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()> {
@@ -50,6 +68,13 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         skip_git_repo_check,
         color,
         last_message_file,
+        output_schema: output_schema_path,
+        max_turns,
+        max_tokens,
+        timeout,
+        max_tool_calls,
+        record,
+        replay,
         json: json_mode,
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
@@ -57,45 +82,13 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     } = cli;
 
     // Determine the prompt source (parent or subcommand) and read from stdin if needed.
-    let prompt_arg = match &command {
+    // Reviews build their own prompt from the selected diff.
+    let prompt = match &command {
         // Allow prompt before the subcommand by falling back to the parent-level prompt
         // when the Resume subcommand did not provide its own prompt.
-        Some(ExecCommand::Resume(args)) => args.prompt.clone().or(prompt),
-        None => prompt,
-    };
-
-    let prompt = match prompt_arg {
-        Some(p) if p != "-" => p,
-        // Either `-` was passed or no positional arg.
-        maybe_dash => {
-            // When no arg (None) **and** stdin is a TTY, bail out early – unless the
-            // user explicitly forced reading via `-`.
-            let force_stdin = matches!(maybe_dash.as_deref(), Some("-"));
-
-            if std::io::stdin().is_terminal() && !force_stdin {
-                eprintln!(
-                    "No prompt provided. Either specify one as an argument or pipe the prompt into stdin."
-                );
-                std::process::exit(1);
-            }
-
-            // Ensure the user knows we are waiting on stdin, as they may
-            // have gotten into this state by mistake. If so, and they are not
-            // writing to stdin, Codex will hang indefinitely, so this should
-            // help them debug in that case.
-            if !force_stdin {
-                eprintln!("Reading prompt from stdin...");
-            }
-            let mut buffer = String::new();
-            if let Err(e) = std::io::stdin().read_to_string(&mut buffer) {
-                eprintln!("Failed to read prompt from stdin: {e}");
-                std::process::exit(1);
-            } else if buffer.trim().is_empty() {
-                eprintln!("No prompt provided via stdin.");
-                std::process::exit(1);
-            }
-            buffer
-        }
+        Some(ExecCommand::Resume(args)) => Some(read_prompt(args.prompt.clone().or(prompt))),
+        Some(ExecCommand::Review(_)) => None,
+        None => Some(read_prompt(prompt)),
     };
 
     let (stdout_with_ansi, stderr_with_ansi) = match color {
@@ -121,6 +114,11 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         .with_writer(std::io::stderr)
         .try_init();
 
+    let review_format = match &command {
+        Some(ExecCommand::Review(args)) => args.review_format,
+        _ => None,
+    };
+
     let sandbox_mode = if full_auto {
         Some(SandboxMode::WorkspaceWrite)
     } else if dangerously_bypass_approvals_and_sandbox {
@@ -165,6 +163,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         show_raw_agent_reasoning: oss.then_some(true),
         tools_web_search_request: None,
         plan_mode_enabled: None,
+        record_dir: record,
+        replay_dir: replay,
     };
     // Parse `-c` overrides.
     let cli_kv_overrides = match config_overrides.parse_overrides() {
@@ -175,14 +175,40 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     };
 
-    let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides)?;
-    let mut event_processor: Box<dyn EventProcessor> = if json_mode {
-        Box::new(EventProcessorWithJsonOutput::new(last_message_file.clone()))
+    let output_schema = match output_schema_path {
+        Some(_) if matches!(command, Some(ExecCommand::Review(_))) => {
+            eprintln!("--output-schema cannot be used with `codex exec review`.");
+            std::process::exit(1);
+        }
+        Some(path) => Some(load_output_schema(&path)?),
+        None => None,
+    };
+
+    let mut config = Config::load_with_cli_overrides(cli_kv_overrides, overrides)?;
+    config.output_schema = output_schema.clone();
+    config.session_budget = SessionBudget {
+        max_turns,
+        max_tokens,
+        timeout,
+        max_tool_calls,
+    };
+    // With --output-schema the last message is written here, once it validates.
+    let processor_last_message_file = if output_schema.is_some() {
+        None
+    } else {
+        last_message_file.clone()
+    };
+    let mut event_processor: Box<dyn EventProcessor> = if json_mode || review_format.is_some() {
+        Box::new(EventProcessorWithJsonOutput::new(
+            processor_last_message_file,
+            review_format,
+            config.cwd.clone(),
+        ))
     } else {
         Box::new(EventProcessorWithHumanOutput::create_with_ansi(
             stdout_with_ansi,
             &config,
-            last_message_file.clone(),
+            processor_last_message_file,
         ))
     };
 
@@ -194,7 +220,24 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
 
     // Print the effective configuration and prompt so users can see what Codex
     // is using.
-    event_processor.print_config_summary(&config, &prompt);
+    let review_request = match &command {
+        Some(ExecCommand::Review(args)) => {
+            let target = args.target.target();
+            let request = build_review_request(&config.cwd, &target)
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!("failed to prepare review of {}: {e}", target.description())
+                })?;
+            Some(request)
+        }
+        _ => None,
+    };
+    let summary_prompt = match (&review_request, &prompt) {
+        (Some(request), _) => format!("/review {}", request.user_facing_hint),
+        (None, Some(prompt)) => prompt.clone(),
+        (None, None) => String::new(),
+    };
+    event_processor.print_config_summary(&config, &summary_prompt);
 
     if !skip_git_repo_check && get_git_repo_root(&config.cwd.to_path_buf()).is_none() {
         eprintln!("Not inside a trusted directory and --skip-git-repo-check was not specified.");
@@ -255,6 +298,12 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
                                     error!("Failed to auto-deny subagent invocation: {err:?}");
                                 }
                             }
+                            if let Some(op) = auto_deny_cost_limit_request(&event) {
+                                warn!("Stopping at cost limit in non-interactive mode");
+                                if let Err(err) = conversation.submit(op).await {
+                                    error!("Failed to deny cost limit request: {err:?}");
+                                }
+                            }
 
                             debug!("Received event: {event:?}");
 
@@ -288,25 +337,82 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         info!("Sent images with event ID: {initial_images_event_id}");
         while let Ok(event) = conversation.next_event().await {
             if event.id == initial_images_event_id
-                && matches!(
-                    event.msg,
-                    EventMsg::TaskComplete(TaskCompleteEvent {
-                        last_agent_message: _,
-                    })
-                )
+                && matches!(event.msg, EventMsg::TaskComplete(TaskCompleteEvent { .. }))
             {
                 break;
             }
         }
     }
 
-    // Send the prompt.
-    let items: Vec<InputItem> = vec![InputItem::Text { text: prompt }];
-    let initial_prompt_task_id = conversation.submit(Op::UserInput { items }).await?;
+    // Send the prompt, or start the review.
+    let reviewing = review_request.is_some();
+    let op = match review_request {
+        Some(review_request) => Op::Review { review_request },
+        None => Op::UserInput {
+            items: vec![InputItem::Text {
+                text: prompt.unwrap_or_default(),
+            }],
+        },
+    };
+    let initial_prompt_task_id = conversation.submit(op).await?;
     info!("Sent prompt with event ID: {initial_prompt_task_id}");
 
     // Run the loop until the task is complete.
+    let mut output_retried = false;
+    let mut output_rejected = false;
+    let mut budget_exhausted = None;
+    let mut blocking_findings = false;
+    // A review that ends without a result must not pass as one without findings.
+    let mut review_result_missing = reviewing;
     while let Some(event) = rx.recv().await {
+        if let EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+            review_output: Some(output),
+        }) = &event.msg
+        {
+            review_result_missing = false;
+            blocking_findings |= has_blocking_findings(output);
+        }
+        if let EventMsg::TaskComplete(TaskCompleteEvent {
+            budget_exhausted: Some(reason),
+            ..
+        }) = &event.msg
+        {
+            eprintln!("Stopped early: {reason}.");
+            budget_exhausted = Some(*reason);
+        }
+        if let Some(schema) = &output_schema
+            && budget_exhausted.is_none()
+            && let EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message, ..
+            }) = &event.msg
+        {
+            match validate_output(schema, last_agent_message.as_deref().unwrap_or_default()) {
+                Ok(_) => {
+                    if let Some(path) = &last_message_file {
+                        handle_last_message(last_agent_message.as_deref(), path);
+                    }
+                }
+                Err(errors) if !output_retried => {
+                    output_retried = true;
+                    eprintln!("Final message does not match --output-schema; asking for a fix:");
+                    for error in &errors {
+                        eprintln!("  - {error}");
+                    }
+                    let items = vec![InputItem::Text {
+                        text: retry_prompt(&errors),
+                    }];
+                    conversation.submit(Op::UserInput { items }).await?;
+                    continue;
+                }
+                Err(errors) => {
+                    eprintln!("Final message still does not match --output-schema:");
+                    for error in &errors {
+                        eprintln!("  - {error}");
+                    }
+                    output_rejected = true;
+                }
+            }
+        }
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
             CodexStatus::Running => continue,
@@ -319,9 +425,59 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
 
+    if budget_exhausted.is_some() {
+        std::process::exit(BUDGET_EXHAUSTED_EXIT_CODE);
+    }
+    if output_rejected {
+        std::process::exit(1);
+    }
+    if review_result_missing {
+        eprintln!("Review finished without a result.");
+        std::process::exit(1);
+    }
+    if blocking_findings {
+        std::process::exit(BLOCKING_FINDINGS_EXIT_CODE);
+    }
     Ok(())
 }
 
+/// Use the prompt argument, or read it from stdin when absent or `-`.
+fn read_prompt(prompt_arg: Option<String>) -> String {
+    match prompt_arg {
+        Some(p) if p != "-" => p,
+        // Either `-` was passed or no positional arg.
+        maybe_dash => {
+            // When no arg (None) **and** stdin is a TTY, bail out early – unless the
+            // user explicitly forced reading via `-`.
+            let force_stdin = matches!(maybe_dash.as_deref(), Some("-"));
+
+            if std::io::stdin().is_terminal() && !force_stdin {
+                eprintln!(
+                    "No prompt provided. Either specify one as an argument or pipe the prompt into stdin."
+                );
+                std::process::exit(1);
+            }
+
+            // Ensure the user knows we are waiting on stdin, as they may
+            // have gotten into this state by mistake. If so, and they are not
+            // writing to stdin, Codex will hang indefinitely, so this should
+            // help them debug in that case.
+            if !force_stdin {
+                eprintln!("Reading prompt from stdin...");
+            }
+            let mut buffer = String::new();
+            if let Err(e) = std::io::stdin().read_to_string(&mut buffer) {
+                eprintln!("Failed to read prompt from stdin: {e}");
+                std::process::exit(1);
+            } else if buffer.trim().is_empty() {
+                eprintln!("No prompt provided via stdin.");
+                std::process::exit(1);
+            }
+            buffer
+        }
+    }
+}
+
 fn auto_deny_subagent_request(event: &Event) -> Option<Op> {
     if let EventMsg::SubagentApprovalRequest(request) = &event.msg {
         Some(Op::SubagentApproval {
@@ -334,6 +490,17 @@ fn auto_deny_subagent_request(event: &Event) -> Option<Op> {
     }
 }
 
+fn auto_deny_cost_limit_request(event: &Event) -> Option<Op> {
+    if let EventMsg::CostLimitApprovalRequest(request) = &event.msg {
+        Some(Op::CostLimitApproval {
+            id: request.id.clone(),
+            decision: ReviewDecision::Denied,
+        })
+    } else {
+        None
+    }
+}
+
 async fn resolve_resume_path(
     config: &Config,
     args: &crate::cli::ResumeArgs,
@@ -357,7 +524,10 @@ async fn resolve_resume_path(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use codex_core::protocol::{Event, EventMsg, SubagentApprovalRequestEvent, TaskCompleteEvent};
+    use codex_core::protocol::{
+        CostLimit, CostLimitApprovalRequestEvent, Event, EventMsg, SubagentApprovalRequestEvent,
+        TaskCompleteEvent,
+    };
 
     #[test]
     fn auto_deny_subagent_request_returns_op() {
@@ -390,9 +560,31 @@ mod tests {
             id: "2".into(),
             msg: EventMsg::TaskComplete(TaskCompleteEvent {
                 last_agent_message: Some("done".into()),
+                budget_exhausted: None,
             }),
         };
 
         assert!(auto_deny_subagent_request(&event).is_none());
     }
+
+    #[test]
+    fn auto_deny_cost_limit_request_returns_op() {
+        let event = Event {
+            id: "3".into(),
+            msg: EventMsg::CostLimitApprovalRequest(CostLimitApprovalRequestEvent {
+                id: "3".into(),
+                limit: CostLimit::Session,
+                limit_usd: 5.0,
+                spent_usd: 5.2,
+            }),
+        };
+
+        match auto_deny_cost_limit_request(&event) {
+            Some(Op::CostLimitApproval { id, decision }) => {
+                assert_eq!(id, "3");
+                assert_eq!(decision, ReviewDecision::Denied);
+            }
+            other => panic!("expected auto-deny op, got {other:?}"),
+        }
+    }
 }
@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use anyhow::Context;
+use serde_json::Value;
+
+/// Read the JSON Schema passed to `--output-schema`.
+pub(crate) fn load_output_schema(path: &Path) -> anyhow::Result<Value> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read output schema {}", path.display()))?;
+    let schema: Value = serde_json::from_str(&contents)
+        .with_context(|| format!("output schema {} is not valid JSON", path.display()))?;
+    if !schema.is_object() {
+        anyhow::bail!("output schema {} must be a JSON object", path.display());
+    }
+    Ok(schema)
+}
+
+/// Follow-up sent to the model when its final message does not match the
+/// schema.
+pub(crate) fn retry_prompt(errors: &[String]) -> String {
+    let mut prompt = String::from("Your final message does not match the required JSON Schema:\n");
+    for error in errors {
+        prompt.push_str(&format!("- {error}\n"));
+    }
+    prompt.push_str(
+        "Reply again with only a JSON value that matches the schema, without any other text.",
+    );
+    prompt
+}
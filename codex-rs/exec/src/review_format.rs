@@ -0,0 +1,373 @@
+//! Machine-readable renderings of [`ReviewOutputEvent`] for CI: SARIF 2.1.0
+//! logs, GitHub Actions workflow annotations, and plain JSON.
+
+use std::path::Path;
+
+use clap::ValueEnum;
+use codex_core::git_info::get_git_repo_root;
+use codex_core::protocol::ReviewCodeLocation;
+use codex_core::protocol::ReviewFinding;
+use codex_core::protocol::ReviewOutputEvent;
+use codex_core::review::BLOCKING_PRIORITY_THRESHOLD;
+use codex_core::review::priority_label;
+use codex_core::review::sorted_findings;
+use serde_json::Value;
+use serde_json::json;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const TOOL_NAME: &str = "codex";
+const TOOL_INFORMATION_URI: &str = "https://github.com/openai/codex";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum ReviewFormat {
+    /// SARIF 2.1.0 log, suitable for code scanning uploads.
+    Sarif,
+    /// GitHub Actions workflow commands (`::error file=...`).
+    Github,
+    /// The raw review result as JSON.
+    Json,
+}
+
+/// How severe a finding is once mapped out of Codex's P0-P3 priorities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    fn from_priority(priority: i32) -> Self {
+        if priority <= BLOCKING_PRIORITY_THRESHOLD {
+            Severity::Error
+        } else if priority == BLOCKING_PRIORITY_THRESHOLD + 1 {
+            Severity::Warning
+        } else {
+            Severity::Note
+        }
+    }
+
+    fn sarif_level(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        }
+    }
+
+    fn github_command(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "notice",
+        }
+    }
+}
+
+/// Render `output` in `format`. Paths inside the git repository containing
+/// `cwd` are reported relative to its root, which is what code scanning and
+/// workflow annotations resolve them against; outside a repository they are
+/// relative to `cwd`.
+pub fn format_review(output: &ReviewOutputEvent, format: ReviewFormat, cwd: &Path) -> String {
+    let root = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+    match format {
+        ReviewFormat::Sarif => {
+            serde_json::to_string_pretty(&to_sarif(output, &root)).unwrap_or_default()
+        }
+        ReviewFormat::Github => to_github_annotations(output, &root),
+        ReviewFormat::Json => serde_json::to_string_pretty(output).unwrap_or_default(),
+    }
+}
+
+/// Plain-text summary used by the human-readable output modes.
+pub fn format_review_text(output: &ReviewOutputEvent, cwd: &Path) -> String {
+    let mut text = String::new();
+    if !output.overall_correctness.is_empty() {
+        text.push_str(&format!("Overall: {}\n", output.overall_correctness));
+    }
+    if !output.overall_explanation.is_empty() {
+        text.push_str(&format!("{}\n", output.overall_explanation.trim()));
+    }
+
+    let findings = sorted_findings(output);
+    if findings.is_empty() {
+        text.push_str("\nNo findings.\n");
+        return text;
+    }
+
+    text.push_str(&format!("\n{} finding(s):\n", findings.len()));
+    for finding in findings {
+        let location = &finding.code_location;
+        let path = location
+            .absolute_file_path
+            .strip_prefix(cwd)
+            .unwrap_or(&location.absolute_file_path);
+        text.push_str(&format!(
+            "\n[{}] {}\n  {}:{}-{} (confidence {:.2})\n",
+            priority_label(finding.priority),
+            finding.title,
+            path.display(),
+            location.line_range.start,
+            location.line_range.end,
+            finding.confidence_score,
+        ));
+        for line in finding.body.trim().lines() {
+            text.push_str(&format!("  {line}\n"));
+        }
+    }
+    text
+}
+
+fn to_sarif(output: &ReviewOutputEvent, root: &Path) -> Value {
+    let findings = sorted_findings(output);
+    let mut priorities: Vec<i32> = findings.iter().map(|f| f.priority).collect();
+    priorities.dedup();
+    let rules: Vec<Value> = priorities
+        .into_iter()
+        .map(|priority| {
+            json!({
+                "id": rule_id(priority),
+                "shortDescription": {
+                    "text": format!("{} code review finding", priority_label(priority)),
+                },
+                "defaultConfiguration": {
+                    "level": Severity::from_priority(priority).sarif_level(),
+                },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|finding| sarif_result(finding, root))
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "informationUri": TOOL_INFORMATION_URI,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+            "properties": {
+                "overallCorrectness": output.overall_correctness,
+                "overallExplanation": output.overall_explanation,
+                "overallConfidenceScore": output.overall_confidence_score,
+            },
+        }],
+    })
+}
+
+fn sarif_result(finding: &ReviewFinding, root: &Path) -> Value {
+    let location = &finding.code_location;
+    let artifact_location = match location.absolute_file_path.strip_prefix(root) {
+        Ok(relative) => json!({
+            "uri": slash_path(relative),
+            "uriBaseId": "%SRCROOT%",
+        }),
+        Err(_) => json!({
+            "uri": format!("file://{}", slash_path(&location.absolute_file_path)),
+        }),
+    };
+    let mut message = finding.title.clone();
+    let body = finding.body.trim();
+    if !body.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(body);
+    }
+
+    json!({
+        "ruleId": rule_id(finding.priority),
+        "level": Severity::from_priority(finding.priority).sarif_level(),
+        "message": { "text": message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": artifact_location,
+                "region": {
+                    "startLine": location.line_range.start.max(1),
+                    "endLine": location.line_range.end.max(location.line_range.start).max(1),
+                },
+            }
+        }],
+        "properties": {
+            "priority": finding.priority,
+            "confidence": finding.confidence_score,
+        },
+    })
+}
+
+fn to_github_annotations(output: &ReviewOutputEvent, root: &Path) -> String {
+    let mut annotations = Vec::new();
+    for finding in sorted_findings(output) {
+        let ReviewCodeLocation {
+            absolute_file_path,
+            line_range,
+        } = &finding.code_location;
+        let path = absolute_file_path
+            .strip_prefix(root)
+            .unwrap_or(absolute_file_path);
+        let title = format!("[{}] {}", priority_label(finding.priority), finding.title);
+        annotations.push(format!(
+            "::{} file={},line={},endLine={},title={}::{}",
+            Severity::from_priority(finding.priority).github_command(),
+            escape_property(&slash_path(path)),
+            line_range.start.max(1),
+            line_range.end.max(line_range.start).max(1),
+            escape_property(&title),
+            escape_data(finding.body.trim()),
+        ));
+    }
+    annotations.join("\n")
+}
+
+fn rule_id(priority: i32) -> String {
+    format!("codex-review/{}", priority_label(priority))
+}
+
+fn slash_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Escape a workflow command message, see
+/// https://github.com/actions/toolkit/blob/main/packages/core/src/command.ts
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn output() -> ReviewOutputEvent {
+        let finding = |title: &str, body: &str, priority: i32, path: &str| ReviewFinding {
+            title: title.to_string(),
+            body: body.to_string(),
+            confidence_score: 0.5,
+            priority,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from(path),
+                line_range: ReviewLineRange { start: 3, end: 4 },
+            },
+        };
+        ReviewOutputEvent {
+            findings: vec![
+                finding("Style nit", "Rename it.", 3, "/repo/src/lib.rs"),
+                finding(
+                    "Races, sometimes",
+                    "Lock first.\n100% reproducible.",
+                    1,
+                    "/repo/src/main.rs",
+                ),
+                finding("Leaks", "", 2, "/elsewhere/util.rs"),
+            ],
+            overall_correctness: "patch is incorrect".to_string(),
+            overall_explanation: "A race.".to_string(),
+            overall_confidence_score: 0.8,
+        }
+    }
+
+    #[test]
+    fn formats_text_sorted_by_priority() {
+        assert_eq!(
+            format_review_text(&output(), Path::new("/repo")),
+            "Overall: patch is incorrect\nA race.\n\n3 finding(s):\n\n\
+             [P1] Races, sometimes\n  src/main.rs:3-4 (confidence 0.50)\n  Lock first.\n  100% reproducible.\n\n\
+             [P2] Leaks\n  /elsewhere/util.rs:3-4 (confidence 0.50)\n\n\
+             [P3] Style nit\n  src/lib.rs:3-4 (confidence 0.50)\n  Rename it.\n"
+        );
+    }
+
+    #[test]
+    fn github_annotations_map_priority_and_escape() {
+        assert_eq!(
+            format_review(&output(), ReviewFormat::Github, Path::new("/repo")),
+            "::error file=src/main.rs,line=3,endLine=4,title=[P1] Races%2C sometimes::Lock first.%0A100%25 reproducible.\n\
+             ::warning file=/elsewhere/util.rs,line=3,endLine=4,title=[P2] Leaks::\n\
+             ::notice file=src/lib.rs,line=3,endLine=4,title=[P3] Style nit::Rename it."
+        );
+    }
+
+    #[test]
+    fn sarif_results_use_relative_uris_and_levels() {
+        let sarif: Value = serde_json::from_str(&format_review(
+            &output(),
+            ReviewFormat::Sarif,
+            Path::new("/repo"),
+        ))
+        .expect("valid json");
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        let rule_ids: Vec<&str> = run["tool"]["driver"]["rules"]
+            .as_array()
+            .expect("rules")
+            .iter()
+            .filter_map(|rule| rule["id"].as_str())
+            .collect();
+        assert_eq!(
+            rule_ids,
+            vec!["codex-review/P1", "codex-review/P2", "codex-review/P3"]
+        );
+
+        let results = run["results"].as_array().expect("results");
+        let levels: Vec<&str> = results
+            .iter()
+            .filter_map(|result| result["level"].as_str())
+            .collect();
+        assert_eq!(levels, vec!["error", "warning", "note"]);
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"],
+            json!({
+                "artifactLocation": { "uri": "src/main.rs", "uriBaseId": "%SRCROOT%" },
+                "region": { "startLine": 3, "endLine": 4 },
+            })
+        );
+        assert_eq!(
+            results[1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "file:///elsewhere/util.rs"
+        );
+        assert_eq!(
+            results[0]["message"]["text"],
+            "Races, sometimes\n\nLock first.\n100% reproducible."
+        );
+    }
+
+    #[test]
+    fn paths_are_relative_to_the_repository_root() {
+        let repo = tempfile::TempDir::new().expect("tempdir");
+        std::fs::create_dir_all(repo.path().join(".git")).expect("create .git");
+        let subdir = repo.path().join("src");
+        let mut output = output();
+        output.findings.truncate(1);
+        output.findings[0].code_location.absolute_file_path = subdir.join("lib.rs");
+
+        let sarif: Value =
+            serde_json::from_str(&format_review(&output, ReviewFormat::Sarif, &subdir))
+                .expect("valid json");
+        assert_eq!(
+            sarif["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"]
+                ["uri"],
+            "src/lib.rs"
+        );
+        assert!(
+            format_review(&output, ReviewFormat::Github, &subdir)
+                .starts_with("::notice file=src/lib.rs,")
+        );
+    }
+}
@@ -0,0 +1,112 @@
+// The shell tool is unavailable to e2e tests on windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use anyhow::Context;
+use assert_cmd::prelude::*;
+use codex_exec::exit_status::BUDGET_EXHAUSTED_EXIT_CODE;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+/// An SSE fixture in which the model always asks to run `calls_per_turn`
+/// commands that each append a line to `calls.txt`, so the session only ends
+/// when a budget runs out.
+fn write_tool_loop_fixture(dir: &Path, calls_per_turn: usize) -> std::path::PathBuf {
+    let mut fixture = String::from(
+        "event: response.created\ndata: {\"type\":\"response.created\",\"response\":{\"id\":\"resp1\"}}\n\n",
+    );
+    for index in 0..calls_per_turn {
+        let call = serde_json::json!({
+            "type": "response.output_item.done",
+            "item": {
+                "type": "function_call",
+                "name": "shell",
+                "arguments": serde_json::json!({
+                    "command": ["sh", "-c", "echo hi >> calls.txt"],
+                })
+                .to_string(),
+                "call_id": format!("call-{index}")
+            }
+        });
+        fixture.push_str(&format!(
+            "event: response.output_item.done\ndata: {call}\n\n"
+        ));
+    }
+    fixture.push_str(
+        "event: response.completed\ndata: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp1\",\"output\":[]}}\n\n",
+    );
+    let path = dir.join("fixture.sse");
+    std::fs::write(&path, fixture).unwrap();
+    path
+}
+
+fn exec(home: &TempDir, budget: &[&str]) -> anyhow::Result<assert_cmd::assert::Assert> {
+    exec_with_calls_per_turn(home, 1, budget)
+}
+
+fn exec_with_calls_per_turn(
+    home: &TempDir,
+    calls_per_turn: usize,
+    budget: &[&str],
+) -> anyhow::Result<assert_cmd::assert::Assert> {
+    let fixture = write_tool_loop_fixture(home.path(), calls_per_turn);
+    Ok(Command::cargo_bin("codex-exec")
+        .context("should find binary for codex-exec")?
+        .env("CODEX_HOME", home.path())
+        .env("OPENAI_API_KEY", "dummy")
+        .env("CODEX_RS_SSE_FIXTURE", fixture)
+        .env("OPENAI_BASE_URL", "http://unused.local")
+        .arg("--skip-git-repo-check")
+        .arg("-s")
+        .arg("danger-full-access")
+        .arg("-C")
+        .arg(home.path())
+        .args(budget)
+        .arg("keep going")
+        .assert())
+}
+
+#[test]
+fn max_turns_stops_the_run_with_budget_exit_code() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    exec(&home, &["--max-turns", "2"])?
+        .code(BUDGET_EXHAUSTED_EXIT_CODE)
+        .stderr(predicates::str::contains(
+            "Stopped early: turn limit reached.",
+        ));
+    Ok(())
+}
+
+#[test]
+fn max_tool_calls_stops_the_run_with_budget_exit_code() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    exec(&home, &["--max-tool-calls", "3"])?
+        .code(BUDGET_EXHAUSTED_EXIT_CODE)
+        .stderr(predicates::str::contains(
+            "Stopped early: tool call limit reached.",
+        ));
+    Ok(())
+}
+
+#[test]
+fn max_tool_calls_applies_within_a_single_turn() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    exec_with_calls_per_turn(&home, 3, &["--max-tool-calls", "2"])?
+        .code(BUDGET_EXHAUSTED_EXIT_CODE)
+        .stderr(predicates::str::contains(
+            "Stopped early: tool call limit reached.",
+        ));
+    let calls = std::fs::read_to_string(home.path().join("calls.txt"))?;
+    assert_eq!(calls.lines().count(), 2);
+    Ok(())
+}
+
+#[test]
+fn expired_timeout_stops_before_the_first_turn() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    exec(&home, &["--timeout", "0s"])?
+        .code(BUDGET_EXHAUSTED_EXIT_CODE)
+        .stderr(predicates::str::contains("Stopped early: timed out."));
+    Ok(())
+}
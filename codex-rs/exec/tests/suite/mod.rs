@@ -1,5 +1,8 @@
 // Aggregates all former standalone integration tests as modules.
 mod apply_patch;
+mod budget;
 mod common;
+mod output_schema;
 mod resume;
+mod review;
 mod sandbox;
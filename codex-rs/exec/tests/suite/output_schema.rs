@@ -0,0 +1,83 @@
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use anyhow::Context;
+use assert_cmd::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+const SCHEMA: &str = r#"{
+  "type": "object",
+  "properties": { "answer": { "type": "integer" } },
+  "required": ["answer"],
+  "additionalProperties": false
+}"#;
+
+/// Write an SSE fixture whose only output is an assistant message with `text`.
+fn write_fixture(dir: &Path, text: &str) -> std::path::PathBuf {
+    let message = serde_json::json!({
+        "type": "response.output_item.done",
+        "item": {
+            "type": "message",
+            "role": "assistant",
+            "content": [{ "type": "output_text", "text": text }]
+        }
+    });
+    let fixture = format!(
+        "event: response.created\ndata: {{\"type\":\"response.created\",\"response\":{{\"id\":\"resp1\"}}}}\n\n\
+         event: response.output_item.done\ndata: {message}\n\n\
+         event: response.completed\ndata: {{\"type\":\"response.completed\",\"response\":{{\"id\":\"resp1\",\"output\":[]}}}}\n\n"
+    );
+    let path = dir.join("fixture.sse");
+    std::fs::write(&path, fixture).unwrap();
+    path
+}
+
+fn exec_with_schema(home: &TempDir, fixture: &Path) -> anyhow::Result<assert_cmd::assert::Assert> {
+    let schema = home.path().join("schema.json");
+    std::fs::write(&schema, SCHEMA)?;
+    Ok(Command::cargo_bin("codex-exec")
+        .context("should find binary for codex-exec")?
+        .env("CODEX_HOME", home.path())
+        .env("OPENAI_API_KEY", "dummy")
+        .env("CODEX_RS_SSE_FIXTURE", fixture)
+        .env("OPENAI_BASE_URL", "http://unused.local")
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(home.path())
+        .arg("--output-schema")
+        .arg(&schema)
+        .arg("--output-last-message")
+        .arg(home.path().join("last.json"))
+        .arg("answer with json")
+        .assert())
+}
+
+#[test]
+fn conforming_output_is_written_and_exits_zero() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    let fixture = write_fixture(home.path(), r#"{"answer": 42}"#);
+
+    exec_with_schema(&home, &fixture)?.success();
+
+    let last = std::fs::read_to_string(home.path().join("last.json"))?;
+    assert_eq!(last, r#"{"answer": 42}"#);
+    Ok(())
+}
+
+#[test]
+fn non_conforming_output_is_retried_then_fails() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    let fixture = write_fixture(home.path(), "The answer is 42.");
+
+    exec_with_schema(&home, &fixture)?
+        .failure()
+        .stderr(predicates::str::contains(
+            "Final message does not match --output-schema",
+        ))
+        .stderr(predicates::str::contains(
+            "Final message still does not match --output-schema",
+        ));
+    assert!(!home.path().join("last.json").exists());
+    Ok(())
+}
@@ -0,0 +1,145 @@
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use anyhow::Context;
+use assert_cmd::prelude::*;
+use codex_exec::exit_status::BLOCKING_FINDINGS_EXIT_CODE;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+/// An SSE fixture whose only message is `review_output` serialized as JSON.
+fn write_review_fixture(dir: &Path, review_output: serde_json::Value) -> std::path::PathBuf {
+    let item = serde_json::json!({
+        "type": "response.output_item.done",
+        "item": {
+            "type": "message",
+            "role": "assistant",
+            "content": [{"type": "output_text", "text": review_output.to_string()}]
+        }
+    });
+    let fixture = format!(
+        "event: response.created\ndata: {{\"type\":\"response.created\",\"response\":{{\"id\":\"resp1\"}}}}\n\n\
+         event: response.output_item.done\ndata: {item}\n\n\
+         event: response.completed\ndata: {{\"type\":\"response.completed\",\"response\":{{\"id\":\"resp1\",\"output\":[]}}}}\n\n"
+    );
+    let path = dir.join("fixture.sse");
+    std::fs::write(&path, fixture).unwrap();
+    path
+}
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(repo)
+        .status()
+        .expect("run git");
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// A repository with one staged file to review.
+fn staged_repo() -> TempDir {
+    let repo = TempDir::new().unwrap();
+    git(repo.path(), &["init", "--quiet"]);
+    std::fs::write(repo.path().join("lib.rs"), "fn main() {}\n").unwrap();
+    git(repo.path(), &["add", "lib.rs"]);
+    repo
+}
+
+fn exec_review(
+    home: &TempDir,
+    repo: &TempDir,
+    priority: i32,
+) -> anyhow::Result<assert_cmd::assert::Assert> {
+    let fixture = write_review_fixture(
+        home.path(),
+        serde_json::json!({
+            "findings": [{
+                "title": "Off by one",
+                "body": "The loop skips the last element.",
+                "confidence_score": 0.9,
+                "priority": priority,
+                "code_location": {
+                    "absolute_file_path": repo.path().join("lib.rs"),
+                    "line_range": {"start": 1, "end": 1}
+                }
+            }],
+            "overall_correctness": "patch is incorrect",
+            "overall_explanation": "One bug.",
+            "overall_confidence_score": 0.8
+        }),
+    );
+    Ok(Command::cargo_bin("codex-exec")
+        .context("should find binary for codex-exec")?
+        .env("CODEX_HOME", home.path())
+        .env("OPENAI_API_KEY", "dummy")
+        .env("CODEX_RS_SSE_FIXTURE", fixture)
+        .env("OPENAI_BASE_URL", "http://unused.local")
+        .arg("-C")
+        .arg(repo.path())
+        .arg("review")
+        .arg("--staged")
+        .arg("--review-format")
+        .arg("json")
+        .assert())
+}
+
+#[test]
+fn blocking_findings_fail_the_review() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    let repo = staged_repo();
+    exec_review(&home, &repo, 1)?
+        .code(BLOCKING_FINDINGS_EXIT_CODE)
+        .stdout(predicates::str::contains("Off by one"));
+    Ok(())
+}
+
+#[test]
+fn minor_findings_pass_the_review() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    let repo = staged_repo();
+    exec_review(&home, &repo, 2)?
+        .success()
+        .stdout(predicates::str::contains("Off by one"));
+    Ok(())
+}
+
+#[test]
+fn review_format_requires_the_review_subcommand() -> anyhow::Result<()> {
+    Command::cargo_bin("codex-exec")
+        .context("should find binary for codex-exec")?
+        .arg("--review-format")
+        .arg("sarif")
+        .arg("hello")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--review-format"));
+    Ok(())
+}
+
+#[test]
+fn review_without_a_result_fails() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    let repo = staged_repo();
+    let fixture = home.path().join("fixture.sse");
+    std::fs::write(
+        &fixture,
+        "event: response.created\ndata: {\"type\":\"response.created\",\"response\":{\"id\":\"resp1\"}}\n\n\
+         event: response.completed\ndata: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp1\",\"output\":[]}}\n\n",
+    )?;
+    Command::cargo_bin("codex-exec")
+        .context("should find binary for codex-exec")?
+        .env("CODEX_HOME", home.path())
+        .env("OPENAI_API_KEY", "dummy")
+        .env("CODEX_RS_SSE_FIXTURE", fixture)
+        .env("OPENAI_BASE_URL", "http://unused.local")
+        .arg("-C")
+        .arg(repo.path())
+        .arg("review")
+        .arg("--staged")
+        .assert()
+        .code(1)
+        .stderr(predicates::str::contains(
+            "Review finished without a result",
+        ));
+    Ok(())
+}
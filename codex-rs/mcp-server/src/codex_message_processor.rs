@@ -3,6 +3,7 @@ use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::json_to_toml::json_to_toml;
 use crate::outgoing_message::OutgoingMessageSender;
 use crate::outgoing_message::OutgoingNotification;
+use crate::resources::ResourceRegistry;
 use codex_core::AuthManager;
 use codex_core::CodexConversation;
 use codex_core::ConversationManager;
@@ -14,6 +15,7 @@ use codex_core::auth::CLIENT_ID;
 use codex_core::auth::get_auth_file;
 use codex_core::auth::login_with_api_key;
 use codex_core::auth::try_read_auth_json;
+use codex_core::budget::SessionBudget;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigToml;
@@ -122,6 +124,7 @@ pub(crate) struct CodexMessageProcessor {
     active_login: Arc<Mutex<Option<ActiveLogin>>>,
     // Queue of pending interrupt requests per conversation. We reply when TurnAborted arrives.
     pending_interrupts: Arc<Mutex<HashMap<ConversationId, Vec<RequestId>>>>,
+    resources: Arc<ResourceRegistry>,
 }
 
 impl CodexMessageProcessor {
@@ -131,6 +134,7 @@ impl CodexMessageProcessor {
         outgoing: Arc<OutgoingMessageSender>,
         codex_linux_sandbox_exe: Option<PathBuf>,
         config: Arc<Config>,
+        resources: Arc<ResourceRegistry>,
     ) -> Self {
         Self {
             auth_manager,
@@ -141,6 +145,7 @@ impl CodexMessageProcessor {
             conversation_listeners: HashMap::new(),
             active_login: Arc::new(Mutex::new(None)),
             pending_interrupts: Arc::new(Mutex::new(HashMap::new())),
+            resources,
         }
     }
 
@@ -1071,6 +1076,7 @@ impl CodexMessageProcessor {
             .insert(subscription_id, cancel_tx);
         let outgoing_for_task = self.outgoing.clone();
         let pending_interrupts = self.pending_interrupts.clone();
+        let resources = self.resources.clone();
         tokio::spawn(async move {
             loop {
                 tokio::select! {
@@ -1078,7 +1084,7 @@ impl CodexMessageProcessor {
                         // User has unsubscribed, so exit this task.
                         break;
                     }
-                    event = conversation.next_event() => {
+                    event = resources.next_event(conversation_id, &conversation) => {
                         let event = match event {
                             Ok(event) => event,
                             Err(err) => {
@@ -1220,6 +1226,19 @@ async fn apply_bespoke_event_handling(
                 on_exec_approval_response(event_id, rx, conversation).await;
             });
         }
+        // There is no client request for cost limits yet, so a reached limit
+        // ends the task.
+        EventMsg::CostLimitApprovalRequest(request) => {
+            if let Err(e) = conversation
+                .submit(Op::CostLimitApproval {
+                    id: request.id,
+                    decision: ReviewDecision::Denied,
+                })
+                .await
+            {
+                error!("failed to deny cost limit request: {e}");
+            }
+        }
         // If this is a TurnAborted, reply to any pending interrupt requests.
         EventMsg::TurnAborted(turn_aborted_event) => {
             let pending = {
@@ -1254,6 +1273,10 @@ fn derive_config_from_params(
         base_instructions,
         include_plan_tool,
         include_apply_patch_tool,
+        max_turns,
+        max_tokens,
+        timeout_seconds,
+        max_tool_calls,
     } = params;
     let overrides = ConfigOverrides {
         model,
@@ -1271,6 +1294,8 @@ fn derive_config_from_params(
         show_raw_agent_reasoning: None,
         tools_web_search_request: None,
         plan_mode_enabled: None,
+        record_dir: None,
+        replay_dir: None,
     };
 
     let cli_overrides = cli_overrides
@@ -1279,7 +1304,14 @@ fn derive_config_from_params(
         .map(|(k, v)| (k, json_to_toml(v)))
         .collect();
 
-    Config::load_with_cli_overrides(cli_overrides, overrides)
+    let mut config = Config::load_with_cli_overrides(cli_overrides, overrides)?;
+    config.session_budget = SessionBudget {
+        max_turns,
+        max_tokens,
+        timeout: timeout_seconds.map(Duration::from_secs),
+        max_tool_calls,
+    };
+    Ok(config)
 }
 
 async fn on_patch_approval_response(
@@ -1360,7 +1392,7 @@ async fn on_exec_approval_response(
     }
 }
 
-fn extract_conversation_summary(
+pub(crate) fn extract_conversation_summary(
     path: PathBuf,
     head: &[serde_json::Value],
 ) -> Option<ConversationSummary> {
@@ -166,6 +166,8 @@ impl CodexToolCallParam {
             show_raw_agent_reasoning: None,
             tools_web_search_request: None,
             plan_mode_enabled: None,
+            record_dir: None,
+            replay_dir: None,
         };
 
         let cli_overrides = cli_overrides
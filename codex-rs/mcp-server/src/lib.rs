@@ -29,6 +29,10 @@ mod json_to_toml;
 pub(crate) mod message_processor;
 mod outgoing_message;
 mod patch_approval;
+mod prompts;
+mod resources;
+mod subagent_tool_config;
+mod subagent_tool_runner;
 
 use crate::message_processor::MessageProcessor;
 use crate::outgoing_message::OutgoingMessage;
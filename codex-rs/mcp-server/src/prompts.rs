@@ -0,0 +1,185 @@
+//! Publishes the user's slash commands and `$CODEX_HOME/prompts` files as MCP
+//! prompts.
+//!
+//! Slash commands are loaded from the same project and user layers the TUI
+//! uses, and take precedence over a custom prompt with the same name. When a
+//! command exists in both layers it is published under its scoped name (e.g.
+//! `project:review`) so every prompt name stays unique.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+
+use codex_core::config::Config;
+use codex_core::custom_prompts::discover_prompts_in_excluding;
+use codex_protocol::custom_prompts::CustomPrompt;
+use codex_slash_commands::Command;
+use codex_slash_commands::CommandRegistry;
+use codex_slash_commands::InterpolationContext;
+use codex_slash_commands::SlashCommandConfig;
+use codex_slash_commands::interpolate_template;
+use mcp_types::ContentBlock;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
+use mcp_types::JSONRPCErrorError;
+use mcp_types::Prompt;
+use mcp_types::PromptArgument;
+use mcp_types::PromptMessage;
+use mcp_types::Role;
+use mcp_types::TextContent;
+
+use crate::error_code::INTERNAL_ERROR_CODE;
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+
+/// Name of the single free-form argument accepted by every prompt. Its value
+/// is split shell-style and substituted for `$ARGUMENTS`, `$1`, `$2`, ...
+pub(crate) const PROMPT_ARGUMENTS_KEY: &str = "arguments";
+
+enum PromptSource {
+    SlashCommand(Command),
+    Custom(CustomPrompt),
+}
+
+struct PromptEntry {
+    name: String,
+    source: PromptSource,
+}
+
+impl PromptEntry {
+    fn to_prompt(&self) -> Prompt {
+        let (description, argument_hint) = match &self.source {
+            PromptSource::SlashCommand(command) => (
+                command.metadata.description.clone(),
+                command.metadata.argument_hint.clone(),
+            ),
+            PromptSource::Custom(prompt) => (
+                Some(format!("Custom prompt from {}", prompt.path.display())),
+                None,
+            ),
+        };
+        Prompt {
+            arguments: Some(vec![PromptArgument {
+                description: Some(
+                    argument_hint.unwrap_or_else(|| "Arguments passed to the prompt".to_string()),
+                ),
+                name: PROMPT_ARGUMENTS_KEY.to_string(),
+                required: Some(false),
+                title: None,
+            }]),
+            description,
+            name: self.name.clone(),
+            title: None,
+        }
+    }
+
+    fn body(&self) -> &str {
+        match &self.source {
+            PromptSource::SlashCommand(command) => &command.body,
+            PromptSource::Custom(prompt) => &prompt.content,
+        }
+    }
+}
+
+pub(crate) async fn list_prompts(config: &Config) -> Result<Vec<Prompt>, JSONRPCErrorError> {
+    let entries = load_prompt_entries(&config.cwd, &config.codex_home).await?;
+    Ok(entries.iter().map(PromptEntry::to_prompt).collect())
+}
+
+pub(crate) async fn get_prompt(
+    config: &Config,
+    params: GetPromptRequestParams,
+) -> Result<GetPromptResult, JSONRPCErrorError> {
+    let GetPromptRequestParams { arguments, name } = params;
+    let entries = load_prompt_entries(&config.cwd, &config.codex_home).await?;
+    let entry = entries
+        .into_iter()
+        .find(|entry| entry.name == name)
+        .ok_or_else(|| JSONRPCErrorError {
+            code: INVALID_REQUEST_ERROR_CODE,
+            message: format!("prompt not found: {name}"),
+            data: None,
+        })?;
+
+    let raw_arguments = arguments
+        .as_ref()
+        .and_then(|args| args.get(PROMPT_ARGUMENTS_KEY))
+        .and_then(|value| value.as_str())
+        .unwrap_or_default();
+    let split_arguments = shlex::split(raw_arguments).unwrap_or_else(|| {
+        raw_arguments
+            .split_whitespace()
+            .map(ToString::to_string)
+            .collect()
+    });
+    let text = interpolate_template(entry.body(), &InterpolationContext::new(split_arguments))
+        .map_err(|err| JSONRPCErrorError {
+            code: INVALID_REQUEST_ERROR_CODE,
+            message: format!("failed to render prompt {name}: {err}"),
+            data: None,
+        })?;
+
+    Ok(GetPromptResult {
+        description: entry.to_prompt().description,
+        messages: vec![PromptMessage {
+            content: ContentBlock::TextContent(TextContent {
+                annotations: None,
+                text,
+                r#type: "text".to_string(),
+            }),
+            role: Role::User,
+        }],
+    })
+}
+
+async fn load_prompt_entries(
+    cwd: &Path,
+    codex_home: &Path,
+) -> Result<Vec<PromptEntry>, JSONRPCErrorError> {
+    let slash_config = SlashCommandConfig::from_environment(
+        Some(cwd.to_path_buf()),
+        Some(codex_home.to_path_buf()),
+    );
+    let registry = CommandRegistry::load(&slash_config)
+        .await
+        .map_err(|err| JSONRPCErrorError {
+            code: INTERNAL_ERROR_CODE,
+            message: format!("failed to load slash commands: {err}"),
+            data: None,
+        })?;
+
+    let commands = registry.all();
+    let mut qualified_counts: HashMap<String, usize> = HashMap::new();
+    for command in &commands {
+        *qualified_counts
+            .entry(command.qualified_name())
+            .or_default() += 1;
+    }
+
+    let mut entries: Vec<PromptEntry> = commands
+        .into_iter()
+        .map(|command| {
+            let qualified = command.qualified_name();
+            let name = if qualified_counts.get(&qualified).copied().unwrap_or(0) > 1 {
+                command.full_name()
+            } else {
+                qualified
+            };
+            PromptEntry {
+                name,
+                source: PromptSource::SlashCommand(command.clone()),
+            }
+        })
+        .collect();
+
+    let taken: HashSet<String> = entries
+        .iter()
+        .map(|entry| entry.name.clone())
+        .chain(qualified_counts.into_keys())
+        .collect();
+    let custom_prompts = discover_prompts_in_excluding(&codex_home.join("prompts"), &taken).await;
+    entries.extend(custom_prompts.into_iter().map(|prompt| PromptEntry {
+        name: prompt.name.clone(),
+        source: PromptSource::Custom(prompt),
+    }));
+    Ok(entries)
+}
@@ -0,0 +1,344 @@
+//! Publishes Codex conversations as MCP resources.
+//!
+//! Every recorded rollout under `$CODEX_HOME/sessions` is exposed as
+//! `codex://conversations/<id>`, and conversations driven through this server
+//! additionally expose the most recent turn diff as
+//! `codex://conversations/<id>/diff`. Clients that subscribe to either URI
+//! receive `notifications/resources/updated` as the session progresses.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use codex_core::CodexConversation;
+use codex_core::Cursor as RolloutCursor;
+use codex_core::RolloutRecorder;
+use codex_core::error::Result as CodexResult;
+use codex_core::find_conversation_path_by_id_str;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_protocol::mcp_protocol::ConversationId;
+use mcp_types::JSONRPCErrorError;
+use mcp_types::ListResourcesResult;
+use mcp_types::ModelContextProtocolNotification;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::Resource;
+use mcp_types::ResourceTemplate;
+use mcp_types::ResourceUpdatedNotification;
+use mcp_types::ResourceUpdatedNotificationParams;
+use mcp_types::TextResourceContents;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::codex_message_processor::extract_conversation_summary;
+use crate::error_code::INTERNAL_ERROR_CODE;
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+use crate::outgoing_message::OutgoingMessageSender;
+use crate::outgoing_message::OutgoingNotification;
+
+const CONVERSATION_URI_PREFIX: &str = "codex://conversations/";
+const TURN_DIFF_URI_SUFFIX: &str = "/diff";
+const ROLLOUT_MIME_TYPE: &str = "application/jsonl";
+const DIFF_MIME_TYPE: &str = "text/x-diff";
+
+/// Number of conversations returned per `resources/list` page.
+const RESOURCES_PAGE_SIZE: usize = 50;
+
+/// A parsed `codex://` resource URI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ResourceUri {
+    /// The full rollout of a recorded conversation.
+    Conversation(ConversationId),
+    /// The unified diff produced by the latest turn of a conversation.
+    TurnDiff(ConversationId),
+}
+
+impl ResourceUri {
+    pub(crate) fn parse(uri: &str) -> Option<Self> {
+        let rest = uri.strip_prefix(CONVERSATION_URI_PREFIX)?;
+        let (id, is_diff) = match rest.strip_suffix(TURN_DIFF_URI_SUFFIX) {
+            Some(id) => (id, true),
+            None => (rest, false),
+        };
+        let id = ConversationId::from(Uuid::parse_str(id).ok()?);
+        if is_diff {
+            Some(Self::TurnDiff(id))
+        } else {
+            Some(Self::Conversation(id))
+        }
+    }
+}
+
+impl std::fmt::Display for ResourceUri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Conversation(id) => write!(f, "{CONVERSATION_URI_PREFIX}{id}"),
+            Self::TurnDiff(id) => write!(f, "{CONVERSATION_URI_PREFIX}{id}{TURN_DIFF_URI_SUFFIX}"),
+        }
+    }
+}
+
+/// Tracks resource state that is not persisted in rollouts (latest turn
+/// diffs) along with the set of URIs the client subscribed to.
+pub(crate) struct ResourceRegistry {
+    codex_home: PathBuf,
+    outgoing: Arc<OutgoingMessageSender>,
+    latest_turn_diffs: Mutex<HashMap<ConversationId, String>>,
+    subscriptions: Mutex<HashSet<String>>,
+}
+
+impl ResourceRegistry {
+    pub(crate) fn new(codex_home: PathBuf, outgoing: Arc<OutgoingMessageSender>) -> Self {
+        Self {
+            codex_home,
+            outgoing,
+            latest_turn_diffs: Mutex::new(HashMap::new()),
+            subscriptions: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub(crate) async fn list_resources(
+        &self,
+        cursor: Option<String>,
+    ) -> Result<ListResourcesResult, JSONRPCErrorError> {
+        let cursor = cursor.and_then(|s| {
+            serde_json::from_value::<RolloutCursor>(serde_json::Value::String(s)).ok()
+        });
+        let page = RolloutRecorder::list_conversations(
+            &self.codex_home,
+            RESOURCES_PAGE_SIZE,
+            cursor.as_ref(),
+        )
+        .await
+        .map_err(|err| internal_error(format!("failed to list conversations: {err}")))?;
+
+        let mut resources = Vec::new();
+
+        // Turn diffs only exist in memory, so surface them once on the first
+        // page rather than interleaving them with the paginated rollouts.
+        if cursor.is_none() {
+            let diffs = self.latest_turn_diffs.lock().await;
+            let mut ids: Vec<&ConversationId> = diffs.keys().collect();
+            ids.sort_by_key(|id| id.to_string());
+            for id in ids {
+                resources.push(Resource {
+                    annotations: None,
+                    description: Some("Unified diff produced by the latest turn".to_string()),
+                    mime_type: Some(DIFF_MIME_TYPE.to_string()),
+                    name: format!("{id} diff"),
+                    size: diffs.get(id).map(|diff| diff.len() as i64),
+                    title: None,
+                    uri: ResourceUri::TurnDiff(*id).to_string(),
+                });
+            }
+        }
+
+        for item in page.items {
+            let size = tokio::fs::metadata(&item.path)
+                .await
+                .ok()
+                .map(|meta| meta.len() as i64);
+            let Some(summary) = extract_conversation_summary(item.path, &item.head) else {
+                continue;
+            };
+            resources.push(Resource {
+                annotations: None,
+                description: summary.timestamp,
+                mime_type: Some(ROLLOUT_MIME_TYPE.to_string()),
+                name: summary.conversation_id.to_string(),
+                size,
+                title: Some(summary.preview),
+                uri: ResourceUri::Conversation(summary.conversation_id).to_string(),
+            });
+        }
+
+        let next_cursor = match page.next_cursor {
+            Some(c) => match serde_json::to_value(&c) {
+                Ok(serde_json::Value::String(s)) => Some(s),
+                _ => None,
+            },
+            None => None,
+        };
+
+        Ok(ListResourcesResult {
+            next_cursor,
+            resources,
+        })
+    }
+
+    pub(crate) fn resource_templates(&self) -> Vec<ResourceTemplate> {
+        vec![
+            ResourceTemplate {
+                annotations: None,
+                description: Some("Recorded rollout of a Codex conversation".to_string()),
+                mime_type: Some(ROLLOUT_MIME_TYPE.to_string()),
+                name: "conversation".to_string(),
+                title: Some("Codex conversation".to_string()),
+                uri_template: format!("{CONVERSATION_URI_PREFIX}{{conversation_id}}"),
+            },
+            ResourceTemplate {
+                annotations: None,
+                description: Some("Unified diff produced by the latest turn".to_string()),
+                mime_type: Some(DIFF_MIME_TYPE.to_string()),
+                name: "turn-diff".to_string(),
+                title: Some("Latest turn diff".to_string()),
+                uri_template: format!(
+                    "{CONVERSATION_URI_PREFIX}{{conversation_id}}{TURN_DIFF_URI_SUFFIX}"
+                ),
+            },
+        ]
+    }
+
+    pub(crate) async fn read_resource(
+        &self,
+        uri: &str,
+    ) -> Result<ReadResourceResult, JSONRPCErrorError> {
+        let parsed = ResourceUri::parse(uri)
+            .ok_or_else(|| invalid_request(format!("unknown resource uri: {uri}")))?;
+
+        let (mime_type, text) = match parsed {
+            ResourceUri::Conversation(id) => {
+                let path = find_conversation_path_by_id_str(&self.codex_home, &id.to_string())
+                    .await
+                    .map_err(|err| internal_error(format!("failed to locate rollout: {err}")))?
+                    .ok_or_else(|| invalid_request(format!("conversation not found: {id}")))?;
+                let text = tokio::fs::read_to_string(&path).await.map_err(|err| {
+                    internal_error(format!(
+                        "failed to read rollout `{}`: {err}",
+                        path.display()
+                    ))
+                })?;
+                (ROLLOUT_MIME_TYPE, text)
+            }
+            ResourceUri::TurnDiff(id) => {
+                let diffs = self.latest_turn_diffs.lock().await;
+                let text = diffs
+                    .get(&id)
+                    .cloned()
+                    .ok_or_else(|| invalid_request(format!("no turn diff recorded for {id}")))?;
+                (DIFF_MIME_TYPE, text)
+            }
+        };
+
+        Ok(ReadResourceResult {
+            contents: vec![ReadResourceResultContents::TextResourceContents(
+                TextResourceContents {
+                    mime_type: Some(mime_type.to_string()),
+                    text,
+                    uri: uri.to_string(),
+                },
+            )],
+        })
+    }
+
+    pub(crate) async fn subscribe(&self, uri: &str) -> Result<(), JSONRPCErrorError> {
+        if ResourceUri::parse(uri).is_none() {
+            return Err(invalid_request(format!("unknown resource uri: {uri}")));
+        }
+        self.subscriptions.lock().await.insert(uri.to_string());
+        Ok(())
+    }
+
+    pub(crate) async fn unsubscribe(&self, uri: &str) {
+        self.subscriptions.lock().await.remove(uri);
+    }
+
+    /// The next event of `conversation`. Every event loop of the server reads
+    /// through here, so each event updates the resources exactly once.
+    pub(crate) async fn next_event(
+        &self,
+        conversation_id: ConversationId,
+        conversation: &CodexConversation,
+    ) -> CodexResult<Event> {
+        let event = conversation.next_event().await?;
+        self.observe_event(conversation_id, &event.msg).await;
+        Ok(event)
+    }
+
+    /// Update resource state from a conversation event and notify any
+    /// subscribers whose resource changed as a result.
+    async fn observe_event(&self, conversation_id: ConversationId, msg: &EventMsg) {
+        let updated = match msg {
+            EventMsg::TurnDiff(event) => {
+                self.latest_turn_diffs
+                    .lock()
+                    .await
+                    .insert(conversation_id, event.unified_diff.clone());
+                ResourceUri::TurnDiff(conversation_id)
+            }
+            EventMsg::TaskComplete(_) | EventMsg::TurnAborted(_) => {
+                ResourceUri::Conversation(conversation_id)
+            }
+            _ => return,
+        };
+        self.notify_updated(&updated).await;
+    }
+
+    async fn notify_updated(&self, uri: &ResourceUri) {
+        let uri = uri.to_string();
+        if !self.subscriptions.lock().await.contains(&uri) {
+            return;
+        }
+        let params = ResourceUpdatedNotificationParams { uri };
+        self.outgoing
+            .send_notification(OutgoingNotification {
+                method: ResourceUpdatedNotification::METHOD.to_string(),
+                params: serde_json::to_value(params).ok(),
+            })
+            .await;
+    }
+}
+
+fn invalid_request(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INVALID_REQUEST_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
+
+fn internal_error(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INTERNAL_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn resource_uri_round_trips() {
+        let id = ConversationId::from(
+            Uuid::parse_str("3f941c35-29b3-493b-b0a4-e25800d9aeb0").expect("uuid"),
+        );
+
+        let conversation = ResourceUri::Conversation(id);
+        assert_eq!(
+            conversation.to_string(),
+            "codex://conversations/3f941c35-29b3-493b-b0a4-e25800d9aeb0"
+        );
+        assert_eq!(
+            ResourceUri::parse(&conversation.to_string()),
+            Some(conversation)
+        );
+
+        let diff = ResourceUri::TurnDiff(id);
+        assert_eq!(
+            diff.to_string(),
+            "codex://conversations/3f941c35-29b3-493b-b0a4-e25800d9aeb0/diff"
+        );
+        assert_eq!(ResourceUri::parse(&diff.to_string()), Some(diff));
+    }
+
+    #[test]
+    fn resource_uri_rejects_unknown_uris() {
+        assert_eq!(ResourceUri::parse("file:///tmp/foo"), None);
+        assert_eq!(ResourceUri::parse("codex://conversations/not-a-uuid"), None);
+    }
+}
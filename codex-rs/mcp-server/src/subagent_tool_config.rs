@@ -0,0 +1,171 @@
+//! Configuration objects accepted by the `codex-subagent` and
+//! `codex-agents-list` MCP tool-calls.
+
+use mcp_types::Tool;
+use mcp_types::ToolInputSchema;
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaSettings;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Client-supplied parameters for a `codex-subagent` tool-call.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct SubagentToolCallParam {
+    /// Name of the subagent to invoke, as listed by `codex-agents-list`.
+    pub name: String,
+
+    /// Additional instructions appended to the subagent's own instructions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+
+    /// Restrict the invocation to a subset of the subagent's allowed tools.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<String>>,
+
+    /// Working directory used to discover project subagents. If relative, it
+    /// is resolved against the server process's current working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+
+    /// Set once the user has approved running the subagent. Required when
+    /// subagent discovery is `auto`, unless `approval_policy` is `never`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmed: Option<bool>,
+}
+
+/// Client-supplied parameters for a `codex-agents-list` tool-call.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct AgentsListToolCallParam {
+    /// Include definitions that failed validation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_invalid: Option<bool>,
+
+    /// Working directory used to discover project subagents. If relative, it
+    /// is resolved against the server process's current working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+}
+
+/// Builds a `Tool` definition for the `codex-subagent` tool-call.
+pub(crate) fn create_tool_for_subagent_tool_call_param() -> Tool {
+    Tool {
+        name: "codex-subagent".to_string(),
+        title: Some("Codex Subagent".to_string()),
+        input_schema: input_schema_for::<SubagentToolCallParam>(),
+        output_schema: None,
+        description: Some(
+            "Invoke a Codex subagent by name and return its summary and transcript URI."
+                .to_string(),
+        ),
+        annotations: None,
+    }
+}
+
+/// Builds a `Tool` definition for the `codex-agents-list` tool-call.
+pub(crate) fn create_tool_for_agents_list_tool_call_param() -> Tool {
+    Tool {
+        name: "codex-agents-list".to_string(),
+        title: Some("Codex Agents List".to_string()),
+        input_schema: input_schema_for::<AgentsListToolCallParam>(),
+        output_schema: None,
+        description: Some(
+            "List the subagents discovered in the project and user `.codex/agents` directories."
+                .to_string(),
+        ),
+        annotations: None,
+    }
+}
+
+fn input_schema_for<T: JsonSchema>() -> ToolInputSchema {
+    let schema = SchemaSettings::draft2019_09()
+        .with(|s| {
+            s.inline_subschemas = true;
+            s.option_add_null_type = false;
+        })
+        .into_generator()
+        .into_root_schema_for::<T>();
+
+    #[expect(clippy::expect_used)]
+    let schema_value =
+        serde_json::to_value(&schema).expect("subagent tool schema should serialise to JSON");
+
+    serde_json::from_value::<ToolInputSchema>(schema_value).unwrap_or_else(|e| {
+        panic!("failed to create Tool from schema: {e}");
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn verify_subagent_tool_json_schema() {
+        let tool = create_tool_for_subagent_tool_call_param();
+        let tool_json = serde_json::to_value(&tool).expect("tool serializes");
+        let expected_tool_json = serde_json::json!({
+          "name": "codex-subagent",
+          "title": "Codex Subagent",
+          "description": "Invoke a Codex subagent by name and return its summary and transcript URI.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "confirmed": {
+                "description": "Set once the user has approved running the subagent. Required when subagent discovery is `auto`, unless `approval_policy` is `never`.",
+                "type": "boolean"
+              },
+              "cwd": {
+                "description": "Working directory used to discover project subagents. If relative, it is resolved against the server process's current working directory.",
+                "type": "string"
+              },
+              "instructions": {
+                "description": "Additional instructions appended to the subagent's own instructions.",
+                "type": "string"
+              },
+              "name": {
+                "description": "Name of the subagent to invoke, as listed by `codex-agents-list`.",
+                "type": "string"
+              },
+              "tools": {
+                "description": "Restrict the invocation to a subset of the subagent's allowed tools.",
+                "items": {
+                  "type": "string"
+                },
+                "type": "array"
+              }
+            },
+            "required": [
+              "name"
+            ]
+          }
+        });
+        assert_eq!(expected_tool_json, tool_json);
+    }
+
+    #[test]
+    fn verify_agents_list_tool_json_schema() {
+        let tool = create_tool_for_agents_list_tool_call_param();
+        let tool_json = serde_json::to_value(&tool).expect("tool serializes");
+        let expected_tool_json = serde_json::json!({
+          "name": "codex-agents-list",
+          "title": "Codex Agents List",
+          "description": "List the subagents discovered in the project and user `.codex/agents` directories.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "cwd": {
+                "description": "Working directory used to discover project subagents. If relative, it is resolved against the server process's current working directory.",
+                "type": "string"
+              },
+              "include-invalid": {
+                "description": "Include definitions that failed validation.",
+                "type": "boolean"
+              }
+            }
+          }
+        });
+        assert_eq!(expected_tool_json, tool_json);
+    }
+}
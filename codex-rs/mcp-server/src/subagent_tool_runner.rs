@@ -0,0 +1,211 @@
+//! Functions that back the `codex-subagent` and `codex-agents-list` MCP
+//! tool-calls.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use codex_core::ConversationManager;
+use codex_core::config::Config;
+use codex_core::protocol::AskForApproval;
+use codex_core::subagents::InvocationSession;
+use codex_core::subagents::SubagentDefinition;
+use codex_core::subagents::SubagentInventory;
+use codex_core::subagents::SubagentInvocationError;
+use codex_core::subagents::SubagentRecord;
+use codex_core::subagents::SubagentScope;
+use mcp_types::CallToolResult;
+use mcp_types::ContentBlock;
+use mcp_types::RequestId;
+use mcp_types::TextContent;
+use serde_json::json;
+use url::Url;
+
+use crate::outgoing_message::OutgoingMessageSender;
+use crate::subagent_tool_config::AgentsListToolCallParam;
+use crate::subagent_tool_config::SubagentToolCallParam;
+
+/// Invoke the requested subagent and send its summary back to the client as
+/// the response to the `tools/call` request identified by `id`.
+pub(crate) async fn run_subagent_tool_call(
+    id: RequestId,
+    param: SubagentToolCallParam,
+    config: Arc<Config>,
+    conversation_manager: Arc<ConversationManager>,
+    outgoing: Arc<OutgoingMessageSender>,
+) {
+    let SubagentToolCallParam {
+        name,
+        instructions,
+        tools,
+        cwd,
+        confirmed,
+    } = param;
+    let config = config_with_cwd(&config, cwd);
+
+    // The server cannot prompt the user itself, so running a subagent that
+    // needs confirmation is left to the client unless the approval policy
+    // never asks.
+    let mut session = InvocationSession::new(SubagentDefinition::normalize_name(&name));
+    if confirmed.unwrap_or(false) || config.approval_policy == AskForApproval::Never {
+        session = session.confirmed();
+    }
+    session.extra_instructions = instructions;
+    if let Some(tools) = tools {
+        session.requested_tools = tools;
+    }
+
+    let result = match conversation_manager.invoke_subagent(&config, session).await {
+        Ok(session) => {
+            let transcript_uri = session
+                .detail_artifacts
+                .iter()
+                .find_map(|path| Url::from_file_path(path).ok())
+                .map(String::from);
+            let summary = session.summary.clone().unwrap_or_default();
+            let mut text = summary.clone();
+            if let Some(uri) = &transcript_uri {
+                text.push_str(&format!("\n\nTranscript: {uri}"));
+            }
+            CallToolResult {
+                content: vec![text_content(text)],
+                is_error: None,
+                structured_content: Some(json!({
+                    "name": session.subagent_name,
+                    "summary": summary,
+                    "transcriptUri": transcript_uri,
+                    "model": session.resolved_model,
+                    "tools": session.requested_tools,
+                })),
+            }
+        }
+        Err(SubagentInvocationError::ConfirmationRequired { subagent, .. }) => CallToolResult {
+            content: vec![text_content(format!(
+                "Subagent '{subagent}' requires confirmation. Ask the user, then call \
+                 codex-subagent again with `confirmed: true`."
+            ))],
+            is_error: Some(true),
+            structured_content: Some(json!({
+                "name": subagent,
+                "confirmationRequired": true,
+            })),
+        },
+        Err(err) => CallToolResult {
+            content: vec![text_content(format!(
+                "Failed to invoke subagent '{name}': {err}"
+            ))],
+            is_error: Some(true),
+            structured_content: None,
+        },
+    };
+
+    outgoing.send_response(id, result).await;
+}
+
+/// Build the response for a `codex-agents-list` tool-call.
+pub(crate) fn list_agents(
+    param: AgentsListToolCallParam,
+    config: &Config,
+    conversation_manager: &ConversationManager,
+) -> CallToolResult {
+    let AgentsListToolCallParam {
+        include_invalid,
+        cwd,
+    } = param;
+    let config = config_with_cwd(config, cwd);
+    let inventory = conversation_manager.subagent_inventory(&config);
+    agents_list_result(
+        &inventory,
+        include_invalid.unwrap_or(false),
+        config.subagents.is_enabled(),
+    )
+}
+
+fn agents_list_result(
+    inventory: &SubagentInventory,
+    include_invalid: bool,
+    enabled: bool,
+) -> CallToolResult {
+    let agents: Vec<serde_json::Value> = inventory.subagents.values().map(record_json).collect();
+    let invalid: Vec<serde_json::Value> = if include_invalid {
+        inventory.invalid().into_iter().map(record_json).collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut lines = Vec::new();
+    if !enabled {
+        lines.push(
+            "Subagents are disabled; set `subagents.enabled = true` in config.toml.".to_string(),
+        );
+    } else if agents.is_empty() && invalid.is_empty() {
+        lines.push("No subagents found.".to_string());
+    }
+    for record in inventory.subagents.values() {
+        lines.push(format!(
+            "- {} ({}): {}",
+            record.definition.name,
+            display_scope(record.definition.scope),
+            record.definition.description
+        ));
+    }
+    if include_invalid {
+        for record in inventory.invalid() {
+            lines.push(format!(
+                "- {} ({}, invalid): {}",
+                record.definition.name,
+                display_scope(record.definition.scope),
+                record.validation_errors.join("; ")
+            ));
+        }
+    }
+
+    CallToolResult {
+        content: vec![text_content(lines.join("\n"))],
+        is_error: None,
+        structured_content: Some(json!({
+            "enabled": enabled,
+            "agents": agents,
+            "invalid": invalid,
+        })),
+    }
+}
+
+fn config_with_cwd(config: &Config, cwd: Option<String>) -> Config {
+    let mut config = config.clone();
+    if let Some(cwd) = cwd {
+        let cwd = PathBuf::from(cwd);
+        config.cwd = if cwd.is_absolute() {
+            cwd
+        } else {
+            config.cwd.join(cwd)
+        };
+    }
+    config
+}
+
+fn record_json(record: &SubagentRecord) -> serde_json::Value {
+    json!({
+        "name": record.definition.name,
+        "scope": display_scope(record.definition.scope),
+        "description": record.definition.description,
+        "tools": record.effective_tools,
+        "model": record.effective_model,
+        "sourcePath": record.definition.source_path,
+        "validationErrors": record.validation_errors,
+    })
+}
+
+fn display_scope(scope: SubagentScope) -> &'static str {
+    match scope {
+        SubagentScope::Project => "project",
+        SubagentScope::User => "user",
+    }
+}
+
+fn text_content(text: String) -> ContentBlock {
+    ContentBlock::TextContent(TextContent {
+        r#type: "text".to_string(),
+        text,
+        annotations: None,
+    })
+}
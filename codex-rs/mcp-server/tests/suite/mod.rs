@@ -8,7 +8,9 @@ mod create_conversation;
 mod interrupt;
 mod list_resume;
 mod login;
+mod resources_and_prompts;
 mod send_message;
 mod set_default_model;
+mod subagent_tools;
 mod user_agent;
 mod user_info;
@@ -0,0 +1,192 @@
+use std::fs;
+use std::path::Path;
+
+use mcp_test_support::McpProcess;
+use mcp_test_support::to_response;
+use mcp_types::ContentBlock;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
+use mcp_types::JSONRPCResponse;
+use mcp_types::ListPromptsResult;
+use mcp_types::ListResourcesResult;
+use mcp_types::ReadResourceRequestParams;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::RequestId;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+use uuid::Uuid;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_list_and_read_conversation_resources() {
+    let codex_home = TempDir::new().expect("create temp dir");
+    let conversation_id = create_fake_rollout(codex_home.path(), "Hello resources");
+
+    let mut mcp = McpProcess::new(codex_home.path())
+        .await
+        .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("init timeout")
+        .expect("init failed");
+
+    let list_id = mcp
+        .send_list_resources_request(None)
+        .await
+        .expect("send resources/list");
+    let list_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+    )
+    .await
+    .expect("resources/list timeout")
+    .expect("resources/list resp");
+    let ListResourcesResult { resources, .. } =
+        to_response::<ListResourcesResult>(list_resp).expect("deserialize resources/list");
+
+    let expected_uri = format!("codex://conversations/{conversation_id}");
+    assert_eq!(resources.len(), 1);
+    assert_eq!(resources[0].uri, expected_uri);
+    assert_eq!(resources[0].title.as_deref(), Some("Hello resources"));
+
+    let read_id = mcp
+        .send_read_resource_request(ReadResourceRequestParams {
+            uri: expected_uri.clone(),
+        })
+        .await
+        .expect("send resources/read");
+    let read_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(read_id)),
+    )
+    .await
+    .expect("resources/read timeout")
+    .expect("resources/read resp");
+    let ReadResourceResult { contents } =
+        to_response::<ReadResourceResult>(read_resp).expect("deserialize resources/read");
+    let [ReadResourceResultContents::TextResourceContents(text)] = contents.as_slice() else {
+        panic!("expected a single text resource, got {contents:?}");
+    };
+    assert_eq!(text.uri, expected_uri);
+    assert!(text.text.contains("Hello resources"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_list_and_get_prompts() {
+    let codex_home = TempDir::new().expect("create temp dir");
+    let prompts_dir = codex_home.path().join("prompts");
+    fs::create_dir_all(&prompts_dir).expect("create prompts dir");
+    fs::write(prompts_dir.join("summarize.md"), "Summarize the repo").expect("write prompt");
+    let commands_dir = codex_home.path().join("commands");
+    fs::create_dir_all(&commands_dir).expect("create commands dir");
+    fs::write(
+        commands_dir.join("greet.md"),
+        "---\ndescription: Greet someone\nargument-hint: <name>\n---\nSay hello to $1",
+    )
+    .expect("write command");
+
+    let mut mcp = McpProcess::new_with_env(
+        codex_home.path(),
+        &[
+            ("CODEX_SLASH_COMMANDS_DIR_PROJECT", None),
+            ("CODEX_SLASH_COMMANDS_DIR_USER", None),
+        ],
+    )
+    .await
+    .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("init timeout")
+        .expect("init failed");
+
+    let list_id = mcp
+        .send_list_prompts_request()
+        .await
+        .expect("send prompts/list");
+    let list_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+    )
+    .await
+    .expect("prompts/list timeout")
+    .expect("prompts/list resp");
+    let ListPromptsResult { prompts, .. } =
+        to_response::<ListPromptsResult>(list_resp).expect("deserialize prompts/list");
+    let names: Vec<&str> = prompts.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["greet", "summarize"]);
+    assert_eq!(prompts[0].description.as_deref(), Some("Greet someone"));
+
+    let get_id = mcp
+        .send_get_prompt_request(GetPromptRequestParams {
+            arguments: Some(json!({ "arguments": "world" })),
+            name: "greet".to_string(),
+        })
+        .await
+        .expect("send prompts/get");
+    let get_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(get_id)),
+    )
+    .await
+    .expect("prompts/get timeout")
+    .expect("prompts/get resp");
+    let GetPromptResult { messages, .. } =
+        to_response::<GetPromptResult>(get_resp).expect("deserialize prompts/get");
+    assert_eq!(messages.len(), 1);
+    let ContentBlock::TextContent(text) = &messages[0].content else {
+        panic!("expected text content, got {:?}", messages[0].content);
+    };
+    assert_eq!(text.text.trim(), "Say hello to world");
+}
+
+fn create_fake_rollout(codex_home: &Path, preview: &str) -> Uuid {
+    let uuid = Uuid::new_v4();
+    let dir = codex_home
+        .join("sessions")
+        .join("2025")
+        .join("01")
+        .join("02");
+    fs::create_dir_all(&dir).unwrap_or_else(|e| panic!("create sessions dir: {e}"));
+
+    let file_path = dir.join(format!("rollout-2025-01-02T12-00-00-{uuid}.jsonl"));
+    let lines = [
+        json!({
+            "timestamp": "2025-01-02T12:00:00Z",
+            "type": "session_meta",
+            "payload": {
+                "id": uuid,
+                "timestamp": "2025-01-02T12:00:00Z",
+                "cwd": "/",
+                "originator": "codex",
+                "cli_version": "0.0.0",
+                "instructions": null
+            }
+        }),
+        json!({
+            "timestamp": "2025-01-02T12:00:00Z",
+            "type": "response_item",
+            "payload": {
+                "type": "message",
+                "role": "user",
+                "content": [{"type": "input_text", "text": preview}]
+            }
+        }),
+        json!({
+            "timestamp": "2025-01-02T12:00:00Z",
+            "type": "event_msg",
+            "payload": {
+                "type": "user_message",
+                "message": preview,
+                "kind": "plain"
+            }
+        }),
+    ];
+    let contents: Vec<String> = lines.iter().map(ToString::to_string).collect();
+    fs::write(file_path, contents.join("\n") + "\n")
+        .unwrap_or_else(|e| panic!("write rollout file: {e}"));
+    uuid
+}
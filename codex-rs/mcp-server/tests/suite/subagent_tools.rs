@@ -0,0 +1,191 @@
+use std::fs;
+
+use mcp_test_support::McpProcess;
+use mcp_test_support::to_response;
+use mcp_types::CallToolResult;
+use mcp_types::JSONRPCResponse;
+use mcp_types::ListToolsResult;
+use mcp_types::RequestId;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_list_tools_includes_subagent_tools() {
+    let codex_home = TempDir::new().expect("create temp dir");
+    let mut mcp = McpProcess::new(codex_home.path())
+        .await
+        .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("init timeout")
+        .expect("init failed");
+
+    let request_id = mcp
+        .send_list_tools_request()
+        .await
+        .expect("send tools/list");
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await
+    .expect("tools/list timeout")
+    .expect("tools/list resp");
+    let ListToolsResult { tools, .. } =
+        to_response::<ListToolsResult>(resp).expect("deserialize tools/list");
+    let names: Vec<&str> = tools.iter().map(|tool| tool.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "codex",
+            "codex-reply",
+            "codex-subagent",
+            "codex-agents-list"
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_agents_list_returns_user_subagents() {
+    let codex_home = TempDir::new().expect("create temp dir");
+    fs::write(
+        codex_home.path().join("config.toml"),
+        "[subagents]\nenabled = true\n",
+    )
+    .expect("write config.toml");
+    let agents_dir = codex_home.path().join("agents");
+    fs::create_dir_all(&agents_dir).expect("create agents dir");
+    fs::write(
+        agents_dir.join("code-reviewer.md"),
+        "---\nname: code-reviewer\ndescription: Reviews diffs\ntools:\n  - git_diff\n---\nReview the current diff.\n",
+    )
+    .expect("write agent definition");
+
+    let mut mcp = McpProcess::new(codex_home.path())
+        .await
+        .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("init timeout")
+        .expect("init failed");
+
+    let request_id = mcp
+        .send_tool_call("codex-agents-list", None)
+        .await
+        .expect("send codex-agents-list");
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await
+    .expect("codex-agents-list timeout")
+    .expect("codex-agents-list resp");
+    let CallToolResult {
+        is_error,
+        structured_content,
+        ..
+    } = to_response::<CallToolResult>(resp).expect("deserialize tools/call");
+    assert_eq!(is_error, None);
+
+    let structured = structured_content.expect("structured content");
+    assert_eq!(structured["enabled"], json!(true));
+    assert_eq!(
+        structured["agents"],
+        json!([{
+            "name": "code-reviewer",
+            "scope": "user",
+            "description": "Reviews diffs",
+            "tools": ["git_diff"],
+            "model": null,
+            "sourcePath": agents_dir.join("code-reviewer.md"),
+            "validationErrors": [],
+        }])
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_subagent_tool_reports_unknown_subagent() {
+    let codex_home = TempDir::new().expect("create temp dir");
+    fs::write(
+        codex_home.path().join("config.toml"),
+        "[subagents]\nenabled = true\n",
+    )
+    .expect("write config.toml");
+
+    let mut mcp = McpProcess::new(codex_home.path())
+        .await
+        .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("init timeout")
+        .expect("init failed");
+
+    let request_id = mcp
+        .send_tool_call("codex-subagent", Some(json!({ "name": "missing" })))
+        .await
+        .expect("send codex-subagent");
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await
+    .expect("codex-subagent timeout")
+    .expect("codex-subagent resp");
+    let CallToolResult { is_error, .. } =
+        to_response::<CallToolResult>(resp).expect("deserialize tools/call");
+    assert_eq!(is_error, Some(true));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_subagent_tool_requires_confirmation() {
+    let codex_home = TempDir::new().expect("create temp dir");
+    fs::write(
+        codex_home.path().join("config.toml"),
+        "approval_policy = \"on-request\"\n\n[subagents]\nenabled = true\ndiscovery = \"auto\"\n",
+    )
+    .expect("write config.toml");
+    let agents_dir = codex_home.path().join("agents");
+    fs::create_dir_all(&agents_dir).expect("create agents dir");
+    fs::write(
+        agents_dir.join("code-reviewer.md"),
+        "---\nname: code-reviewer\ndescription: Reviews diffs\n---\nReview the current diff.\n",
+    )
+    .expect("write agent definition");
+
+    let mut mcp = McpProcess::new(codex_home.path())
+        .await
+        .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("init timeout")
+        .expect("init failed");
+
+    let request_id = mcp
+        .send_tool_call("codex-subagent", Some(json!({ "name": "code-reviewer" })))
+        .await
+        .expect("send codex-subagent");
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await
+    .expect("codex-subagent timeout")
+    .expect("codex-subagent resp");
+    let CallToolResult {
+        is_error,
+        structured_content,
+        ..
+    } = to_response::<CallToolResult>(resp).expect("deserialize tools/call");
+    assert_eq!(is_error, Some(true));
+    assert_eq!(
+        structured_content,
+        Some(json!({
+            "name": "code-reviewer",
+            "confirmationRequired": true,
+        }))
+    );
+}
@@ -4,6 +4,7 @@ mod pull;
 mod url;
 
 pub use client::OllamaClient;
+use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use codex_core::config::Config;
 pub use pull::CliProgressReporter;
 pub use pull::PullEvent;
@@ -42,3 +43,19 @@ pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
 
     Ok(())
 }
+
+/// The models pulled into the local Ollama server, cached alongside other
+/// providers' listings by `codex_core::model_discovery`.
+pub async fn discover_models(config: &Config) -> std::io::Result<Vec<String>> {
+    codex_core::model_discovery::discover_models(
+        &config.codex_home,
+        BUILT_IN_OSS_MODEL_PROVIDER_ID,
+        || async {
+            crate::OllamaClient::try_from_oss_provider(config)
+                .await?
+                .fetch_models()
+                .await
+        },
+    )
+    .await
+}
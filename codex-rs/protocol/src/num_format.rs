@@ -71,6 +71,21 @@ pub fn format_si_suffix(n: u64) -> String {
     format_si_suffix_with_formatter(n, formatter())
 }
 
+/// Format an amount of US dollars to the cent. Amounts below a cent show as
+/// "<$0.01" so that a priced session never looks free.
+///
+/// Examples:
+///   - 0.0 -> "$0.00"
+///   - 0.004 -> "<$0.01"
+///   - 12.345 -> "$12.35"
+pub fn format_usd(usd: f64) -> String {
+    if usd > 0.0 && usd < 0.005 {
+        "<$0.01".to_string()
+    } else {
+        format!("${usd:.2}")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,4 +110,12 @@ mod tests {
         // Above 1000G we keep whole‑G precision (no higher unit supported here).
         assert_eq!(fmt(1_234_000_000_000), "1,234G");
     }
+
+    #[test]
+    fn usd() {
+        assert_eq!(format_usd(0.0), "$0.00");
+        assert_eq!(format_usd(0.004), "<$0.01");
+        assert_eq!(format_usd(0.5), "$0.50");
+        assert_eq!(format_usd(12.345), "$12.35");
+    }
 }
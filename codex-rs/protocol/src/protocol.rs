@@ -30,6 +30,7 @@ use crate::plan_mode::PlanModeUpdatedEvent;
 use crate::plan_tool::UpdatePlanArgs;
 use mcp_types::CallToolResult;
 use mcp_types::Tool as McpTool;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_with::serde_as;
@@ -161,6 +162,15 @@ pub enum Op {
         decision: SubagentApprovalDecision,
     },
 
+    /// Answer a [`EventMsg::CostLimitApprovalRequest`].
+    CostLimitApproval {
+        /// The id of the request being answered.
+        id: String,
+        /// `Approved` continues until the limit is reached again,
+        /// `ApprovedForSession` stops asking, anything else ends the task.
+        decision: ReviewDecision,
+    },
+
     /// Append an entry to the persistent cross-session message history.
     ///
     /// Note the entry is not guaranteed to be logged if the user has
@@ -199,7 +209,11 @@ pub enum Op {
     /// Request the agent to summarize the current conversation context.
     /// The agent will use its existing context (either conversation history or previous response id)
     /// to generate a summary which will be returned as an AgentMessage event.
-    Compact,
+    Compact {
+        /// Extra guidance for the summary, e.g. what to focus on.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        instructions: Option<String>,
+    },
 
     /// Enter Plan Mode without executing commands.
     EnterPlanMode,
@@ -216,13 +230,44 @@ pub enum Op {
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
 
+    /// Revert the workspace files changed during the last `turns` turns to the
+    /// checkpoint taken before them. Unless `force` is set, nothing is restored
+    /// when a file the agent changed was edited again afterwards.
+    /// Reply is delivered via `EventMsg::CheckpointRestored`.
+    RestoreCheckpoint {
+        turns: u32,
+        #[serde(default)]
+        force: bool,
+    },
+
+    /// Pin a note to the conversation so it is carried over verbatim when the
+    /// history is compacted. Without `text`, only reports the current pins.
+    /// Reply is delivered via `EventMsg::ContextPinned`.
+    Pin {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text: Option<String>,
+    },
+
     /// Request to shut down codex instance.
     Shutdown,
 }
 
 /// Determines the conditions under which the user is consulted to approve
 /// running the command proposed by Codex.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Display, TS)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Display,
+    TS,
+    JsonSchema,
+)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "kebab-case")]
 pub enum AskForApproval {
@@ -526,6 +571,9 @@ pub enum EventMsg {
     /// Prompt the user to approve or deny invoking a subagent.
     SubagentApprovalRequest(SubagentApprovalRequestEvent),
 
+    /// A cost limit was reached; ask the user whether to keep going.
+    CostLimitApprovalRequest(CostLimitApprovalRequestEvent),
+
     BackgroundEvent(BackgroundEventEvent),
 
     /// Notification that a model stream experienced an error or disconnect
@@ -588,6 +636,12 @@ pub enum EventMsg {
 
     /// Exited review mode with an optional final result to apply.
     ExitedReviewMode(ExitedReviewModeEvent),
+
+    /// Result of an `Op::RestoreCheckpoint` request.
+    CheckpointRestored(CheckpointRestoredEvent),
+
+    /// Notes currently pinned to the conversation, in response to `Op::Pin`.
+    ContextPinned(ContextPinnedEvent),
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -595,6 +649,24 @@ pub struct ExitedReviewModeEvent {
     pub review_output: Option<ReviewOutputEvent>,
 }
 
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq, TS)]
+pub struct CheckpointRestoredEvent {
+    /// Number of turns that were rolled back.
+    pub turns: u32,
+    /// False when the restore was refused because of `conflicts`.
+    pub applied: bool,
+    /// Workspace-relative paths written or removed by the restore.
+    pub restored_paths: Vec<PathBuf>,
+    /// Workspace-relative paths changed by the agent that were edited again
+    /// after the last checkpoint.
+    pub conflicts: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq, TS)]
+pub struct ContextPinnedEvent {
+    pub pins: Vec<String>,
+}
+
 // Individual event payload types matching each `EventMsg` variant.
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -629,6 +701,38 @@ pub struct HookReloadResultEvent {
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct TaskCompleteEvent {
     pub last_agent_message: Option<String>,
+    /// Set when the task was stopped early because a session budget ran out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_exhausted: Option<BudgetExhausted>,
+}
+
+/// The session budget that stopped a task before the model finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetExhausted {
+    /// The maximum number of model turns was reached.
+    MaxTurns,
+    /// The maximum number of tokens was used.
+    MaxTokens,
+    /// The session ran for longer than its timeout.
+    Timeout,
+    /// The maximum number of tool calls was made.
+    MaxToolCalls,
+    /// A cost limit was reached and the user chose not to continue.
+    MaxCost,
+}
+
+impl fmt::Display for BudgetExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            BudgetExhausted::MaxTurns => "turn limit reached",
+            BudgetExhausted::MaxTokens => "token limit reached",
+            BudgetExhausted::Timeout => "timed out",
+            BudgetExhausted::MaxToolCalls => "tool call limit reached",
+            BudgetExhausted::MaxCost => "cost limit reached",
+        };
+        f.write_str(reason)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -640,6 +744,10 @@ pub struct TaskStartedEvent {
 pub struct TokenUsage {
     pub input_tokens: u64,
     pub cached_input_tokens: u64,
+    /// Input tokens written to the prompt cache, which some providers bill
+    /// above the input price. Counted in `input_tokens`.
+    #[serde(default)]
+    pub cache_write_input_tokens: u64,
     pub output_tokens: u64,
     pub reasoning_output_tokens: u64,
     pub total_tokens: u64,
@@ -650,6 +758,20 @@ pub struct TokenUsageInfo {
     pub total_token_usage: TokenUsage,
     pub last_token_usage: TokenUsage,
     pub model_context_window: Option<u64>,
+    /// What the usage cost, when the model has known pricing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<CostInfo>,
+}
+
+/// Spend in US dollars, priced from the model's per-token rates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize, TS)]
+pub struct CostInfo {
+    /// Cost of every request made in this session.
+    pub session_usd: f64,
+    /// Cost of the most recent request.
+    pub last_usd: f64,
+    /// Cost of every session today, this one included.
+    pub daily_usd: f64,
 }
 
 impl TokenUsageInfo {
@@ -668,6 +790,7 @@ impl TokenUsageInfo {
                 total_token_usage: TokenUsage::default(),
                 last_token_usage: TokenUsage::default(),
                 model_context_window,
+                cost: None,
             },
         };
         if let Some(last) = last {
@@ -744,6 +867,7 @@ impl TokenUsage {
     pub fn add_assign(&mut self, other: &TokenUsage) {
         self.input_tokens += other.input_tokens;
         self.cached_input_tokens += other.cached_input_tokens;
+        self.cache_write_input_tokens += other.cache_write_input_tokens;
         self.output_tokens += other.output_tokens;
         self.reasoning_output_tokens += other.reasoning_output_tokens;
         self.total_tokens += other.total_tokens;
@@ -1008,8 +1132,20 @@ pub struct SessionMetaLine {
     pub meta: SessionMeta,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub git: Option<GitInfo>,
+    /// Set when this session was forked from another recorded session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<ForkedFrom>,
+}
+
+/// The session a fork branched off and how much of it was kept.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, TS)]
+pub struct ForkedFrom {
+    pub conversation_id: ConversationId,
+    /// Number of user turns carried over from the parent session.
+    pub turn: usize,
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Debug, Clone, TS)]
 #[serde(tag = "type", content = "payload", rename_all = "snake_case")]
 pub enum RolloutItem {
@@ -1023,6 +1159,12 @@ pub enum RolloutItem {
 #[derive(Serialize, Deserialize, Clone, Debug, TS)]
 pub struct CompactedItem {
     pub message: String,
+    /// Model that produced the summary. Absent in older rollouts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// User guidance passed via `/compact <instructions>`, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
 }
 
 impl From<CompactedItem> for ResponseItem {
@@ -1046,6 +1188,18 @@ pub struct TurnContextItem {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub effort: Option<ReasoningEffortConfig>,
     pub summary: ReasoningSummaryConfig,
+    /// Set when the turn moved to `model` because the previous one failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<ModelFallbackItem>,
+}
+
+/// A switch to the next entry of `fallback_models`. Models are written as
+/// `provider:model`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, TS)]
+pub struct ModelFallbackItem {
+    pub from: String,
+    pub to: String,
+    pub reason: String,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -1219,6 +1373,35 @@ pub struct SubagentApprovalRequestEvent {
     pub model: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct CostLimitApprovalRequestEvent {
+    /// Identifier for correlating the [`Op::CostLimitApproval`] response.
+    pub id: String,
+    /// Which limit was reached.
+    pub limit: CostLimit,
+    /// The limit, in US dollars.
+    pub limit_usd: f64,
+    /// What has been spent against the limit so far, in US dollars.
+    pub spent_usd: f64,
+}
+
+/// The cost limits set by `max_session_cost` and `max_daily_cost`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum CostLimit {
+    Session,
+    Daily,
+}
+
+impl fmt::Display for CostLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CostLimit::Session => f.write_str("session"),
+            CostLimit::Daily => f.write_str("daily"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct BackgroundEventEvent {
     pub message: String,
@@ -1342,13 +1525,32 @@ pub enum SubagentApprovalDecision {
 pub enum FileChange {
     Add {
         content: String,
+        /// Permission bits the file is created with, if the patch sets them.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<u32>,
     },
     Delete {
+        /// Empty for a binary file.
         content: String,
+        /// Size in bytes of a deleted binary file, whose content is not
+        /// reported.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        binary_size: Option<u64>,
     },
     Update {
         unified_diff: String,
         move_path: Option<PathBuf>,
+        /// New permission bits, if the patch changes them.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<u32>,
+    },
+    /// A binary file added, or replaced when `replace` is set. Only its size
+    /// is reported.
+    Binary {
+        size: u64,
+        replace: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<u32>,
     },
 }
 
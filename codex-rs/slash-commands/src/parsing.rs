@@ -23,7 +23,7 @@ struct RawFrontmatter {
 fn strip_leading_blank_lines(s: &str) -> &str {
     let mut offset = 0;
     for segment in s.split_inclusive('\n') {
-        let without_newline = segment.trim_end_matches(|c| c == '\n' || c == '\r');
+        let without_newline = segment.trim_end_matches(['\n', '\r']);
         if without_newline.trim().is_empty() {
             offset += segment.len();
             continue;
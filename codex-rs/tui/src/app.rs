@@ -11,11 +11,13 @@ use crate::slash_command::CustomSlashCommand;
 use crate::tui;
 use crate::tui::TuiEvent;
 use codex_ansi_escape::ansi_escape_line;
+use codex_common::CliConfigOverrides;
+use codex_common::summarize_sandbox_policy;
 use codex_core::AuthManager;
 use codex_core::ConversationManager;
 use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
 use codex_core::config::persist_model_selection;
-use codex_core::model_family::find_family_for_model;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::HookRegistrySnapshotEvent;
@@ -55,6 +57,11 @@ pub(crate) struct App {
     /// Config is stored here so we can recreate ChatWidgets as needed.
     pub(crate) config: Config,
     pub(crate) active_profile: Option<String>,
+    /// `-c` overrides from the command line, reapplied when switching profiles.
+    pub(crate) cli_config_overrides: CliConfigOverrides,
+    /// Overrides from the other command line flags, such as `--model` and
+    /// `--sandbox`, likewise reapplied when switching profiles.
+    pub(crate) launch_overrides: ConfigOverrides,
     #[cfg(feature = "slash_commands")]
     pub(crate) custom_slash_commands: Vec<CustomSlashCommand>,
 
@@ -79,23 +86,13 @@ pub(crate) struct App {
     pub(crate) hook_panel_active: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub(crate) struct HookRegistryState {
     snapshot: HookRegistrySnapshot,
     is_loading: bool,
     last_error: Option<String>,
 }
 
-impl Default for HookRegistryState {
-    fn default() -> Self {
-        Self {
-            snapshot: HookRegistrySnapshot::default(),
-            is_loading: false,
-            last_error: None,
-        }
-    }
-}
-
 impl HookRegistryState {
     fn clear(&mut self) {
         self.snapshot = HookRegistrySnapshot::default();
@@ -152,11 +149,14 @@ async fn load_custom_slash_commands(
 }
 
 impl App {
+    #[allow(clippy::too_many_arguments)]
     pub async fn run(
         tui: &mut tui::Tui,
         auth_manager: Arc<AuthManager>,
         config: Config,
         active_profile: Option<String>,
+        cli_config_overrides: CliConfigOverrides,
+        launch_overrides: ConfigOverrides,
         initial_prompt: Option<String>,
         initial_images: Vec<PathBuf>,
         resume_selection: ResumeSelection,
@@ -178,23 +178,10 @@ impl App {
 
         let enhanced_keys_supported = supports_keyboard_enhancement().unwrap_or(false);
 
-        let chat_widget = match resume_selection {
-            ResumeSelection::StartFresh | ResumeSelection::Exit => {
-                let init = crate::chatwidget::ChatWidgetInit {
-                    config: config.clone(),
-                    frame_requester: tui.frame_requester(),
-                    app_event_tx: app_event_tx.clone(),
-                    initial_prompt: initial_prompt.clone(),
-                    initial_images: initial_images.clone(),
-                    enhanced_keys_supported,
-                    auth_manager: auth_manager.clone(),
-                    #[cfg(feature = "slash_commands")]
-                    custom_slash_commands: custom_slash_commands.clone(),
-                };
-                ChatWidget::new(init, conversation_manager.clone())
-            }
-            ResumeSelection::Resume(path) => {
-                let resumed = conversation_manager
+        let existing = match resume_selection {
+            ResumeSelection::StartFresh | ResumeSelection::Exit => None,
+            ResumeSelection::Resume(path) => Some(
+                conversation_manager
                     .resume_conversation_from_rollout(
                         config.clone(),
                         path.clone(),
@@ -203,25 +190,35 @@ impl App {
                     .await
                     .wrap_err_with(|| {
                         format!("Failed to resume session from {}", path.display())
-                    })?;
-                let init = crate::chatwidget::ChatWidgetInit {
-                    config: config.clone(),
-                    frame_requester: tui.frame_requester(),
-                    app_event_tx: app_event_tx.clone(),
-                    initial_prompt: initial_prompt.clone(),
-                    initial_images: initial_images.clone(),
-                    enhanced_keys_supported,
-                    auth_manager: auth_manager.clone(),
-                    #[cfg(feature = "slash_commands")]
-                    custom_slash_commands: custom_slash_commands.clone(),
-                };
-                ChatWidget::new_from_existing(
-                    init,
-                    conversation_manager.clone(),
-                    resumed.conversation,
-                    resumed.session_configured,
-                )
-            }
+                    })?,
+            ),
+            ResumeSelection::Fork { path, turn } => Some(
+                conversation_manager
+                    .fork_conversation_at_turn(turn, config.clone(), path.clone())
+                    .await
+                    .wrap_err_with(|| format!("Failed to fork session from {}", path.display()))?,
+            ),
+        };
+
+        let init = crate::chatwidget::ChatWidgetInit {
+            config: config.clone(),
+            frame_requester: tui.frame_requester(),
+            app_event_tx: app_event_tx.clone(),
+            initial_prompt: initial_prompt.clone(),
+            initial_images: initial_images.clone(),
+            enhanced_keys_supported,
+            auth_manager: auth_manager.clone(),
+            #[cfg(feature = "slash_commands")]
+            custom_slash_commands: custom_slash_commands.clone(),
+        };
+        let chat_widget = match existing {
+            None => ChatWidget::new(init, conversation_manager.clone()),
+            Some(existing) => ChatWidget::new_from_existing(
+                init,
+                conversation_manager.clone(),
+                existing.conversation,
+                existing.session_configured,
+            ),
         };
 
         let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
@@ -233,6 +230,8 @@ impl App {
             auth_manager: auth_manager.clone(),
             config,
             active_profile,
+            cli_config_overrides,
+            launch_overrides,
             #[cfg(feature = "slash_commands")]
             custom_slash_commands: custom_slash_commands.clone(),
             file_search,
@@ -418,6 +417,22 @@ impl App {
                 ));
                 tui.frame_requester().schedule_frame();
             }
+            AppEvent::OpenReviewFindings(output) => {
+                let _ = tui.enter_alt_screen();
+                self.overlay = Some(Overlay::new_review(
+                    &output,
+                    self.config.cwd.clone(),
+                    self.config.file_opener,
+                    self.app_event_tx.clone(),
+                ));
+                tui.frame_requester().schedule_frame();
+            }
+            AppEvent::FixReviewFindings(prompt) => {
+                self.chat_widget.submit_text_message(prompt);
+            }
+            AppEvent::RewindConversation(turns) => {
+                self.rewind_conversation(turns);
+            }
             AppEvent::StartFileSearch(query) => {
                 if !query.is_empty() {
                     self.file_search.on_user_query(query);
@@ -432,9 +447,10 @@ impl App {
             AppEvent::UpdateModel(model) => {
                 self.chat_widget.set_model(&model);
                 self.config.model = model.clone();
-                if let Some(family) = find_family_for_model(&model) {
-                    self.config.model_family = family;
-                }
+                self.config.model_family = self.config.model_family_or_default(&model);
+            }
+            AppEvent::ModelsDiscovered(models) => {
+                self.chat_widget.set_discovered_models(models);
             }
             AppEvent::PersistModelSelection { model, effort } => {
                 let profile = self.active_profile.as_deref();
@@ -474,6 +490,9 @@ impl App {
             AppEvent::UpdateSandboxPolicy(policy) => {
                 self.chat_widget.set_sandbox_policy(policy);
             }
+            AppEvent::SwitchProfile(profile) => {
+                self.switch_profile(profile);
+            }
             #[cfg(feature = "slash_commands")]
             AppEvent::CustomSlashCommandsReloaded { commands } => {
                 self.custom_slash_commands = commands.clone();
@@ -530,6 +549,65 @@ impl App {
         tui.frame_requester().schedule_frame();
     }
 
+    /// Reload the config with `profile` active and apply what can change
+    /// mid-session through `OverrideTurnContext`. The rest takes effect in
+    /// the next session.
+    fn switch_profile(&mut self, profile: String) {
+        let loaded = self
+            .cli_config_overrides
+            .parse_overrides()
+            .and_then(|cli_overrides| {
+                let overrides = ConfigOverrides {
+                    cwd: Some(self.config.cwd.clone()),
+                    config_profile: Some(profile.clone()),
+                    ..self.launch_overrides.clone()
+                };
+                Config::load_with_cli_overrides(cli_overrides, overrides)
+                    .map_err(|err| err.to_string())
+            });
+        let mut config = match loaded {
+            Ok(config) => config,
+            Err(err) => {
+                self.chat_widget
+                    .add_error_message(format!("Failed to switch to profile `{profile}`: {err}"));
+                return;
+            }
+        };
+        config.model_stream_recording = self.config.model_stream_recording.clone();
+
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::OverrideTurnContext {
+                cwd: None,
+                approval_policy: Some(config.approval_policy),
+                sandbox_policy: Some(config.sandbox_policy.clone()),
+                model: Some(config.model.clone()),
+                effort: Some(config.model_reasoning_effort),
+                summary: Some(config.model_reasoning_summary),
+            }));
+
+        let needs_new_session = config.model_provider_id != self.config.model_provider_id
+            || config.mcp_servers != self.config.mcp_servers
+            || config.tools_web_search_request != self.config.tools_web_search_request
+            || config.include_view_image_tool != self.config.include_view_image_tool
+            || config.plan_mode != self.config.plan_mode
+            || config.subagents != self.config.subagents;
+        let message = format!(
+            "Switched to profile `{profile}`: {}, approvals {}, sandbox {}",
+            config.model,
+            config.approval_policy,
+            summarize_sandbox_policy(&config.sandbox_policy),
+        );
+        self.chat_widget.apply_profile_config(config.clone());
+        self.config = config;
+        self.active_profile = Some(profile);
+        self.chat_widget.add_info_message(
+            message,
+            needs_new_session.then(|| {
+                "Its provider, MCP server, tool, plan mode and subagent settings apply to new sessions; use /new to start one.".to_string()
+            }),
+        );
+    }
+
     fn on_update_reasoning_effort(&mut self, effort: Option<ReasoningEffortConfig>) {
         self.chat_widget.set_reasoning_effort(effort);
         self.config.model_reasoning_effort = effort;
@@ -672,6 +750,8 @@ mod tests {
             auth_manager,
             config,
             active_profile: None,
+            cli_config_overrides: CliConfigOverrides::default(),
+            launch_overrides: ConfigOverrides::default(),
             #[cfg(feature = "slash_commands")]
             custom_slash_commands: Vec::new(),
             file_search,
@@ -725,7 +805,7 @@ mod tests {
         let event = rx.try_recv().expect("expected EnterPlanMode op");
         match event {
             AppEvent::CodexOp(Op::EnterPlanMode) => {}
-            other => panic!("unexpected event: {:?}", other),
+            other => panic!("unexpected event: {other:?}"),
         }
         assert!(!app.backtrack.primed);
 
@@ -753,9 +833,7 @@ mod tests {
 
         app.chat_widget.handle_codex_event(Event {
             id: "plan-activated".into(),
-            msg: EventMsg::PlanModeActivated(PlanModeActivatedEvent {
-                session: plan.clone(),
-            }),
+            msg: EventMsg::PlanModeActivated(PlanModeActivatedEvent { session: plan }),
         });
 
         while rx.try_recv().is_ok() {}
@@ -765,7 +843,7 @@ mod tests {
         let event = rx.try_recv().expect("expected ExitPlanMode op");
         match event {
             AppEvent::CodexOp(Op::ExitPlanMode) => {}
-            other => panic!("unexpected event: {:?}", other),
+            other => panic!("unexpected event: {other:?}"),
         }
     }
 }
@@ -80,6 +80,7 @@ impl App {
             kind: KeyEventKind::Press | KeyEventKind::Repeat,
             ..
         }) = event
+            && !matches!(self.overlay, Some(Overlay::Review(_)))
         {
             // First Esc in transcript overlay: begin backtrack preview at latest user message.
             self.begin_overlay_backtrack_preview(tui);
@@ -268,6 +269,16 @@ impl App {
         self.reset_backtrack_state();
     }
 
+    /// Drop the last `turns` user turns without going through the Esc flow,
+    /// e.g. after `/rewind` restored the matching workspace checkpoint.
+    pub(crate) fn rewind_conversation(&mut self, turns: usize) {
+        if let Some(base_id) = self.chat_widget.conversation_id() {
+            let prefill = backtrack_helpers::nth_last_user_text(&self.transcript_lines, turns)
+                .unwrap_or_default();
+            self.request_backtrack(prefill, base_id, turns);
+        }
+    }
+
     /// Clear all backtrack-related state and composer hints.
     pub(crate) fn reset_backtrack_state(&mut self) {
         self.backtrack.primed = false;
@@ -1,5 +1,6 @@
 use codex_core::protocol::ConversationPathResponseEvent;
 use codex_core::protocol::Event;
+use codex_core::protocol::ReviewOutputEvent;
 use codex_file_search::FileMatch;
 
 use crate::history_cell::HistoryCell;
@@ -41,6 +42,16 @@ pub(crate) enum AppEvent {
     /// Result of computing a `/diff` command.
     DiffResult(String),
 
+    /// Show the findings of a completed review in the review overlay.
+    OpenReviewFindings(ReviewOutputEvent),
+
+    /// Ask the agent to fix review findings; carries the prepared prompt.
+    FixReviewFindings(String),
+
+    /// Files were restored to a checkpoint `n` turns back; drop the same
+    /// number of turns from the conversation.
+    RewindConversation(usize),
+
     InsertHistoryCell(Box<dyn HistoryCell>),
 
     StartCommitAnimation,
@@ -53,6 +64,10 @@ pub(crate) enum AppEvent {
     /// Update the current model slug in the running app and widget.
     UpdateModel(String),
 
+    /// Models the session's provider serves, offered by `/model` alongside
+    /// the presets.
+    ModelsDiscovered(Vec<String>),
+
     /// Persist the selected model and reasoning effort to the appropriate config.
     PersistModelSelection {
         model: String,
@@ -65,6 +80,9 @@ pub(crate) enum AppEvent {
     /// Update the current sandbox policy in the running app and widget.
     UpdateSandboxPolicy(SandboxPolicy),
 
+    /// Switch the running session to the named config profile.
+    SwitchProfile(String),
+
     /// Forwarded conversation history snapshot from the current conversation.
     ConversationHistory(ConversationPathResponseEvent),
 
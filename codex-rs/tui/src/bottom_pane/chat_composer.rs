@@ -1,5 +1,6 @@
 use codex_core::protocol::TokenUsageInfo;
 use codex_protocol::num_format::format_si_suffix;
+use codex_protocol::num_format::format_usd;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
@@ -1403,6 +1404,13 @@ impl WidgetRef for ChatComposer {
                         ))
                         .style(Style::default().add_modifier(Modifier::DIM)),
                     );
+                    if let Some(cost) = token_usage_info.cost {
+                        hint.push("   ".into());
+                        hint.push(
+                            Span::from(format_usd(cost.session_usd))
+                                .style(Style::default().add_modifier(Modifier::DIM)),
+                        );
+                    }
                     let last_token_usage = &token_usage_info.last_token_usage;
                     if let Some(context_window) = token_usage_info.model_context_window {
                         let percent_remaining: u8 = if context_window > 0 {
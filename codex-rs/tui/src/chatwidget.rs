@@ -3,6 +3,8 @@ use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use codex_core::ExportFormat;
+use codex_core::Transcript;
 use codex_core::config::Config;
 use codex_core::config_types::Notifications;
 use codex_core::protocol::AgentMessageDeltaEvent;
@@ -13,12 +15,17 @@ use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::CheckpointRestoredEvent;
+use codex_core::protocol::ContextPinnedEvent;
+use codex_core::protocol::ConversationPathResponseEvent;
+use codex_core::protocol::CostLimitApprovalRequestEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::InputMessageKind;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
@@ -27,6 +34,7 @@ use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::SubagentApprovalRequestEvent;
 use codex_core::protocol::TaskCompleteEvent;
@@ -37,6 +45,8 @@ use codex_core::protocol::TurnDiffEvent;
 use codex_core::protocol::UserMessageEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::review::ReviewTarget;
+use codex_core::review::build_review_request;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::plan_mode::PlanModeActivatedEvent;
 use codex_protocol::plan_mode::PlanModeAppliedEvent;
@@ -100,11 +110,14 @@ use codex_common::approval_presets::builtin_approval_presets;
 use codex_common::model_presets::ModelPreset;
 use codex_common::model_presets::builtin_model_presets;
 use codex_core::AuthManager;
+use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use codex_core::ConversationManager;
+use codex_core::model_discovery::discover_provider_models;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_file_search::FileMatch;
+use codex_protocol::mcp_protocol::AuthMode;
 use codex_protocol::mcp_protocol::ConversationId;
 #[cfg(feature = "slash_commands")]
 use codex_slash_commands::CommandRegistry as DynamicCommandRegistry;
@@ -169,7 +182,21 @@ pub(crate) struct ChatWidget {
     plan_mode_session: Option<PlanModeSessionPayload>,
     plan_mode_dialog_shown: bool,
     plan_mode_dialog_pending: bool,
+    // Whether the next CheckpointRestored event came from `/rewind` and should
+    // also drop the restored turns from the conversation.
+    rewind_conversation_pending: bool,
+    // Export requested with `/export`, written once the flushed rollout path arrives.
+    pending_export: Option<ExportRequest>,
     default_placeholder: String,
+    // Models reported by the provider, listed in `/model` after the presets.
+    discovered_models: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ExportRequest {
+    format: ExportFormat,
+    redact: bool,
+    output: Option<PathBuf>,
 }
 
 struct UserMessage {
@@ -202,6 +229,33 @@ async fn load_custom_slash_commands_for_config(
         .collect())
 }
 
+/// List the provider's models in the background and report them with
+/// [`AppEvent::ModelsDiscovered`]. Failures only mean `/model` shows the
+/// presets alone.
+fn spawn_model_discovery(config: &Config, auth_manager: &AuthManager, tx: AppEventSender) {
+    let auth = auth_manager.auth();
+    // The ChatGPT backend serves exactly the preset models.
+    if config.model_provider.requires_openai_auth
+        && auth
+            .as_ref()
+            .is_some_and(|auth| auth.mode == AuthMode::ChatGPT)
+    {
+        return;
+    }
+    let config = config.clone();
+    tokio::spawn(async move {
+        let models = if config.model_provider_id == BUILT_IN_OSS_MODEL_PROVIDER_ID {
+            codex_ollama::discover_models(&config).await
+        } else {
+            discover_provider_models(&config, auth).await
+        };
+        match models {
+            Ok(models) => tx.send(AppEvent::ModelsDiscovered(models)),
+            Err(err) => debug!("failed to discover models: {err}"),
+        }
+    });
+}
+
 fn create_initial_user_message(text: String, image_paths: Vec<PathBuf>) -> Option<UserMessage> {
     if text.is_empty() && image_paths.is_empty() {
         None
@@ -470,6 +524,18 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_cost_limit_approval_request(&mut self, event: CostLimitApprovalRequestEvent) {
+        self.flush_answer_stream_with_separator();
+        self.bottom_pane
+            .push_approval_request(ApprovalRequest::CostLimit {
+                id: event.id,
+                limit: event.limit,
+                limit_usd: event.limit_usd,
+                spent_usd: event.spent_usd,
+            });
+        self.request_redraw();
+    }
+
     fn on_exec_command_begin(&mut self, ev: ExecCommandBeginEvent) {
         self.flush_answer_stream_with_separator();
         let ev2 = ev.clone();
@@ -553,6 +619,108 @@ impl ChatWidget {
         debug!("BackgroundEvent: {message}");
     }
 
+    fn on_entered_review_mode(&mut self, request: ReviewRequest) {
+        self.add_to_history(history_cell::new_info_event(
+            format!("Reviewing {}…", request.user_facing_hint),
+            None,
+        ));
+        self.request_redraw();
+    }
+
+    fn on_exited_review_mode(&mut self, event: ExitedReviewModeEvent) {
+        match event.review_output {
+            Some(output) => {
+                self.add_to_history(history_cell::new_review_output(&output, &self.config.cwd));
+                if !output.findings.is_empty() {
+                    self.app_event_tx.send(AppEvent::OpenReviewFindings(output));
+                }
+            }
+            None => self.add_to_history(history_cell::new_error_event(
+                "Review finished without producing a result.".to_string(),
+            )),
+        }
+        self.request_redraw();
+    }
+
+    fn on_context_pinned(&mut self, event: ContextPinnedEvent) {
+        self.add_to_history(history_cell::new_pinned_notes(&event.pins));
+        self.request_redraw();
+    }
+
+    fn on_checkpoint_restored(&mut self, event: CheckpointRestoredEvent) {
+        let rewind_conversation = std::mem::take(&mut self.rewind_conversation_pending);
+        if event.applied {
+            let turns = if event.turns == 1 {
+                "1 turn".to_string()
+            } else {
+                format!("{} turns", event.turns)
+            };
+            self.add_to_history(history_cell::new_checkpoint_restored(
+                format!("Restored files from {turns} ago"),
+                &event.restored_paths,
+            ));
+            if rewind_conversation {
+                self.app_event_tx
+                    .send(AppEvent::RewindConversation(event.turns as usize));
+            }
+        } else {
+            self.add_to_history(history_cell::new_checkpoint_conflicts(&event.conflicts));
+        }
+        self.request_redraw();
+    }
+
+    /// Resolve the `/review` argument into a diff off the UI thread and submit
+    /// it as `Op::Review` once ready.
+    fn start_review(&mut self, arguments: &str) {
+        let cwd = self.config.cwd.clone();
+        let arguments = arguments.to_string();
+        let tx = self.app_event_tx.clone();
+        tokio::spawn(async move {
+            let target = ReviewTarget::resolve(&cwd, &arguments).await;
+            match build_review_request(&cwd, &target).await {
+                Ok(review_request) => tx.send(AppEvent::CodexOp(Op::Review { review_request })),
+                Err(err) => tx.send(AppEvent::InsertHistoryCell(Box::new(
+                    history_cell::new_error_event(format!("Failed to start review: {err}")),
+                ))),
+            }
+        });
+    }
+
+    /// Render the flushed rollout at `ev.path` and write it next to the
+    /// session (or to the requested path) off the UI thread.
+    fn start_export(&mut self, request: ExportRequest, ev: &ConversationPathResponseEvent) {
+        let output = match request.output {
+            Some(output) => self.config.cwd.join(output),
+            None => self.config.cwd.join(format!(
+                "codex-session-{}.{}",
+                ev.conversation_id,
+                request.format.extension()
+            )),
+        };
+        let rollout_path = ev.path.clone();
+        let tx = self.app_event_tx.clone();
+        tokio::spawn(async move {
+            let result = async {
+                let mut transcript = Transcript::load(&rollout_path).await?;
+                if request.redact {
+                    transcript.redact();
+                }
+                tokio::fs::write(&output, transcript.render(request.format)).await
+            }
+            .await;
+            let cell = match result {
+                Ok(()) => history_cell::new_info_event(
+                    format!("Exported conversation to {}", output.display()),
+                    request.redact.then(|| "(secrets redacted)".to_string()),
+                ),
+                Err(err) => {
+                    history_cell::new_error_event(format!("Failed to export conversation: {err}"))
+                }
+            };
+            tx.send(AppEvent::InsertHistoryCell(Box::new(cell)));
+        });
+    }
+
     fn on_stream_error(&mut self, message: String) {
         // Show stream errors in the transcript so users see retry/backoff info.
         self.add_to_history(history_cell::new_stream_error_event(message));
@@ -814,6 +982,7 @@ impl ChatWidget {
             app_event_tx.clone(),
             conversation_manager.clone(),
         );
+        spawn_model_discovery(&config, &auth_manager, app_event_tx.clone());
 
         Self {
             app_event_tx,
@@ -846,7 +1015,10 @@ impl ChatWidget {
             plan_mode_session: None,
             plan_mode_dialog_shown: false,
             plan_mode_dialog_pending: false,
+            rewind_conversation_pending: false,
+            pending_export: None,
             default_placeholder,
+            discovered_models: Vec::new(),
         }
     }
 
@@ -885,6 +1057,7 @@ impl ChatWidget {
 
         let codex_op_tx =
             spawn_agent_from_existing(conversation, session_configured, app_event_tx.clone());
+        spawn_model_discovery(&config, &auth_manager, app_event_tx.clone());
 
         Self {
             app_event_tx,
@@ -917,7 +1090,10 @@ impl ChatWidget {
             plan_mode_session: None,
             plan_mode_dialog_shown: false,
             plan_mode_dialog_pending: false,
+            rewind_conversation_pending: false,
+            pending_export: None,
             default_placeholder,
+            discovered_models: Vec::new(),
         }
     }
 
@@ -1019,6 +1195,64 @@ impl ChatWidget {
         }
     }
 
+    /// Parse `[<n>] [--force]` for `/undo` and `/rewind`.
+    fn parse_checkpoint_args(arguments: &str, default_turns: u32) -> Result<(u32, bool), String> {
+        let mut turns = None;
+        let mut force = false;
+        for arg in arguments.split_whitespace() {
+            match arg {
+                "--force" | "-f" => force = true,
+                other => match other.parse::<u32>() {
+                    Ok(n) if n > 0 && turns.is_none() => turns = Some(n),
+                    _ => {
+                        return Err(format!(
+                            "Unexpected argument '{other}'. Expected a positive number of turns and optionally --force."
+                        ));
+                    }
+                },
+            }
+        }
+        Ok((turns.unwrap_or(default_turns), force))
+    }
+
+    /// Parse `[md|html|json] [--redact] [<path>]` for `/export`. The format
+    /// defaults to the output file's extension, then Markdown.
+    fn parse_export_args(arguments: &str) -> Result<ExportRequest, String> {
+        let mut format = None;
+        let mut redact = false;
+        let mut output: Option<PathBuf> = None;
+        for arg in arguments.split_whitespace() {
+            if arg == "--redact" {
+                redact = true;
+            } else if format.is_none()
+                && output.is_none()
+                && let Ok(parsed) = arg.parse::<ExportFormat>()
+            {
+                format = Some(parsed);
+            } else if output.is_none() {
+                output = Some(PathBuf::from(arg));
+            } else {
+                return Err(format!(
+                    "Unexpected argument '{arg}'. Usage: /export [md|html|json] [--redact] [path]"
+                ));
+            }
+        }
+        let format = format
+            .or_else(|| {
+                output
+                    .as_ref()
+                    .and_then(|path| path.extension())
+                    .and_then(|ext| ext.to_str())
+                    .and_then(|ext| ext.parse().ok())
+            })
+            .unwrap_or(ExportFormat::Markdown);
+        Ok(ExportRequest {
+            format,
+            redact,
+            output,
+        })
+    }
+
     pub(crate) fn attach_image(
         &mut self,
         path: PathBuf,
@@ -1070,14 +1304,66 @@ impl ChatWidget {
             },
             SlashCommand::Compact => {
                 self.clear_token_usage();
-                self.app_event_tx.send(AppEvent::CodexOp(Op::Compact));
+                let instructions = arguments.trim();
+                self.app_event_tx.send(AppEvent::CodexOp(Op::Compact {
+                    instructions: (!instructions.is_empty()).then(|| instructions.to_string()),
+                }));
+            }
+            SlashCommand::Pin => {
+                let text = arguments.trim();
+                self.app_event_tx.send(AppEvent::CodexOp(Op::Pin {
+                    text: (!text.is_empty()).then(|| text.to_string()),
+                }));
+            }
+            SlashCommand::Review => {
+                self.start_review(arguments);
             }
+            SlashCommand::Undo | SlashCommand::Rewind => {
+                let is_rewind = cmd == SlashCommand::Rewind;
+                match Self::parse_checkpoint_args(arguments, 1) {
+                    Ok((turns, force)) if is_rewind || turns == 1 => {
+                        self.rewind_conversation_pending = is_rewind;
+                        self.app_event_tx
+                            .send(AppEvent::CodexOp(Op::RestoreCheckpoint { turns, force }));
+                    }
+                    Ok(_) => {
+                        self.add_to_history(history_cell::new_error_event(
+                            "'/undo' only reverts the last turn; use '/rewind <n>' to go further back."
+                                .to_string(),
+                        ));
+                        self.request_redraw();
+                    }
+                    Err(message) => {
+                        self.add_to_history(history_cell::new_error_event(message));
+                        self.request_redraw();
+                    }
+                }
+            }
+            SlashCommand::Export => match Self::parse_export_args(arguments) {
+                Ok(request) => {
+                    self.pending_export = Some(request);
+                    self.app_event_tx.send(AppEvent::CodexOp(Op::GetPath));
+                }
+                Err(message) => {
+                    self.add_to_history(history_cell::new_error_event(message));
+                    self.request_redraw();
+                }
+            },
             SlashCommand::Model => {
                 self.open_model_popup();
             }
             SlashCommand::Approvals => {
                 self.open_approvals_popup();
             }
+            SlashCommand::Profile => {
+                let name = arguments.trim();
+                if name.is_empty() {
+                    self.open_profile_popup();
+                } else {
+                    self.app_event_tx
+                        .send(AppEvent::SwitchProfile(name.to_string()));
+                }
+            }
             SlashCommand::Quit => {
                 self.app_event_tx.send(AppEvent::ExitRequest);
             }
@@ -1144,6 +1430,7 @@ impl ChatWidget {
                                 PathBuf::from("/tmp/test.txt"),
                                 FileChange::Add {
                                     content: "test".to_string(),
+                                    mode: None,
                                 },
                             ),
                             (
@@ -1151,6 +1438,7 @@ impl ChatWidget {
                                 FileChange::Update {
                                     unified_diff: "+test\n-test2".to_string(),
                                     move_path: None,
+                                    mode: None,
                                 },
                             ),
                         ]),
@@ -1377,6 +1665,7 @@ impl ChatWidget {
                 self.on_apply_patch_approval_request(id.unwrap_or_default(), ev)
             }
             EventMsg::SubagentApprovalRequest(event) => self.on_subagent_approval_request(event),
+            EventMsg::CostLimitApprovalRequest(event) => self.on_cost_limit_approval_request(event),
             EventMsg::ExecCommandBegin(ev) => self.on_exec_command_begin(ev),
             EventMsg::ExecCommandOutputDelta(delta) => self.on_exec_command_output_delta(delta),
             EventMsg::PatchApplyBegin(ev) => self.on_patch_apply_begin(ev),
@@ -1401,6 +1690,9 @@ impl ChatWidget {
                 }
             }
             EventMsg::ConversationPath(ev) => {
+                if let Some(request) = self.pending_export.take() {
+                    self.start_export(request, &ev);
+                }
                 self.app_event_tx
                     .send(crate::app_event::AppEvent::ConversationHistory(ev));
             }
@@ -1408,8 +1700,10 @@ impl ChatWidget {
             EventMsg::HookExecLogResponse(_) => {}
             EventMsg::HookValidationResult(_) => {}
             EventMsg::HookReloadResult(_) => {}
-            EventMsg::EnteredReviewMode(_) => {}
-            EventMsg::ExitedReviewMode(_) => {}
+            EventMsg::EnteredReviewMode(request) => self.on_entered_review_mode(request),
+            EventMsg::ExitedReviewMode(ev) => self.on_exited_review_mode(ev),
+            EventMsg::CheckpointRestored(ev) => self.on_checkpoint_restored(ev),
+            EventMsg::ContextPinned(ev) => self.on_context_pinned(ev),
         }
     }
 
@@ -1496,10 +1790,17 @@ impl ChatWidget {
         self.add_to_history(history_cell::new_status_output(
             &self.config,
             usage_ref,
+            self.token_info.as_ref().and_then(|ti| ti.cost.as_ref()),
             &self.conversation_id,
         ));
     }
 
+    /// Models the provider lists, offered in the model popup after the
+    /// presets.
+    pub(crate) fn set_discovered_models(&mut self, models: Vec<String>) {
+        self.discovered_models = models;
+    }
+
     /// Open a popup to choose the model preset (model + reasoning effort).
     pub(crate) fn open_model_popup(&mut self) {
         let current_model = self.config.model.clone();
@@ -1550,6 +1851,39 @@ impl ChatWidget {
             });
         }
 
+        // Discovered models keep the current reasoning effort; the model's
+        // capabilities come from its family or `[model_capabilities]`.
+        let provider_name = self.config.model_provider.name.clone();
+        for model in self
+            .discovered_models
+            .iter()
+            .filter(|model| !presets.iter().any(|preset| preset.model == model.as_str()))
+        {
+            let model_slug = model.clone();
+            let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                tx.send(AppEvent::CodexOp(Op::OverrideTurnContext {
+                    cwd: None,
+                    approval_policy: None,
+                    sandbox_policy: None,
+                    model: Some(model_slug.clone()),
+                    effort: None,
+                    summary: None,
+                }));
+                tx.send(AppEvent::UpdateModel(model_slug.clone()));
+                tx.send(AppEvent::PersistModelSelection {
+                    model: model_slug.clone(),
+                    effort: current_effort,
+                });
+                tracing::info!("New model: {model_slug}");
+            })];
+            items.push(SelectionItem {
+                name: model.clone(),
+                description: Some(format!("Discovered from {provider_name}")),
+                is_current: *model == current_model,
+                actions,
+            });
+        }
+
         self.bottom_pane.show_selection_view(
             "Select model and reasoning level".to_string(),
             Some("Switch between OpenAI models for this and future Codex CLI session".to_string()),
@@ -1599,6 +1933,58 @@ impl ChatWidget {
         );
     }
 
+    /// Open a popup to choose one of the profiles defined in `config.toml`.
+    pub(crate) fn open_profile_popup(&mut self) {
+        let profiles = self.config.profiles.clone();
+        if profiles.is_empty() {
+            self.add_info_message(
+                "No profiles are defined in config.toml.".to_string(),
+                Some("Add a [profiles.<name>] table to define one.".to_string()),
+            );
+            return;
+        }
+
+        let mut profiles: Vec<_> = profiles.into_iter().collect();
+        profiles.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let items = profiles
+            .into_iter()
+            .map(|(name, profile)| {
+                let summary: Vec<String> = [
+                    profile.model,
+                    profile.approval_policy.map(|policy| policy.to_string()),
+                    profile.sandbox_mode.map(|mode| mode.to_string()),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                let is_current = self.config.active_profile.as_deref() == Some(name.as_str());
+                let profile_name = name.clone();
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::SwitchProfile(profile_name.clone()));
+                })];
+                SelectionItem {
+                    name,
+                    description: (!summary.is_empty()).then(|| summary.join(", ")),
+                    is_current,
+                    actions,
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(
+            "Select profile".to_string(),
+            Some("Switch the model, approvals and sandbox for this session".to_string()),
+            Some("Press Enter to confirm or Esc to go back".to_string()),
+            items,
+        );
+    }
+
+    /// Replace the widget's config copy with one loaded for another profile.
+    pub(crate) fn apply_profile_config(&mut self, config: Config) {
+        self.session_header.set_model(&config.model);
+        self.config = config;
+    }
+
     /// Set the approval policy in the widget's config copy.
     pub(crate) fn set_approval_policy(&mut self, policy: AskForApproval) {
         self.config.approval_policy = policy;
@@ -7,6 +7,7 @@ use codex_core::ConversationManager;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigToml;
+use codex_core::config_profile::ConfigProfile;
 use codex_core::plan_tool::PlanItemArg;
 use codex_core::plan_tool::StepStatus;
 use codex_core::plan_tool::UpdatePlanArgs;
@@ -274,7 +275,10 @@ fn make_chatwidget_manual() -> (
         plan_mode_session: None,
         plan_mode_dialog_shown: false,
         plan_mode_dialog_pending: false,
+        rewind_conversation_pending: false,
+        pending_export: None,
         default_placeholder: "Ask Codex to do anything".to_string(),
+        discovered_models: Vec::new(),
     };
     (widget, rx, op_rx)
 }
@@ -488,6 +492,34 @@ fn open_fixture(name: &str) -> File {
     File::open(name).expect("open fixture file")
 }
 
+#[test]
+fn model_popup_offers_discovered_models() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.model = "llama3:8b".to_string();
+    // Preset models are not listed twice.
+    chat.set_discovered_models(vec!["gpt-5".to_string(), "llama3:8b".to_string()]);
+
+    chat.open_model_popup();
+    // The current model starts selected.
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    match rx.try_recv() {
+        Ok(AppEvent::CodexOp(Op::OverrideTurnContext { model, effort, .. })) => {
+            assert_eq!(model.as_deref(), Some("llama3:8b"));
+            assert_eq!(effort, None);
+        }
+        other => panic!("expected OverrideTurnContext, got {other:?}"),
+    }
+    match rx.try_recv() {
+        Ok(AppEvent::UpdateModel(model)) => assert_eq!(model, "llama3:8b"),
+        other => panic!("expected UpdateModel, got {other:?}"),
+    }
+    match rx.try_recv() {
+        Ok(AppEvent::PersistModelSelection { model, .. }) => assert_eq!(model, "llama3:8b"),
+        other => panic!("expected PersistModelSelection, got {other:?}"),
+    }
+}
+
 #[test]
 fn empty_enter_during_task_does_not_queue() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
@@ -665,6 +697,142 @@ fn disabled_slash_command_while_task_running_snapshot() {
     assert_snapshot!(blob);
 }
 
+#[test]
+fn profile_command_with_name_requests_profile_switch() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.dispatch_command(SlashCommand::Profile, " autonomous ");
+    match rx.try_recv() {
+        Ok(AppEvent::SwitchProfile(profile)) => assert_eq!(profile, "autonomous"),
+        other => panic!("expected SwitchProfile event, got {other:?}"),
+    }
+
+    let mut config = test_config();
+    config.model = "o3".to_string();
+    config.active_profile = Some("autonomous".to_string());
+    chat.apply_profile_config(config);
+    assert_eq!(chat.config_ref().model, "o3");
+    assert_eq!(
+        chat.config_ref().active_profile.as_deref(),
+        Some("autonomous")
+    );
+}
+
+#[test]
+fn profile_popup_lists_profiles_from_loaded_config() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.profiles = HashMap::from([
+        ("fast".to_string(), ConfigProfile::default()),
+        (
+            "safe".to_string(),
+            ConfigProfile {
+                model: Some("o3".to_string()),
+                ..Default::default()
+            },
+        ),
+    ]);
+    chat.config.active_profile = Some("safe".to_string());
+
+    chat.open_profile_popup();
+    // The active profile starts selected.
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    match rx.try_recv() {
+        Ok(AppEvent::SwitchProfile(profile)) => assert_eq!(profile, "safe"),
+        other => panic!("expected SwitchProfile event, got {other:?}"),
+    }
+}
+
+#[test]
+fn rewind_restores_checkpoint_then_rewinds_conversation() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.dispatch_command(SlashCommand::Rewind, "2 --force");
+    match rx.try_recv() {
+        Ok(AppEvent::CodexOp(Op::RestoreCheckpoint { turns, force })) => {
+            assert_eq!((turns, force), (2, true));
+        }
+        other => panic!("expected RestoreCheckpoint op, got {other:?}"),
+    }
+
+    chat.handle_codex_event(Event {
+        id: "rewind".into(),
+        msg: EventMsg::CheckpointRestored(CheckpointRestoredEvent {
+            turns: 2,
+            applied: true,
+            restored_paths: vec![PathBuf::from("src/lib.rs")],
+            conflicts: Vec::new(),
+        }),
+    });
+    let mut rewound = None;
+    while let Ok(ev) = rx.try_recv() {
+        if let AppEvent::RewindConversation(turns) = ev {
+            rewound = Some(turns);
+        }
+    }
+    assert_eq!(rewound, Some(2));
+}
+
+#[test]
+fn undo_conflicts_do_not_rewind_conversation() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.dispatch_command(SlashCommand::Undo, "");
+    assert!(matches!(
+        rx.try_recv(),
+        Ok(AppEvent::CodexOp(Op::RestoreCheckpoint {
+            turns: 1,
+            force: false
+        }))
+    ));
+
+    chat.handle_codex_event(Event {
+        id: "undo".into(),
+        msg: EventMsg::CheckpointRestored(CheckpointRestoredEvent {
+            turns: 1,
+            applied: false,
+            restored_paths: Vec::new(),
+            conflicts: vec![PathBuf::from("notes.md")],
+        }),
+    });
+    let cells = drain_insert_history(&mut rx);
+    let blob = lines_to_single_string(cells.last().expect("conflict cell"));
+    assert!(blob.contains("notes.md"), "unexpected cell: {blob}");
+    assert!(blob.contains("--force"), "unexpected cell: {blob}");
+}
+
+#[test]
+fn export_requests_flushed_rollout_path() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.dispatch_command(SlashCommand::Export, "html --redact");
+    assert!(matches!(rx.try_recv(), Ok(AppEvent::CodexOp(Op::GetPath))));
+    assert_eq!(
+        chat.pending_export,
+        Some(ExportRequest {
+            format: ExportFormat::Html,
+            redact: true,
+            output: None,
+        })
+    );
+}
+
+#[test]
+fn export_args_infer_format_from_path() {
+    assert_eq!(
+        ChatWidget::parse_export_args("notes/session.json"),
+        Ok(ExportRequest {
+            format: ExportFormat::Json,
+            redact: false,
+            output: Some(PathBuf::from("notes/session.json")),
+        })
+    );
+    assert_eq!(
+        ChatWidget::parse_export_args("").map(|request| request.format),
+        Ok(ExportFormat::Markdown)
+    );
+    assert!(ChatWidget::parse_export_args("md a.md b.md").is_err());
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn binary_size_transcript_snapshot() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
@@ -921,6 +1089,7 @@ fn approval_modal_patch_snapshot() {
         PathBuf::from("README.md"),
         FileChange::Add {
             content: "hello\nworld\n".into(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -1116,6 +1285,7 @@ fn apply_patch_events_emit_history_cells() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -1142,6 +1312,7 @@ fn apply_patch_events_emit_history_cells() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     let begin = PatchApplyBeginEvent {
@@ -1188,6 +1359,7 @@ fn apply_patch_manual_approval_adjusts_header() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -1206,6 +1378,7 @@ fn apply_patch_manual_approval_adjusts_header() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -1235,6 +1408,7 @@ fn apply_patch_manual_flow_snapshot() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -1255,6 +1429,7 @@ fn apply_patch_manual_flow_snapshot() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -1288,6 +1463,7 @@ fn apply_patch_approval_sends_op_with_submission_id() {
         PathBuf::from("file.rs"),
         FileChange::Add {
             content: "fn main(){}\n".into(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -1328,7 +1504,10 @@ fn apply_patch_full_flow_integration_like() {
     let mut changes = HashMap::new();
     changes.insert(
         PathBuf::from("pkg.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-xyz".into(),
@@ -1371,7 +1550,10 @@ fn apply_patch_full_flow_integration_like() {
     let mut changes2 = HashMap::new();
     changes2.insert(
         PathBuf::from("pkg.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-xyz".into(),
@@ -1402,7 +1584,10 @@ fn apply_patch_untrusted_shows_approval_modal() {
     let mut changes = HashMap::new();
     changes.insert(
         PathBuf::from("a.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-1".into(),
@@ -1450,6 +1635,7 @@ fn apply_patch_request_shows_diff_summary() {
         FileChange::Add {
             // Two lines (no trailing empty line counted)
             content: "line one\nline two\n".into(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -1571,6 +1757,7 @@ fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
         id: "s1".into(),
         msg: EventMsg::TaskComplete(TaskCompleteEvent {
             last_agent_message: None,
+            budget_exhausted: None,
         }),
     });
 
@@ -1886,6 +2073,7 @@ printf 'fenced within fenced\n'
         id: "t1".into(),
         msg: EventMsg::TaskComplete(TaskCompleteEvent {
             last_agent_message: None,
+            budget_exhausted: None,
         }),
     });
     for lines in drain_insert_history(&mut rx) {
@@ -2116,6 +2304,7 @@ fn plan_mode_dialog_shows_after_update() {
         id: "task-complete".into(),
         msg: EventMsg::TaskComplete(TaskCompleteEvent {
             last_agent_message: None,
+            budget_exhausted: None,
         }),
     });
 
@@ -2187,6 +2376,7 @@ fn plan_mode_refine_resets_dialog_and_reopens_on_update() {
         id: "task-complete".into(),
         msg: EventMsg::TaskComplete(TaskCompleteEvent {
             last_agent_message: None,
+            budget_exhausted: None,
         }),
     });
 
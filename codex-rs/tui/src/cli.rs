@@ -27,6 +27,11 @@ pub struct Cli {
     #[clap(skip)]
     pub resume_session_id: Option<String>,
 
+    /// Internal: fork the resumed session after this user turn instead of
+    /// continuing it. Set by `codex resume --fork-at <TURN>`.
+    #[clap(skip)]
+    pub resume_fork_at: Option<usize>,
+
     /// Model the agent should use.
     #[arg(long, short = 'm')]
     pub model: Option<String>,
@@ -76,6 +81,16 @@ pub struct Cli {
     #[arg(long = "search", default_value_t = false)]
     pub web_search: bool,
 
+    /// Record every model request and its streamed response to this
+    /// directory so the session can be replayed with `--replay`.
+    #[arg(long = "record", value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Serve model responses recorded with `--record` from this directory
+    /// instead of calling the model provider.
+    #[arg(long = "replay", value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 }
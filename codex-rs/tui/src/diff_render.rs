@@ -48,6 +48,8 @@ struct Row {
     #[allow(dead_code)]
     path: PathBuf,
     move_path: Option<PathBuf>,
+    /// Permission bits the patch sets, if any.
+    mode: Option<u32>,
     added: usize,
     removed: usize,
     change: FileChange,
@@ -57,9 +59,10 @@ fn collect_rows(changes: &HashMap<PathBuf, FileChange>) -> Vec<Row> {
     let mut rows: Vec<Row> = Vec::new();
     for (path, change) in changes.iter() {
         let (added, removed) = match change {
-            FileChange::Add { content } => (content.lines().count(), 0),
-            FileChange::Delete { content } => (0, content.lines().count()),
+            FileChange::Add { content, .. } => (content.lines().count(), 0),
+            FileChange::Delete { content, .. } => (0, content.lines().count()),
             FileChange::Update { unified_diff, .. } => calculate_add_remove_from_diff(unified_diff),
+            FileChange::Binary { .. } => (0, 0),
         };
         let move_path = match change {
             FileChange::Update {
@@ -68,9 +71,16 @@ fn collect_rows(changes: &HashMap<PathBuf, FileChange>) -> Vec<Row> {
             } => Some(new.clone()),
             _ => None,
         };
+        let mode = match change {
+            FileChange::Add { mode, .. }
+            | FileChange::Update { mode, .. }
+            | FileChange::Binary { mode, .. } => *mode,
+            FileChange::Delete { .. } => None,
+        };
         rows.push(Row {
             path: path.clone(),
             move_path,
+            mode,
             added,
             removed,
             change: change.clone(),
@@ -111,6 +121,9 @@ fn render_changes_block(
         if let Some(move_path) = &row.move_path {
             spans.push(format!(" → {}", display_path_for(move_path, cwd)).into());
         }
+        if let Some(mode) = row.mode {
+            spans.push(format!(" (mode {mode:o})").dim());
+        }
         spans
     };
 
@@ -136,7 +149,7 @@ fn render_changes_block(
         HeaderKind::Edited => {
             if let [row] = &rows[..] {
                 let verb = match &row.change {
-                    FileChange::Add { .. } => "Added",
+                    FileChange::Add { .. } | FileChange::Binary { replace: false, .. } => "Added",
                     FileChange::Delete { .. } => "Deleted",
                     _ => "Edited",
                 };
@@ -190,7 +203,7 @@ fn render_changes_block(
         }
 
         match r.change {
-            FileChange::Add { content } => {
+            FileChange::Add { content, .. } => {
                 for (i, raw) in content.lines().enumerate() {
                     out.extend(push_wrapped_diff_line(
                         i + 1,
@@ -200,7 +213,17 @@ fn render_changes_block(
                     ));
                 }
             }
-            FileChange::Delete { content } => {
+            FileChange::Delete {
+                binary_size: Some(size),
+                ..
+            }
+            | FileChange::Binary { size, .. } => {
+                out.push(RtLine::from(vec![
+                    "    ".into(),
+                    format!("binary file, {size} bytes").dim(),
+                ]));
+            }
+            FileChange::Delete { content, .. } => {
                 for (i, raw) in content.lines().enumerate() {
                     out.extend(push_wrapped_diff_line(
                         i + 1,
@@ -427,6 +450,7 @@ mod tests {
             PathBuf::from("README.md"),
             FileChange::Add {
                 content: "first line\nsecond line\n".to_string(),
+                mode: None,
             },
         );
 
@@ -448,6 +472,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: Some(PathBuf::from("src/lib_new.rs")),
+                mode: None,
             },
         );
 
@@ -481,6 +506,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -502,6 +528,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -524,6 +551,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -545,6 +573,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -571,6 +600,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: Some(PathBuf::from("new_name.rs")),
+                mode: None,
             },
         );
 
@@ -596,6 +626,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch_a,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -604,6 +635,7 @@ mod tests {
             PathBuf::from("b.txt"),
             FileChange::Add {
                 content: "new\n".to_string(),
+                mode: None,
             },
         );
 
@@ -624,6 +656,7 @@ mod tests {
             PathBuf::from("new_file.txt"),
             FileChange::Add {
                 content: "alpha\nbeta\n".to_string(),
+                mode: None,
             },
         );
 
@@ -637,6 +670,42 @@ mod tests {
         snapshot_lines("apply_add_block", lines, 80, 10);
     }
 
+    #[test]
+    fn ui_snapshot_apply_binary_and_mode_block() {
+        let mut changes: HashMap<PathBuf, FileChange> = HashMap::new();
+        changes.insert(
+            PathBuf::from("assets/logo.png"),
+            FileChange::Binary {
+                size: 1024,
+                replace: false,
+                mode: None,
+            },
+        );
+        changes.insert(
+            PathBuf::from("run.sh"),
+            FileChange::Add {
+                content: "#!/bin/sh\necho hi\n".to_string(),
+                mode: Some(0o755),
+            },
+        );
+        changes.insert(
+            PathBuf::from("assets/old.png"),
+            FileChange::Delete {
+                content: String::new(),
+                binary_size: Some(2048),
+            },
+        );
+
+        let lines = diff_summary_for_tests(
+            &changes,
+            PatchEventType::ApplyBegin {
+                auto_approved: true,
+            },
+        );
+
+        snapshot_lines_text("apply_binary_and_mode_block", &lines);
+    }
+
     #[test]
     fn ui_snapshot_apply_delete_block() {
         // Write a temporary file so the delete renderer can read original content
@@ -648,6 +717,7 @@ mod tests {
             tmp_path.clone(),
             FileChange::Delete {
                 content: "first\nsecond\nthird\n".to_string(),
+                binary_size: None,
             },
         );
 
@@ -677,6 +747,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -707,6 +778,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -741,6 +813,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: Some(abs_new),
+                mode: None,
             },
         );
 
@@ -26,13 +26,19 @@ use codex_core::plan_tool::PlanItemArg;
 use codex_core::plan_tool::StepStatus;
 use codex_core::plan_tool::UpdatePlanArgs;
 use codex_core::project_doc::discover_project_doc_paths;
+use codex_core::protocol::CostInfo;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpInvocation;
+use codex_core::protocol::ReviewOutputEvent;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_core::review::is_blocking_finding;
+use codex_core::review::priority_label;
+use codex_core::review::sorted_findings;
 use codex_protocol::mcp_protocol::ConversationId;
+use codex_protocol::num_format::format_usd;
 use codex_protocol::num_format::format_with_separators;
 use codex_protocol::parse_command::ParsedCommand;
 use image::DynamicImage;
@@ -1126,6 +1132,7 @@ pub(crate) fn new_completed_mcp_tool_call(
 pub(crate) fn new_status_output(
     config: &Config,
     usage: &TokenUsage,
+    cost: Option<&CostInfo>,
     session_id: &Option<ConversationId>,
 ) -> PlainHistoryCell {
     let mut lines: Vec<Line<'static>> = Vec::new();
@@ -1286,6 +1293,21 @@ pub(crate) fn new_status_output(
         "  • Total: ".into(),
         format_with_separators(usage.blended_total()).into(),
     ]));
+    // Cost: <session> [of <limit>], Today: <daily> [of <limit>]
+    if let Some(cost) = cost {
+        let spend = |usd: f64, limit: Option<f64>| match limit {
+            Some(limit) => format!("{} of {} limit", format_usd(usd), format_usd(limit)),
+            None => format_usd(usd),
+        };
+        lines.push(Line::from(vec![
+            "  • Cost: ".into(),
+            spend(cost.session_usd, config.cost_limits.max_session_usd).into(),
+        ]));
+        lines.push(Line::from(vec![
+            "  • Today: ".into(),
+            spend(cost.daily_usd, config.cost_limits.max_daily_usd).into(),
+        ]));
+    }
 
     PlainHistoryCell { lines }
 }
@@ -1355,6 +1377,114 @@ pub(crate) fn new_mcp_tools_output(
     PlainHistoryCell { lines }
 }
 
+/// Render the structured result of a `/review` run, most severe findings first.
+pub(crate) fn new_review_output(output: &ReviewOutputEvent, cwd: &Path) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec!["/review".magenta().into(), "".into()];
+
+    if !output.overall_correctness.is_empty() {
+        lines.push(
+            vec![
+                "Overall: ".bold(),
+                output.overall_correctness.clone().into(),
+            ]
+            .into(),
+        );
+    }
+    for line in output.overall_explanation.trim().lines() {
+        lines.push(line.to_string().dim().into());
+    }
+
+    let findings = sorted_findings(output);
+    if findings.is_empty() {
+        lines.push("".into());
+        lines.push("  • No findings.".italic().into());
+        return PlainHistoryCell { lines };
+    }
+
+    for finding in findings {
+        let label = format!("[{}]", priority_label(finding.priority));
+        let label = if is_blocking_finding(finding) {
+            label.red().bold()
+        } else {
+            label.bold()
+        };
+        let location = &finding.code_location;
+        let path = location
+            .absolute_file_path
+            .strip_prefix(cwd)
+            .unwrap_or(&location.absolute_file_path);
+        lines.push("".into());
+        lines.push(vec![label, " ".into(), finding.title.clone().bold()].into());
+        lines.push(
+            vec![
+                "  ".into(),
+                format!(
+                    "{}:{}-{}",
+                    path.display(),
+                    location.line_range.start,
+                    location.line_range.end
+                )
+                .cyan(),
+                format!(" (confidence {:.2})", finding.confidence_score).dim(),
+            ]
+            .into(),
+        );
+        for line in finding.body.trim().lines() {
+            lines.push(format!("  {line}").into());
+        }
+    }
+
+    PlainHistoryCell { lines }
+}
+
+/// List the notes pinned with `/pin`.
+pub(crate) fn new_pinned_notes(pins: &[String]) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec!["/pin".magenta().into()];
+    if pins.is_empty() {
+        lines.push("  └ no pinned notes; add one with /pin <note>".dim().into());
+    }
+    for (idx, pin) in pins.iter().enumerate() {
+        for (line_idx, line) in pin.lines().enumerate() {
+            let prefix = match (idx, line_idx) {
+                (0, 0) => "  └ ",
+                (_, 0) => "    ",
+                _ => "      ",
+            };
+            lines.push(vec![prefix.dim(), line.to_string().into()].into());
+        }
+    }
+    PlainHistoryCell { lines }
+}
+
+/// Summarize the files written back by `/undo` or `/rewind`.
+pub(crate) fn new_checkpoint_restored(title: String, paths: &[PathBuf]) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec![vec!["✓ ".green(), title.into()].into()];
+    if paths.is_empty() {
+        lines.push("  └ no file changes to revert".dim().into());
+    }
+    for (idx, path) in paths.iter().enumerate() {
+        let prefix = if idx == 0 { "  └ " } else { "    " };
+        lines.push(vec![prefix.dim(), path.display().to_string().into()].into());
+    }
+    PlainHistoryCell { lines }
+}
+
+/// Explain why a checkpoint restore was refused.
+pub(crate) fn new_checkpoint_conflicts(paths: &[PathBuf]) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec![
+        vec![
+            "■ ".red(),
+            "Files changed since the checkpoint; nothing was restored.".into(),
+        ]
+        .into(),
+    ];
+    for path in paths {
+        lines.push(vec!["    ".into(), path.display().to_string().red()].into());
+    }
+    lines.push("  Re-run with --force to overwrite them.".dim().into());
+    PlainHistoryCell { lines }
+}
+
 pub(crate) fn new_info_event(message: String, hint: Option<String>) -> PlainHistoryCell {
     let mut line = vec!["> ".into(), message.into()];
     if let Some(hint) = hint {
@@ -140,6 +140,8 @@ pub async fn run_main(
         show_raw_agent_reasoning: cli.oss.then_some(true),
         tools_web_search_request: cli.web_search.then_some(true),
         plan_mode_enabled: cli.plan.then_some(true),
+        record_dir: cli.record.clone(),
+        replay_dir: cli.replay.clone(),
     };
     let raw_overrides = cli.config_overrides.raw_overrides.clone();
     let overrides_cli = codex_common::CliConfigOverrides { raw_overrides };
@@ -156,7 +158,7 @@ pub async fn run_main(
         // Load configuration and support CLI overrides.
 
         #[allow(clippy::print_stderr)]
-        match Config::load_with_cli_overrides(cli_kv_overrides.clone(), overrides) {
+        match Config::load_with_cli_overrides(cli_kv_overrides.clone(), overrides.clone()) {
             Ok(config) => config,
             Err(err) => {
                 eprintln!("Error loading configuration: {err}");
@@ -245,6 +247,7 @@ pub async fn run_main(
     run_ratatui_app(
         cli,
         config,
+        overrides,
         internal_storage,
         active_profile,
         should_show_trust_screen,
@@ -256,6 +259,7 @@ pub async fn run_main(
 async fn run_ratatui_app(
     cli: Cli,
     config: Config,
+    launch_overrides: ConfigOverrides,
     mut internal_storage: InternalStorage,
     active_profile: Option<String>,
     should_show_trust_screen: bool,
@@ -379,6 +383,15 @@ async fn run_ratatui_app(
         resume_picker::ResumeSelection::StartFresh
     };
 
+    // `codex resume --fork-at <TURN>` branches the chosen session instead of
+    // continuing it.
+    let resume_selection = match (resume_selection, cli.resume_fork_at) {
+        (resume_picker::ResumeSelection::Resume(path), Some(turn)) => {
+            resume_picker::ResumeSelection::Fork { path, turn }
+        }
+        (selection, _) => selection,
+    };
+
     if should_show_model_rollout_prompt(
         &cli,
         &config,
@@ -409,13 +422,20 @@ async fn run_ratatui_app(
         }
     }
 
-    let Cli { prompt, images, .. } = cli;
+    let Cli {
+        prompt,
+        images,
+        config_overrides,
+        ..
+    } = cli;
 
     let app_result = App::run(
         &mut tui,
         auth_manager,
         config,
         active_profile,
+        config_overrides,
+        launch_overrides,
         prompt,
         images,
         resume_selection,
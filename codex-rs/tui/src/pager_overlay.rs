@@ -1,12 +1,26 @@
+use std::collections::HashMap;
 use std::io::Result;
+use std::path::Path;
+use std::path::PathBuf;
 use std::time::Duration;
 
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
 use crate::render::line_utils::push_owned_lines;
 use crate::tui;
 use crate::tui::TuiEvent;
+use codex_core::config_types::UriBasedFileOpener;
+use codex_core::protocol::ReviewCodeLocation;
+use codex_core::protocol::ReviewFinding;
+use codex_core::protocol::ReviewOutputEvent;
+use codex_core::review::fix_findings_prompt;
+use codex_core::review::is_blocking_finding;
+use codex_core::review::priority_label;
+use codex_core::review::sorted_findings;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::Color;
@@ -21,6 +35,7 @@ use ratatui::widgets::WidgetRef;
 pub(crate) enum Overlay {
     Transcript(TranscriptOverlay),
     Static(StaticOverlay),
+    Review(ReviewOverlay),
 }
 
 impl Overlay {
@@ -32,10 +47,20 @@ impl Overlay {
         Self::Static(StaticOverlay::with_title(lines, title))
     }
 
+    pub(crate) fn new_review(
+        output: &ReviewOutputEvent,
+        cwd: PathBuf,
+        file_opener: UriBasedFileOpener,
+        app_event_tx: AppEventSender,
+    ) -> Self {
+        Self::Review(ReviewOverlay::new(output, cwd, file_opener, app_event_tx))
+    }
+
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match self {
             Overlay::Transcript(o) => o.handle_event(tui, event),
             Overlay::Static(o) => o.handle_event(tui, event),
+            Overlay::Review(o) => o.handle_event(tui, event),
         }
     }
 
@@ -43,6 +68,7 @@ impl Overlay {
         match self {
             Overlay::Transcript(o) => o.is_done(),
             Overlay::Static(o) => o.is_done(),
+            Overlay::Review(o) => o.is_done(),
         }
     }
 }
@@ -527,6 +553,323 @@ impl StaticOverlay {
     }
 }
 
+/// Lines of surrounding context shown around a finding's line range.
+const REVIEW_HUNK_CONTEXT_LINES: usize = 3;
+
+const REVIEW_KEY_HINTS: &[(&str, &str)] = &[
+    ("q", "quit"),
+    ("↑/↓", "select"),
+    ("Space", "mark"),
+    ("o", "open"),
+    ("⏎", "fix marked"),
+];
+
+/// Interactive list of review findings. The selected finding's code is shown
+/// from the working tree, can be opened via the configured `file_opener`, and
+/// marked findings can be sent back to the agent to fix.
+pub(crate) struct ReviewOverlay {
+    view: PagerView,
+    findings: Vec<ReviewFinding>,
+    marked: Vec<bool>,
+    selected: usize,
+    cwd: PathBuf,
+    file_opener: UriBasedFileOpener,
+    app_event_tx: AppEventSender,
+    status: Option<String>,
+    is_done: bool,
+    /// Files shown in the code preview, read once per overlay rather than on
+    /// every selection change.
+    file_contents: HashMap<PathBuf, std::result::Result<String, String>>,
+}
+
+impl ReviewOverlay {
+    pub(crate) fn new(
+        output: &ReviewOutputEvent,
+        cwd: PathBuf,
+        file_opener: UriBasedFileOpener,
+        app_event_tx: AppEventSender,
+    ) -> Self {
+        let findings: Vec<ReviewFinding> = sorted_findings(output).into_iter().cloned().collect();
+        let mut overlay = Self {
+            view: PagerView::new(Vec::new(), "R E V I E W".to_string(), 0),
+            marked: vec![false; findings.len()],
+            findings,
+            selected: 0,
+            cwd,
+            file_opener,
+            app_event_tx,
+            status: None,
+            is_done: false,
+            file_contents: HashMap::new(),
+        };
+        overlay.rebuild_lines();
+        overlay
+    }
+
+    fn rebuild_lines(&mut self) {
+        let location = self
+            .findings
+            .get(self.selected)
+            .map(|finding| finding.code_location.clone());
+        let hunk = location.map(|location| {
+            let contents = self.file_contents(&location.absolute_file_path);
+            hunk_lines(&location, contents)
+        });
+        let mut lines: Vec<Line<'static>> = Vec::new();
+        if self.findings.is_empty() {
+            lines.push("No findings.".italic().into());
+        }
+        for (idx, finding) in self.findings.iter().enumerate() {
+            let is_selected = idx == self.selected;
+            let pointer = if is_selected { "› " } else { "  " };
+            let checkbox = if self.marked[idx] { "[x] " } else { "[ ] " };
+            let title = if is_selected {
+                finding.title.clone().cyan().bold()
+            } else {
+                finding.title.clone().into()
+            };
+            lines.push(
+                vec![
+                    pointer.cyan(),
+                    checkbox.into(),
+                    priority_span(finding),
+                    " ".into(),
+                    title,
+                    format!("  {}", self.location_label(&finding.code_location)).dim(),
+                ]
+                .into(),
+            );
+        }
+
+        if let Some(finding) = self.findings.get(self.selected) {
+            lines.push("".into());
+            lines.push(
+                vec![
+                    priority_span(finding),
+                    " ".into(),
+                    finding.title.clone().bold(),
+                ]
+                .into(),
+            );
+            lines.push(
+                vec![
+                    self.location_label(&finding.code_location).cyan(),
+                    format!(" (confidence {:.2})", finding.confidence_score).dim(),
+                ]
+                .into(),
+            );
+            for line in finding.body.trim().lines() {
+                lines.push(line.to_string().into());
+            }
+            lines.push("".into());
+        }
+        lines.extend(hunk.into_iter().flatten());
+
+        self.view.lines = lines;
+        self.view.wrap_cache = None;
+    }
+
+    fn file_contents(&mut self, path: &Path) -> &std::result::Result<String, String> {
+        self.file_contents
+            .entry(path.to_path_buf())
+            .or_insert_with(|| std::fs::read_to_string(path).map_err(|err| err.to_string()))
+    }
+
+    fn location_label(&self, location: &ReviewCodeLocation) -> String {
+        let path = location
+            .absolute_file_path
+            .strip_prefix(&self.cwd)
+            .unwrap_or(&location.absolute_file_path);
+        format!(
+            "{}:{}-{}",
+            path.display(),
+            location.line_range.start,
+            location.line_range.end
+        )
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.findings.is_empty() {
+            return;
+        }
+        let last = self.findings.len() - 1;
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+        // Keep the selected row on screen; the details follow the list.
+        self.view.scroll_offset = self.view.scroll_offset.min(self.selected);
+        self.status = None;
+        self.rebuild_lines();
+    }
+
+    fn toggle_marked(&mut self) {
+        if let Some(marked) = self.marked.get_mut(self.selected) {
+            *marked = !*marked;
+            self.rebuild_lines();
+        }
+    }
+
+    fn open_selected(&mut self) {
+        let Some(finding) = self.findings.get(self.selected) else {
+            return;
+        };
+        let Some(scheme) = self.file_opener.get_scheme() else {
+            self.status =
+                Some("Set `file_opener` in config.toml to open findings in an editor.".to_string());
+            return;
+        };
+        let uri = location_uri(scheme, &finding.code_location);
+        self.status = Some(match webbrowser::open(&uri) {
+            Ok(()) => format!("Opened {}", self.location_label(&finding.code_location)),
+            Err(err) => format!("Failed to open {uri}: {err}"),
+        });
+    }
+
+    /// Send the marked findings (or the selected one when nothing is marked)
+    /// back to the agent and close the overlay.
+    fn submit_fixes(&mut self) {
+        let marked: Vec<&ReviewFinding> = self
+            .findings
+            .iter()
+            .zip(&self.marked)
+            .filter_map(|(finding, marked)| marked.then_some(finding))
+            .collect();
+        let findings = if marked.is_empty() {
+            self.findings.get(self.selected).into_iter().collect()
+        } else {
+            marked
+        };
+        if findings.is_empty() {
+            return;
+        }
+        self.app_event_tx
+            .send(AppEvent::FixReviewFindings(fix_findings_prompt(&findings)));
+        self.is_done = true;
+    }
+
+    /// Handle keys specific to the review list. Returns false for keys that
+    /// should fall through to the pager.
+    fn handle_review_key(&mut self, key_event: KeyEvent) -> bool {
+        if !matches!(key_event.kind, KeyEventKind::Press | KeyEventKind::Repeat) {
+            return false;
+        }
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.is_done = true,
+            KeyCode::Char('c') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.is_done = true
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Char(' ') => self.toggle_marked(),
+            KeyCode::Char('o') => self.open_selected(),
+            KeyCode::Enter => self.submit_fixes(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn render_hints(&self, area: Rect, buf: &mut Buffer) {
+        let line1 = Rect::new(area.x, area.y, area.width, 1);
+        let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
+        let line3 = Rect::new(area.x, area.y.saturating_add(2), area.width, 1);
+        render_key_hints(line1, buf, &[("PgUp/PgDn", "page"), ("Home/End", "jump")]);
+        render_key_hints(line2, buf, REVIEW_KEY_HINTS);
+        if let Some(status) = &self.status {
+            Line::from(vec![" ".into(), status.clone().dim()]).render_ref(line3, buf);
+        }
+    }
+
+    pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let top_h = area.height.saturating_sub(3);
+        let top = Rect::new(area.x, area.y, area.width, top_h);
+        let bottom = Rect::new(area.x, area.y + top_h, area.width, 3);
+        self.view.render(top, buf);
+        self.render_hints(bottom, buf);
+    }
+
+    pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
+        match event {
+            TuiEvent::Key(key_event) => {
+                if self.handle_review_key(key_event) {
+                    tui.frame_requester().schedule_frame();
+                    Ok(())
+                } else {
+                    self.view.handle_key_event(tui, key_event)
+                }
+            }
+            TuiEvent::Draw => {
+                tui.draw(u16::MAX, |frame| {
+                    self.render(frame.area(), frame.buffer);
+                })?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.is_done
+    }
+}
+
+fn priority_span(finding: &ReviewFinding) -> Span<'static> {
+    let label = format!("[{}]", priority_label(finding.priority));
+    if is_blocking_finding(finding) {
+        label.red().bold()
+    } else {
+        label.bold()
+    }
+}
+
+/// Same URI shape as the file citations rewritten in `markdown_render`.
+fn location_uri(scheme: &str, location: &ReviewCodeLocation) -> String {
+    let path = location
+        .absolute_file_path
+        .to_string_lossy()
+        .replace('\\', "/");
+    format!("{scheme}://file{path}:{}", location.line_range.start)
+}
+
+/// The finding's lines (plus some context) from the file's `contents` in the
+/// working tree. Lines inside the finding's range are emphasized.
+fn hunk_lines(
+    location: &ReviewCodeLocation,
+    contents: &std::result::Result<String, String>,
+) -> Vec<Line<'static>> {
+    let contents = match contents {
+        Ok(contents) => contents,
+        Err(err) => {
+            return vec![
+                format!(
+                    "Unable to read {}: {err}",
+                    location.absolute_file_path.display()
+                )
+                .italic()
+                .into(),
+            ];
+        }
+    };
+    let start = location.line_range.start.max(1) as usize;
+    let end = (location.line_range.end as usize).max(start);
+    let first = start.saturating_sub(REVIEW_HUNK_CONTEXT_LINES).max(1);
+    let last = end.saturating_add(REVIEW_HUNK_CONTEXT_LINES);
+    let width = last.to_string().len();
+
+    contents
+        .lines()
+        .enumerate()
+        .map(|(idx, text)| (idx + 1, text))
+        .skip(first - 1)
+        .take(last + 1 - first)
+        .map(|(line_no, text)| {
+            let gutter = format!("{line_no:>width$} │ ");
+            if (start..=end).contains(&line_no) {
+                vec![gutter.cyan(), text.to_string().bold()].into()
+            } else {
+                vec![gutter.dim(), text.to_string().dim()].into()
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -663,4 +1006,114 @@ mod tests {
             "wrapped length should grow or stay same after append"
         );
     }
+
+    fn review_output_in(dir: &Path) -> ReviewOutputEvent {
+        let finding = |title: &str, priority: i32, start: u32, end: u32| ReviewFinding {
+            title: title.to_string(),
+            body: format!("Details for {title}."),
+            confidence_score: 0.5,
+            priority,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: dir.join("lib.rs"),
+                line_range: codex_core::protocol::ReviewLineRange { start, end },
+            },
+        };
+        ReviewOutputEvent {
+            findings: vec![
+                finding("Style nit", 3, 1, 1),
+                finding("Panics on empty input", 0, 4, 5),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn review_overlay_in(
+        dir: &Path,
+    ) -> (
+        ReviewOverlay,
+        tokio::sync::mpsc::UnboundedReceiver<AppEvent>,
+    ) {
+        let source: String = (1..=9).map(|i| format!("line {i}\n")).collect();
+        std::fs::write(dir.join("lib.rs"), source).expect("write source");
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let overlay = ReviewOverlay::new(
+            &review_output_in(dir),
+            dir.to_path_buf(),
+            UriBasedFileOpener::None,
+            AppEventSender::new(tx),
+        );
+        (overlay, rx)
+    }
+
+    fn press(overlay: &mut ReviewOverlay, code: KeyCode) {
+        overlay.handle_review_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn review_overlay_snapshot_shows_sorted_findings_and_hunk() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (mut overlay, _rx) = review_overlay_in(dir.path());
+        let mut term = Terminal::new(TestBackend::new(60, 24)).expect("term");
+        term.draw(|f| overlay.render(f.area(), f.buffer_mut()))
+            .expect("draw");
+        assert_snapshot!(term.backend());
+    }
+
+    #[test]
+    fn review_overlay_sends_marked_findings_to_fix() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (mut overlay, mut rx) = review_overlay_in(dir.path());
+
+        press(&mut overlay, KeyCode::Down);
+        press(&mut overlay, KeyCode::Char(' '));
+        press(&mut overlay, KeyCode::Enter);
+
+        assert!(overlay.is_done());
+        match rx.try_recv() {
+            Ok(AppEvent::FixReviewFindings(prompt)) => {
+                assert!(prompt.contains("[P3] Style nit"), "{prompt}");
+                assert!(!prompt.contains("Panics on empty input"), "{prompt}");
+            }
+            other => panic!("expected FixReviewFindings, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn review_overlay_reads_each_file_once() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (mut overlay, _rx) = review_overlay_in(dir.path());
+        std::fs::remove_file(dir.path().join("lib.rs")).expect("remove source");
+
+        press(&mut overlay, KeyCode::Down);
+
+        let text: Vec<String> = overlay.view.lines.iter().map(ToString::to_string).collect();
+        assert!(
+            text.iter().any(|line| line.ends_with("│ line 1")),
+            "{text:?}"
+        );
+        assert!(!text.iter().any(|line| line.contains("Unable to read")));
+    }
+
+    #[test]
+    fn review_overlay_open_requires_file_opener() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (mut overlay, _rx) = review_overlay_in(dir.path());
+        press(&mut overlay, KeyCode::Char('o'));
+        assert_eq!(
+            overlay.status.as_deref(),
+            Some("Set `file_opener` in config.toml to open findings in an editor.")
+        );
+    }
+
+    #[test]
+    fn location_uri_matches_citation_format() {
+        let location = ReviewCodeLocation {
+            absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+            line_range: codex_core::protocol::ReviewLineRange { start: 7, end: 9 },
+        };
+        assert_eq!(
+            location_uri("vscode", &location),
+            "vscode://file/repo/src/lib.rs:7"
+        );
+    }
 }
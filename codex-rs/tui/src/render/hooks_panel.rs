@@ -119,7 +119,7 @@ fn render_layer_summary(layer: &HookLayerSummary) -> Line<'static> {
     spans.push(format!("{} loaded", layer.loaded_hooks).dim());
     if skipped > 0 {
         spans.push(", ".dim());
-        spans.push(format!("{} skipped", skipped).yellow().dim());
+        spans.push(format!("{skipped} skipped").red().dim());
     }
     spans.push(")".dim());
     Line::from(spans)
@@ -133,7 +133,7 @@ fn render_skipped_hook(skipped: &SkippedHook) -> Line<'static> {
     }
     spans.push(
         format!("({})", format_skip_reason(&skipped.reason))
-            .yellow()
+            .red()
             .dim(),
     );
     if let Some(details) = &skipped.details {
@@ -241,7 +241,7 @@ fn scope_badge(scope: &HookScope) -> Span<'static> {
     match scope {
         HookScope::ManagedPolicy { name } => format!("managed:{name}").cyan().bold(),
         HookScope::Project { .. } => "project".magenta().bold(),
-        HookScope::LocalUser { .. } => "local".yellow().bold(),
+        HookScope::LocalUser { .. } => "local".green().bold(),
     }
 }
 
@@ -7,6 +7,11 @@ use codex_core::ConversationItem;
 use codex_core::ConversationsPage;
 use codex_core::Cursor;
 use codex_core::RolloutRecorder;
+use codex_core::SessionIndex;
+use codex_core::SessionSearchHit;
+use codex_core::SessionSearchQuery;
+use codex_core::UserTurn;
+use codex_core::list_user_turns;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -28,17 +33,25 @@ use codex_protocol::protocol::InputMessageKind;
 use codex_protocol::protocol::USER_MESSAGE_BEGIN;
 
 const PAGE_SIZE: usize = 25;
+const SEARCH_LIMIT: usize = 200;
 
 #[derive(Debug, Clone)]
 pub enum ResumeSelection {
     StartFresh,
     Resume(PathBuf),
+    /// Start a new session from the first `turn` user turns of `path`.
+    Fork {
+        path: PathBuf,
+        turn: usize,
+    },
     Exit,
 }
 
-/// Interactive session picker that lists recorded rollout files with simple
-/// search and pagination. Shows the first user input as the preview, relative
-/// time (e.g., "5 seconds ago"), and the absolute path.
+/// Interactive session picker that lists recorded rollout files with
+/// pagination. Typing runs a full-text search over all sessions through the
+/// on-disk [`SessionIndex`]. Shows the first user input as the preview and
+/// relative time (e.g., "5 seconds ago"). Tab lists the user turns of the
+/// selected session so it can be forked from any of them.
 pub async fn run_resume_picker(tui: &mut Tui, codex_home: &Path) -> Result<ResumeSelection> {
     let alt = AltScreenGuard::enter(tui);
     let mut state = PickerState::new(codex_home.to_path_buf(), alt.tui.frame_requester());
@@ -97,12 +110,25 @@ struct PickerState {
     selected: usize,
     // search
     query: String,
+    /// Loaded on the first keystroke; `None` until then or if loading failed.
+    index: Option<SessionIndex>,
+    index_failed: bool,
+    search_error: Option<String>,
+    /// Set while choosing the turn to fork the selected session from.
+    fork: Option<ForkTurns>,
+}
+
+struct ForkTurns {
+    path: PathBuf,
+    preview: String,
+    turns: Vec<UserTurn>,
+    selected: usize,
 }
 
 #[derive(Debug, Clone)]
 struct Pagination {
     current_anchor: Option<Cursor>,
-    backstack: Vec<Option<Cursor>>, // track previous anchors for ←/a
+    backstack: Vec<Option<Cursor>>, // track previous anchors for ←
     next_cursor: Option<Cursor>,
     page_index: usize,
 }
@@ -129,6 +155,10 @@ impl PickerState {
             filtered_rows: Vec::new(),
             selected: 0,
             query: String::new(),
+            index: None,
+            index_failed: false,
+            search_error: None,
+            fork: None,
         }
     }
 
@@ -137,6 +167,9 @@ impl PickerState {
     }
 
     async fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ResumeSelection>> {
+        if self.fork.is_some() {
+            return Ok(self.handle_fork_key(key));
+        }
         match key.code {
             KeyCode::Esc => return Ok(Some(ResumeSelection::StartFresh)),
             KeyCode::Char('c')
@@ -151,6 +184,9 @@ impl PickerState {
                     return Ok(Some(ResumeSelection::Resume(row.path.clone())));
                 }
             }
+            KeyCode::Tab => {
+                self.open_fork_turns().await;
+            }
             KeyCode::Up => {
                 if self.selected > 0 {
                     self.selected -= 1;
@@ -163,14 +199,15 @@ impl PickerState {
                 }
                 self.request_frame();
             }
-            KeyCode::Left | KeyCode::Char('a') => {
+            KeyCode::Left => {
                 self.prev_page().await?;
             }
-            KeyCode::Right | KeyCode::Char('d') => {
+            KeyCode::Right => {
                 self.next_page().await?;
             }
             KeyCode::Backspace => {
                 self.query.pop();
+                self.load_index().await;
                 self.apply_filter();
             }
             KeyCode::Char(c) => {
@@ -181,6 +218,7 @@ impl PickerState {
                     && !key.modifiers.contains(crossterm::event::KeyModifiers::ALT)
                 {
                     self.query.push(c);
+                    self.load_index().await;
                     self.apply_filter();
                 }
             }
@@ -189,8 +227,61 @@ impl PickerState {
         Ok(None)
     }
 
+    fn handle_fork_key(&mut self, key: KeyEvent) -> Option<ResumeSelection> {
+        let fork = self.fork.as_mut()?;
+        match key.code {
+            KeyCode::Esc => self.fork = None,
+            KeyCode::Char('c')
+                if key
+                    .modifiers
+                    .contains(crossterm::event::KeyModifiers::CONTROL) =>
+            {
+                return Some(ResumeSelection::Exit);
+            }
+            KeyCode::Enter => {
+                let turn = fork.turns.get(fork.selected)?.turn;
+                return Some(ResumeSelection::Fork {
+                    path: fork.path.clone(),
+                    turn,
+                });
+            }
+            KeyCode::Up => fork.selected = fork.selected.saturating_sub(1),
+            KeyCode::Down => {
+                if fork.selected + 1 < fork.turns.len() {
+                    fork.selected += 1;
+                }
+            }
+            _ => {}
+        }
+        self.request_frame();
+        None
+    }
+
+    /// Load the user turns of the selected session and switch to choosing
+    /// a fork point. The most recent turn is preselected.
+    async fn open_fork_turns(&mut self) {
+        let Some(row) = self.filtered_rows.get(self.selected) else {
+            return;
+        };
+        let turns = match list_user_turns(&row.path).await {
+            Ok(turns) => turns,
+            Err(err) => {
+                tracing::warn!("failed to read {}: {err}", row.path.display());
+                Vec::new()
+            }
+        };
+        self.fork = Some(ForkTurns {
+            path: row.path.clone(),
+            preview: row.preview.clone(),
+            selected: turns.len().saturating_sub(1),
+            turns,
+        });
+        self.request_frame();
+    }
+
     async fn prev_page(&mut self) -> Result<()> {
-        if self.pagination.page_index == 0 {
+        // Search results are not paginated.
+        if self.pagination.page_index == 0 || !self.query.is_empty() {
             return Ok(());
         }
         // current_anchor points to the page we just loaded; backstack[page_index-1] is the anchor to reload
@@ -209,6 +300,9 @@ impl PickerState {
     }
 
     async fn next_page(&mut self) -> Result<()> {
+        if !self.query.is_empty() {
+            return Ok(());
+        }
         if let Some(next) = self.pagination.next_cursor.clone() {
             // Record the anchor for the page we are moving to at index new_index
             let new_index = self.pagination.page_index + 1;
@@ -234,10 +328,44 @@ impl PickerState {
         Ok(())
     }
 
+    async fn load_index(&mut self) {
+        if self.index.is_some() || self.index_failed || self.query.is_empty() {
+            return;
+        }
+        let codex_home = self.codex_home.clone();
+        match tokio::task::spawn_blocking(move || SessionIndex::open(&codex_home)).await {
+            Ok(Ok(index)) => self.index = Some(index),
+            Ok(Err(err)) => {
+                tracing::warn!("failed to load session search index: {err}");
+                self.index_failed = true;
+            }
+            Err(err) => {
+                tracing::warn!("failed to load session search index: {err}");
+                self.index_failed = true;
+            }
+        }
+    }
+
     fn apply_filter(&mut self) {
+        self.search_error = None;
         if self.query.is_empty() {
             self.filtered_rows = self.all_rows.clone();
+        } else if let Some(index) = &self.index {
+            match SessionSearchQuery::parse(&self.query) {
+                Ok(query) => {
+                    self.filtered_rows = index
+                        .search(&query, SEARCH_LIMIT)
+                        .iter()
+                        .map(hit_to_row)
+                        .collect();
+                }
+                Err(err) => {
+                    self.search_error = Some(err);
+                    self.filtered_rows.clear();
+                }
+            }
         } else {
+            // Without an index, fall back to filtering the current page.
             let q = self.query.to_lowercase();
             self.filtered_rows = self
                 .all_rows
@@ -253,6 +381,27 @@ impl PickerState {
     }
 }
 
+fn hit_to_row(hit: &SessionSearchHit) -> Row {
+    let first = hit
+        .first_user_message
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or("(no message yet)");
+    // Show where the match is when it is not in the first message.
+    let preview = match hit.snippets.first() {
+        Some(snippet) if !first.contains(snippet.text.trim_matches('…')) => {
+            format!("{first}  · {}: {}", snippet.kind.label(), snippet.text)
+        }
+        _ => first.to_string(),
+    };
+    Row {
+        path: hit.path.clone(),
+        preview,
+        ts: hit.timestamp,
+    }
+}
+
 fn to_rows(page: ConversationsPage) -> Vec<Row> {
     page.items.into_iter().map(|it| head_to_row(&it)).collect()
 }
@@ -329,6 +478,11 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
         ])
         .areas(area);
 
+        if let Some(fork) = &state.fork {
+            draw_fork_turns(frame, [header, search, list, hint], fork);
+            return;
+        }
+
         // Header
         frame.render_widget_ref(
             Line::from(vec!["Resume a previous session".bold().cyan()]),
@@ -336,12 +490,20 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
         );
 
         // Search line
-        let q = if state.query.is_empty() {
-            "Type to search".dim().to_string()
+        let search_line: Line = if state.query.is_empty() {
+            "Type to search all sessions (filters: cwd: model: branch: since: until:)"
+                .dim()
+                .into()
+        } else if let Some(err) = &state.search_error {
+            vec![
+                format!("Search: {}  ", state.query).into(),
+                err.clone().red(),
+            ]
+            .into()
         } else {
-            format!("Search: {}", state.query)
+            format!("Search: {}", state.query).into()
         };
-        frame.render_widget_ref(Line::from(q), search);
+        frame.render_widget_ref(search_line, search);
 
         // List
         render_list(frame, list, state);
@@ -350,13 +512,15 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
         let hint_line: Line = vec![
             "Enter".bold(),
             " to resume  ".into(),
+            "Tab".bold(),
+            " to fork  ".into(),
             "Esc".bold(),
             " to start new  ".into(),
             "Ctrl+C".into(),
             " to quit  ".dim(),
-            "←/a".into(),
+            "←".into(),
             " prev  ".dim(),
-            "→/d".into(),
+            "→".into(),
             " next".dim(),
         ]
         .into();
@@ -364,6 +528,65 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
     })
 }
 
+fn draw_fork_turns(
+    frame: &mut crate::custom_terminal::Frame,
+    [header, session, list, hint]: [Rect; 4],
+    fork: &ForkTurns,
+) {
+    frame.render_widget_ref(
+        Line::from(vec!["Fork a session after a turn".bold().cyan()]),
+        header,
+    );
+    let max_cols = session.width as usize;
+    frame.render_widget_ref(
+        Line::from(truncate_text(&fork.preview, max_cols).dim()),
+        session,
+    );
+
+    if fork.turns.is_empty() {
+        frame.render_widget_ref(Line::from("No turns to fork from".italic().dim()), list);
+    } else {
+        let capacity = list.height as usize;
+        let start = fork.selected.saturating_sub(capacity.saturating_sub(1));
+        let end = fork.turns.len().min(start + capacity);
+        let mut y = list.y;
+        for (idx, turn) in fork.turns[start..end].iter().enumerate() {
+            let is_sel = start + idx == fork.selected;
+            let marker = if is_sel { "> ".bold() } else { "  ".into() };
+            let number = format!("{:>3}", turn.turn).dim();
+            let max_cols = list.width.saturating_sub(7) as usize;
+            let message = truncate_text(&turn_preview(&turn.message), max_cols);
+            let line: Line = vec![marker, number, "  ".into(), message.into()].into();
+            frame.render_widget_ref(line, Rect::new(list.x, y, list.width, 1));
+            y = y.saturating_add(1);
+        }
+    }
+
+    let hint_line: Line = vec![
+        "Enter".bold(),
+        " to fork after this turn  ".into(),
+        "Esc".bold(),
+        " to go back  ".into(),
+        "Ctrl+C".into(),
+        " to quit".dim(),
+    ]
+    .into();
+    frame.render_widget_ref(hint_line, hint);
+}
+
+/// First line of a user message, without any ide context prefix.
+fn turn_preview(message: &str) -> String {
+    let message = match message.find(USER_MESSAGE_BEGIN) {
+        Some(idx) => &message[idx + USER_MESSAGE_BEGIN.len()..],
+        None => message,
+    };
+    let message = message.trim();
+    match message.lines().next() {
+        Some(line) if !line.is_empty() => line.to_string(),
+        _ => "(image)".to_string(),
+    }
+}
+
 fn render_list(frame: &mut crate::custom_terminal::Frame, area: Rect, state: &PickerState) {
     let rows = &state.filtered_rows;
     if rows.is_empty() {
@@ -494,4 +717,141 @@ mod tests {
         assert!(rows[0].preview.contains('A'));
         assert!(rows[1].preview.contains('B'));
     }
+
+    #[tokio::test]
+    async fn typing_searches_all_sessions_through_the_index() {
+        let codex_home = tempfile::TempDir::new().expect("tempdir");
+        let dir = codex_home.path().join("sessions/2025/01/02");
+        std::fs::create_dir_all(&dir).expect("create sessions dir");
+        let id = "00000000-0000-0000-0000-000000000001";
+        let lines = [
+            json!({
+                "timestamp": "2025-01-02T00:00:00.000Z",
+                "type": "session_meta",
+                "payload": {
+                    "id": id,
+                    "timestamp": "2025-01-02T00:00:00.000Z",
+                    "cwd": "/repo",
+                    "originator": "codex_cli_rs",
+                    "cli_version": "0.0.0",
+                    "instructions": null,
+                },
+            }),
+            json!({
+                "timestamp": "2025-01-02T00:00:01.000Z",
+                "type": "event_msg",
+                "payload": { "type": "user_message", "message": "Fix the flaky test" },
+            }),
+            json!({
+                "timestamp": "2025-01-02T00:00:02.000Z",
+                "type": "event_msg",
+                "payload": { "type": "agent_message", "message": "The migration lock was racing." },
+            }),
+        ];
+        let path = dir.join(format!("rollout-2025-01-02T00-00-00-{id}.jsonl"));
+        std::fs::write(
+            &path,
+            lines.iter().map(|l| format!("{l}\n")).collect::<String>(),
+        )
+        .expect("write rollout");
+
+        let mut state = PickerState::new(
+            codex_home.path().to_path_buf(),
+            FrameRequester::test_dummy(),
+        );
+        for c in "migration".chars() {
+            state
+                .handle_key(KeyEvent::from(KeyCode::Char(c)))
+                .await
+                .expect("handle key");
+        }
+        assert_eq!(state.filtered_rows.len(), 1);
+        assert_eq!(state.filtered_rows[0].path, path);
+        assert_eq!(
+            state.filtered_rows[0].preview,
+            "Fix the flaky test  · codex: The migration lock was racing."
+        );
+
+        for c in " since:bogus".chars() {
+            state
+                .handle_key(KeyEvent::from(KeyCode::Char(c)))
+                .await
+                .expect("handle key");
+        }
+        assert!(state.filtered_rows.is_empty());
+        assert!(state.search_error.is_some());
+    }
+
+    #[tokio::test]
+    async fn tab_picks_a_turn_to_fork_from() {
+        let codex_home = tempfile::TempDir::new().expect("tempdir");
+        let dir = codex_home.path().join("sessions/2025/01/02");
+        std::fs::create_dir_all(&dir).expect("create sessions dir");
+        let id = "00000000-0000-0000-0000-000000000002";
+        let user = |text: &str| {
+            json!({
+                "timestamp": "2025-01-02T00:00:01.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "message",
+                    "role": "user",
+                    "content": [{ "type": "input_text", "text": text }],
+                },
+            })
+        };
+        let lines = [
+            json!({
+                "timestamp": "2025-01-02T00:00:00.000Z",
+                "type": "session_meta",
+                "payload": {
+                    "id": id,
+                    "timestamp": "2025-01-02T00:00:00.000Z",
+                    "cwd": "/repo",
+                    "originator": "codex_cli_rs",
+                    "cli_version": "0.0.0",
+                    "instructions": null,
+                },
+            }),
+            user("<environment_context>\n  <cwd>/repo</cwd>\n</environment_context>"),
+            user("first try"),
+            json!({
+                "timestamp": "2025-01-02T00:00:01.000Z",
+                "type": "event_msg",
+                "payload": { "type": "user_message", "message": "first try" },
+            }),
+            user("second try"),
+        ];
+        let path = dir.join(format!("rollout-2025-01-02T00-00-00-{id}.jsonl"));
+        std::fs::write(
+            &path,
+            lines.iter().map(|l| format!("{l}\n")).collect::<String>(),
+        )
+        .expect("write rollout");
+
+        let mut state = PickerState::new(
+            codex_home.path().to_path_buf(),
+            FrameRequester::test_dummy(),
+        );
+        state.load_page(None).await.expect("load page");
+        state
+            .handle_key(KeyEvent::from(KeyCode::Tab))
+            .await
+            .expect("handle key");
+        let fork = state.fork.as_ref().expect("turn list open");
+        assert_eq!(fork.turns.len(), 2);
+        assert_eq!(fork.selected, 1);
+
+        state
+            .handle_key(KeyEvent::from(KeyCode::Up))
+            .await
+            .expect("handle key");
+        let selection = state
+            .handle_key(KeyEvent::from(KeyCode::Enter))
+            .await
+            .expect("handle key");
+        assert!(matches!(
+            selection,
+            Some(ResumeSelection::Fork { path: p, turn: 1 }) if p == path
+        ));
+    }
 }
@@ -18,6 +18,7 @@ pub enum SlashCommand {
     // more frequently used commands should be listed first.
     Model,
     Approvals,
+    Profile,
     Help,
     New,
     Init,
@@ -25,6 +26,11 @@ pub enum SlashCommand {
     ExitPlan,
     ApplyPlan,
     Compact,
+    Pin,
+    Review,
+    Undo,
+    Rewind,
+    Export,
     Diff,
     Mention,
     Status,
@@ -45,12 +51,18 @@ impl SlashCommand {
             SlashCommand::ExitPlan => "exit Plan Mode and restore the previous approval policy",
             SlashCommand::ApplyPlan => "apply the captured plan and choose the next approval mode",
             SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
+            SlashCommand::Pin => "pin a note so it is kept when the conversation is compacted",
+            SlashCommand::Review => "review current changes, a branch, a commit, or staged changes",
+            SlashCommand::Undo => "revert the file changes made during the last turn",
+            SlashCommand::Rewind => "restore files and conversation to <n> turns ago",
+            SlashCommand::Export => "export this conversation as Markdown, HTML or JSON",
             SlashCommand::Quit => "exit Codex",
             SlashCommand::Diff => "show git diff (including untracked files)",
             SlashCommand::Mention => "mention a file",
             SlashCommand::Status => "show current session configuration and token usage",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Approvals => "choose what Codex can do without approval",
+            SlashCommand::Profile => "switch to another config profile",
             SlashCommand::Help => "show slash command help and reload custom commands",
             SlashCommand::Mcp => "list configured MCP tools",
             SlashCommand::Logout => "log out of Codex",
@@ -74,10 +86,16 @@ impl SlashCommand {
             | SlashCommand::ExitPlan
             | SlashCommand::ApplyPlan
             | SlashCommand::Compact
+            | SlashCommand::Review
+            | SlashCommand::Undo
+            | SlashCommand::Rewind
             | SlashCommand::Model
             | SlashCommand::Approvals
+            | SlashCommand::Profile
             | SlashCommand::Logout => false,
             SlashCommand::Diff
+            | SlashCommand::Pin
+            | SlashCommand::Export
             | SlashCommand::Mention
             | SlashCommand::Status
             | SlashCommand::Mcp
@@ -10,6 +10,7 @@ use std::path::PathBuf;
 use std::sync::LazyLock;
 
 use codex_core::protocol::AskForApproval;
+use codex_core::protocol::CostLimit;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::SubagentApprovalDecision;
@@ -33,9 +34,11 @@ use crate::app_event_sender::AppEventSender;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::history_cell;
 use crate::text_formatting::truncate_text;
+use codex_protocol::num_format::format_usd;
 use codex_protocol::plan_mode::PlanModeSessionPayload;
 
 /// Request coming from the agent that needs user approval.
+#[allow(clippy::large_enum_variant)]
 pub(crate) enum ApprovalRequest {
     Exec {
         id: String,
@@ -59,6 +62,12 @@ pub(crate) enum ApprovalRequest {
         requested_tools: Vec<String>,
         model: Option<String>,
     },
+    CostLimit {
+        id: String,
+        limit: CostLimit,
+        limit_usd: f64,
+        spent_usd: f64,
+    },
 }
 
 /// Options displayed in the *select* mode.
@@ -175,6 +184,29 @@ static SUBAGENT_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
     ]
 });
 
+static COST_LIMIT_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
+    vec![
+        SelectOption {
+            label: Line::from(vec!["Y".underlined(), "es".into()]),
+            description: "Keep going and ask again once as much has been spent on top",
+            key: KeyCode::Char('y'),
+            action: SelectAction::Review(ReviewDecision::Approved),
+        },
+        SelectOption {
+            label: Line::from(vec!["A".underlined(), "lways".into()]),
+            description: "Keep going and stop asking about this limit for this session",
+            key: KeyCode::Char('a'),
+            action: SelectAction::Review(ReviewDecision::ApprovedForSession),
+        },
+        SelectOption {
+            label: Line::from(vec!["N".underlined(), "o".into()]),
+            description: "Stop the current task",
+            key: KeyCode::Char('n'),
+            action: SelectAction::Review(ReviewDecision::Denied),
+        },
+    ]
+});
+
 /// A modal prompting the user to approve or deny the pending request.
 pub(crate) struct UserApprovalWidget {
     approval_request: ApprovalRequest,
@@ -225,20 +257,20 @@ impl UserApprovalWidget {
                 let mut contents: Vec<Line> = Vec::new();
                 let title = session.plan_artifact.title.trim();
                 let title = if title.is_empty() { "Plan" } else { title };
-                contents.push(Line::from(format!("Plan ready: {}", title)));
+                contents.push(Line::from(format!("Plan ready: {title}")));
                 if !session.plan_artifact.next_actions.is_empty() {
                     contents.push(Line::from("Next actions:"));
                     for action in session.plan_artifact.next_actions.iter().take(3) {
-                        contents.push(Line::from(format!("  • {}", action)));
+                        contents.push(Line::from(format!("  • {action}")));
                     }
                     if session.plan_artifact.next_actions.len() > 3 {
                         let remaining = session.plan_artifact.next_actions.len() - 3;
-                        contents.push(Line::from(format!("  • … and {} more", remaining)));
+                        contents.push(Line::from(format!("  • … and {remaining} more")));
                     }
                 }
                 if !session.allowed_tools.is_empty() {
                     let tools = session.allowed_tools.join(", ");
-                    contents.push(Line::from(format!("Allowed tools: {}", tools)));
+                    contents.push(Line::from(format!("Allowed tools: {tools}")));
                 }
                 contents.push(Line::from("Select how to proceed with the plan."));
                 Paragraph::new(contents).wrap(Wrap { trim: false })
@@ -253,38 +285,54 @@ impl UserApprovalWidget {
                 model,
             } => {
                 let mut contents: Vec<Line> = Vec::new();
-                contents.push(Line::from(format!("Invoke subagent: {}", name)));
-                if let Some(desc) = description {
-                    if !desc.trim().is_empty() {
-                        contents.push(Line::from(desc.clone().italic()));
-                        contents.push(Line::from(""));
-                    }
+                contents.push(Line::from(format!("Invoke subagent: {name}")));
+                if let Some(desc) = description
+                    && !desc.trim().is_empty()
+                {
+                    contents.push(Line::from(desc.clone().italic()));
+                    contents.push(Line::from(""));
                 }
                 if let Some(model) = model {
-                    contents.push(Line::from(format!("Model: {}", model)));
+                    contents.push(Line::from(format!("Model: {model}")));
                 }
                 if !requested_tools.is_empty() {
                     contents.push(Line::from("Requested tools:"));
                     for tool in requested_tools {
-                        contents.push(Line::from(format!("  • {}", tool)));
+                        contents.push(Line::from(format!("  • {tool}")));
                     }
                 } else if !allowed_tools.is_empty() {
                     contents.push(Line::from("Allowed tools:"));
                     for tool in allowed_tools {
-                        contents.push(Line::from(format!("  • {}", tool)));
+                        contents.push(Line::from(format!("  • {tool}")));
                     }
                 }
-                if let Some(instructions) = extra_instructions {
-                    if !instructions.trim().is_empty() {
-                        contents.push(Line::from("Extra instructions:"));
-                        for line in instructions.lines() {
-                            contents.push(Line::from(format!("  {}", line)));
-                        }
+                if let Some(instructions) = extra_instructions
+                    && !instructions.trim().is_empty()
+                {
+                    contents.push(Line::from("Extra instructions:"));
+                    for line in instructions.lines() {
+                        contents.push(Line::from(format!("  {line}")));
                     }
                 }
                 contents.push(Line::from("Approve invoking this subagent?"));
                 Paragraph::new(contents).wrap(Wrap { trim: false })
             }
+            ApprovalRequest::CostLimit {
+                limit,
+                limit_usd,
+                spent_usd,
+                ..
+            } => {
+                let contents = vec![
+                    Line::from(format!(
+                        "The {limit} cost limit of {} has been reached ({} spent).",
+                        format_usd(*limit_usd),
+                        format_usd(*spent_usd)
+                    )),
+                    Line::from(""),
+                ];
+                Paragraph::new(contents).wrap(Wrap { trim: false })
+            }
         };
 
         Self {
@@ -293,6 +341,7 @@ impl UserApprovalWidget {
                 ApprovalRequest::ApplyPatch { .. } => &PATCH_SELECT_OPTIONS,
                 ApprovalRequest::PlanMode { .. } => &PLAN_SELECT_OPTIONS,
                 ApprovalRequest::Subagent { .. } => &SUBAGENT_SELECT_OPTIONS,
+                ApprovalRequest::CostLimit { .. } => &COST_LIMIT_SELECT_OPTIONS,
             },
             approval_request,
             app_event_tx,
@@ -336,6 +385,7 @@ impl UserApprovalWidget {
             ApprovalRequest::Subagent { .. } => {
                 self.send_subagent_decision(SubagentApprovalDecision::Denied)
             }
+            ApprovalRequest::CostLimit { .. } => self.send_decision(ReviewDecision::Denied),
             _ => self.send_decision(ReviewDecision::Abort),
         }
     }
@@ -358,6 +408,7 @@ impl UserApprovalWidget {
                 ApprovalRequest::Subagent { .. } => {
                     self.send_subagent_decision(SubagentApprovalDecision::Denied)
                 }
+                ApprovalRequest::CostLimit { .. } => self.send_decision(ReviewDecision::Denied),
                 _ => self.send_decision(ReviewDecision::Abort),
             },
             other => {
@@ -510,6 +561,32 @@ impl UserApprovalWidget {
             ApprovalRequest::Subagent { .. } => {
                 // Subagent approval decisions are handled by send_subagent_decision.
             }
+            ApprovalRequest::CostLimit { limit, .. } => {
+                let spans: Vec<Span<'static>> = match decision {
+                    ReviewDecision::Approved => vec![
+                        "✔ ".fg(Color::Green),
+                        "You ".into(),
+                        "approved".bold(),
+                        format!(" going past the {limit} cost limit").into(),
+                    ],
+                    ReviewDecision::ApprovedForSession => vec![
+                        "✔ ".fg(Color::Green),
+                        "You ".into(),
+                        "approved".bold(),
+                        format!(" going past the {limit} cost limit").into(),
+                        " for this session".bold(),
+                    ],
+                    ReviewDecision::Denied | ReviewDecision::Abort => vec![
+                        "✗ ".fg(Color::Red),
+                        "You ".into(),
+                        "stopped".bold(),
+                        format!(" at the {limit} cost limit").into(),
+                    ],
+                };
+                self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                    history_cell::new_user_approval_decision(vec![Line::from(spans)]),
+                )));
+            }
         }
 
         if let Some(op) = match &self.approval_request {
@@ -521,6 +598,10 @@ impl UserApprovalWidget {
                 id: id.clone(),
                 decision,
             }),
+            ApprovalRequest::CostLimit { id, .. } => Some(Op::CostLimitApproval {
+                id: id.clone(),
+                decision,
+            }),
             ApprovalRequest::PlanMode { .. } => None,
             ApprovalRequest::Subagent { .. } => None,
         } {
@@ -577,6 +658,7 @@ impl WidgetRef for &UserApprovalWidget {
             ApprovalRequest::ApplyPatch { .. } => "Apply changes?",
             ApprovalRequest::PlanMode { .. } => "Accept plan?",
             ApprovalRequest::Subagent { .. } => "Run subagent?",
+            ApprovalRequest::CostLimit { .. } => "Keep going?",
         };
         Line::from(title).render(title_area, buf);
 
@@ -790,4 +872,29 @@ mod tests {
 
         assert!(saw_denied, "expected subagent denial op");
     }
+
+    #[test]
+    fn cost_limit_escape_denies() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let request = ApprovalRequest::CostLimit {
+            id: "cost-1".into(),
+            limit: CostLimit::Daily,
+            limit_usd: 20.0,
+            spent_usd: 20.4,
+        };
+        let mut widget = UserApprovalWidget::new(request, tx);
+        widget.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(widget.is_complete());
+
+        let mut saw_denied = false;
+        while let Ok(event) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::CostLimitApproval { id, decision }) = event {
+                assert_eq!(id, "cost-1");
+                assert_eq!(decision, ReviewDecision::Denied);
+                saw_denied = true;
+            }
+        }
+        assert!(saw_denied, "expected denial op");
+    }
 }